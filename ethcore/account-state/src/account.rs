@@ -504,6 +504,16 @@ impl Account {
 		self.balance = self.balance - *x;
 	}
 
+	/// Set account balance.
+	pub fn set_balance(&mut self, x: U256) {
+		self.balance = x;
+	}
+
+	/// Set account nonce.
+	pub fn set_nonce(&mut self, x: U256) {
+		self.nonce = x;
+	}
+
 	/// Commit the `storage_changes` to the backing DB and update `storage_root`.
 	pub fn commit_storage(&mut self, trie_factory: &TrieFactory, db: &mut dyn HashDB<KeccakHasher, DBValue>) -> TrieResult<()> {
 		let mut t = trie_factory.from_existing(db, &mut self.storage_root)?;
@@ -25,6 +25,7 @@ use std::collections::HashSet;
 use std::sync::Arc;
 
 use ethereum_types::{Address, H256};
+use ethtrie::{Result as TrieResult, TrieError};
 use hash_db::{AsHashDB, EMPTY_PREFIX, HashDB, Prefix};
 use kvdb::DBValue;
 use memory_db::{HashKey, MemoryDB};
@@ -69,6 +70,24 @@ pub trait Backend: Send {
 	/// Check whether an account is known to be empty. Returns true if known to be
 	/// empty, false otherwise.
 	fn is_known_null(&self, address: &Address) -> bool;
+
+	/// Make sure the trie nodes needed to look up the account at `address` are present
+	/// in the backing database. Backends which hold the full state have nothing to do.
+	fn prefetch_account(&self, _address: &Address) -> TrieResult<()> { Ok(()) }
+
+	/// Make sure the trie nodes needed to look up `key` in the storage of the account at
+	/// `address` are present in the backing database.
+	fn prefetch_storage(&self, _address: &Address, _key: &H256) -> TrieResult<()> { Ok(()) }
+
+	/// Whether trie nodes missing from the backing database can be fetched with
+	/// `prefetch_node`.
+	fn fetches_nodes(&self) -> bool { false }
+
+	/// Make sure the trie node `hash` is present in the backing database. `address` is the
+	/// account whose storage trie holds the node, `None` for nodes of the account trie.
+	fn prefetch_node(&self, _address: Option<&Address>, hash: &H256) -> TrieResult<()> {
+		Err(Box::new(TrieError::IncompleteDatabase(*hash)))
+	}
 }
 
 /// A raw backend used to check proofs of execution.
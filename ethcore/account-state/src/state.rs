@@ -31,17 +31,19 @@ use std::{
 
 use common_types::{
 	state_diff::StateDiff,
+	state_override::{StateOverride, StorageOverride},
 	basic_account::BasicAccount,
 	errors::EthcoreError as Error,
 };
-use ethereum_types::{Address, H256, U256};
+use ethereum_types::{Address, BigEndianHash, H256, U256};
 use ethtrie::{TrieDB, Result as TrieResult};
 use trie_vm_factories::{Factories, VmFactory};
-use hash_db::HashDB;
+use hash_db::{AsHashDB, HashDB, Prefix};
 use keccak_hash::{KECCAK_EMPTY, KECCAK_NULL_RLP};
 use keccak_hasher::KeccakHasher;
 use kvdb::DBValue;
 use log::{warn, trace};
+use memory_db::{HashKey, MemoryDB};
 use parity_bytes::Bytes;
 use pod::{self, PodAccount, PodState};
 use trie_db::{Trie, TrieError, Recorder};
@@ -539,6 +541,15 @@ impl<B: Backend> State<B> {
 		// 2. If there's an entry for the account in the global cache check for the key or load it into that account.
 		// 3. If account is missing in the global cache load it into the local cache and cache the key there.
 
+		// storage missing from the backing database is fetched only when the trie is read.
+		let f_at = |account: &Account, db: &dyn HashDB<KeccakHasher, DBValue>, key: &H256| -> TrieResult<H256> {
+			if let Some(value) = f_cached_at(account, key) {
+				return Ok(value);
+			}
+			self.db.prefetch_storage(address, key)?;
+			f_at(account, db, key)
+		};
+
 		{
 			// check local cache first without updating
 			let local_cache = self.cache.borrow_mut();
@@ -581,6 +592,7 @@ impl<B: Backend> State<B> {
 
 		// check if the account could exist before any requests to trie
 		if self.db.is_known_null(address) { return Ok(H256::zero()) }
+		self.db.prefetch_account(address)?;
 
 		// account is not found in the global cache, get from the DB and insert into local
 		let db = &self.db.as_hash_db();
@@ -709,6 +721,10 @@ impl<B: Backend> State<B> {
 	/// Commits our cached account changes into the trie.
 	pub fn commit(&mut self) -> Result<(), Error> {
 		assert!(self.checkpoints.borrow().is_empty());
+		if self.db.fetches_nodes() {
+			self.fetch_commit_nodes()?;
+		}
+
 		// first, commit the sub trees.
 		let mut accounts = self.cache.borrow_mut();
 		for (address, ref mut a) in accounts.iter_mut().filter(|&(_, ref a)| a.is_dirty()) {
@@ -743,6 +759,58 @@ impl<B: Backend> State<B> {
 		Ok(())
 	}
 
+	/// Replay the trie changes of `commit` in a scratch database, fetching the trie nodes
+	/// they need which are missing from the backend. Removing an item can need the node of a
+	/// sibling which no lookup has touched.
+	fn fetch_commit_nodes(&self) -> TrieResult<()> {
+		let mut fetched = HashSet::new();
+		loop {
+			let (address, hash) = match self.replay_commit() {
+				Ok(()) => return Ok(()),
+				Err((address, e)) => match *e {
+					TrieError::IncompleteDatabase(hash) if fetched.insert(hash) => (address, hash),
+					_ => return Err(e),
+				},
+			};
+			trace!(target: "state", "Fetching trie node {:?} needed to commit", hash);
+			self.db.prefetch_node(address.as_ref(), &hash)?;
+		}
+	}
+
+	/// Apply the trie changes of `commit` to a scratch database on top of the backend,
+	/// returning the account whose storage trie failed, if any, along with the error.
+	fn replay_commit(&self) -> Result<(), (Option<Address>, Box<ethtrie::TrieError>)> {
+		let accounts = self.cache.borrow();
+		let mut db = Scratch::new(self.db.as_hash_db());
+		for (address, a) in accounts.iter().filter(|&(_, ref a)| a.is_dirty()) {
+			if let Some(ref account) = a.account {
+				let mut storage_root = account.base_storage_root();
+				let mut account_db = self.factories.accountdb.create(&mut db, account.address_hash(address));
+				let mut trie = self.factories.trie.from_existing(account_db.as_hash_db_mut(), &mut storage_root)
+					.map_err(|e| (Some(*address), e))?;
+				for (key, value) in account.storage_changes() {
+					let result = if value.is_zero() {
+						trie.remove(key.as_bytes())
+					} else {
+						trie.insert(key.as_bytes(), &rlp::encode(&value.into_uint()))
+					};
+					result.map_err(|e| (Some(*address), e))?;
+				}
+			}
+		}
+
+		let mut root = self.root;
+		let mut trie = self.factories.trie.from_existing(&mut db, &mut root).map_err(|e| (None, e))?;
+		for (address, a) in accounts.iter().filter(|&(_, ref a)| a.is_dirty()) {
+			let result = match a.account {
+				Some(ref account) => trie.insert(address.as_bytes(), &account.rlp()),
+				None => trie.remove(address.as_bytes()),
+			};
+			result.map_err(|e| (None, e))?;
+		}
+		Ok(())
+	}
+
 	/// Propagate local cache into shared canonical state cache.
 	fn propagate_to_global_cache(&mut self) {
 		let mut addresses = self.cache.borrow_mut();
@@ -836,7 +904,6 @@ impl<B: Backend> State<B> {
 	/// This function is only intended for use in small tests or with fresh accounts.
 	/// It requires FatDB.
 	fn account_to_pod_account(&self, account: &Account, address: &Address) -> Result<PodAccount, Error> {
-		use ethereum_types::BigEndianHash;
 		assert!(self.factories.trie.is_fat());
 
 		let mut pod_storage = BTreeMap::new();
@@ -996,6 +1063,7 @@ impl<B: Backend> State<B> {
 				if check_null && self.db.is_known_null(a) { return Ok(f(None)); }
 
 				// not found in the global cache, get from the DB and insert into local
+				self.db.prefetch_account(a)?;
 				let db = &self.db.as_hash_db();
 				let db = self.factories.trie.readonly(db, &self.root)?;
 				let from_rlp = |b: &[u8]| Account::from_rlp(b).expect("decoding db value failed");
@@ -1029,6 +1097,7 @@ impl<B: Backend> State<B> {
 				Some(acc) => self.insert_cache(a, AccountEntry::new_clean_cached(acc)),
 				None => {
 					let maybe_acc = if !self.db.is_known_null(a) {
+						self.db.prefetch_account(a)?;
 						let db = &self.db.as_hash_db();
 						let db = self.factories.trie.readonly(db, &self.root)?;
 						let from_rlp = |b:&[u8]| { Account::from_rlp(b).expect("decoding db value failed") };
@@ -1072,6 +1141,35 @@ impl<B: Backend> State<B> {
 	pub fn patch_account(&self, a: &Address, code: Arc<Bytes>, storage: HashMap<H256, H256>) -> TrieResult<()> {
 		Ok(self.require(a, false)?.reset_code_and_storage(code, storage))
 	}
+
+	/// Apply overrides of balances, nonces, code and storage. Meant for throwaway states of
+	/// non-persistent calls.
+	pub fn apply_override(&mut self, overrides: &StateOverride) -> TrieResult<()> {
+		for (address, account) in overrides {
+			if let Some(balance) = account.balance {
+				self.require(address, false)?.set_balance(balance);
+			}
+			if let Some(nonce) = account.nonce {
+				self.require(address, false)?.set_nonce(nonce);
+			}
+			if let Some(ref code) = account.code {
+				self.reset_code(address, code.clone())?;
+			}
+			match account.storage {
+				Some(StorageOverride::Replace(ref storage)) => {
+					let code = self.code(address)?.unwrap_or_default();
+					self.patch_account(address, code, storage.iter().map(|(k, v)| (*k, *v)).collect())?;
+				},
+				Some(StorageOverride::Diff(ref diff)) => {
+					for (key, value) in diff {
+						self.set_storage(address, *key, *value)?;
+					}
+				},
+				None => {},
+			}
+		}
+		Ok(())
+	}
 }
 
 // State proof implementations; useful for light client protocols.
@@ -1161,3 +1259,45 @@ impl<B: Backend + Clone> Clone for State<B> {
 		}
 	}
 }
+
+/// Database keeping changes aside from the read-only database below it, so that trie changes
+/// can be tried out without applying them.
+struct Scratch<'a> {
+	base: &'a dyn HashDB<KeccakHasher, DBValue>,
+	changed: MemoryDB<KeccakHasher, HashKey<KeccakHasher>, DBValue>,
+}
+
+impl<'a> Scratch<'a> {
+	fn new(base: &'a dyn HashDB<KeccakHasher, DBValue>) -> Self {
+		Scratch {
+			base,
+			changed: journaldb::new_memory_db(),
+		}
+	}
+}
+
+impl<'a> HashDB<KeccakHasher, DBValue> for Scratch<'a> {
+	fn get(&self, key: &H256, prefix: Prefix) -> Option<DBValue> {
+		self.changed.get(key, prefix).or_else(|| self.base.get(key, prefix))
+	}
+
+	fn contains(&self, key: &H256, prefix: Prefix) -> bool {
+		self.get(key, prefix).is_some()
+	}
+
+	fn insert(&mut self, prefix: Prefix, value: &[u8]) -> H256 {
+		self.changed.insert(prefix, value)
+	}
+
+	fn emplace(&mut self, key: H256, prefix: Prefix, value: DBValue) {
+		self.changed.emplace(key, prefix, value)
+	}
+
+	// nodes are addressed by their hash, so keeping the removed ones is harmless.
+	fn remove(&mut self, _key: &H256, _prefix: Prefix) { }
+}
+
+impl<'a> AsHashDB<KeccakHasher, DBValue> for Scratch<'a> {
+	fn as_hash_db(&self) -> &dyn HashDB<KeccakHasher, DBValue> { self }
+	fn as_hash_db_mut(&mut self) -> &mut dyn HashDB<KeccakHasher, DBValue> { self }
+}
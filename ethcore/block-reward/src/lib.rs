@@ -23,7 +23,7 @@ use ethabi::FunctionOutputDecoder;
 use ethabi_contract::use_contract;
 use ethereum_types::{Address, U256};
 use common_types::{
-	BlockNumber,
+	block_reward::BlockReward,
 	errors::{EngineError, EthcoreError as Error},
 };
 use keccak_hash::keccak;
@@ -34,48 +34,14 @@ use trace::{Tracer, ExecutiveTracer, Tracing};
 
 use_contract!(block_reward_contract, "res/block_reward.json");
 
-/// The kind of block reward.
-/// Depending on the consensus engine the allocated block reward might have
-/// different semantics which could lead e.g. to different reward values.
-#[derive(PartialEq, Eq, Clone, Copy, Debug)]
-pub enum RewardKind {
-	/// Reward attributed to the block author.
-	Author,
-	/// Reward attributed to the author(s) of empty step(s) included in the block (AuthorityRound engine).
-	EmptyStep,
-	/// Reward attributed by an external protocol (e.g. block reward contract).
-	External,
-	/// Reward attributed to the block uncle(s) with given difference.
-	Uncle(u8),
-}
-
-impl RewardKind {
-	/// Create `RewardKind::Uncle` from given current block number and uncle block number.
-	pub fn uncle(number: BlockNumber, uncle: BlockNumber) -> Self {
-		RewardKind::Uncle(if number > uncle && number - uncle <= u8::max_value().into() { (number - uncle) as u8 } else { 0 })
-	}
-}
-
-impl From<RewardKind> for u16 {
-	fn from(reward_kind: RewardKind) -> Self {
-		match reward_kind {
-			RewardKind::Author => 0,
-			RewardKind::EmptyStep => 2,
-			RewardKind::External => 3,
+pub use common_types::block_reward::RewardKind;
 
-			RewardKind::Uncle(depth) => 100 + depth as u16,
-		}
-	}
-}
-
-impl Into<trace::RewardType> for RewardKind {
-	fn into(self) -> trace::RewardType {
-		match self {
-			RewardKind::Author => trace::RewardType::Block,
-			RewardKind::Uncle(_) => trace::RewardType::Uncle,
-			RewardKind::EmptyStep => trace::RewardType::EmptyStep,
-			RewardKind::External => trace::RewardType::External,
-		}
+fn reward_type(reward_kind: RewardKind) -> trace::RewardType {
+	match reward_kind {
+		RewardKind::Author => trace::RewardType::Block,
+		RewardKind::Uncle(_) => trace::RewardType::Uncle,
+		RewardKind::EmptyStep => trace::RewardType::EmptyStep,
+		RewardKind::External => trace::RewardType::External,
 	}
 }
 
@@ -136,7 +102,7 @@ impl BlockRewardContract {
 }
 
 /// Applies the given block rewards, i.e. adds the given balance to each beneficiary' address.
-/// If tracing is enabled the operations are recorded.
+/// The rewards are recorded on the block, and if tracing is enabled the operations are traced.
 pub fn apply_block_rewards(
 	rewards: &[(Address, RewardKind, U256)],
 	block: &mut ExecutedBlock,
@@ -145,12 +111,13 @@ pub fn apply_block_rewards(
 	for &(ref author, _, ref block_reward) in rewards {
 		machine.add_balance(block, author, block_reward)?;
 	}
+	block.rewards.extend(rewards.iter().map(|&(address, kind, amount)| BlockReward { address, kind, amount }));
 
 	if let Tracing::Enabled(ref mut traces) = *block.traces_mut() {
 		let mut tracer = ExecutiveTracer::default();
 
 		for &(address, reward_kind, amount) in rewards {
-			tracer.trace_reward(address, amount, reward_kind.into());
+			tracer.trace_reward(address, amount, reward_type(reward_kind));
 		}
 
 		traces.push(tracer.drain().into());
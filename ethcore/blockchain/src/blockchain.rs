@@ -27,6 +27,7 @@ use common_types::{
 	BlockNumber,
 	blockchain_info::BlockChainInfo,
 	block::{BlockInfo, BlockLocation, BranchBecomingCanonChainData},
+	block_reward::BlockReward,
 	encoded,
 	engines::ForkChoice,
 	engines::epoch::{Transition as EpochTransition, PendingTransition as PendingEpochTransition},
@@ -40,7 +41,7 @@ use common_types::{
 	views::{BlockView, HeaderView},
 };
 use ethcore_db::cache_manager::CacheManager;
-use ethcore_db::keys::{BlockReceipts, BlockDetails, TransactionAddress, EPOCH_KEY_PREFIX, EpochTransitions};
+use ethcore_db::keys::{BlockReceipts, BlockRewards, BlockDetails, TransactionAddress, EPOCH_KEY_PREFIX, EpochTransitions};
 use ethcore_db::{self as db, Writable, Readable, CacheUpdatePolicy};
 use ethereum_types::{H256, Bloom, BloomRef, U256};
 use util_mem::{MallocSizeOf, allocators::new_malloc_size_ops};
@@ -142,10 +143,13 @@ pub trait BlockProvider {
 	/// Get the block body (uncles and transactions).
 	fn block_body(&self, hash: &H256) -> Option<encoded::Body>;
 
+	/// Number of the first block whose header carries a base fee.
+	fn eip1559_transition(&self) -> BlockNumber;
+
 	/// Get a list of uncles for a given block.
 	/// Returns None if block does not exist.
 	fn uncles(&self, hash: &H256) -> Option<Vec<Header>> {
-		self.block_body(hash).map(|body| body.uncles())
+		self.block_body(hash).map(|body| body.uncles(self.eip1559_transition()))
 	}
 
 	/// Get a list of uncle hashes for a given block.
@@ -247,6 +251,10 @@ pub struct BlockChain {
 	pending_block_hashes: RwLock<HashMap<BlockNumber, H256>>,
 	pending_block_details: RwLock<HashMap<H256, BlockDetails>>,
 	pending_transaction_addresses: RwLock<HashMap<H256, Option<TransactionAddress>>>,
+
+	eip1559_transition: BlockNumber,
+	// Number of the genesis block, which is only non-zero for chains forked from another one.
+	genesis_number: BlockNumber,
 }
 
 impl BlockProvider for BlockChain {
@@ -260,6 +268,14 @@ impl BlockProvider for BlockChain {
 		self.first_block.clone()
 	}
 
+	fn eip1559_transition(&self) -> BlockNumber {
+		self.eip1559_transition
+	}
+
+	fn genesis_hash(&self) -> H256 {
+		self.block_hash(self.genesis_number).expect("Genesis hash should always exist")
+	}
+
 	fn best_ancient_block(&self) -> Option<H256> {
 		self.best_ancient_block.read().as_ref().map(|b| b.hash)
 	}
@@ -480,7 +496,7 @@ impl<'a> Iterator for AncestryWithMetadataIter<'a> {
 		} else {
 			let details = self.chain.block_details(&self.current);
 			let header = self.chain.block_header_data(&self.current)
-				.map(|h| h.decode().expect("Stored block header data is valid RLP; qed"));
+				.map(|h| h.decode(self.chain.eip1559_transition).expect("Stored block header data is valid RLP; qed"));
 
 			match (details, header) {
 				(Some(details), Some(header)) => {
@@ -570,6 +586,8 @@ impl BlockChain {
 			pending_block_hashes: RwLock::new(HashMap::new()),
 			pending_block_details: RwLock::new(HashMap::new()),
 			pending_transaction_addresses: RwLock::new(HashMap::new()),
+			eip1559_transition: config.eip1559_transition,
+			genesis_number: view!(BlockView, genesis).header_view().number(),
 		};
 
 		// load best block
@@ -619,7 +637,7 @@ impl BlockChain {
 			let mut best_block = bc.best_block.write();
 			*best_block = BestBlock {
 				total_difficulty: best_block_total_difficulty,
-				header: best_block_rlp.decode_header(),
+				header: best_block_rlp.decode_header(bc.eip1559_transition),
 				block: best_block_rlp,
 			};
 		}
@@ -633,9 +651,9 @@ impl BlockChain {
 				.expect("Low level database error when fetching 'best ancient' block. Some issue with disk?")
 				.map(|h| H256::from_slice(&h));
 			let best_ancient_number;
-			if best_ancient.is_none() && best_block_number > 1 && bc.block_hash(1).is_none() {
+			if best_ancient.is_none() && best_block_number > bc.genesis_number + 1 && bc.block_hash(bc.genesis_number + 1).is_none() {
 				best_ancient = Some(bc.genesis_hash());
-				best_ancient_number = Some(0);
+				best_ancient_number = Some(bc.genesis_number);
 			} else {
 				best_ancient_number = best_ancient.as_ref().and_then(|h| bc.block_number(h));
 			}
@@ -899,7 +917,7 @@ impl BlockChain {
 		let mut best_block = self.best_block.write();
 		*best_block = BestBlock {
 			total_difficulty: best_block_total_difficulty,
-			header: best_block_rlp.decode_header(),
+			header: best_block_rlp.decode_header(self.eip1559_transition),
 			block: best_block_rlp,
 		};
 	}
@@ -1073,6 +1091,16 @@ impl BlockChain {
 		batch.write(db::COL_EXTRA, &hash, &t);
 	}
 
+	/// Write the rewards paid when closing a block.
+	pub fn insert_block_rewards(&self, batch: &mut DBTransaction, hash: H256, rewards: Vec<BlockReward>) {
+		batch.write(db::COL_EXTRA, &hash, &BlockRewards { rewards });
+	}
+
+	/// Get the rewards paid when closing a block, if they were recorded when it was imported.
+	pub fn block_rewards(&self, hash: &H256) -> Option<Vec<BlockReward>> {
+		self.db.key_value().read(db::COL_EXTRA, hash).map(|r: BlockRewards| r.rewards)
+	}
+
 	/// Get a pending epoch transition by block hash.
 	// TODO: implement removal safely: this can only be done upon finality of a block
 	// that _uses_ the pending transition.
@@ -1231,7 +1259,7 @@ impl BlockChain {
 				batch.put(db::COL_EXTRA, b"best", update.info.hash.as_bytes());
 				*best_block = Some(BestBlock {
 					total_difficulty: update.info.total_difficulty,
-					header: update.block.decode_header(),
+					header: update.block.decode_header(self.eip1559_transition),
 					block: update.block,
 				});
 			}
@@ -2191,6 +2219,7 @@ mod tests {
 			outcome: TransactionOutcome::StateRoot(H256::zero()),
 			gas_used: 10_000.into(),
 			log_bloom: Default::default(),
+			tx_type: Default::default(),
 			logs: vec![
 				LogEntry { address: Default::default(), topics: vec![], data: vec![1], },
 				LogEntry { address: Default::default(), topics: vec![], data: vec![2], },
@@ -2200,6 +2229,7 @@ mod tests {
 			outcome: TransactionOutcome::StateRoot(H256::zero()),
 			gas_used: 10_000.into(),
 			log_bloom: Default::default(),
+			tx_type: Default::default(),
 			logs: vec![
 				LogEntry { address: Default::default(), topics: vec![], data: vec![3], },
 			],
@@ -2209,6 +2239,7 @@ mod tests {
 				outcome: TransactionOutcome::StateRoot(H256::zero()),
 				gas_used: 10_000.into(),
 				log_bloom: Default::default(),
+				tx_type: Default::default(),
 				logs: vec![
 					LogEntry { address: Default::default(), topics: vec![], data: vec![4], },
 				],
@@ -2219,6 +2250,7 @@ mod tests {
 				outcome: TransactionOutcome::StateRoot(H256::zero()),
 				gas_used: 10_000.into(),
 				log_bloom: Default::default(),
+				tx_type: Default::default(),
 				logs: vec![
 					LogEntry { address: Default::default(), topics: vec![], data: vec![5], },
 				],
@@ -192,7 +192,7 @@ impl BlockBuilder {
 			let metadata = get_metadata();
 			let block_number = parent_number + 1;
 			let transactions = metadata.transactions;
-			let transactions_root = ordered_trie_root(transactions.iter().map(rlp::encode));
+			let transactions_root = ordered_trie_root(transactions.iter().map(|t| t.encode()));
 
 			block.header.set_parent_hash(parent_hash);
 			block.header.set_number(block_number);
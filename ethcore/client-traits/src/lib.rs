@@ -26,6 +26,7 @@ use call_contract::CallContract;
 use registrar::RegistrarClient;
 use common_types::{
 	basic_account::BasicAccount,
+	block_reward::{BlockReward, RewardKind},
 	block_status::BlockStatus,
 	blockchain_info::BlockChainInfo,
 	BlockNumber,
@@ -54,6 +55,8 @@ use stats;
 use trace::{
 	FlatTrace,
 	localized::LocalizedTrace,
+	StructLog,
+	StructLoggerConfig,
 	VMTrace,
 };
 use common_types::{
@@ -135,6 +138,9 @@ pub trait BlockInfo: Send + Sync {
 
 	/// Get address code hash at given block's state.
 	fn code_hash(&self, address: &Address, id: BlockId) -> Option<H256>;
+
+	/// Number of the first block whose header carries a base fee.
+	fn eip1559_transition(&self) -> BlockNumber;
 }
 
 /// Provides various information on a transaction by it's ID
@@ -172,6 +178,10 @@ pub trait EngineClient: Sync + Send + ChainInfo {
 	/// The block corresponding the the parent hash must be stored already.
 	fn epoch_transition_for(&self, parent_hash: H256) -> Option<EpochTransition>;
 
+	/// Check a block that isn't sealed yet, like a consensus proposal, against its parent and by
+	/// executing it, as it would be checked on import.
+	fn verify_unsealed_block(&self, block: Unverified) -> EthcoreResult<()>;
+
 	/// Attempt to cast the engine client to a full client.
 	fn as_full_client(&self) -> Option<&dyn BlockChainClient>;
 
@@ -180,6 +190,12 @@ pub trait EngineClient: Sync + Send + ChainInfo {
 
 	/// Get raw block header data by block id.
 	fn block_header(&self, id: BlockId) -> Option<encoded::Header>;
+
+	/// Get the data the engine stored in the database under `key`, if any.
+	fn engine_data(&self, _key: &[u8]) -> Option<Bytes> { None }
+
+	/// Store engine data in the database under `key`, replacing any previous value.
+	fn set_engine_data(&self, _key: &[u8], _value: &[u8]) {}
 }
 
 /// Provides methods to import block into blockchain
@@ -326,6 +342,12 @@ pub trait BlockChainClient:
 	/// Replays all the transactions in a given block for inspection.
 	fn replay_block_transactions(&self, block: BlockId, analytics: CallAnalytics) -> Result<Box<dyn Iterator<Item = (H256, Executed<FlatTrace, VMTrace>)>>, CallError>;
 
+	/// Replays a given transaction, recording Geth-style struct logs.
+	fn replay_with_struct_logger(&self, t: TransactionId, config: StructLoggerConfig) -> Result<Executed<FlatTrace, Vec<StructLog>>, CallError>;
+
+	/// Replays all the transactions in a given block, recording Geth-style struct logs.
+	fn replay_block_transactions_with_struct_logger(&self, block: BlockId, config: StructLoggerConfig) -> Result<Box<dyn Iterator<Item = (H256, Executed<FlatTrace, Vec<StructLog>>)>>, CallError>;
+
 	/// Returns traces matching given filter.
 	fn filter_traces(&self, filter: TraceFilter) -> Option<Vec<LocalizedTrace>>;
 
@@ -338,12 +360,22 @@ pub trait BlockChainClient:
 	/// Returns traces created by transaction from block.
 	fn block_traces(&self, trace: BlockId) -> Option<Vec<LocalizedTrace>>;
 
+	/// Returns the rewards paid when closing the given block, if they were recorded on import.
+	fn block_rewards(&self, id: BlockId) -> Option<Vec<BlockReward>>;
+
+	/// Calls `reward` of the block reward contract at `contract` for `beneficiaries`, on top of
+	/// the state of the given block, and returns the rewards it would pay. Nothing is applied.
+	fn dry_run_block_rewards(&self, id: BlockId, contract: Address, beneficiaries: Vec<(Address, RewardKind)>) -> Result<Vec<BlockReward>, CallError>;
+
 	/// Get last hashes starting from best block.
 	fn last_hashes(&self) -> LastHashes;
 
 	/// List all ready transactions that should be propagated to other peers.
 	fn transactions_to_propagate(&self) -> Vec<Arc<VerifiedTransaction>>;
 
+	/// Get the transaction with the given hash from the transaction queue.
+	fn queued_transaction(&self, hash: &H256) -> Option<Arc<VerifiedTransaction>>;
+
 	/// Sorted list of transaction gas prices from at least last sample_size blocks.
 	fn gas_price_corpus(&self, sample_size: usize) -> stats::Corpus<U256> {
 		let mut h = self.chain_info().best_block_hash;
@@ -524,6 +556,11 @@ pub trait ChainNotify: Send + Sync {
 		// does nothing by default
 	}
 
+	/// fires when the engine marks blocks as finalized, in the order they were finalized.
+	fn blocks_finalized(&self, _finalized: &[H256]) {
+		// does nothing by default
+	}
+
 	/// fires when chain achieves active mode
 	fn start(&self) {
 		// does nothing by default
@@ -557,6 +594,7 @@ pub trait ImportExportBlocks {
 	/// destination could be a file or stdout.
 	/// If the format is hex, each block is written on a new line.
 	/// For binary exports, all block data is written to the same line.
+	/// For binary exports with receipts, each block is written as an RLP list of the block and its receipts.
 	fn export_blocks<'a>(
 		&self,
 		destination: Box<dyn std::io::Write + 'a>,
@@ -570,6 +608,8 @@ pub trait ImportExportBlocks {
 	/// For hex format imports, it attempts to read the blocks on a line by line basis.
 	/// For binary format imports, reads the 8 byte RLP header in order to decode the block
 	/// length to be read.
+	/// Blocks imported with their receipts up to the best block are inserted as ancient blocks,
+	/// without being executed.
 	fn import_blocks<'a>(
 		&self,
 		source: Box<dyn std::io::Read + 'a>,
@@ -20,6 +20,7 @@ use std::io::Write;
 use std::convert::AsRef;
 
 use common_types::BlockNumber;
+use common_types::block_reward::BlockReward;
 use common_types::engines::epoch::Transition as EpochTransition;
 use common_types::receipt::Receipt;
 use ethereum_types::{H256, H264, U256};
@@ -45,6 +46,8 @@ pub enum ExtrasIndex {
 	EpochTransitions = 5,
 	/// Pending epoch transition data index.
 	PendingEpochTransition = 6,
+	/// Block rewards index.
+	BlockRewards = 7,
 }
 
 fn with_index(hash: &H256, i: ExtrasIndex) -> H264 {
@@ -101,6 +104,14 @@ impl Key<BlockReceipts> for H256 {
 	}
 }
 
+impl Key<BlockRewards> for H256 {
+	type Target = H264;
+
+	fn key(&self) -> H264 {
+		with_index(self, ExtrasIndex::BlockRewards)
+	}
+}
+
 impl Key<common_types::engines::epoch::PendingTransition> for H256 {
 	type Target = H264;
 
@@ -218,6 +229,13 @@ impl BlockReceipts {
 	}
 }
 
+/// Contains all rewards paid when closing a block.
+#[derive(Debug, Clone, RlpEncodableWrapper, RlpDecodableWrapper)]
+pub struct BlockRewards {
+	/// Block rewards
+	pub rewards: Vec<BlockReward>,
+}
+
 /// Candidate transitions to an epoch with specific number.
 #[derive(Clone, RlpEncodable, RlpDecodable)]
 pub struct EpochTransitions {
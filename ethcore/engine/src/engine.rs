@@ -16,6 +16,7 @@
 
 //! Consensus engine specification and basic implementations.
 
+use std::path::Path;
 use std::sync::{Weak, Arc};
 use std::collections::BTreeMap;
 
@@ -25,7 +26,7 @@ use common_types::{
 	ancestry_action::AncestryAction,
 	header::{Header, ExtendedHeader},
 	engines::{
-		Seal, SealingState, Headers, PendingTransitionStore,
+		Seal, SealingState, Headers, PendingTransitionStore, ValidatorStats, RandomnessRound,
 		params::CommonParams,
 		machine as machine_types,
 		machine::{AuxiliaryData, AuxiliaryRequest},
@@ -37,11 +38,13 @@ use common_types::{
 use client_traits::EngineClient;
 
 use ethereum_types::{H256, U256, Address};
+use keccak_hash::keccak;
 use parity_crypto::publickey::Signature;
 use machine::{
 	Machine,
 	executed_block::ExecutedBlock,
 };
+use stats::PrometheusRegistry;
 use vm::{EnvInfo, Schedule, ActionType, ActionValue};
 
 use crate::signer::EngineSigner;
@@ -163,6 +166,10 @@ pub trait Engine: Sync + Send {
 	/// Optional maximum gas limit.
 	fn maximum_gas_limit(&self) -> Option<U256> { None }
 
+	/// Block numbers at which rules specific to the engine change, such as block rewards or
+	/// difficulty. They are hard forks in addition to the transitions of the common parameters.
+	fn hard_forks(&self) -> Vec<BlockNumber> { Vec::new() }
+
 	/// Block transformation functions, before the transactions.
 	/// `epoch_begin` set to true if this block kicks off an epoch.
 	fn on_new_block(
@@ -314,26 +321,65 @@ pub trait Engine: Sync + Send {
 	/// Sign using the EngineSigner, to be used for consensus tx signing.
 	fn sign(&self, _hash: H256) -> Result<Signature, Error> { unimplemented!() }
 
+	/// Sign the Keccak-256 hash of `data` using the EngineSigner, see `EngineSigner::sign_data`.
+	fn sign_data(&self, data: &[u8]) -> Result<Signature, Error> {
+		self.sign(keccak(data))
+	}
+
+	/// Whether the engine signer has to decrypt messages and provide its public key, e.g. to
+	/// take part in an on-chain randomness protocol.
+	fn requires_signer_secret(&self) -> bool { false }
+
+	/// Whether the engine only ever signs through `sign_data`, so that signers which can't sign a
+	/// bare hash, like remote signing services, are enough.
+	fn signs_data_only(&self) -> bool { false }
+
 	/// Add Client which can be used for sealing, potentially querying the state and sending messages.
 	fn register_client(&self, _client: Weak<dyn EngineClient>) {}
 
 	/// Trigger next step of the consensus engine.
 	fn step(&self) {}
 
-	/// Snapshot mode for the engine: Unsupported, PoW or PoA
+	/// Snapshot mode for the engine: Unsupported, PoW, PoA or Clique
 	fn snapshot_mode(&self) -> Snapshotting { Snapshotting::Unsupported }
 
-	/// Return a new open block header timestamp based on the parent timestamp.
-	fn open_block_header_timestamp(&self, parent_timestamp: u64) -> u64 {
+	/// Verify the consecutive headers restored from a snapshot, oldest first. Unlike
+	/// `verify_block_family` this can't rely on the client's chain, which is still being
+	/// restored. Engines keeping consensus state outside of the state trie rebuild it here.
+	fn verify_snapshot_headers(&self, _headers: &[Header]) -> Result<(), Error> { Ok(()) }
+
+	/// Add engine-specific metrics, e.g. the consensus step, to a Prometheus scrape.
+	fn prometheus_metrics(&self, _registry: &mut PrometheusRegistry) {}
+
+	/// Validator liveness statistics for the blocks `from..=to` of the canonical chain.
+	/// If `from` is `None` the engine picks the start of the range, e.g. the beginning of the
+	/// validator set epoch containing `to`.
+	fn validator_stats(&self, _from: Option<BlockNumber>, _to: BlockNumber) -> Result<ValidatorStats, Error> {
+		Err(EngineError::Custom("Validator statistics are not supported by this engine".into()).into())
+	}
+
+	/// Our validator's participation in the rounds of on-chain randomness generation that are
+	/// still kept by the engine, oldest first.
+	fn randomness_rounds(&self) -> Result<Vec<RandomnessRound>, Error> {
+		Err(EngineError::Custom("Randomness rounds are not supported by this engine".into()).into())
+	}
+
+	/// Set the directory for data the engine keeps outside of the database, e.g. secrets the
+	/// validator needs to remember across restarts. Fails if data the engine can't safely run
+	/// without could not be opened.
+	fn set_data_dir(&self, _dir: &Path) -> Result<(), Error> { Ok(()) }
+
+	/// Return a new open block header timestamp based on the parent header.
+	fn open_block_header_timestamp(&self, parent: &Header) -> u64 {
 		use std::{time, cmp};
 
 		let now = time::SystemTime::now().duration_since(time::UNIX_EPOCH).unwrap_or_default();
-		cmp::max(now.as_secs() as u64, parent_timestamp + 1)
+		cmp::max(now.as_secs() as u64, parent.timestamp() + 1)
 	}
 
-	/// Check whether the parent timestamp is valid.
-	fn is_timestamp_valid(&self, header_timestamp: u64, parent_timestamp: u64) -> bool {
-		header_timestamp > parent_timestamp
+	/// Check whether the header timestamp is valid with respect to the parent header.
+	fn is_timestamp_valid(&self, header: &Header, parent: &Header) -> bool {
+		header.timestamp() > parent.timestamp()
 	}
 
 	/// Gather all ancestry actions. Called at the last stage when a block is committed. The Engine must guarantee that
@@ -17,6 +17,7 @@
 //! A signer used by Engines which need to sign messages.
 
 use ethereum_types::{H256, Address};
+use keccak_hash::keccak;
 use parity_crypto::publickey::{ecies, Public, Signature, KeyPair, Error};
 
 /// Everything that an Engine needs to sign messages.
@@ -24,6 +25,23 @@ pub trait EngineSigner: Send + Sync {
 	/// Sign a consensus message hash.
 	fn sign(&self, hash: H256) -> Result<Signature, Error>;
 
+	/// Sign the seal hash of a block issued at the given consensus step. Signers protecting
+	/// against slashing refuse to sign a different block for a step they already signed.
+	fn sign_seal(&self, _step: u64, hash: H256) -> Result<Signature, Error> {
+		self.sign(hash)
+	}
+
+	/// Sign the Keccak-256 hash of `data`. Signers backed by a service which hashes what it
+	/// signs are given `data` itself rather than its hash.
+	fn sign_data(&self, data: &[u8]) -> Result<Signature, Error> {
+		self.sign(keccak(data))
+	}
+
+	/// Like `sign_seal`, for the seal hash `keccak(data)`.
+	fn sign_seal_data(&self, step: u64, data: &[u8]) -> Result<Signature, Error> {
+		self.sign_seal(step, keccak(data))
+	}
+
 	/// Signing address
 	fn address(&self) -> Address;
 
@@ -0,0 +1,206 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Parity Ethereum.
+
+// Parity Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Equivocation detection and proofs.
+//!
+//! A validator equivocates if it seals two different blocks for the same step. The two sealed
+//! headers are a self-contained proof of the misbehaviour: anyone can check the signatures
+//! without access to the chain, e.g. a validator set contract slashing the validator.
+
+use std::collections::BTreeMap;
+
+use common_types::{
+	BlockNumber,
+	errors::{BlockError, EngineError, EthcoreError as Error},
+	header::Header,
+};
+use ethereum_types::Address;
+use log::trace;
+use rlp::{DecoderError, Encodable, Rlp, RlpStream};
+use unexpected::Mismatch;
+
+use super::{header_empty_steps_raw, header_expected_seal_fields, header_seal_hash, header_signature, header_step};
+
+/// Two different headers sealed by the same validator for the same step.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EquivocationProof {
+	/// The header seen first.
+	pub first: Header,
+	/// The conflicting header.
+	pub second: Header,
+}
+
+impl EquivocationProof {
+	/// Verify the proof, returning the address of the equivocating validator.
+	pub fn verify(&self, empty_steps_transition: u64) -> Result<Address, Error> {
+		if self.first.hash() == self.second.hash() {
+			Err(EngineError::InsufficientProof("equivocation proof of identical headers".into()))?;
+		}
+
+		let first_signer = header_signer(&self.first, empty_steps_transition)?;
+		let second_signer = header_signer(&self.second, empty_steps_transition)?;
+		if first_signer != second_signer {
+			Err(EngineError::InsufficientProof(
+				format!("equivocation proof of headers by different signers: {} and {}", first_signer, second_signer)))?;
+		}
+
+		let first_step = header_step(&self.first, empty_steps_transition)?;
+		let second_step = header_step(&self.second, empty_steps_transition)?;
+		if first_step != second_step {
+			Err(EngineError::InsufficientProof(
+				format!("equivocation proof of headers at different steps: {} and {}", first_step, second_step)))?;
+		}
+
+		Ok(first_signer)
+	}
+
+	/// Decode a proof of a chain whose headers carry a base fee from `eip1559_transition` on.
+	pub fn decode_rlp(rlp: &Rlp, eip1559_transition: BlockNumber) -> Result<Self, DecoderError> {
+		if rlp.item_count()? != 2 {
+			return Err(DecoderError::RlpIncorrectListLen);
+		}
+		Ok(EquivocationProof {
+			first: Header::decode_rlp(&rlp.at(0)?, eip1559_transition)?,
+			second: Header::decode_rlp(&rlp.at(1)?, eip1559_transition)?,
+		})
+	}
+}
+
+impl Encodable for EquivocationProof {
+	fn rlp_append(&self, s: &mut RlpStream) {
+		s.begin_list(2)
+			.append(&self.first)
+			.append(&self.second);
+	}
+}
+
+/// Verify an RLP-encoded equivocation proof, as submitted to the validator set with
+/// `report_malicious`. Returns the address of the equivocating validator.
+pub fn verify_equivocation_proof(
+	proof: &[u8],
+	empty_steps_transition: u64,
+	eip1559_transition: BlockNumber,
+) -> Result<Address, Error> {
+	let proof = EquivocationProof::decode_rlp(&Rlp::new(proof), eip1559_transition)?;
+	proof.verify(empty_steps_transition)
+}
+
+/// The validator which sealed the header, recovered from the seal signature.
+pub(crate) fn header_signer(header: &Header, empty_steps_transition: u64) -> Result<Address, Error> {
+	let expected_seal_fields = header_expected_seal_fields(header, empty_steps_transition);
+	if header.seal().len() != expected_seal_fields {
+		Err(BlockError::InvalidSealArity(Mismatch { expected: expected_seal_fields, found: header.seal().len() }))?;
+	}
+
+	let signature = header_signature(header, empty_steps_transition)?;
+	let empty_steps_rlp = if header.number() >= empty_steps_transition {
+		Some(header_empty_steps_raw(header))
+	} else {
+		None
+	};
+	let public = parity_crypto::publickey::recover(&signature, &header_seal_hash(header, empty_steps_rlp))?;
+	Ok(parity_crypto::publickey::public_to_address(&public))
+}
+
+/// Remembers the most recent headers of each validator by step, to detect equivocations.
+#[derive(Default)]
+pub struct EquivocationDetector {
+	headers: BTreeMap<(u64, Address), Header>,
+}
+
+impl EquivocationDetector {
+	/// Record a header sealed at `step`. Returns a proof if its author already sealed a
+	/// different header at the same step.
+	pub fn insert(&mut self, step: u64, header: &Header) -> Option<EquivocationProof> {
+		let key = (step, *header.author());
+		match self.headers.get(&key) {
+			Some(first) if first.hash() != header.hash() => {
+				trace!(target: "engine", "Validator {} sealed blocks {} and {} at step {}",
+					header.author(), first.hash(), header.hash(), step);
+				Some(EquivocationProof { first: first.clone(), second: header.clone() })
+			}
+			Some(_) => None,
+			None => {
+				self.headers.insert(key, header.clone());
+				None
+			}
+		}
+	}
+
+	/// Forget the headers of the steps before `oldest_step`.
+	pub fn prune(&mut self, oldest_step: u64) {
+		self.headers = self.headers.split_off(&(oldest_step, Address::zero()));
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use common_types::header::Header;
+	use ethereum_types::{H520, U256};
+	use parity_crypto::publickey::{Generator, KeyPair, Random, sign};
+	use rlp::encode;
+
+	use super::{EquivocationDetector, EquivocationProof, verify_equivocation_proof};
+
+	fn sealed_header(key: &KeyPair, step: u64, gas_limit: u64) -> Header {
+		let mut header = Header::default();
+		header.set_number(1);
+		header.set_author(key.address());
+		header.set_gas_limit(U256::from(gas_limit));
+		let signature: H520 = sign(key.secret(), &header.bare_hash()).unwrap().into();
+		header.set_seal(vec![encode(&step), encode(&signature)]);
+		header
+	}
+
+	#[test]
+	fn detects_different_headers_at_the_same_step() {
+		let key = Random.generate().unwrap();
+		let mut detector = EquivocationDetector::default();
+
+		let first = sealed_header(&key, 5, 1000);
+		assert!(detector.insert(5, &first).is_none());
+		assert!(detector.insert(5, &first).is_none());
+		assert!(detector.insert(6, &sealed_header(&key, 6, 1001)).is_none());
+
+		let second = sealed_header(&key, 5, 1001);
+		let proof = detector.insert(5, &second).unwrap();
+		assert_eq!(proof, EquivocationProof { first, second });
+		assert_eq!(verify_equivocation_proof(&encode(&proof), u64::max_value(), u64::max_value()).unwrap(), key.address());
+
+		// pruned steps are forgotten.
+		detector.prune(6);
+		assert!(detector.insert(5, &sealed_header(&key, 5, 1002)).is_none());
+	}
+
+	#[test]
+	fn rejects_invalid_proofs() {
+		let key = Random.generate().unwrap();
+		let other = Random.generate().unwrap();
+		let verify = |first, second| EquivocationProof { first, second }.verify(u64::max_value());
+
+		assert!(verify(sealed_header(&key, 5, 1000), sealed_header(&key, 5, 1000)).is_err());
+		assert!(verify(sealed_header(&key, 5, 1000), sealed_header(&key, 6, 1001)).is_err());
+		assert!(verify(sealed_header(&key, 5, 1000), sealed_header(&other, 5, 1001)).is_err());
+
+		// re-attributing a header to another validator invalidates its signature.
+		let mut forged = sealed_header(&other, 5, 1001);
+		forged.set_author(key.address());
+		assert!(verify(sealed_header(&key, 5, 1000), forged).is_err());
+
+		assert!(verify(sealed_header(&key, 5, 1000), sealed_header(&key, 5, 1001)).is_ok());
+		assert!(verify_equivocation_proof(&[0xc0], u64::max_value(), u64::max_value()).is_err());
+	}
+}
@@ -0,0 +1,385 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Parity Ethereum.
+
+// Parity Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Explicit finality votes and certificates.
+//!
+//! After importing a block, every validator signs and broadcasts a vote for it. Once a quorum
+//! of the current validator set voted for the same block, the votes form a finality
+//! certificate: the block and all its ancestors are final, regardless of how many blocks were
+//! built on top of it. Certificates are compact finality proofs for light clients.
+
+use std::collections::BTreeMap;
+
+use common_types::{BlockNumber, header::Header};
+use ethereum_types::{H256, H520, Address};
+use keccak_hash::keccak;
+use log::trace;
+use parity_crypto::publickey::{self, Error};
+use rlp::{Decodable, DecoderError, Encodable, Rlp, RlpStream};
+use validator_set::SimpleList;
+
+/// Domain separator of the signed vote messages, so that a vote signature can never be
+/// mistaken for the signature of a block or an empty step.
+const VOTE_DOMAIN: &[u8] = b"aura finality vote";
+
+/// Maximum number of blocks for which votes are collected at a time.
+const MAX_PENDING_BLOCKS: usize = 128;
+
+/// The hash signed by a validator voting for the given block.
+pub fn vote_message(number: BlockNumber, block_hash: &H256) -> H256 {
+	keccak(vote_message_data(number, block_hash))
+}
+
+/// The data whose hash is signed by a validator voting for the given block.
+pub fn vote_message_data(number: BlockNumber, block_hash: &H256) -> Vec<u8> {
+	let mut s = RlpStream::new_list(3);
+	s.append(&VOTE_DOMAIN).append(&number).append(block_hash);
+	s.out()
+}
+
+/// Whether `signers` distinct validators out of `validators` are a quorum at block `number`.
+fn is_quorum(signers: usize, validators: usize, number: BlockNumber, two_thirds_majority_transition: BlockNumber) -> bool {
+	if number < two_thirds_majority_transition {
+		signers * 2 > validators
+	} else {
+		signers * 3 > validators * 2
+	}
+}
+
+/// A validator's vote for a block, broadcast as a consensus message.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FinalityVote {
+	/// Signature of the vote message by the validator.
+	pub signature: H520,
+	/// Number of the block voted for.
+	pub number: BlockNumber,
+	/// Hash of the block voted for.
+	pub block_hash: H256,
+}
+
+impl FinalityVote {
+	/// Recover the address of the validator which signed the vote.
+	pub fn author(&self) -> Result<Address, Error> {
+		let message = vote_message(self.number, &self.block_hash);
+		let public = publickey::recover(&self.signature.into(), &message)?;
+		Ok(publickey::public_to_address(&public))
+	}
+}
+
+// Votes are lists of three items, empty step messages of two.
+impl Encodable for FinalityVote {
+	fn rlp_append(&self, s: &mut RlpStream) {
+		s.begin_list(3)
+			.append(&self.signature)
+			.append(&self.number)
+			.append(&self.block_hash);
+	}
+}
+
+impl Decodable for FinalityVote {
+	fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+		if rlp.item_count()? != 3 {
+			return Err(DecoderError::RlpIncorrectListLen);
+		}
+		Ok(FinalityVote {
+			signature: rlp.val_at(0)?,
+			number: rlp.val_at(1)?,
+			block_hash: rlp.val_at(2)?,
+		})
+	}
+}
+
+/// Votes of a quorum of validators for a block.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FinalityCertificate {
+	/// Number of the finalized block.
+	pub number: BlockNumber,
+	/// Hash of the finalized block.
+	pub block_hash: H256,
+	/// Signatures of the vote message by the validators.
+	pub signatures: Vec<H520>,
+}
+
+impl FinalityCertificate {
+	/// Returns `true` if the certificate is signed by a quorum of distinct members of `validators`.
+	pub fn verify(&self, validators: &SimpleList, two_thirds_majority_transition: BlockNumber) -> bool {
+		let message = vote_message(self.number, &self.block_hash);
+		let mut signers = Vec::with_capacity(self.signatures.len());
+		for signature in &self.signatures {
+			let signer = match publickey::recover(&(*signature).into(), &message) {
+				Ok(public) => publickey::public_to_address(&public),
+				Err(_) => return false,
+			};
+			if !validators.contains(&signer) || signers.contains(&signer) {
+				trace!(target: "finality", "Certificate of block {} has an invalid signer {}", self.block_hash, signer);
+				return false;
+			}
+			signers.push(signer);
+		}
+		is_quorum(signers.len(), validators.len(), self.number, two_thirds_majority_transition)
+	}
+}
+
+impl Encodable for FinalityCertificate {
+	fn rlp_append(&self, s: &mut RlpStream) {
+		s.begin_list(3)
+			.append(&self.number)
+			.append(&self.block_hash)
+			.append_list(&self.signatures);
+	}
+}
+
+impl Decodable for FinalityCertificate {
+	fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+		if rlp.item_count()? != 3 {
+			return Err(DecoderError::RlpIncorrectListLen);
+		}
+		Ok(FinalityCertificate {
+			number: rlp.val_at(0)?,
+			block_hash: rlp.val_at(1)?,
+			signatures: rlp.list_at(2)?,
+		})
+	}
+}
+
+/// Encode a finality proof made of a certificate and the headers from the proven block up to
+/// the certified one.
+pub fn encode_certified_proof(certificate: &FinalityCertificate, headers: &[Header]) -> Vec<u8> {
+	let mut s = RlpStream::new_list(2);
+	s.append(certificate).append_list(headers);
+	s.out()
+}
+
+/// Decode a finality proof made by `encode_certified_proof`. Returns `None` for any other
+/// proof, e.g. a plain list of headers.
+pub fn decode_certified_proof(proof: &[u8], eip1559_transition: BlockNumber) -> Option<(FinalityCertificate, Vec<Header>)> {
+	let rlp = Rlp::new(proof);
+	// a header has more than three fields, so a list of headers never matches.
+	if rlp.item_count().ok()? != 2 || rlp.at(0).ok()?.item_count().ok()? != 3 {
+		return None;
+	}
+	Some((rlp.val_at(0).ok()?, Header::decode_rlp_list(&rlp.at(1).ok()?, eip1559_transition).ok()?))
+}
+
+/// Collects votes until a quorum of validators voted for the same block.
+#[derive(Default)]
+pub struct FinalityVotes {
+	/// Signatures of the votes by block number and hash, and by validator.
+	votes: BTreeMap<(BlockNumber, H256), BTreeMap<Address, H520>>,
+	/// Number of the most recently certified block.
+	last_certified: Option<BlockNumber>,
+}
+
+impl FinalityVotes {
+	/// Add a vote by `signer`. Votes by non-members of `validators`, votes for blocks at or
+	/// below the last certified one and votes for blocks more than `MAX_PENDING_BLOCKS` away
+	/// from the child of the best block `best_number` are ignored. Returns the certificate of
+	/// the block once it has a quorum.
+	pub fn insert(
+		&mut self,
+		vote: FinalityVote,
+		signer: Address,
+		validators: &SimpleList,
+		two_thirds_majority_transition: BlockNumber,
+		best_number: BlockNumber,
+	) -> Option<FinalityCertificate> {
+		if !validators.contains(&signer) {
+			trace!(target: "finality", "Ignoring finality vote by non-validator {}", signer);
+			return None;
+		}
+		if self.last_certified.map_or(false, |number| vote.number <= number) {
+			return None;
+		}
+		// the block being imported is the child of the best block.
+		let highest = best_number.saturating_add(1);
+		let lowest = highest.saturating_sub(MAX_PENDING_BLOCKS as BlockNumber - 1);
+		if vote.number < lowest || vote.number > highest {
+			trace!(target: "finality", "Ignoring finality vote for block #{} outside of #{}..=#{}", vote.number, lowest, highest);
+			return None;
+		}
+		// votes for blocks that fell out of the range can't be completed anymore.
+		self.votes = self.votes.split_off(&(lowest, H256::zero()));
+
+		let key = (vote.number, vote.block_hash);
+		let signers = {
+			let signatures = self.votes.entry(key).or_insert_with(BTreeMap::new);
+			signatures.insert(signer, vote.signature);
+			signatures.keys().filter(|a| validators.contains(a)).count()
+		};
+
+		if !is_quorum(signers, validators.len(), vote.number, two_thirds_majority_transition) {
+			// all votes are in range, so too many of them means forks: keep the most recent blocks.
+			while self.votes.len() > MAX_PENDING_BLOCKS {
+				let oldest = *self.votes.keys().next().expect("there are more than zero entries; qed");
+				self.votes.remove(&oldest);
+			}
+			return None;
+		}
+
+		let signatures = self.votes.remove(&key).expect("inserted above; qed");
+		// votes for the certified block's ancestors are not needed anymore.
+		self.votes = match vote.number.checked_add(1) {
+			Some(next) => self.votes.split_off(&(next, H256::zero())),
+			None => BTreeMap::new(),
+		};
+		self.last_certified = Some(vote.number);
+
+		Some(FinalityCertificate {
+			number: vote.number,
+			block_hash: vote.block_hash,
+			signatures: signatures.into_iter()
+				.filter(|(signer, _)| validators.contains(signer))
+				.map(|(_, signature)| signature)
+				.collect(),
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use common_types::{BlockNumber, header::Header};
+	use ethereum_types::{H256, Address};
+	use parity_crypto::publickey::{Generator, KeyPair, Random, sign};
+	use rlp::{self, Rlp};
+	use validator_set::SimpleList;
+
+	use super::{
+		FinalityCertificate, FinalityVote, FinalityVotes, MAX_PENDING_BLOCKS, decode_certified_proof,
+		encode_certified_proof, vote_message,
+	};
+
+	fn vote(key: &KeyPair, number: u64, block_hash: H256) -> FinalityVote {
+		let signature = sign(key.secret(), &vote_message(number, &block_hash)).unwrap().into();
+		FinalityVote { signature, number, block_hash }
+	}
+
+	fn validators(keys: &[KeyPair]) -> SimpleList {
+		SimpleList::new(keys.iter().map(KeyPair::address).collect())
+	}
+
+	#[test]
+	fn vote_rlp_roundtrip_and_author() {
+		let key = Random.generate().unwrap();
+		let vote = vote(&key, 5, H256::random());
+		let encoded = rlp::encode(&vote);
+
+		assert_eq!(Rlp::new(&encoded).item_count().unwrap(), 3);
+		let decoded: FinalityVote = rlp::decode(&encoded).unwrap();
+		assert_eq!(decoded, vote);
+		assert_eq!(decoded.author().unwrap(), key.address());
+	}
+
+	#[test]
+	fn certifies_block_at_quorum() {
+		let keys: Vec<_> = (0..4).map(|_| Random.generate().unwrap()).collect();
+		let validators = validators(&keys);
+		let hash = H256::random();
+		let mut votes = FinalityVotes::default();
+
+		// 2/3 majority: three out of four validators.
+		for key in &keys[..2] {
+			assert!(votes.insert(vote(key, 1, hash), key.address(), &validators, 0, 1).is_none());
+		}
+		// a repeated vote doesn't count twice.
+		assert!(votes.insert(vote(&keys[1], 1, hash), keys[1].address(), &validators, 0, 1).is_none());
+		// a vote by a non-validator is ignored.
+		let outsider = Random.generate().unwrap();
+		assert!(votes.insert(vote(&outsider, 1, hash), outsider.address(), &validators, 0, 1).is_none());
+
+		let certificate = votes.insert(vote(&keys[2], 1, hash), keys[2].address(), &validators, 0, 1).unwrap();
+		assert_eq!(certificate.number, 1);
+		assert_eq!(certificate.block_hash, hash);
+		assert_eq!(certificate.signatures.len(), 3);
+		assert!(certificate.verify(&validators, 0));
+		// a simple majority is enough before the transition.
+		assert!(certificate.verify(&validators, 2));
+
+		// blocks at or below the certified one can't be certified anymore.
+		assert!(votes.insert(vote(&keys[3], 1, hash), keys[3].address(), &validators, 0, 1).is_none());
+	}
+
+	#[test]
+	fn keeps_votes_near_the_best_block() {
+		let keys: Vec<_> = (0..4).map(|_| Random.generate().unwrap()).collect();
+		let validators = validators(&keys);
+		let mut votes = FinalityVotes::default();
+		let insert = |votes: &mut FinalityVotes, key: &KeyPair, number, hash, best| {
+			votes.insert(vote(key, number, hash), key.address(), &validators, 0, best)
+		};
+
+		// votes for blocks far above the best one can't evict the pending ones.
+		let hash = H256::random();
+		for key in &keys[..2] {
+			assert!(insert(&mut votes, key, 300, hash, 299).is_none());
+		}
+		for number in 302..(302 + MAX_PENDING_BLOCKS as u64) {
+			assert!(insert(&mut votes, &keys[0], number, H256::random(), 299).is_none());
+		}
+		assert!(insert(&mut votes, &keys[2], 300, hash, 299).is_some());
+
+		// votes for blocks too far below the best one are ignored.
+		let hash = H256::random();
+		let best = 400 + MAX_PENDING_BLOCKS as u64;
+		for key in &keys[..3] {
+			assert!(insert(&mut votes, key, 400, hash, best).is_none());
+		}
+
+		// no overflow at the highest block number.
+		let (hash, best) = (H256::random(), BlockNumber::max_value());
+		for key in &keys[..2] {
+			assert!(insert(&mut votes, key, best, hash, best).is_none());
+		}
+		assert!(insert(&mut votes, &keys[2], best, hash, best).is_some());
+	}
+
+	#[test]
+	fn rejects_certificates_without_quorum() {
+		let keys: Vec<_> = (0..4).map(|_| Random.generate().unwrap()).collect();
+		let validators = validators(&keys);
+		let hash = H256::random();
+		let outsider = Random.generate().unwrap();
+		let sign_with = |keys: &[&KeyPair]| FinalityCertificate {
+			number: 1,
+			block_hash: hash,
+			signatures: keys.iter().map(|key| vote(key, 1, hash).signature).collect(),
+		};
+
+		assert!(!sign_with(&[&keys[0], &keys[1]]).verify(&validators, 0));
+		// duplicated signatures.
+		assert!(!sign_with(&[&keys[0], &keys[0], &keys[1]]).verify(&validators, 0));
+		// a signature by a non-validator.
+		assert!(!sign_with(&[&keys[0], &keys[1], &outsider]).verify(&validators, 0));
+		assert!(sign_with(&[&keys[0], &keys[1], &keys[2]]).verify(&validators, 0));
+		assert!(!sign_with(&[&keys[0], &keys[1], &keys[2]]).verify(&SimpleList::new(vec![Address::random()]), 0));
+	}
+
+	#[test]
+	fn certified_proof_roundtrip() {
+		let key = Random.generate().unwrap();
+		let mut header = Header::default();
+		header.set_number(3);
+		let certificate = FinalityCertificate {
+			number: 3,
+			block_hash: header.hash(),
+			signatures: vec![vote(&key, 3, header.hash()).signature],
+		};
+
+		let proof = encode_certified_proof(&certificate, &[header.clone()]);
+		assert_eq!(decode_certified_proof(&proof, BlockNumber::max_value()), Some((certificate, vec![header.clone()])));
+		// a plain list of headers is not a certified proof.
+		assert!(decode_certified_proof(&rlp::encode_list(&[header.clone(), header]), BlockNumber::max_value()).is_none());
+	}
+}
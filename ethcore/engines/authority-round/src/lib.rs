@@ -31,10 +31,11 @@
 //! * "Malicious" reports are made only if the sender misbehaved deliberately (or due to a
 //!   software bug), e.g. if they proposed multiple blocks with the same step number.
 
-use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashSet, VecDeque};
 use std::{cmp, fmt};
 use std::iter::{self, FromIterator};
 use std::ops::Deref;
+use std::path::Path;
 use std::sync::atomic::{AtomicU64, AtomicBool, Ordering as AtomicOrdering};
 use std::sync::{Weak, Arc};
 use std::time::{UNIX_EPOCH, Duration};
@@ -73,6 +74,9 @@ use common_types::{
 		PendingTransitionStore,
 		Seal,
 		SealingState,
+		RandomnessRound,
+		ValidatorLiveness,
+		ValidatorStats,
 		machine::{Call, AuxiliaryData},
 	},
 	errors::{BlockError, EthcoreError as Error, EngineError},
@@ -80,14 +84,31 @@ use common_types::{
 	snapshot::Snapshotting,
 	transaction::SignedTransaction,
 };
+use stats::PrometheusRegistry;
 use unexpected::{Mismatch, OutOfBounds};
 use validator_set::{ValidatorSet, SimpleList, new_validator_set_posdao};
 
+mod equivocation;
 mod finality;
+mod finality_votes;
 mod randomness;
+mod randomness_journal;
+mod slashing_protection;
+mod step_duration;
 pub(crate) mod util;
 
+pub use self::equivocation::{EquivocationProof, verify_equivocation_proof};
+pub use self::slashing_protection::SlashingProtection;
+
+use self::equivocation::{EquivocationDetector, header_signer};
 use self::finality::RollingFinality;
+use self::randomness_journal::RandomnessJournal;
+use self::slashing_protection::Signed;
+use self::finality_votes::{
+	FinalityCertificate, FinalityVote, FinalityVotes, decode_certified_proof, encode_certified_proof,
+	vote_message_data,
+	vote_message,
+};
 
 /// `AuthorityRound` params.
 pub struct AuthorityRoundParams {
@@ -120,6 +141,9 @@ pub struct AuthorityRoundParams {
 	pub empty_steps_transition: u64,
 	/// First block for which a 2/3 quorum (instead of 1/2) is required.
 	pub two_thirds_majority_transition: BlockNumber,
+	/// First block for which validators broadcast signed finality votes. Votes are only counted
+	/// without immediate transitions, as they are checked against the epoch's validator set.
+	pub finality_votes_transition: BlockNumber,
 	/// Number of accepted empty steps.
 	pub maximum_empty_steps: usize,
 	/// Transition block to strict empty steps validation.
@@ -129,6 +153,9 @@ pub struct AuthorityRoundParams {
 	/// The addresses of contracts that determine the block gas limit with their associated block
 	/// numbers.
 	pub block_gas_limit_contract_transitions: BTreeMap<u64, Address>,
+	/// The addresses of contracts that schedule step duration changes with their associated block
+	/// numbers. They are read at epoch transitions, so they require non-immediate transitions.
+	pub step_duration_contract_transitions: BTreeMap<u64, Address>,
 	/// If set, this is the block number at which the consensus engine switches from AuRa to AuRa
 	/// with POSDAO modifications.
 	pub posdao_transition: Option<BlockNumber>,
@@ -139,6 +166,27 @@ const U16_MAX: usize = ::std::u16::MAX as usize;
 /// The number of recent block hashes for which the gas limit override is memoized.
 const GAS_LIMIT_OVERRIDE_CACHE_CAPACITY: usize = 10;
 
+/// The number of epochs for which the step durations are memoized.
+const EPOCH_STEP_DURATIONS_CACHE_CAPACITY: usize = 16;
+
+/// The number of blocks for which the validator liveness contribution is memoized.
+const LIVENESS_CACHE_CAPACITY: usize = 10_000;
+
+/// The maximum number of blocks covered by a single `validator_stats` query.
+const MAX_VALIDATOR_STATS_RANGE: u64 = 10_000;
+
+/// The number of recent finality certificates kept, by block hash.
+const FINALITY_CERTIFICATES_CACHE_CAPACITY: usize = 128;
+
+/// Blocks more than this number of steps old are not voted for, e.g. while syncing.
+const MAX_FINALITY_VOTE_AGE: u64 = 2;
+
+/// The number of finality votes for blocks that are not imported yet kept until they are.
+const MAX_PENDING_FINALITY_VOTES: usize = 1024;
+
+/// Prefix of the database keys of finality certificates, followed by the block hash.
+const FINALITY_CERTIFICATE_KEY_PREFIX: &[u8] = b"aura_finality_certificate";
+
 impl From<ethjson::spec::AuthorityRoundParams> for AuthorityRoundParams {
 	fn from(p: ethjson::spec::AuthorityRoundParams) -> Self {
 		let map_step_duration = |u: ethjson::uint::Uint| {
@@ -197,6 +245,12 @@ impl From<ethjson::spec::AuthorityRoundParams> for AuthorityRoundParams {
 			.into_iter()
 			.map(|(block_num, address)| (block_num.into(), address.into()))
 			.collect();
+		let step_duration_contract_transitions: BTreeMap<_, _> =
+			p.step_duration_contract_transitions
+			.unwrap_or_default()
+			.into_iter()
+			.map(|(block_num, address)| (block_num.into(), address.into()))
+			.collect();
 		AuthorityRoundParams {
 			step_durations,
 			validators: new_validator_set_posdao(p.validators, p.posdao_transition.map(Into::into)),
@@ -211,16 +265,18 @@ impl From<ethjson::spec::AuthorityRoundParams> for AuthorityRoundParams {
 			empty_steps_transition: p.empty_steps_transition.map_or(u64::max_value(), |n| ::std::cmp::max(n.into(), 1)),
 			maximum_empty_steps: p.maximum_empty_steps.map_or(0, Into::into),
 			two_thirds_majority_transition: p.two_thirds_majority_transition.map_or_else(BlockNumber::max_value, Into::into),
+			finality_votes_transition: p.finality_votes_transition.map_or_else(BlockNumber::max_value, Into::into),
 			strict_empty_steps_transition: p.strict_empty_steps_transition.map_or(0, Into::into),
 			randomness_contract_address,
 			block_gas_limit_contract_transitions,
+			step_duration_contract_transitions,
 			posdao_transition: p.posdao_transition.map(Into::into),
 		}
 	}
 }
 
 /// A triple containing the first step number and the starting timestamp of the given step duration.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 struct StepDurationInfo {
 	transition_step: u64,
 	transition_timestamp: u64,
@@ -232,8 +288,8 @@ struct StepDurationInfo {
 struct Step {
 	calibrate: bool, // whether calibration is enabled.
 	inner: AtomicU64,
-	/// Planned durations of steps.
-	durations: Vec<StepDurationInfo>,
+	/// Planned durations of steps. Replaced when a step duration contract schedules changes.
+	durations: RwLock<Vec<StepDurationInfo>>,
 }
 
 impl Step {
@@ -254,7 +310,7 @@ impl Step {
 	fn opt_duration_remaining(&self) -> Option<Duration> {
 		let next_step = self.load().checked_add(1)?;
 		let StepDurationInfo { transition_step, transition_timestamp, step_duration } =
-			self.durations.iter()
+			self.durations.read().iter()
 			.take_while(|info| info.transition_step < next_step)
 			.last()
 			.expect("durations cannot be empty")
@@ -291,7 +347,7 @@ impl Step {
 	fn opt_calibrate(&self) -> Option<()> {
 		let now = unix_now().as_secs();
 		let StepDurationInfo { transition_step, transition_timestamp, step_duration } =
-			self.durations.iter()
+			self.durations.read().iter()
 			.take_while(|info| info.transition_timestamp < now)
 			.last()
 			.expect("durations cannot be empty")
@@ -302,6 +358,12 @@ impl Step {
 		Some(())
 	}
 
+	/// Replaces the planned durations of steps, and recalibrates the step number.
+	fn set_durations(&self, durations: Vec<StepDurationInfo>) {
+		*self.durations.write() = durations;
+		self.calibrate();
+	}
+
 	fn check_future(&self, given: u64) -> Result<(), Option<OutOfBounds<u64>>> {
 		const REJECTED_STEP_DRIFT: u64 = 4;
 
@@ -319,7 +381,7 @@ impl Step {
 			Err(None)
 		// wait a bit for blocks in near future
 		} else if given > current {
-			let d = self.durations.iter().take_while(|info| info.transition_step <= current).last()
+			let d = self.durations.read().iter().take_while(|info| info.transition_step <= current).last()
 				.expect("Duration map has at least a 0 entry.")
 				.step_duration;
 			Err(Some(OutOfBounds {
@@ -595,18 +657,57 @@ pub struct AuthorityRound {
 	two_thirds_majority_transition: BlockNumber,
 	maximum_empty_steps: usize,
 	machine: Machine,
-	/// History of step hashes recently received from peers.
-	received_step_hashes: RwLock<BTreeMap<(u64, Address), H256>>,
+	/// Headers recently received from peers, by step and author.
+	equivocation_detector: Mutex<EquivocationDetector>,
 	/// If set, enables random number contract integration. It maps the transition block to the contract address.
 	randomness_contract_address: BTreeMap<u64, Address>,
 	/// The addresses of contracts that determine the block gas limit.
 	block_gas_limit_contract_transitions: BTreeMap<u64, Address>,
 	/// Memoized gas limit overrides, by block hash.
 	gas_limit_override_cache: Mutex<LruCache<H256, Option<U256>>>,
+	/// Step durations from the spec, by the timestamp they take effect at.
+	step_durations: BTreeMap<u64, u64>,
+	/// The addresses of contracts that schedule step duration changes, by starting block number.
+	step_duration_contract_transitions: BTreeMap<u64, Address>,
+	/// Step durations in force in each epoch, by the hash of the epoch transition block.
+	epoch_step_durations: Mutex<LruCache<H256, Arc<BTreeMap<u64, u64>>>>,
 	/// The block number at which the consensus engine switches from AuRa to AuRa with POSDAO
 	/// modifications. For details about POSDAO, see the whitepaper:
 	/// https://www.xdaichain.com/for-validators/posdao-whitepaper
 	posdao_transition: Option<BlockNumber>,
+	/// Number of skipped primaries reported to the validator set as benign misbehaviour.
+	skipped_steps_reported: AtomicU64,
+	/// Number of valid empty step messages collected, either received from peers or generated.
+	empty_steps_collected: AtomicU64,
+	/// Memoized validator liveness contributions, by block hash.
+	liveness_cache: Mutex<LruCache<H256, BlockLiveness>>,
+	/// First block for which validators broadcast signed finality votes.
+	finality_votes_transition: BlockNumber,
+	/// Finality votes received from validators or generated, until they form a certificate.
+	finality_votes: Mutex<FinalityVotes>,
+	/// Finality votes for blocks that are not imported yet, with their signers.
+	pending_finality_votes: Mutex<VecDeque<(FinalityVote, Address)>>,
+	/// Recent finality certificates, by block hash. All certificates are stored in the database.
+	finality_certificates: Mutex<LruCache<H256, FinalityCertificate>>,
+	/// Number of the last block we voted for.
+	last_finality_vote: Mutex<BlockNumber>,
+	/// Local journal of the numbers committed to the randomness contract, once the data
+	/// directory is set.
+	randomness_journal: Mutex<Option<RandomnessJournal>>,
+	/// Highest steps signed by the engine signer, once the data directory is set.
+	slashing_protection: Mutex<Option<SlashingProtection>>,
+}
+
+/// Validator liveness contribution of a single block.
+#[derive(Debug, Clone, Default)]
+struct BlockLiveness {
+	/// The block author.
+	author: Address,
+	/// Signers of the empty steps included in the block's seal.
+	empty_steps_signers: Vec<Address>,
+	/// Primaries of the steps between the parent and the block which produced neither a block nor
+	/// an empty step, with the number of such steps.
+	missed: BTreeMap<Address, u64>,
 }
 
 // header-chain validator.
@@ -616,6 +717,7 @@ struct EpochVerifier {
 	empty_steps_transition: u64,
 	/// First block for which a 2/3 quorum (instead of 1/2) is required.
 	two_thirds_majority_transition: BlockNumber,
+	eip1559_transition: BlockNumber,
 }
 
 impl engine::EpochVerifier for EpochVerifier {
@@ -628,11 +730,15 @@ impl engine::EpochVerifier for EpochVerifier {
 	}
 
 	fn check_finality_proof(&self, proof: &[u8]) -> Option<Vec<H256>> {
+		if let Some((certificate, headers)) = decode_certified_proof(proof, self.eip1559_transition) {
+			return self.check_certified_proof(&certificate, &headers);
+		}
+
 		let signers = self.subchain_validators.clone().into_inner();
 		let mut finality_checker = RollingFinality::blank(signers, self.two_thirds_majority_transition);
 		let mut finalized = Vec::new();
 
-		let headers: Vec<Header> = Rlp::new(proof).as_list().ok()?;
+		let headers = Header::decode_rlp_list(&Rlp::new(proof), self.eip1559_transition).ok()?;
 
 		{
 			let mut push_header = |parent_header: &Header, header: Option<&Header>| {
@@ -674,16 +780,39 @@ impl engine::EpochVerifier for EpochVerifier {
 	}
 }
 
+impl EpochVerifier {
+	// The headers must be consecutive and end with the certified block, which finalizes all of them.
+	fn check_certified_proof(&self, certificate: &FinalityCertificate, headers: &[Header]) -> Option<Vec<H256>> {
+		let last = headers.last()?;
+		if last.hash() != certificate.block_hash || last.number() != certificate.number {
+			return None
+		}
+		if headers.windows(2).any(|w| *w[1].parent_hash() != w[0].hash() || w[1].number() != w[0].number() + 1) {
+			return None
+		}
+		if !certificate.verify(&self.subchain_validators, self.two_thirds_majority_transition) {
+			return None
+		}
+		Some(headers.iter().map(Header::hash).collect())
+	}
+}
+
 fn header_seal_hash(header: &Header, empty_steps_rlp: Option<&[u8]>) -> H256 {
+	match empty_steps_rlp {
+		Some(_) => keccak(header_seal_data(header, empty_steps_rlp)),
+		None => header.bare_hash(),
+	}
+}
+
+// The data whose hash is signed in the seal of the header.
+fn header_seal_data(header: &Header, empty_steps_rlp: Option<&[u8]>) -> Vec<u8> {
 	match empty_steps_rlp {
 		Some(empty_steps_rlp) => {
 			let mut message = header.bare_hash().as_bytes().to_vec();
 			message.extend_from_slice(empty_steps_rlp);
-			keccak(message)
-		},
-		None => {
-			header.bare_hash()
+			message
 		},
+		None => header.bare_rlp(),
 	}
 }
 
@@ -796,6 +925,13 @@ fn verify_external(header: &Header, validators: &dyn ValidatorSet, empty_steps_t
 	}
 }
 
+// The database key of the finality certificate of the block with the given hash.
+fn finality_certificate_key(hash: &H256) -> Vec<u8> {
+	let mut key = FINALITY_CERTIFICATE_KEY_PREFIX.to_vec();
+	key.extend_from_slice(hash.as_bytes());
+	key
+}
+
 fn combine_proofs(signal_number: BlockNumber, set_proof: &[u8], finality_proof: &[u8]) -> Vec<u8> {
 	let mut stream = RlpStream::new_list(3);
 	stream.append(&signal_number).append(&set_proof).append(&finality_proof);
@@ -853,28 +989,16 @@ impl AuthorityRound {
 		let should_timeout = our_params.start_step.is_none();
 		let initial_step = our_params.start_step.unwrap_or(0);
 
-		let mut durations = Vec::new();
-		{
-			let mut dur_info = StepDurationInfo {
-				transition_step: 0u64,
-				transition_timestamp: 0u64,
-				step_duration: our_params.step_durations[&0],
-			};
-			durations.push(dur_info);
-			for (time, dur) in our_params.step_durations.iter().skip(1) {
-				let (step, time) = next_step_time_duration(dur_info, *time)
-					.ok_or(BlockError::TimestampOverflow)?;
-				dur_info.transition_step = step;
-				dur_info.transition_timestamp = time;
-				dur_info.step_duration = *dur;
-				durations.push(dur_info);
-			}
+		if our_params.immediate_transitions && !our_params.step_duration_contract_transitions.is_empty() {
+			warn!(target: "engine", "Step duration contracts are only read at epoch transitions, and are ignored with immediate transitions.");
 		}
+		let durations = step_duration_infos(&our_params.step_durations)
+			.ok_or(BlockError::TimestampOverflow)?;
 
 		let step = Step {
 			inner: AtomicU64::new(initial_step),
 			calibrate: our_params.start_step.is_none(),
-			durations,
+			durations: RwLock::new(durations),
 		};
 		step.calibrate();
 		let engine = Arc::new(
@@ -898,11 +1022,24 @@ impl AuthorityRound {
 				two_thirds_majority_transition: our_params.two_thirds_majority_transition,
 				strict_empty_steps_transition: our_params.strict_empty_steps_transition,
 				machine,
-				received_step_hashes: RwLock::new(Default::default()),
+				equivocation_detector: Mutex::new(EquivocationDetector::default()),
 				randomness_contract_address: our_params.randomness_contract_address,
 				block_gas_limit_contract_transitions: our_params.block_gas_limit_contract_transitions,
+				step_durations: our_params.step_durations,
+				step_duration_contract_transitions: our_params.step_duration_contract_transitions,
+				epoch_step_durations: Mutex::new(LruCache::new(EPOCH_STEP_DURATIONS_CACHE_CAPACITY)),
 				gas_limit_override_cache: Mutex::new(LruCache::new(GAS_LIMIT_OVERRIDE_CACHE_CAPACITY)),
 				posdao_transition: our_params.posdao_transition,
+				skipped_steps_reported: AtomicU64::new(0),
+				empty_steps_collected: AtomicU64::new(0),
+				liveness_cache: Mutex::new(LruCache::new(LIVENESS_CACHE_CAPACITY)),
+				finality_votes_transition: our_params.finality_votes_transition,
+				finality_votes: Mutex::new(FinalityVotes::default()),
+				pending_finality_votes: Mutex::new(VecDeque::new()),
+				finality_certificates: Mutex::new(LruCache::new(FINALITY_CERTIFICATES_CACHE_CAPACITY)),
+				last_finality_vote: Mutex::new(0),
+				randomness_journal: Mutex::new(None),
+				slashing_protection: Mutex::new(None),
 			});
 
 		// Do not initialize timeouts for tests.
@@ -922,16 +1059,95 @@ impl AuthorityRound {
 		Ok(if self.immediate_transitions {
 			(CowLike::Borrowed(&*self.validators), header.number())
 		} else {
-			let mut epoch_manager = self.epoch_manager.lock();
 			let client = self.upgrade_client_or("Unable to verify sig")?;
+			let (validators, epoch_hash, epoch_number) = {
+				let mut epoch_manager = self.epoch_manager.lock();
+				if !epoch_manager.zoom_to_after(&*client, &self.machine, &*self.validators, *header.parent_hash()) {
+					debug!(target: "engine", "Unable to zoom to epoch.");
+					return Err(EngineError::MissingParent(*header.parent_hash()).into())
+				}
+				(epoch_manager.validators().clone(), epoch_manager.epoch_transition_hash, epoch_manager.epoch_transition_number)
+			};
+			self.update_step_durations(&*client, *header.parent_hash(), epoch_hash, epoch_number)?;
 
-			if !epoch_manager.zoom_to_after(&*client, &self.machine, &*self.validators, *header.parent_hash()) {
-				debug!(target: "engine", "Unable to zoom to epoch.");
-				return Err(EngineError::MissingParent(*header.parent_hash()).into())
+			(CowLike::Owned(validators), epoch_number)
+		})
+	}
+
+	/// Returns the step durations in force in the epoch starting at the given transition block, by
+	/// the timestamp they take effect at. Changes scheduled by the step duration contract are only
+	/// accepted for timestamps after the transition block; the durations of earlier steps are the
+	/// ones of the previous epoch. Fails if the schedule of an epoch can't be read, since guessing
+	/// it would put the step timer out of line with the other validators.
+	fn epoch_step_durations(
+		&self,
+		client: &dyn EngineClient,
+		epoch_hash: H256,
+		epoch_number: BlockNumber,
+	) -> Result<Arc<BTreeMap<u64, u64>>, EngineError> {
+		let address = match self.step_duration_contract_transitions.range(..=epoch_number).last() {
+			Some((_, &address)) => address,
+			None => return Ok(Arc::new(self.step_durations.clone())),
+		};
+		if let Some(durations) = self.epoch_step_durations.lock().get_mut(&epoch_hash) {
+			return Ok(durations.clone());
+		}
+
+		let header = client.block_header(BlockId::Hash(epoch_hash)).ok_or_else(|| {
+			EngineError::Custom(format!("Epoch transition block #{} {} not found", epoch_number, epoch_hash))
+		})?;
+		let previous = match client.epoch_transition_for(header.parent_hash()) {
+			Some(ref transition) if epoch_number > 0 =>
+				self.epoch_step_durations(client, transition.block_hash, transition.block_number)?,
+			_ => Arc::new(self.step_durations.clone()),
+		};
+
+		let full_client = client.as_full_client().ok_or(EngineError::RequiresClient)?;
+		let scheduled = step_duration::scheduled_step_durations(full_client, epoch_hash, address).map_err(|err| {
+			EngineError::FailedSystemCall(format!("Failed to read the step duration contract at epoch #{}: {}", epoch_number, err))
+		})?;
+
+		let transition_timestamp = header.timestamp();
+		let mut durations = self.step_durations.clone();
+		durations.extend(previous.range(..=transition_timestamp));
+		for (timestamp, duration) in scheduled {
+			if timestamp > transition_timestamp {
+				durations.insert(timestamp, duration);
+			} else if durations.get(&timestamp) != Some(&duration) {
+				warn!(target: "engine", "Rejected step duration {} scheduled at past timestamp {} by the contract at epoch #{}.",
+					duration, timestamp, epoch_number);
 			}
+		}
+		// every validator rejects the same overflowing schedule, so keeping the previous one is safe.
+		if step_duration_infos(&durations).is_none() {
+			warn!(target: "engine", "Step durations scheduled by the contract at epoch #{} overflow.", epoch_number);
+			return Ok(previous);
+		}
 
-			(CowLike::Owned(epoch_manager.validators().clone()), epoch_manager.epoch_transition_number)
-		})
+		debug!(target: "engine", "Step durations at epoch #{}: {:?}", epoch_number, durations);
+		let durations = Arc::new(durations);
+		self.epoch_step_durations.lock().insert(epoch_hash, durations.clone());
+		Ok(durations)
+	}
+
+	/// Switches the step timer to the step durations of the given epoch, if the block with the given
+	/// parent is on top of the best block. Blocks of side forks and old blocks don't affect the steps.
+	fn update_step_durations(
+		&self,
+		client: &dyn EngineClient,
+		parent_hash: H256,
+		epoch_hash: H256,
+		epoch_number: BlockNumber,
+	) -> Result<(), EngineError> {
+		if self.step_duration_contract_transitions.is_empty() || client.chain_info().best_block_hash != parent_hash {
+			return Ok(());
+		}
+		let durations = step_duration_infos(&*self.epoch_step_durations(client, epoch_hash, epoch_number)?)
+			.expect("epoch step durations are checked for overflow; qed");
+		if *self.step.inner.durations.read() != durations {
+			self.step.inner.set_durations(durations);
+		}
+		Ok(())
 	}
 
 	fn empty_steps(&self, from_step: u64, to_step: u64, parent_hash: H256) -> Vec<EmptyStep> {
@@ -968,14 +1184,19 @@ impl AuthorityRound {
 	}
 
 	fn handle_empty_step_message(&self, empty_step: EmptyStep) {
-		self.empty_steps.lock().insert(empty_step);
+		if self.empty_steps.lock().insert(empty_step) {
+			self.empty_steps_collected.fetch_add(1, AtomicOrdering::Relaxed);
+		}
 	}
 
 	fn generate_empty_step(&self, parent_hash: &H256) {
 		let step = self.step.inner.load();
 		let empty_step_rlp = empty_step_rlp(step, parent_hash);
+		if !self.check_slashing_protection(Signed::EmptyStep, step) {
+			return;
+		}
 
-		if let Ok(signature) = self.sign(keccak(&empty_step_rlp)).map(Into::into) {
+		if let Ok(signature) = self.sign_data(&empty_step_rlp).map(Into::into) {
 			let message_rlp = empty_step_full_rlp(&signature, &empty_step_rlp);
 
 			let parent_hash = *parent_hash;
@@ -990,6 +1211,166 @@ impl AuthorityRound {
 		}
 	}
 
+	fn handle_finality_vote(&self, vote: FinalityVote, signer: Address) {
+		let header = self.upgrade_client_or(None).ok()
+			.and_then(|client| client.block_header(BlockId::Hash(vote.block_hash)));
+		match header {
+			Some(header) => {
+				if header.number() != vote.number {
+					trace!(target: "finality", "Ignoring finality vote with a wrong block number {:?}", vote);
+					return;
+				}
+				self.count_finality_vote(vote, signer, header.parent_hash());
+			}
+			None => {
+				// the block is counted against its epoch's validators once it is imported.
+				let mut pending = self.pending_finality_votes.lock();
+				if pending.len() >= MAX_PENDING_FINALITY_VOTES {
+					pending.pop_front();
+				}
+				pending.push_back((vote, signer));
+			}
+		}
+	}
+
+	// Count the votes received before the block with the given header was imported.
+	fn count_pending_finality_votes(&self, header: &Header) {
+		let block_hash = header.hash();
+		let votes: Vec<_> = {
+			let mut pending = self.pending_finality_votes.lock();
+			let (votes, rest) = pending.drain(..).partition(|(vote, _)| vote.block_hash == block_hash);
+			*pending = rest;
+			votes
+		};
+		for (vote, signer) in votes {
+			if vote.number == header.number() {
+				self.count_finality_vote(vote, signer, *header.parent_hash());
+			}
+		}
+	}
+
+	// Count a vote for the block with the given parent against the validators of the block's epoch.
+	fn count_finality_vote(&self, vote: FinalityVote, signer: Address, parent_hash: H256) {
+		let validators = match self.epoch_validators_after(parent_hash) {
+			Some(validators) => validators,
+			None => {
+				debug!(target: "finality", "Unable to get the validators of the block voted for by {:?}", vote);
+				return;
+			}
+		};
+		let best_number = match self.upgrade_client_or(None) {
+			Ok(client) => client.chain_info().best_block_number,
+			Err(_) => return,
+		};
+		let (number, block_hash) = (vote.number, vote.block_hash);
+		let certificate = self.finality_votes.lock()
+			.insert(vote, signer, &validators, self.two_thirds_majority_transition, best_number);
+
+		if let Some(certificate) = certificate {
+			debug!(target: "finality", "Block #{} ({}) has a finality certificate", number, block_hash);
+			if let Ok(client) = self.upgrade_client_or(None) {
+				client.set_engine_data(&finality_certificate_key(&block_hash), &encode(&certificate));
+			}
+			self.finality_certificates.lock().insert(block_hash, certificate);
+		}
+	}
+
+	// The validators of the epoch of the children of the block with the given hash.
+	fn epoch_validators_after(&self, hash: H256) -> Option<SimpleList> {
+		let client = self.upgrade_client_or(None).ok()?;
+		let transition = client.epoch_transition_for(hash)?;
+		{
+			let epoch_manager = self.epoch_manager.lock();
+			if epoch_manager.epoch_transition_hash == transition.block_hash {
+				return Some(epoch_manager.validators().clone());
+			}
+		}
+		let (signal_number, set_proof, _) = destructure_proofs(&transition.proof).ok()?;
+		self.validators.epoch_set(signal_number == 0, &self.machine, signal_number, set_proof)
+			.ok()
+			.map(|(list, _)| list)
+	}
+
+	// The finality certificate of the block with the given hash, from the cache or the database.
+	fn finality_certificate(&self, hash: &H256) -> Option<FinalityCertificate> {
+		if let Some(certificate) = self.finality_certificates.lock().get_mut(hash) {
+			return Some(certificate.clone());
+		}
+		let client = self.upgrade_client_or(None).ok()?;
+		let certificate: FinalityCertificate = client.engine_data(&finality_certificate_key(hash))
+			.and_then(|data| ::rlp::decode(&data).ok())?;
+		self.finality_certificates.lock().insert(*hash, certificate.clone());
+		Some(certificate)
+	}
+
+	/// Sign and broadcast a finality vote for a newly imported block, if we are a validator.
+	/// We vote at most once per block number, and never for a lower number than before.
+	fn generate_finality_vote(&self, header: &Header) {
+		let number = header.number();
+		if number < self.finality_votes_transition {
+			return;
+		}
+		let step = match header_step(header, self.empty_steps_transition) {
+			Ok(step) => step,
+			Err(_) => return,
+		};
+		if step + MAX_FINALITY_VOTE_AGE < self.step.inner.load() {
+			return;
+		}
+		let me = match self.address() {
+			Some(me) => me,
+			None => return,
+		};
+		if !self.epoch_manager.lock().validators().iter().any(|v| *v == me) {
+			return;
+		}
+
+		let mut last_vote = self.last_finality_vote.lock();
+		if number <= *last_vote {
+			return;
+		}
+
+		let block_hash = header.hash();
+		match self.sign_data(&vote_message_data(number, &block_hash)) {
+			Ok(signature) => {
+				*last_vote = number;
+				let vote = FinalityVote { signature: signature.into(), number, block_hash };
+				trace!(target: "finality", "broadcasting finality vote: {:?}", vote);
+				self.broadcast_message(::rlp::encode(&vote));
+				// the block isn't in the database yet, but its parent is.
+				self.count_finality_vote(vote, me, *header.parent_hash());
+			}
+			Err(_) => warn!(target: "finality", "generate_finality_vote: FAIL: accounts secret key unavailable"),
+		}
+	}
+
+	// Returns the hashes of the chain head and its unfinalized ancestors since the finality votes
+	// transition that are finalized by a certificate, oldest first.
+	fn certified_ancestry(&self, chain_head: &Header, ancestry: &mut dyn Iterator<Item=Header>) -> Vec<H256> {
+		let mut certified = false;
+		let mut finalized: Vec<_> = iter::once(chain_head.hash())
+			.chain(ancestry
+				.take_while(|header| header.number() >= self.finality_votes_transition)
+				.map(|header| header.hash()))
+			.filter(|hash| {
+				certified = certified || self.finality_certificate(hash).is_some();
+				certified
+			})
+			.collect();
+		finalized.reverse();
+		finalized
+	}
+
+	// Finality proof for the first of `headers` made of the certificate of the oldest
+	// certified one and the headers up to it.
+	fn certified_finality_proof(&self, headers: &[Header]) -> Option<Vec<u8>> {
+		let (position, certificate) = headers.iter()
+			.enumerate()
+			.filter_map(|(position, header)| self.finality_certificate(&header.hash()).map(|c| (position, c)))
+			.next()?;
+		Some(encode_certified_proof(&certificate, &headers[..=position]))
+	}
+
 	fn broadcast_message(&self, message: Vec<u8>) {
 		if let Ok(c) = self.upgrade_client_or(None) {
 			c.broadcast_consensus_message(message);
@@ -1026,6 +1407,7 @@ impl AuthorityRound {
 						header.number(), set_number, skipped_primary, me
 					);
 					self.validators.report_benign(&skipped_primary, set_number, header.number());
+					self.skipped_steps_reported.fetch_add(1, AtomicOrdering::Relaxed);
 				} else {
 					trace!(target: "engine", "Primary that skipped is self, not self-reporting. Own address: {}", me);
 				}
@@ -1033,6 +1415,47 @@ impl AuthorityRound {
 		}
 	}
 
+	// Computes the liveness contribution of a canonical block: its author, the signers of its empty
+	// steps and the primaries which missed their steps since the parent block.
+	fn block_liveness(&self, client: &dyn EngineClient, header: &Header) -> Result<BlockLiveness, Error> {
+		if let Some(liveness) = self.liveness_cache.lock().get_mut(&header.hash()) {
+			return Ok(liveness.clone());
+		}
+
+		let empty_steps = if header.number() >= self.empty_steps_transition {
+			header_empty_steps(header)?
+		} else {
+			Vec::new()
+		};
+
+		let mut liveness = BlockLiveness {
+			author: *header.author(),
+			..Default::default()
+		};
+		for empty_step in &empty_steps {
+			liveness.empty_steps_signers.push(empty_step.author()?);
+		}
+
+		// the step of the genesis block is arbitrary, so don't count the steps before block 1.
+		if header.number() > 1 {
+			let parent = client.block_header(BlockId::Hash(*header.parent_hash()))
+				.ok_or_else(|| EngineError::MissingParent(*header.parent_hash()))?
+				.decode(self.machine.params().eip1559_transition)?;
+			let step = header_step(header, self.empty_steps_transition)?;
+			let parent_step = header_step(&parent, self.empty_steps_transition)?;
+			let covered: HashSet<u64> = empty_steps.iter().map(|e| e.step).collect();
+			let (validators, _) = self.epoch_set(header)?;
+
+			for step in (parent_step + 1..step).filter(|s| !covered.contains(s)) {
+				let primary = step_proposer(&*validators, header.parent_hash(), step);
+				*liveness.missed.entry(primary).or_insert(0) += 1;
+			}
+		}
+
+		self.liveness_cache.lock().insert(header.hash(), liveness.clone());
+		Ok(liveness)
+	}
+
 	// Returns the hashes of all ancestor blocks that are finalized by the given `chain_head`.
 	fn build_finality(&self, chain_head: &Header, ancestry: &mut dyn Iterator<Item=Header>) -> Vec<H256> {
 		if self.immediate_transitions { return Vec::new() }
@@ -1117,9 +1540,15 @@ impl AuthorityRound {
 
 		// Random number generation
 		let contract = util::BoundContract::new(&*client, BlockId::Latest, contract_addr);
+		let mut journal = self.randomness_journal.lock();
+		if let Some(journal) = journal.as_mut() {
+			if let Err(err) = randomness::settle_journal(&contract, journal) {
+				warn!(target: "engine", "Failed to settle the randomness journal: {}", err);
+			}
+		}
 		let phase = randomness::RandomnessPhase::load(&contract, our_addr)
 			.map_err(|err| EngineError::Custom(format!("Randomness error in load(): {:?}", err)))?;
-		let data = match phase.advance(&contract, &mut OsRng, signer.as_ref())
+		let data = match phase.advance(&contract, &mut OsRng, signer.as_ref(), journal.as_mut())
 				.map_err(|err| EngineError::Custom(format!("Randomness error in advance(): {:?}", err)))? {
 			Some(data) => data,
 			None => return Ok(Vec::new()), // Nothing to commit or reveal at the moment.
@@ -1130,6 +1559,41 @@ impl AuthorityRound {
 		Ok(vec![full_client.create_transaction(tx_request)?])
 	}
 
+	/// Signs the seal of a block issued at the given step, the hash of `data`.
+	fn sign_seal(&self, step: u64, data: &[u8]) -> Result<Signature, Error> {
+		if !self.check_slashing_protection(Signed::Block, step) {
+			return Err(EngineError::Custom(format!("Refusing to sign a second block for step {}", step)).into());
+		}
+		Ok(self.signer.read()
+			.as_ref()
+			.ok_or(parity_crypto::publickey::Error::InvalidAddress)?
+			.sign_seal_data(step, data)?
+		)
+	}
+
+	/// Records that the engine signer is about to sign a message of `kind` for `step`, if the
+	/// slashing-protection database is open. Returns whether signing is safe.
+	fn check_slashing_protection(&self, kind: Signed, step: u64) -> bool {
+		let signer = match *self.signer.read() {
+			Some(ref signer) => signer.address(),
+			None => return true,
+		};
+		match *self.slashing_protection.lock() {
+			Some(ref mut db) => match db.record(signer, kind, step) {
+				Ok(true) => true,
+				Ok(false) => {
+					warn!(target: "engine", "Refusing to sign {:?} for step {}: {} signed for this step or a later one already.", kind, step, signer);
+					false
+				}
+				Err(err) => {
+					warn!(target: "engine", "Refusing to sign {:?} for step {}: failed to update the slashing-protection database: {}", kind, step, err);
+					false
+				}
+			},
+			None => true,
+		}
+	}
+
 	/// Returns the reference to the client, if registered.
 	fn upgrade_client_or<'a, T>(&self, opt_error_msg: T) -> Result<Arc<dyn EngineClient>, EngineError>
 		where T: Into<Option<&'a str>>,
@@ -1343,12 +1807,19 @@ impl Engine for AuthorityRound {
 		let validators = if self.immediate_transitions {
 			CowLike::Borrowed(&*self.validators)
 		} else {
-			let mut epoch_manager = self.epoch_manager.lock();
-			if !epoch_manager.zoom_to_after(&*client, &self.machine, &*self.validators, parent.hash()) {
-				debug!(target: "engine", "Not preparing block: Unable to zoom to epoch.");
+			let (validators, epoch_hash, epoch_number) = {
+				let mut epoch_manager = self.epoch_manager.lock();
+				if !epoch_manager.zoom_to_after(&*client, &self.machine, &*self.validators, parent.hash()) {
+					debug!(target: "engine", "Not preparing block: Unable to zoom to epoch.");
+					return SealingState::NotReady;
+				}
+				(epoch_manager.validators().clone(), epoch_manager.epoch_transition_hash, epoch_manager.epoch_transition_number)
+			};
+			if let Err(err) = self.update_step_durations(&*client, parent.hash(), epoch_hash, epoch_number) {
+				warn!(target: "engine", "Not preparing block: {}", err);
 				return SealingState::NotReady;
 			}
-			CowLike::Owned(epoch_manager.validators().clone())
+			CowLike::Owned(validators)
 		};
 
 		let step = self.step.inner.load();
@@ -1368,6 +1839,21 @@ impl Engine for AuthorityRound {
 		}
 
 		let rlp = Rlp::new(rlp);
+
+		// finality votes are lists of three items, empty steps of two.
+		if rlp.item_count().map_err(fmt_err)? == 3 {
+			let vote: FinalityVote = rlp.as_val().map_err(fmt_err)?;
+			if vote.number < self.finality_votes_transition {
+				trace!(target: "finality", "handle_message: finality vote before the transition {:?}", vote);
+				return Ok(());
+			}
+			match vote.author() {
+				Ok(signer) => self.handle_finality_vote(vote, signer),
+				Err(_) => trace!(target: "finality", "handle_message: received invalid finality vote {:?}", vote),
+			}
+			return Ok(());
+		}
+
 		let empty_step: EmptyStep = rlp.as_val().map_err(fmt_err)?;
 
 		if empty_step.verify(&*self.validators).unwrap_or(false) {
@@ -1459,7 +1945,7 @@ impl Engine for AuthorityRound {
 				None
 			};
 
-			if let Ok(signature) = self.sign(header_seal_hash(header, empty_steps_rlp.as_ref().map(|e| &**e))) {
+			if let Ok(signature) = self.sign_seal(step, &header_seal_data(header, empty_steps_rlp.as_ref().map(|e| &**e))) {
 				trace!(target: "engine", "generate_seal: Issuing a block for step {}.", step);
 
 				// only issue the seal if we were the first to reach the compare_and_swap.
@@ -1627,30 +2113,21 @@ impl Engine for AuthorityRound {
 			|| (header.number() >= self.validate_step_transition && step <= parent_step) {
 			warn!(target: "engine", "Multiple blocks proposed for step {}.", parent_step);
 
-			self.validators.report_malicious(header.author(), set_number, header.number(), Default::default());
+			// a seal that isn't the author's signature is no evidence against the author. The parent and
+			// the block are only an equivocation proof if sealed at the same step by the same validator.
+			let proof = EquivocationProof { first: parent.clone(), second: header.clone() };
+			match proof.verify(self.empty_steps_transition) {
+				Ok(signer) if signer == *header.author() => {
+					self.validators.report_malicious(header.author(), set_number, header.number(), encode(&proof));
+				},
+				_ if header_signer(header, self.empty_steps_transition).ok() == Some(*header.author()) => {
+					self.validators.report_malicious(header.author(), set_number, header.number(), Default::default());
+				},
+				_ => trace!(target: "engine", "Not reporting {}: block #{} isn't sealed by it.", header.author(), header.number()),
+			}
 			Err(EngineError::DoubleVote(*header.author()))?;
 		}
 
-		// Report malice if the validator produced other sibling blocks in the same step.
-		let received_step_key = (step, *header.author());
-		let new_hash = header.hash();
-		if self.received_step_hashes.read().get(&received_step_key).map_or(false, |h| *h != new_hash) {
-			trace!(target: "engine", "Validator {} produced sibling blocks in the same step", header.author());
-			self.validators.report_malicious(header.author(), set_number, header.number(), Default::default());
-		} else {
-			self.received_step_hashes.write().insert(received_step_key, new_hash);
-		}
-
-		// Remove hash records older than two full rounds of steps (picked as a reasonable trade-off between
-		// memory consumption and fault-tolerance).
-		let sibling_malice_detection_period = 2 * validators.count(&parent.hash()) as u64;
-		let oldest_step = parent_step.saturating_sub(sibling_malice_detection_period);
-		if oldest_step > 0 {
-			let mut rsh = self.received_step_hashes.write();
-			let new_rsh = rsh.split_off(&(oldest_step, Address::zero()));
-			*rsh = new_rsh;
-		}
-
 		// If empty step messages are enabled we will validate the messages in the seal, missing messages are not
 		// reported as there's no way to tell whether the empty step message was never sent or simply not included.
 		let empty_steps_len = if header.number() >= self.empty_steps_transition {
@@ -1744,6 +2221,22 @@ impl Engine for AuthorityRound {
 				// we can drop all accumulated empty step messages that are older than this header's step
 				let header_step = header_step(header, self.empty_steps_transition)?;
 				self.clear_empty_steps(header_step.into());
+
+				// Report malice if the validator produced other sibling blocks in the same step. Headers
+				// are only recorded once their seal is verified, so that forged ones can't be reported.
+				let equivocation = self.equivocation_detector.lock().insert(header_step, header);
+				if let Some(proof) = equivocation {
+					trace!(target: "engine", "Validator {} produced sibling blocks in the same step", header.author());
+					self.validators.report_malicious(header.author(), set_number, header.number(), encode(&proof));
+				}
+
+				// Remove header records older than two full rounds of steps (picked as a reasonable trade-off
+				// between memory consumption and fault-tolerance).
+				let sibling_malice_detection_period = 2 * validators.count(header.parent_hash()) as u64;
+				let oldest_step = header_step.saturating_sub(sibling_malice_detection_period);
+				if oldest_step > 0 {
+					self.equivocation_detector.lock().prune(oldest_step);
+				}
 			},
 			_ => {},
 		}
@@ -1855,7 +2348,9 @@ impl Engine for AuthorityRound {
 				finality_proof.push(finalized_header);
 				finality_proof.reverse();
 
-				let finality_proof = ::rlp::encode_list(&finality_proof);
+				// a finality certificate is a shorter proof than the signatures of the headers.
+				let finality_proof = self.certified_finality_proof(&finality_proof)
+					.unwrap_or_else(|| ::rlp::encode_list(&finality_proof));
 
 				self.epoch_manager.lock().note_new_epoch();
 
@@ -1888,6 +2383,7 @@ impl Engine for AuthorityRound {
 					subchain_validators: list,
 					empty_steps_transition: self.empty_steps_transition,
 					two_thirds_majority_transition: self.two_thirds_majority_transition,
+					eip1559_transition: self.machine.params().eip1559_transition,
 				});
 
 				match finalize {
@@ -1916,6 +2412,48 @@ impl Engine for AuthorityRound {
 		)
 	}
 
+	fn sign_data(&self, data: &[u8]) -> Result<Signature, Error> {
+		Ok(self.signer.read()
+			.as_ref()
+			.ok_or(parity_crypto::publickey::Error::InvalidAddress)?
+			.sign_data(data)?
+		)
+	}
+
+	fn signs_data_only(&self) -> bool {
+		// seals, empty steps, finality votes and engine transactions are all signed as data.
+		true
+	}
+
+	fn requires_signer_secret(&self) -> bool {
+		// the randomness protocol encrypts the committed numbers to the signer's key.
+		!self.randomness_contract_address.is_empty()
+	}
+
+	fn set_data_dir(&self, dir: &Path) -> Result<(), Error> {
+		// Signing without the slashing-protection database could equivocate after a restart.
+		let db = SlashingProtection::open(&dir.join("slashing_protection")).map_err(|err| {
+			EngineError::Custom(format!("Failed to open the slashing-protection database: {}", err))
+		})?;
+		*self.slashing_protection.lock() = Some(db);
+		if self.randomness_contract_address.is_empty() {
+			return Ok(());
+		}
+		// Without the journal, committed numbers can't be revealed after a restart.
+		let journal = RandomnessJournal::open(&dir.join("randomness")).map_err(|err| {
+			EngineError::Custom(format!("Failed to open the randomness journal: {}", err))
+		})?;
+		*self.randomness_journal.lock() = Some(journal);
+		Ok(())
+	}
+
+	fn randomness_rounds(&self) -> Result<Vec<RandomnessRound>, Error> {
+		match *self.randomness_journal.lock() {
+			Some(ref journal) => Ok(journal.rounds()),
+			None => Err(EngineError::Custom("The randomness journal is not enabled".into()).into()),
+		}
+	}
+
 	fn snapshot_mode(&self) -> Snapshotting {
 		if self.immediate_transitions {
 			Snapshotting::Unsupported
@@ -1924,11 +2462,91 @@ impl Engine for AuthorityRound {
 		}
 	}
 
-	fn ancestry_actions(&self, header: &Header, ancestry: &mut dyn Iterator<Item=ExtendedHeader>) -> Vec<AncestryAction> {
-		let finalized = self.build_finality(
-			header,
-			&mut ancestry.take_while(|e| !e.is_finalized).map(|e| e.header),
+	fn prometheus_metrics(&self, registry: &mut PrometheusRegistry) {
+		registry.register_gauge(
+			"aura_step",
+			"Current AuRa consensus step",
+			self.step.inner.load() as i64,
 		);
+		registry.register_gauge(
+			"aura_empty_steps_pending",
+			"Empty step messages waiting to be included in a block",
+			self.empty_steps.lock().len() as i64,
+		);
+		registry.register_counter(
+			"aura_empty_steps_collected",
+			"Empty step messages collected since startup",
+			self.empty_steps_collected.load(AtomicOrdering::Relaxed) as i64,
+		);
+		registry.register_counter(
+			"aura_skipped_steps_reported",
+			"Skipped primaries reported as benign misbehaviour since startup",
+			self.skipped_steps_reported.load(AtomicOrdering::Relaxed) as i64,
+		);
+	}
+
+	fn validator_stats(&self, from: Option<BlockNumber>, to: BlockNumber) -> Result<ValidatorStats, Error> {
+		let client = self.upgrade_client_or("Unable to compute validator stats")?;
+		let header_by_number = |number| -> Result<Header, Error> {
+			client.block_header(BlockId::Number(number))
+				.ok_or_else(|| EngineError::Custom(format!("Unknown block #{}", number)))?
+				.decode(self.machine.params().eip1559_transition)
+				.map_err(Into::into)
+		};
+
+		let from = match from {
+			Some(from) => from,
+			None => {
+				let (_, epoch_start) = self.epoch_set(&header_by_number(to)?)?;
+				cmp::max(epoch_start, to.saturating_sub(MAX_VALIDATOR_STATS_RANGE - 1))
+			}
+		};
+		if from > to {
+			return Err(EngineError::Custom(format!("Invalid block range: #{} is after #{}", from, to)).into());
+		}
+		if to - from >= MAX_VALIDATOR_STATS_RANGE {
+			return Err(EngineError::Custom(format!("Block range is limited to {} blocks", MAX_VALIDATOR_STATS_RANGE)).into());
+		}
+
+		let mut stats = ValidatorStats {
+			from_block: from,
+			to_block: to,
+			validators: BTreeMap::new(),
+		};
+		// the genesis block has no author.
+		for number in cmp::max(from, 1)..=to {
+			let liveness = self.block_liveness(&*client, &header_by_number(number)?)?;
+			stats.validators.entry(liveness.author).or_insert_with(ValidatorLiveness::default).produced_blocks += 1;
+			for signer in liveness.empty_steps_signers {
+				stats.validators.entry(signer).or_insert_with(ValidatorLiveness::default).empty_steps_signed += 1;
+			}
+			for (primary, missed) in liveness.missed {
+				stats.validators.entry(primary).or_insert_with(ValidatorLiveness::default).missed_steps += missed;
+			}
+		}
+
+		Ok(stats)
+	}
+
+	fn ancestry_actions(&self, header: &Header, ancestry: &mut dyn Iterator<Item=ExtendedHeader>) -> Vec<AncestryAction> {
+		let mut ancestry = ancestry.take_while(|e| !e.is_finalized).map(|e| e.header);
+		let finalized = if header.number() >= self.finality_votes_transition {
+			// both need the whole unfinalized ancestry.
+			let ancestry: Vec<_> = ancestry.collect();
+			let mut finalized = self.build_finality(header, &mut ancestry.iter().cloned());
+
+			self.generate_finality_vote(header);
+			self.count_pending_finality_votes(header);
+			// a certified block finalizes all of its ancestors.
+			for hash in self.certified_ancestry(header, &mut ancestry.into_iter()) {
+				if !finalized.contains(&hash) {
+					finalized.push(hash);
+				}
+			}
+			finalized
+		} else {
+			self.build_finality(header, &mut ancestry)
+		};
 
 		if !finalized.is_empty() {
 			debug!(target: "finality", "Finalizing blocks: {:?}", finalized);
@@ -1960,6 +2578,25 @@ impl Engine for AuthorityRound {
 	}
 }
 
+/// Plans the steps for the given step durations, by the timestamp they take effect at. The entry
+/// at `0` must be defined. Returns `None` on timestamp overflow.
+fn step_duration_infos(step_durations: &BTreeMap<u64, u64>) -> Option<Vec<StepDurationInfo>> {
+	let mut dur_info = StepDurationInfo {
+		transition_step: 0u64,
+		transition_timestamp: 0u64,
+		step_duration: step_durations[&0],
+	};
+	let mut durations = vec![dur_info];
+	for (time, dur) in step_durations.iter().skip(1) {
+		let (step, time) = next_step_time_duration(dur_info, *time)?;
+		dur_info.transition_step = step;
+		dur_info.transition_timestamp = time;
+		dur_info.step_duration = *dur;
+		durations.push(dur_info);
+	}
+	Some(durations)
+}
+
 /// A helper accumulator function mapping a step duration and a step duration transition timestamp
 /// to the corresponding step number and the correct starting second of the step.
 fn next_step_time_duration(info: StepDurationInfo, time: u64) -> Option<(u64, u64)>
@@ -1984,10 +2621,12 @@ mod tests {
 	use std::time::Duration;
 	use keccak_hash::keccak;
 	use accounts::AccountProvider;
+	use client_traits::ChainInfo;
 	use ethabi_contract::use_contract;
 	use ethereum_types::{Address, H520, H256, U256};
-	use parity_crypto::publickey::Signature;
+	use parity_crypto::publickey::{Generator, KeyPair, Random, Signature, sign};
 	use common_types::{
+		BlockNumber,
 		header::Header,
 		engines::{Seal, params::CommonParams},
 		ids::BlockId,
@@ -2010,10 +2649,12 @@ mod tests {
 	use validator_set::{TestSet, SimpleList};
 	use ethjson;
 	use serde_json;
+	use stats::PrometheusRegistry;
 
 	use super::{
-		AuthorityRoundParams, AuthorityRound, EmptyStep, SealedEmptyStep, StepDurationInfo,
+		AuthorityRoundParams, AuthorityRound, EmptyStep, EpochVerifier, SealedEmptyStep, StepDurationInfo,
 		calculate_score, util::BoundContract, next_step_time_duration,
+		finality_votes::{FinalityCertificate, FinalityVote, encode_certified_proof, vote_message},
 	};
 
 	fn build_aura<F>(f: F) -> Arc<AuthorityRound> where
@@ -2034,8 +2675,10 @@ mod tests {
 			block_reward_contract_transitions: Default::default(),
 			strict_empty_steps_transition: 0,
 			two_thirds_majority_transition: 0,
+			finality_votes_transition: u64::max_value(),
 			randomness_contract_address: BTreeMap::new(),
 			block_gas_limit_contract_transitions: BTreeMap::new(),
+			step_duration_contract_transitions: BTreeMap::new(),
 			posdao_transition: Some(0),
 		};
 
@@ -2305,14 +2948,14 @@ mod tests {
 
 	#[test]
 	fn reports_multiple_blocks_per_step() {
-		let tap = AccountProvider::transient_provider();
+		let tap = Arc::new(AccountProvider::transient_provider());
 		let addr0 = tap.insert_account(keccak("0").into(), &"0".into()).unwrap();
 		let addr1 = tap.insert_account(keccak("1").into(), &"1".into()).unwrap();
 
 		let validator_set = TestSet::from_validators(vec![addr0, addr1]);
 		let aura = build_aura(|p| p.validators = Box::new(validator_set.clone()));
 
-		aura.set_signer(Some(Box::new((Arc::new(tap), addr0, "0".into()))));
+		aura.set_signer(Some(Box::new((tap.clone(), addr1, "1".into()))));
 
 		let mut parent_header: Header = Header::default();
 		parent_header.set_number(2);
@@ -2322,17 +2965,41 @@ mod tests {
 		header.set_number(3);
 		header.set_difficulty(calculate_score(1, 2, 0));
 		header.set_gas_limit("222222".parse::<U256>().unwrap());
-		header.set_seal(vec![encode(&2usize)]);
-		header.set_author(addr1);
+		header.set_author(addr0);
+
+		let seal = |header: &mut Header, step: usize, signer: Address, password: &str| {
+			let signature = tap.sign(signer, Some(password.into()), header.bare_hash()).unwrap();
+			header.set_seal(vec![encode(&step), encode(&(&*signature as &[u8]))]);
+		};
 
 		// First sibling block.
+		seal(&mut header, 2, addr0, "0");
 		assert!(aura.verify_block_family(&header, &parent_header).is_ok());
+		assert!(aura.verify_block_external(&header).is_ok());
 		assert_eq!(validator_set.last_malicious(), 0);
 
-		// Second sibling block: should be reported.
+		// Second sibling block with a forged seal: rejected, but not reported.
 		header.set_gas_limit("222223".parse::<U256>().unwrap());
+		seal(&mut header, 2, addr1, "1");
+		assert!(aura.verify_block_family(&header, &parent_header).is_ok());
+		assert!(aura.verify_block_external(&header).is_err());
+		assert_eq!(validator_set.last_malicious(), 0);
+
+		// Second sibling block sealed by its author: should be reported.
+		seal(&mut header, 2, addr0, "0");
 		assert!(aura.verify_block_family(&header, &parent_header).is_ok());
+		assert!(aura.verify_block_external(&header).is_ok());
+		assert_eq!(validator_set.last_malicious(), 3);
+
+		// A block at the step of its parent is only reported if sealed by its author.
+		header.set_number(4);
+		header.set_difficulty(calculate_score(1, 1, 0));
+		seal(&mut header, 1, addr1, "1");
+		assert!(aura.verify_block_family(&header, &parent_header).is_err());
 		assert_eq!(validator_set.last_malicious(), 3);
+		seal(&mut header, 1, addr0, "0");
+		assert!(aura.verify_block_family(&header, &parent_header).is_err());
+		assert_eq!(validator_set.last_malicious(), 4);
 	}
 
 	#[test]
@@ -2353,11 +3020,11 @@ mod tests {
 		let step = Step {
 			calibrate: false,
 			inner: AtomicU64::new(::std::u64::MAX),
-			durations: [StepDurationInfo {
+			durations: RwLock::new([StepDurationInfo {
 				transition_step: 0,
 				transition_timestamp: 0,
 				step_duration: 1,
-			}].to_vec().into_iter().collect(),
+			}].to_vec()),
 		};
 		step.increment();
 	}
@@ -2369,11 +3036,11 @@ mod tests {
 		let step = Step {
 			calibrate: false,
 			inner: AtomicU64::new(::std::u64::MAX),
-			durations: [StepDurationInfo {
+			durations: RwLock::new([StepDurationInfo {
 				transition_step: 0,
 				transition_timestamp: 0,
 				step_duration: 1,
-			}].to_vec().into_iter().collect(),
+			}].to_vec()),
 		};
 		step.duration_remaining();
 	}
@@ -2403,11 +3070,11 @@ mod tests {
 		let step = Step {
 			calibrate: true,
 			inner: AtomicU64::new(::std::u64::MAX),
-			durations: [
+			durations: RwLock::new([
 				StepDurationInfo { transition_step: 0, transition_timestamp: 0, step_duration: 1 },
 				StepDurationInfo { transition_step: now, transition_timestamp: now, step_duration: 2 },
 				StepDurationInfo { transition_step: now + 1, transition_timestamp: now + 2, step_duration: 4 },
-			].to_vec().into_iter().collect(),
+			].to_vec()),
 		};
 		// calibrated step `now`
 		step.calibrate();
@@ -2424,6 +3091,58 @@ mod tests {
 		assert!(duration_remaining <= Duration::from_secs(4));
 	}
 
+	#[test]
+	fn test_set_step_durations() {
+		use super::{step_duration_infos, Step};
+
+		let step = Step {
+			calibrate: false,
+			inner: AtomicU64::new(20),
+			durations: RwLock::new(step_duration_infos(&[(0, 5)].to_vec().into_iter().collect()).unwrap()),
+		};
+		assert_eq!(step.durations.read().len(), 1);
+
+		// a change scheduled at timestamp 100 takes effect at step 20, and timestamp 100.
+		step.set_durations(step_duration_infos(&[(0, 5), (100, 2)].to_vec().into_iter().collect()).unwrap());
+		assert_eq!(*step.durations.read(), vec![
+			StepDurationInfo { transition_step: 0, transition_timestamp: 0, step_duration: 5 },
+			StepDurationInfo { transition_step: 20, transition_timestamp: 100, step_duration: 2 },
+		]);
+		assert!(step_duration_infos(&[(0, ::std::u64::MAX), (100, 2)].to_vec().into_iter().collect()).is_none());
+	}
+
+	#[test]
+	fn reads_step_durations_from_contract() {
+		use super::step_duration_infos;
+
+		let client = generate_dummy_client_with_spec(spec::new_test_round_step_duration_contract);
+		let genesis_hash = client.chain_info().genesis_hash;
+		let build = || build_aura(|p| {
+			p.step_durations = [(0, 5)].to_vec().into_iter().collect();
+			p.immediate_transitions = false;
+			p.step_duration_contract_transitions = [(0, Address::from_low_u64_be(0x43))].to_vec().into_iter().collect();
+		});
+		let engine = build();
+		engine.register_client(Arc::downgrade(&client) as _);
+
+		// the contract schedules changes at timestamps 50, 100 and 1000, the genesis block is at 100.
+		let durations = engine.epoch_step_durations(&*client, genesis_hash, 0).unwrap();
+		assert_eq!(*durations, [(0, 5), (1000, 2)].to_vec().into_iter().collect::<BTreeMap<_, _>>());
+
+		// blocks on top of the best block switch the step timer to the durations of their epoch.
+		engine.update_step_durations(&*client, genesis_hash, genesis_hash, 0).unwrap();
+		assert_eq!(*engine.step.inner.durations.read(), step_duration_infos(&durations).unwrap());
+
+		// other blocks don't.
+		let engine = build();
+		engine.register_client(Arc::downgrade(&client) as _);
+		engine.update_step_durations(&*client, H256::repeat_byte(1), genesis_hash, 0).unwrap();
+		assert_eq!(engine.step.inner.durations.read().len(), 1);
+
+		// the durations of an unknown epoch are not guessed.
+		assert!(engine.epoch_step_durations(&*client, H256::repeat_byte(1), 0).is_err());
+	}
+
 	#[test]
 	#[should_panic(expected="called `Result::unwrap()` on an `Err` value: Engine(Custom(\"step duration cannot be 0\"))")]
 	fn test_step_duration_zero() {
@@ -2474,6 +3193,49 @@ mod tests {
 		}
 	}
 
+	#[test]
+	fn epoch_verifier_checks_finality_certificates() {
+		use engine::EpochVerifier as _;
+
+		let tap = AccountProvider::transient_provider();
+		let validators: Vec<_> = (0..3)
+			.map(|i| tap.insert_account(keccak(i.to_string()).into(), &"0".into()).unwrap())
+			.collect();
+		let engine = build_aura(|_| {});
+		let verifier = EpochVerifier {
+			step: engine.step.clone(),
+			subchain_validators: SimpleList::new(validators.clone()),
+			empty_steps_transition: u64::max_value(),
+			two_thirds_majority_transition: 0,
+			eip1559_transition: BlockNumber::max_value(),
+		};
+
+		let mut parent = Header::default();
+		parent.set_number(1);
+		let mut header = Header::default();
+		header.set_number(2);
+		header.set_parent_hash(parent.hash());
+		let certificate = |signers: &[Address]| FinalityCertificate {
+			number: 2,
+			block_hash: header.hash(),
+			signatures: signers.iter()
+				.map(|a| tap.sign(*a, Some("0".into()), vote_message(2, &header.hash())).unwrap().into())
+				.collect(),
+		};
+		let headers = vec![parent.clone(), header.clone()];
+
+		let proof = encode_certified_proof(&certificate(&validators), &headers);
+		assert_eq!(verifier.check_finality_proof(&proof), Some(vec![parent.hash(), header.hash()]));
+
+		// two out of three validators are not a 2/3 majority.
+		let proof = encode_certified_proof(&certificate(&validators[..2]), &headers);
+		assert_eq!(verifier.check_finality_proof(&proof), None);
+
+		// the headers must end with the certified block.
+		let proof = encode_certified_proof(&certificate(&validators), &headers[..1]);
+		assert_eq!(verifier.check_finality_proof(&proof), None);
+	}
+
 	#[test]
 	fn broadcast_empty_step_message() {
 		let (spec, tap, accounts) = setup_empty_steps();
@@ -2511,6 +3273,62 @@ mod tests {
 		assert_eq!(len, notify.messages.read().len());
 	}
 
+	#[test]
+	fn reports_empty_steps_in_metrics() {
+		let (spec, tap, accounts) = setup_empty_steps();
+
+		let engine = &*spec.engine;
+		let genesis_header = spec.genesis_header();
+		let db1 = spec.ensure_db_good(get_temp_state_db(), &Default::default()).unwrap();
+		let last_hashes = Arc::new(vec![genesis_header.hash()]);
+
+		let client = generate_dummy_client_with_spec(spec::new_test_round_empty_steps);
+		engine.register_client(Arc::downgrade(&client) as _);
+		engine.set_signer(Some(Box::new((tap.clone(), accounts[0], "1".into()))));
+
+		let b1 = OpenBlock::new(engine, Default::default(), false, db1, &genesis_header, last_hashes, accounts[0], (3141562.into(), 31415620.into()), vec![], false).unwrap();
+		let b1 = b1.close_and_lock().unwrap();
+		assert_eq!(engine.generate_seal(&b1, &genesis_header), Seal::None);
+
+		let mut registry = PrometheusRegistry::new(String::new());
+		engine.prometheus_metrics(&mut registry);
+		let rendered = registry.render();
+		assert!(rendered.contains("aura_step 2\n"));
+		assert!(rendered.contains("aura_empty_steps_pending 1\n"));
+		assert!(rendered.contains("aura_empty_steps_collected 1\n"));
+		assert!(rendered.contains("aura_skipped_steps_reported 0\n"));
+	}
+
+	#[test]
+	fn validator_stats_count_missed_steps() {
+		let client = generate_dummy_client_with_spec_and_data(spec::new_test_round, 0, 0, &[], true);
+		let tap = Arc::new(AccountProvider::transient_provider());
+		let addr1 = tap.insert_account(keccak("1").into(), &"1".into()).unwrap();
+		let addr2 = tap.insert_account(keccak("0").into(), &"0".into()).unwrap();
+
+		let signer = Box::new((tap.clone(), addr1, "1".into()));
+		client.miner().set_author(Author::Sealer(signer.clone()));
+		let engine = client.engine();
+		engine.set_signer(Some(signer));
+		engine.register_client(Arc::downgrade(&client) as _);
+
+		// only `addr1` is sealing, so every other step is missed by `addr2`.
+		for _ in 0..6 {
+			engine.step();
+		}
+		assert_eq!(client.chain_info().best_block_number, 3);
+
+		let stats = engine.validator_stats(Some(1), 3).unwrap();
+		assert_eq!((stats.from_block, stats.to_block), (1, 3));
+		assert_eq!(stats.validators[&addr1].produced_blocks, 3);
+		assert_eq!(stats.validators[&addr1].missed_steps, 0);
+		assert_eq!(stats.validators[&addr2].produced_blocks, 0);
+		// the steps before block 1 are not counted.
+		assert_eq!(stats.validators[&addr2].missed_steps, 2);
+
+		assert!(engine.validator_stats(Some(3), 1).is_err());
+	}
+
 	#[test]
 	fn seal_with_empty_steps() {
 		let (spec, tap, accounts) = setup_empty_steps();
@@ -3025,4 +3843,64 @@ mod tests {
 		let deserialized: ethjson::spec::AuthorityRound = serde_json::from_str(config).unwrap();
 		AuthorityRoundParams::from(deserialized.params);
 	}
+
+	#[test]
+	fn certifies_blocks_against_their_epoch_and_stores_certificates() {
+		let client = generate_dummy_client_with_spec(spec::new_test_round);
+		let keys: Vec<_> = (0..3).map(|_| Random.generate().unwrap()).collect();
+		let validators: Vec<_> = keys.iter().map(KeyPair::address).collect();
+		let build = || {
+			let engine = build_aura(|p| {
+				p.validators = Box::new(TestSet::new(Default::default(), Default::default(), validators.clone()));
+				p.finality_votes_transition = 0;
+			});
+			engine.register_client(Arc::downgrade(&client) as _);
+			engine
+		};
+		let engine = build();
+
+		let mut header = Header::default();
+		header.set_number(1);
+		header.set_parent_hash(client.chain_info().genesis_hash);
+		let hash = header.hash();
+
+		// the block isn't imported yet, so the votes are kept until it is.
+		for key in &keys {
+			let signature = sign(key.secret(), &vote_message(1, &hash)).unwrap().into();
+			engine.handle_finality_vote(FinalityVote { signature, number: 1, block_hash: hash }, key.address());
+		}
+		assert!(engine.finality_certificate(&hash).is_none());
+
+		// the epoch manager hasn't seen any block, the votes count against the block's epoch.
+		engine.count_pending_finality_votes(&header);
+		let certificate = engine.finality_certificate(&hash).unwrap();
+		assert!(certificate.verify(&SimpleList::new(validators.clone()), 0));
+
+		// certificates survive a restart.
+		assert_eq!(build().finality_certificate(&hash), Some(certificate));
+	}
+
+	#[test]
+	fn fails_to_set_data_dir_without_slashing_protection() {
+		let dir = tempdir::TempDir::new("aura").unwrap();
+		// a file where the database directory should be.
+		std::fs::write(dir.path().join("slashing_protection"), b"").unwrap();
+
+		let engine = build_aura(|_| {});
+		assert!(engine.set_data_dir(dir.path()).is_err());
+		assert!(engine.slashing_protection.lock().is_none());
+	}
+
+	#[test]
+	fn fails_to_set_data_dir_without_randomness_journal() {
+		let dir = tempdir::TempDir::new("aura").unwrap();
+		// a file where the journal directory should be.
+		std::fs::write(dir.path().join("randomness"), b"").unwrap();
+
+		let engine = build_aura(|p| {
+			p.randomness_contract_address.insert(0, Address::from_low_u64_be(1));
+		});
+		assert!(engine.set_data_dir(dir.path()).is_err());
+		assert!(engine.randomness_journal.lock().is_none());
+	}
 }
@@ -66,9 +66,15 @@
 //! 1. `RandomnessPhase::load()` the phase from the blockchain data.
 //! 2. Call `RandomnessPhase::advance()`.
 //!
+//! If a `RandomnessJournal` is passed to `advance()`, committed numbers are also kept locally, and
+//! revealed from the journal if the number stored in the contract can't be decrypted anymore.
+//! `settle_journal()` records the outcome of the journaled rounds once they ended.
+//!
 //! A production implementation of a randomness contract can be found here:
 //! https://github.com/poanetwork/posdao-contracts/blob/4fddb108993d4962951717b49222327f3d94275b/contracts/RandomAuRa.sol
 
+use std::io;
+
 use derive_more::Display;
 use ethabi::Hash;
 use ethabi_contract::use_contract;
@@ -80,6 +86,7 @@ use parity_bytes::Bytes;
 use rand::Rng;
 use engine::signer::EngineSigner;
 
+use crate::randomness_journal::RandomnessJournal;
 use crate::util::{BoundContract, CallError};
 
 /// Random number type expected by the contract: This is generated locally, kept secret during the commit phase, and
@@ -99,7 +106,7 @@ pub enum RandomnessPhase {
 	/// window to make a commitment, i.e. having failed to commit during the commit phase.
 	Waiting,
 	/// Indicates a commitment is possible, but still missing.
-	BeforeCommit,
+	BeforeCommit { our_address: Address, round: U256 },
 	/// Indicates a successful commitment, waiting for the commit phase to end.
 	Committed,
 	/// Indicates revealing is expected as the next step.
@@ -130,6 +137,9 @@ pub enum PhaseError {
 	/// Failed to get the engine signer's public key.
 	#[display(fmt = "Failed to get the engine signer's public key")]
 	MissingPublicKey,
+	/// Failed to write to the randomness journal.
+	#[display(fmt = "Failed to write to the randomness journal: {}", _0)]
+	Journal(io::Error),
 }
 
 impl From<CryptoError> for PhaseError {
@@ -177,7 +187,7 @@ impl RandomnessPhase {
 			}
 
 			if !committed {
-				Ok(RandomnessPhase::BeforeCommit)
+				Ok(RandomnessPhase::BeforeCommit { our_address, round })
 			} else {
 				Ok(RandomnessPhase::Committed)
 			}
@@ -206,10 +216,11 @@ impl RandomnessPhase {
 		contract: &BoundContract,
 		rng: &mut R,
 		signer: &dyn EngineSigner,
+		journal: Option<&mut RandomnessJournal>,
 	) -> Result<Option<Bytes>, PhaseError> {
 		match self {
 			RandomnessPhase::Waiting | RandomnessPhase::Committed => Ok(None),
-			RandomnessPhase::BeforeCommit => {
+			RandomnessPhase::BeforeCommit { round, .. } => {
 				// Generate a new random number, but don't reveal it yet. Instead, we publish its hash to the
 				// randomness contract, together with the number encrypted to ourselves. That way we will later be
 				// able to decrypt and reveal it, and other parties are able to verify it against the hash.
@@ -218,6 +229,11 @@ impl RandomnessPhase {
 				let public = signer.public().ok_or(PhaseError::MissingPublicKey)?;
 				let cipher = ecies::encrypt(&public, &number_hash.0, number.as_bytes())?;
 
+				// Journal the number before committing, so it can't be committed without being journaled.
+				if let Some(journal) = journal {
+					journal.insert(signer, contract.address(), round, &number).map_err(PhaseError::Journal)?;
+				}
+
 				debug!(target: "engine", "Randomness contract: committing {}.", number_hash);
 				// Return the call data for the transaction that commits the hash and the encrypted number.
 				let (data, _decoder) = aura_random::functions::commit_hash::call(number_hash, cipher);
@@ -230,15 +246,22 @@ impl RandomnessPhase {
 					.call_const(call)
 					.map_err(PhaseError::LoadFailed)?;
 
-				// Decrypt the number and check against the hash.
-				let number_bytes = signer.decrypt(&committed_hash.0, &cipher)?;
-				let number = if number_bytes.len() == 32 {
-					RandNumber::from_slice(&number_bytes)
-				} else {
-					// This can only happen if there is a bug in the smart contract,
-					// or if the entire network goes awry.
-					error!(target: "engine", "Decrypted random number has the wrong length.");
-					return Err(PhaseError::BadRandNumber);
+				// Prefer the journaled number, in case the contract's copy can't be decrypted. Otherwise
+				// decrypt the number and check against the hash.
+				let journaled = journal.as_ref().and_then(|j| j.number(signer, contract.address(), round, &committed_hash));
+				let number = match journaled {
+					Some(number) => number,
+					None => {
+						let number_bytes = signer.decrypt(&committed_hash.0, &cipher)?;
+						if number_bytes.len() == 32 {
+							RandNumber::from_slice(&number_bytes)
+						} else {
+							// This can only happen if there is a bug in the smart contract,
+							// or if the entire network goes awry.
+							error!(target: "engine", "Decrypted random number has the wrong length.");
+							return Err(PhaseError::BadRandNumber);
+						}
+					}
 				};
 				let number_hash: Hash = keccak(number.as_bytes());
 				if number_hash != committed_hash {
@@ -250,8 +273,27 @@ impl RandomnessPhase {
 				// We are now sure that we have the correct secret and can reveal it. So we return the call data for the
 				// transaction that stores the revealed random bytes on the contract.
 				let (data, _decoder) = aura_random::functions::reveal_number::call(number.as_bytes());
+				// If the transaction doesn't make it into a block, the reveal is retried with our next block.
+				if let Some(journal) = journal {
+					journal.note_reveal(contract.address(), round).map_err(PhaseError::Journal)?;
+				}
 				Ok(Some(data))
 			}
 		}
 	}
 }
+
+/// Record whether we revealed the numbers of the journaled rounds that ended.
+pub fn settle_journal(contract: &BoundContract, journal: &mut RandomnessJournal) -> Result<(), PhaseError> {
+	let round = contract
+		.call_const(aura_random::functions::current_collect_round::call())
+		.map_err(PhaseError::LoadFailed)?;
+
+	for (past_round, validator) in journal.unsettled_rounds(contract.address(), round) {
+		let revealed = contract
+			.call_const(aura_random::functions::sent_reveal::call(past_round, validator))
+			.map_err(PhaseError::LoadFailed)?;
+		journal.settle(contract.address(), past_round, revealed).map_err(PhaseError::Journal)?;
+	}
+	Ok(())
+}
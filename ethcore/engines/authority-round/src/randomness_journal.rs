@@ -0,0 +1,325 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Parity Ethereum.
+
+// Parity Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Local journal of the random numbers committed to a randomness contract.
+//!
+//! The contract stores each committed number encrypted to the engine signer's key, so it can't be
+//! revealed anymore if the contract's copy can't be decrypted. The journal keeps a copy of every
+//! committed number which survives restarts, encrypted to a key derived from a signature of the
+//! engine signer. Signatures are deterministic, so the same key is derived again after a restart
+//! and no key is ever written to disk.
+//!
+//! The journal is written before the commitment is sent, and the file is replaced atomically, so
+//! a crash never leaves a committed number behind without its journal entry.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use common_types::engines::{RandomnessRound, RandomnessRoundStatus};
+use ethereum_types::{Address, H256, U256};
+use keccak_hash::keccak;
+use log::{debug, warn};
+use parity_bytes::Bytes;
+use engine::signer::EngineSigner;
+use parity_crypto::publickey::{ecies, KeyPair, Secret};
+use parity_path::restrict_permissions_owner;
+use rlp::{Rlp, RlpStream};
+
+use crate::randomness::RandNumber;
+
+/// Number of ended rounds kept in the journal for inspection.
+const MAX_SETTLED_ROUNDS: usize = 32;
+
+/// Message signed by the engine signer to derive the journal's key.
+const KEY_MESSAGE: &[u8] = b"randomness journal key";
+const JOURNAL_FILE: &str = "journal";
+const JOURNAL_TMP_FILE: &str = "journal.tmp";
+
+/// A journaled commitment.
+#[derive(Debug, Clone, PartialEq)]
+struct Entry {
+	validator: Address,
+	number_hash: H256,
+	/// The number, encrypted to the journal's key of `validator`.
+	cipher: Bytes,
+	status: RandomnessRoundStatus,
+	reveal_attempts: u64,
+}
+
+/// Encrypted journal of committed random numbers, by contract and round.
+pub struct RandomnessJournal {
+	dir: PathBuf,
+	entries: BTreeMap<(Address, U256), Entry>,
+}
+
+impl RandomnessJournal {
+	/// Open the journal in `dir`, creating it if it doesn't exist yet.
+	pub fn open(dir: &Path) -> io::Result<Self> {
+		fs::create_dir_all(dir)?;
+
+		let entries = match fs::read(dir.join(JOURNAL_FILE)) {
+			Ok(data) => decode_entries(&data).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("{}", e)))?,
+			Err(ref e) if e.kind() == io::ErrorKind::NotFound => BTreeMap::new(),
+			Err(e) => return Err(e),
+		};
+		debug!(target: "engine", "Loaded randomness journal with {} rounds from {}", entries.len(), dir.display());
+
+		Ok(RandomnessJournal { dir: dir.to_owned(), entries })
+	}
+
+	/// Store the number `signer` is about to commit in `round` of `contract`.
+	pub fn insert(&mut self, signer: &dyn EngineSigner, contract: Address, round: U256, number: &RandNumber) -> io::Result<()> {
+		let number_hash = keccak(number.as_bytes());
+		let cipher = journal_key(signer)
+			.and_then(|key| ecies::encrypt(key.public(), number_hash.as_bytes(), number.as_bytes()))
+			.map_err(|e| io::Error::new(io::ErrorKind::Other, format!("{}", e)))?;
+		self.entries.insert((contract, round), Entry {
+			validator: signer.address(),
+			number_hash,
+			cipher,
+			status: RandomnessRoundStatus::Committed,
+			reveal_attempts: 0,
+		});
+		self.save()
+	}
+
+	/// The journaled number of `round` of `contract`, if it was committed by `signer` and matches the
+	/// committed hash.
+	pub fn number(&self, signer: &dyn EngineSigner, contract: Address, round: U256, committed_hash: &H256) -> Option<RandNumber> {
+		let entry = self.entries.get(&(contract, round))?;
+		if entry.validator != signer.address() {
+			return None;
+		}
+		if entry.number_hash != *committed_hash {
+			warn!(target: "engine", "Journaled random number of round {} doesn't agree with the committed hash.", round);
+			return None;
+		}
+		let number = journal_key(signer)
+			.and_then(|key| ecies::decrypt(key.secret(), committed_hash.as_bytes(), &entry.cipher))
+			.ok()?;
+		if number.len() != 32 || keccak(&number) != *committed_hash {
+			warn!(target: "engine", "Failed to decrypt the journaled random number of round {}.", round);
+			return None;
+		}
+		Some(RandNumber::from_slice(&number))
+	}
+
+	/// Record that a transaction revealing the number of `round` of `contract` was created.
+	pub fn note_reveal(&mut self, contract: Address, round: U256) -> io::Result<()> {
+		match self.entries.get_mut(&(contract, round)) {
+			Some(entry) => entry.reveal_attempts += 1,
+			None => return Ok(()),
+		}
+		self.save()
+	}
+
+	/// The rounds of `contract` before `current_round` whose outcome is still unknown, with the
+	/// validator which committed in each of them.
+	pub fn unsettled_rounds(&self, contract: Address, current_round: U256) -> Vec<(U256, Address)> {
+		self.entries.iter()
+			.filter(|((c, round), entry)| {
+				*c == contract && *round < current_round && entry.status == RandomnessRoundStatus::Committed
+			})
+			.map(|((_, round), entry)| (*round, entry.validator))
+			.collect()
+	}
+
+	/// Record whether the number of an ended round was revealed. Only the most recent ended rounds
+	/// are kept.
+	pub fn settle(&mut self, contract: Address, round: U256, revealed: bool) -> io::Result<()> {
+		match self.entries.get_mut(&(contract, round)) {
+			Some(entry) if revealed => entry.status = RandomnessRoundStatus::Revealed,
+			Some(entry) => {
+				warn!(target: "engine", "Random number committed in round {} was never revealed.", round);
+				entry.status = RandomnessRoundStatus::Missed;
+			}
+			None => return Ok(()),
+		}
+
+		let mut settled: Vec<_> = self.entries.iter()
+			.filter(|(_, entry)| entry.status != RandomnessRoundStatus::Committed)
+			.map(|(&(contract, round), _)| (round, contract))
+			.collect();
+		settled.sort();
+		let prune = settled.len().saturating_sub(MAX_SETTLED_ROUNDS);
+		for (round, contract) in settled.into_iter().take(prune) {
+			self.entries.remove(&(contract, round));
+		}
+
+		self.save()
+	}
+
+	/// All journaled rounds, oldest first.
+	pub fn rounds(&self) -> Vec<RandomnessRound> {
+		let mut rounds: Vec<_> = self.entries.iter()
+			.map(|((contract, round), entry)| RandomnessRound {
+				contract: *contract,
+				round: *round,
+				validator: entry.validator,
+				number_hash: entry.number_hash,
+				status: entry.status,
+				reveal_attempts: entry.reveal_attempts,
+			})
+			.collect();
+		rounds.sort_by_key(|r| (r.round, r.contract));
+		rounds
+	}
+
+	// Replace the journal file atomically.
+	fn save(&self) -> io::Result<()> {
+		let tmp_path = self.dir.join(JOURNAL_TMP_FILE);
+		{
+			let mut file = fs::File::create(&tmp_path)?;
+			restrict_permissions_owner(&tmp_path, true, false)
+				.map_err(|e| io::Error::new(io::ErrorKind::Other, format!("{}", e)))?;
+			file.write_all(&encode_entries(&self.entries))?;
+			file.sync_all()?;
+		}
+		fs::rename(&tmp_path, self.dir.join(JOURNAL_FILE))
+	}
+}
+
+// The key the numbers committed by `signer` are encrypted to.
+fn journal_key(signer: &dyn EngineSigner) -> Result<KeyPair, parity_crypto::publickey::Error> {
+	let signature = signer.sign_data(KEY_MESSAGE)?;
+	KeyPair::from_secret(Secret::from(keccak(&signature[..])))
+}
+
+fn status_code(status: RandomnessRoundStatus) -> u8 {
+	match status {
+		RandomnessRoundStatus::Committed => 0,
+		RandomnessRoundStatus::Revealed => 1,
+		RandomnessRoundStatus::Missed => 2,
+	}
+}
+
+fn encode_entries(entries: &BTreeMap<(Address, U256), Entry>) -> Bytes {
+	let mut s = RlpStream::new_list(entries.len());
+	for ((contract, round), entry) in entries {
+		s.begin_list(7)
+			.append(contract)
+			.append(round)
+			.append(&entry.validator)
+			.append(&entry.number_hash)
+			.append(&entry.cipher)
+			.append(&status_code(entry.status))
+			.append(&entry.reveal_attempts);
+	}
+	s.out()
+}
+
+fn decode_entries(data: &[u8]) -> Result<BTreeMap<(Address, U256), Entry>, rlp::DecoderError> {
+	let mut entries = BTreeMap::new();
+	for item in Rlp::new(data).iter() {
+		let status = match item.val_at::<u8>(5)? {
+			0 => RandomnessRoundStatus::Committed,
+			1 => RandomnessRoundStatus::Revealed,
+			2 => RandomnessRoundStatus::Missed,
+			_ => return Err(rlp::DecoderError::Custom("Unknown randomness round status")),
+		};
+		entries.insert((item.val_at(0)?, item.val_at(1)?), Entry {
+			validator: item.val_at(2)?,
+			number_hash: item.val_at(3)?,
+			cipher: item.val_at(4)?,
+			status,
+			reveal_attempts: item.val_at(6)?,
+		});
+	}
+	Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+	use std::fs;
+
+	use common_types::engines::RandomnessRoundStatus;
+	use engine::signer::{self, EngineSigner};
+	use ethereum_types::{Address, H256, U256};
+	use keccak_hash::keccak;
+	use parity_crypto::publickey::{KeyPair, Secret};
+	use tempdir::TempDir;
+
+	use super::RandomnessJournal;
+
+	fn signer(seed: &str) -> Box<dyn EngineSigner> {
+		signer::from_keypair(KeyPair::from_secret(Secret::from(keccak(seed))).unwrap())
+	}
+
+	#[test]
+	fn journal_survives_reopening() {
+		let dir = TempDir::new("randomness").unwrap();
+		let contract = Address::from_low_u64_be(1);
+		let signer = signer("validator");
+		let validator = signer.address();
+		let number = H256::random();
+		let number_hash = keccak(number.as_bytes());
+
+		{
+			let mut journal = RandomnessJournal::open(dir.path()).unwrap();
+			journal.insert(&*signer, contract, U256::from(3), &number).unwrap();
+			journal.note_reveal(contract, U256::from(3)).unwrap();
+		}
+		// the journal holds neither the number nor the key it's encrypted to.
+		for file in fs::read_dir(dir.path()).unwrap() {
+			let data = fs::read(file.unwrap().path()).unwrap();
+			assert!(!data.windows(32).any(|w| w == number.as_bytes()));
+		}
+
+		let mut journal = RandomnessJournal::open(dir.path()).unwrap();
+		assert_eq!(journal.number(&*signer, contract, U256::from(3), &number_hash), Some(number));
+		assert_eq!(journal.number(&*signer, contract, U256::from(3), &H256::random()), None);
+		assert_eq!(journal.number(&*signer, contract, U256::from(4), &number_hash), None);
+		assert_eq!(journal.number(&*self::signer("other"), contract, U256::from(3), &number_hash), None);
+
+		let rounds = journal.rounds();
+		assert_eq!(rounds.len(), 1);
+		assert_eq!(rounds[0].validator, validator);
+		assert_eq!(rounds[0].number_hash, number_hash);
+		assert_eq!(rounds[0].status, RandomnessRoundStatus::Committed);
+		assert_eq!(rounds[0].reveal_attempts, 1);
+
+		assert!(journal.unsettled_rounds(contract, U256::from(3)).is_empty());
+		assert_eq!(journal.unsettled_rounds(contract, U256::from(4)), vec![(U256::from(3), validator)]);
+		journal.settle(contract, U256::from(3), true).unwrap();
+		assert!(journal.unsettled_rounds(contract, U256::from(4)).is_empty());
+
+		let journal = RandomnessJournal::open(dir.path()).unwrap();
+		assert_eq!(journal.rounds()[0].status, RandomnessRoundStatus::Revealed);
+	}
+
+	#[test]
+	fn prunes_old_settled_rounds() {
+		let dir = TempDir::new("randomness").unwrap();
+		let contract = Address::from_low_u64_be(1);
+		let signer = signer("validator");
+		let mut journal = RandomnessJournal::open(dir.path()).unwrap();
+
+		for round in 0..(super::MAX_SETTLED_ROUNDS as u64 + 2) {
+			journal.insert(&*signer, contract, round.into(), &H256::random()).unwrap();
+		}
+		for round in 0..(super::MAX_SETTLED_ROUNDS as u64 + 1) {
+			journal.settle(contract, round.into(), false).unwrap();
+		}
+
+		let rounds = journal.rounds();
+		assert_eq!(rounds.len(), super::MAX_SETTLED_ROUNDS + 1);
+		assert_eq!(rounds[0].round, U256::from(1));
+		assert_eq!(rounds[0].status, RandomnessRoundStatus::Missed);
+		assert_eq!(rounds.last().unwrap().status, RandomnessRoundStatus::Committed);
+	}
+}
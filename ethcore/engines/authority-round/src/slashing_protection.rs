@@ -0,0 +1,242 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Parity Ethereum.
+
+// Parity Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Local slashing-protection database of the steps signed by the engine.
+//!
+//! Before a block seal or an empty step message is signed, its step is checked against the
+//! highest step signed so far by the same key, and stored. Signing is refused unless the step is
+//! strictly higher, so a node restarted from an old database copy, or run twice with the same
+//! key, can't produce two different blocks or empty steps for one step. The step is written to
+//! disk before the signature is produced.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use ethereum_types::{Address, U256};
+use ethjson::slashing_protection::{Interchange, SignedSteps, INTERCHANGE_VERSION};
+use ethjson::uint::Uint;
+use log::debug;
+use parity_bytes::Bytes;
+use parity_path::restrict_permissions_owner;
+use rlp::{Rlp, RlpStream};
+
+const DB_FILE: &str = "signed_steps";
+const DB_TMP_FILE: &str = "signed_steps.tmp";
+
+/// Kind of message signed for a step.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Signed {
+	/// A block seal.
+	Block,
+	/// An empty step message.
+	EmptyStep,
+}
+
+/// Highest steps signed by a key.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct Record {
+	block_step: Option<u64>,
+	empty_step: Option<u64>,
+}
+
+impl Record {
+	fn step_mut(&mut self, kind: Signed) -> &mut Option<u64> {
+		match kind {
+			Signed::Block => &mut self.block_step,
+			Signed::EmptyStep => &mut self.empty_step,
+		}
+	}
+}
+
+/// Highest signed steps, by signer.
+pub struct SlashingProtection {
+	dir: PathBuf,
+	records: BTreeMap<Address, Record>,
+}
+
+impl SlashingProtection {
+	/// Open the database in `dir`, creating it if it doesn't exist yet.
+	pub fn open(dir: &Path) -> io::Result<Self> {
+		fs::create_dir_all(dir)?;
+		let records = match fs::read(dir.join(DB_FILE)) {
+			Ok(data) => decode_records(&data).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("{}", e)))?,
+			Err(ref e) if e.kind() == io::ErrorKind::NotFound => BTreeMap::new(),
+			Err(e) => return Err(e),
+		};
+		debug!(target: "engine", "Loaded slashing protection for {} signers from {}", records.len(), dir.display());
+
+		Ok(SlashingProtection { dir: dir.to_owned(), records })
+	}
+
+	/// Record that `signer` is about to sign a message of `kind` for `step`. Returns `false`, and
+	/// records nothing, if a message of that kind was signed for the same or a higher step already.
+	pub fn record(&mut self, signer: Address, kind: Signed, step: u64) -> io::Result<bool> {
+		let mut record = self.records.get(&signer).cloned().unwrap_or_default();
+		let last = record.step_mut(kind);
+		if last.map_or(false, |last| last >= step) {
+			return Ok(false);
+		}
+		*last = Some(step);
+		let previous = self.records.insert(signer, record);
+		if let Err(e) = self.save() {
+			match previous {
+				Some(previous) => self.records.insert(signer, previous),
+				None => self.records.remove(&signer),
+			};
+			return Err(e);
+		}
+		Ok(true)
+	}
+
+	/// The signed steps of all signers, in the interchange format.
+	pub fn export(&self) -> Interchange {
+		Interchange {
+			version: Uint(INTERCHANGE_VERSION.into()),
+			signers: self.records.iter()
+				.map(|(address, record)| SignedSteps {
+					address: (*address).into(),
+					last_block_step: record.block_step.map(|step| Uint(step.into())),
+					last_empty_step: record.empty_step.map(|step| Uint(step.into())),
+				})
+				.collect(),
+		}
+	}
+
+	/// Merge signed steps in the interchange format, keeping the highest step of each signer.
+	pub fn import(&mut self, interchange: Interchange) -> io::Result<()> {
+		let invalid = |e: String| io::Error::new(io::ErrorKind::InvalidData, e);
+		if interchange.version.0 != U256::from(INTERCHANGE_VERSION) {
+			return Err(invalid(format!("Unsupported interchange version {}", interchange.version.0)));
+		}
+		let to_step = |step: Option<Uint>| -> io::Result<Option<u64>> {
+			match step {
+				Some(Uint(step)) if step > U256::from(u64::max_value()) => Err(invalid(format!("Step {} is out of range", step))),
+				Some(Uint(step)) => Ok(Some(step.low_u64())),
+				None => Ok(None),
+			}
+		};
+
+		for signer in interchange.signers {
+			let imported = Record {
+				block_step: to_step(signer.last_block_step)?,
+				empty_step: to_step(signer.last_empty_step)?,
+			};
+			let record = self.records.entry(signer.address.into()).or_default();
+			record.block_step = record.block_step.max(imported.block_step);
+			record.empty_step = record.empty_step.max(imported.empty_step);
+		}
+		self.save()
+	}
+
+	// Replace the database file atomically.
+	fn save(&self) -> io::Result<()> {
+		let tmp_path = self.dir.join(DB_TMP_FILE);
+		{
+			let mut file = fs::File::create(&tmp_path)?;
+			restrict_permissions_owner(&tmp_path, true, false)
+				.map_err(|e| io::Error::new(io::ErrorKind::Other, format!("{}", e)))?;
+			file.write_all(&encode_records(&self.records))?;
+			file.sync_all()?;
+		}
+		fs::rename(&tmp_path, self.dir.join(DB_FILE))
+	}
+}
+
+fn encode_records(records: &BTreeMap<Address, Record>) -> Bytes {
+	let mut s = RlpStream::new_list(records.len());
+	for (address, record) in records {
+		s.begin_list(3).append(address);
+		for step in &[record.block_step, record.empty_step] {
+			match step {
+				Some(step) => s.begin_list(1).append(step),
+				None => s.begin_list(0),
+			};
+		}
+	}
+	s.out()
+}
+
+fn decode_records(data: &[u8]) -> Result<BTreeMap<Address, Record>, rlp::DecoderError> {
+	let decode_step = |item: Rlp| -> Result<Option<u64>, rlp::DecoderError> {
+		match item.item_count()? {
+			0 => Ok(None),
+			_ => item.val_at(0).map(Some),
+		}
+	};
+	let mut records = BTreeMap::new();
+	for item in Rlp::new(data).iter() {
+		records.insert(item.val_at(0)?, Record {
+			block_step: decode_step(item.at(1)?)?,
+			empty_step: decode_step(item.at(2)?)?,
+		});
+	}
+	Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+	use ethereum_types::Address;
+	use ethjson::uint::Uint;
+	use tempdir::TempDir;
+
+	use super::{Signed, SlashingProtection};
+
+	#[test]
+	fn refuses_steps_not_above_the_signed_ones() {
+		let dir = TempDir::new("slashing_protection").unwrap();
+		let signer = Address::from_low_u64_be(1);
+
+		{
+			let mut db = SlashingProtection::open(dir.path()).unwrap();
+			assert!(db.record(signer, Signed::Block, 5).unwrap());
+			assert!(!db.record(signer, Signed::Block, 5).unwrap());
+			assert!(!db.record(signer, Signed::Block, 4).unwrap());
+			// empty steps and blocks are tracked separately.
+			assert!(db.record(signer, Signed::EmptyStep, 5).unwrap());
+			assert!(db.record(Address::from_low_u64_be(2), Signed::Block, 1).unwrap());
+		}
+
+		let mut db = SlashingProtection::open(dir.path()).unwrap();
+		assert!(!db.record(signer, Signed::Block, 5).unwrap());
+		assert!(!db.record(signer, Signed::EmptyStep, 5).unwrap());
+		assert!(db.record(signer, Signed::Block, 6).unwrap());
+	}
+
+	#[test]
+	fn imports_the_highest_steps() {
+		let (from, to) = (TempDir::new("slashing_protection").unwrap(), TempDir::new("slashing_protection").unwrap());
+		let (a, b) = (Address::from_low_u64_be(1), Address::from_low_u64_be(2));
+
+		let mut source = SlashingProtection::open(from.path()).unwrap();
+		source.record(a, Signed::Block, 10).unwrap();
+		source.record(b, Signed::EmptyStep, 3).unwrap();
+
+		let mut db = SlashingProtection::open(to.path()).unwrap();
+		db.record(a, Signed::Block, 20).unwrap();
+		db.record(b, Signed::EmptyStep, 1).unwrap();
+		db.import(source.export()).unwrap();
+
+		assert!(!db.record(a, Signed::Block, 20).unwrap());
+		assert!(!db.record(b, Signed::EmptyStep, 3).unwrap());
+		assert!(db.record(b, Signed::Block, 1).unwrap());
+
+		let exported = SlashingProtection::open(to.path()).unwrap().export();
+		assert_eq!(exported.signers.len(), 2);
+		assert_eq!(exported.signers[0].last_block_step, Some(Uint(20.into())));
+	}
+}
@@ -0,0 +1,60 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Parity Ethereum.
+
+// Parity Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Step duration changes scheduled by a contract.
+//!
+//! The contract returns every change it ever scheduled, as the timestamps the changes take effect
+//! at and the new durations. The engine reads it at the state of epoch transition blocks, so all
+//! nodes plan the same steps, and merges the changes into the durations of the previous epoch.
+//! Changes at or before the timestamp of the transition block are rejected, so the steps of sealed
+//! blocks are never renumbered.
+
+use std::collections::BTreeMap;
+
+use client_traits::BlockChainClient;
+use common_types::ids::BlockId;
+use ethabi::FunctionOutputDecoder;
+use ethabi_contract::use_contract;
+use ethereum_types::{Address, H256, U256};
+
+use crate::U16_MAX;
+
+use_contract!(step_duration_contract, "../../res/contracts/authority_round_step_duration.json");
+
+/// Reads the step duration changes scheduled by the contract at `address`, at the state of the
+/// block with the given hash. Returns a map from the timestamps of the changes to the durations.
+pub fn scheduled_step_durations(
+	client: &dyn BlockChainClient,
+	block_hash: H256,
+	address: Address,
+) -> Result<BTreeMap<u64, u64>, String> {
+	let (data, decoder) = step_duration_contract::functions::step_durations::call();
+	let value = client.call_contract(BlockId::Hash(block_hash), address, data)?;
+	let (timestamps, durations) = decoder.decode(&value).map_err(|e| e.to_string())?;
+	if timestamps.len() != durations.len() {
+		return Err("invalid data returned by step duration contract: both arrays must have the same size".into());
+	}
+
+	timestamps.into_iter().zip(durations).map(|(timestamp, duration): (U256, U256)| {
+		if timestamp > U256::from(u64::max_value()) {
+			return Err(format!("step duration change timestamp {} is out of range", timestamp));
+		}
+		if duration.is_zero() || duration > U256::from(U16_MAX) {
+			return Err(format!("invalid step duration {} scheduled at {}", duration, timestamp));
+		}
+		Ok((timestamp.as_u64(), duration.as_u64()))
+	}).collect()
+}
@@ -67,6 +67,11 @@ impl<'a> BoundContract<'a> {
 		}
 	}
 
+	/// The address of the contract.
+	pub fn address(&self) -> Address {
+		self.contract_addr
+	}
+
 	/// Perform a function call to an Ethereum machine that doesn't create a transaction or change the state.
 	///
 	/// Runs a constant function call on `client`. The `call` value can be serialized by calling any
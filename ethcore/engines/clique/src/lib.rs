@@ -97,6 +97,7 @@ use common_types::{
 		machine::Call,
 	},
 	errors::{BlockError, EthcoreError as Error, EngineError},
+	snapshot::Snapshotting,
 };
 
 use crate::{
@@ -331,7 +332,7 @@ impl Clique {
 							return Err(BlockError::UnknownParent(last_parent_hash))?;
 						}
 						Some(next) => {
-							chain.push_front(next.decode()?);
+							chain.push_front(next.decode(self.machine.params().eip1559_transition)?);
 						}
 					}
 				}
@@ -343,7 +344,7 @@ impl Clique {
 
 				let last_checkpoint_header = match c.block_header(BlockId::Hash(last_checkpoint_hash)) {
 					None => return Err(EngineError::CliqueMissingCheckpoint(last_checkpoint_hash))?,
-					Some(header) => header.decode()?,
+					Some(header) => header.decode(self.machine.params().eip1559_transition)?,
 				};
 
 				let last_checkpoint_state = match block_state_by_hash.get_mut(&last_checkpoint_hash) {
@@ -714,6 +715,48 @@ impl Engine for Clique {
 		Ok(())
 	}
 
+	fn snapshot_mode(&self) -> Snapshotting {
+		Snapshotting::Clique { epoch_length: self.epoch_length }
+	}
+
+	/// Rebuild the state of the restored head from the checkpoint the snapshot starts at,
+	/// applying every header on top of it the way `verify_block_family` would.
+	fn verify_snapshot_headers(&self, headers: &[Header]) -> Result<(), Error> {
+		let (checkpoint, headers) = match headers.split_first() {
+			Some(split) => split,
+			None => return Ok(()),
+		};
+		if checkpoint.number() % self.epoch_length != 0 {
+			Err(EngineError::CliqueMissingCheckpoint(checkpoint.hash()))?
+		}
+
+		let mut state = self.new_checkpoint_state(checkpoint)?;
+		let mut parent = checkpoint;
+		for header in headers {
+			if parent.hash() != *header.parent_hash() || header.number() != parent.number() + 1 {
+				Err(BlockError::UnknownParent(parent.hash()))?
+			}
+			let limit = parent.timestamp().saturating_add(self.period);
+			if limit > header.timestamp() {
+				let max = CheckedSystemTime::checked_add(UNIX_EPOCH, Duration::from_secs(header.timestamp()));
+				let found = CheckedSystemTime::checked_add(UNIX_EPOCH, Duration::from_secs(limit))
+					.ok_or(BlockError::TimestampOverflow)?;
+
+				Err(BlockError::InvalidTimestamp(OutOfBounds {
+					min: None,
+					max,
+					found,
+				}.into()))?
+			}
+			state.apply(header, header.number() % self.epoch_length == 0)?;
+			state.calc_next_timestamp(header.timestamp(), self.period)?;
+			parent = header;
+		}
+
+		self.block_state_by_hash.write().insert(parent.hash(), state);
+		Ok(())
+	}
+
 	fn genesis_epoch_data(&self, header: &Header, _call: &Call) -> Result<Vec<u8>, String> {
 		let mut state = self.new_checkpoint_state(header).expect("Unable to parse genesis data.");
 		state.calc_next_timestamp(header.timestamp(), self.period).map_err(|e| format!("{}", e))?;
@@ -787,13 +830,13 @@ impl Engine for Clique {
 	}
 
 	/// Clique timestamp is set to parent + period , or current time which ever is higher.
-	fn open_block_header_timestamp(&self, parent_timestamp: u64) -> u64 {
+	fn open_block_header_timestamp(&self, parent: &Header) -> u64 {
 		let now = time::SystemTime::now().duration_since(time::UNIX_EPOCH).unwrap_or_default();
-		cmp::max(now.as_secs() as u64, parent_timestamp.saturating_add(self.period))
+		cmp::max(now.as_secs() as u64, parent.timestamp().saturating_add(self.period))
 	}
 
-	fn is_timestamp_valid(&self, header_timestamp: u64, parent_timestamp: u64) -> bool {
-		header_timestamp >= parent_timestamp.saturating_add(self.period)
+	fn is_timestamp_valid(&self, header: &Header, parent: &Header) -> bool {
+		header.timestamp() >= parent.timestamp().saturating_add(self.period)
 	}
 
 	// Clique uses the author field for voting, the real author is hidden in the `extra_data` field.
@@ -804,3 +804,26 @@ fn bonus_consensus_should_keep_track_of_votes_before_latest_per_signer() {
 	let tags = tester.into_tags(tester.clique_signers(&vote.hash()));
 	assert_eq!(&tags, &['A', 'B', 'C', 'D', 'E']);
 }
+
+#[test]
+fn snapshot_headers_rebuild_state_from_checkpoint() {
+	let tester = CliqueTester::with(3, 1, vec!['A', 'B', 'C']);
+
+	let block = tester.new_block_and_import(CliqueBlockType::Empty, &tester.genesis, None, 'A').unwrap();
+	let block = tester.new_block_and_import(CliqueBlockType::Empty, &block, None, 'B').unwrap();
+	let checkpoint = tester.new_block_and_import(CliqueBlockType::Checkpoint, &block, None, 'C').unwrap();
+	let vote = tester.new_block_and_import(CliqueBlockType::Vote(VoteType::Add), &checkpoint,
+										   Some(tester.signers[&'D'].address()), 'A').unwrap();
+	let head = tester.new_block_and_import(CliqueBlockType::Empty, &vote, None, 'B').unwrap();
+
+	// a fresh engine, as after restoring a snapshot.
+	let restored = Clique::with_test(3, 1);
+	restored.verify_snapshot_headers(&[checkpoint.clone(), vote.clone(), head.clone()]).unwrap();
+	let state = restored.state_no_backfill(&head.hash()).expect("head state is rebuilt");
+	assert_eq!(state.signers(), tester.get_state_at_block(&head.hash()).signers());
+
+	match restored.verify_snapshot_headers(&[vote, head]).unwrap_err() {
+		Error::Engine(EngineError::CliqueMissingCheckpoint(_)) => (),
+		_ => assert!(true == false, "Wrong error kind"),
+	}
+}
@@ -245,6 +245,23 @@ impl Engine for Ethash {
 
 	fn maximum_gas_limit(&self) -> Option<U256> { Some(0x7fff_ffff_ffff_ffffu64.into()) }
 
+	fn hard_forks(&self) -> Vec<BlockNumber> {
+		let params = &self.ethash_params;
+		vec![
+			params.homestead_transition,
+			params.difficulty_hardfork_transition,
+			params.bomb_defuse_transition,
+			params.eip100b_transition,
+			params.ecip1010_pause_transition,
+			params.ecip1010_continue_transition,
+			params.expip2_transition,
+			params.progpow_transition,
+		].into_iter()
+			.chain(params.difficulty_bomb_delays.keys().cloned())
+			.chain(params.block_reward.keys().cloned())
+			.collect()
+	}
+
 	/// Apply the block reward on finalisation of the block.
 	/// This assumes that all uncles are valid uncles (i.e. of at least one generation before the current).
 	fn on_close_block(&self, block: &mut ExecutedBlock, _parent_header: &Header) -> Result<(), Error> {
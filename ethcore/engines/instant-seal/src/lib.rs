@@ -24,6 +24,7 @@ use common_types::{
 		params::CommonParams,
 	},
 	errors::EthcoreError as Error,
+	snapshot::Snapshotting,
 };
 use engine::Engine;
 use ethjson;
@@ -101,7 +102,7 @@ impl Engine for InstantSeal {
 		Ok(())
 	}
 
-	fn open_block_header_timestamp(&self, parent_timestamp: u64) -> u64 {
+	fn open_block_header_timestamp(&self, parent: &Header) -> u64 {
 		use std::{time, cmp};
 
 		let dur = time::SystemTime::now().duration_since(time::UNIX_EPOCH).unwrap_or_default();
@@ -109,11 +110,15 @@ impl Engine for InstantSeal {
 		if self.params.millisecond_timestamp {
 			now = now * 1000 + dur.subsec_millis() as u64;
 		}
-		cmp::max(now, parent_timestamp)
+		cmp::max(now, parent.timestamp())
 	}
 
-	fn is_timestamp_valid(&self, header_timestamp: u64, parent_timestamp: u64) -> bool {
-		header_timestamp >= parent_timestamp
+	fn is_timestamp_valid(&self, header: &Header, parent: &Header) -> bool {
+		header.timestamp() >= parent.timestamp()
+	}
+
+	fn snapshot_mode(&self) -> Snapshotting {
+		Snapshotting::PoW { blocks: 10_000, max_restore_blocks: 10_000 }
 	}
 
 	fn params(&self) -> &CommonParams {
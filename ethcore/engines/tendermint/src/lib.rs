@@ -0,0 +1,1202 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Parity Ethereum.
+
+// Parity Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Tendermint BFT consensus engine with instant finality.
+//!
+//! Validators agree on each block in rounds. The proposer of a round broadcasts an unsealed
+//! block, validators prevote for it and, once more than two thirds prevoted for the same
+//! block, lock on it and precommit it. More than two thirds of precommits commit the block:
+//! the precommit signatures become its seal and it is final as soon as it is imported. A round
+//! that doesn't reach agreement within its timeouts is followed by the next round, with the
+//! next validator as proposer.
+//!
+//! Validators execute a proposal and check it against its parent before they prevote for it.
+//! A committed block that the client still rejects on import releases the lock on it after the
+//! commit timeout, so that the next rounds can agree on another block.
+
+mod message;
+mod vote_collector;
+
+use std::collections::{BTreeMap, HashSet};
+use std::sync::{Arc, Weak};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use client_traits::{EngineClient, ForceUpdateSealing, TransactionRequest};
+use common_types::{
+	BlockNumber,
+	ancestry_action::AncestryAction,
+	block_status::BlockStatus,
+	engines::{
+		Headers,
+		PendingTransitionStore,
+		SealingState,
+		Seal,
+		params::CommonParams,
+		machine::{AuxiliaryData, Call},
+	},
+	errors::{BlockError, EngineError, EthcoreError as Error},
+	header::{Header, ExtendedHeader},
+	ids::BlockId,
+	snapshot::Snapshotting,
+	transaction::SignedTransaction,
+	verification::Unverified,
+};
+use block_reward::{self, RewardKind};
+use engine::{Engine, ConstructedVerifier, signer::EngineSigner};
+use ethereum_types::{Address, H256, U256};
+use log::{debug, trace, warn};
+use machine::{Machine, executed_block::ExecutedBlock};
+use parity_bytes::Bytes;
+use parity_crypto::publickey::Signature;
+use parking_lot::{Mutex, RwLock};
+use rlp::{Rlp, RlpStream};
+use unexpected::Mismatch;
+use validator_set::{ValidatorSet, SimpleList, new_validator_set_posdao};
+
+use self::message::{Message, Proposal, Step, Vote, VoteStep, encode_seal, message_hash, seal_signers};
+use self::vote_collector::{DoubleVote, VoteCollector};
+
+/// Most messages for the next height kept until the current block is imported.
+const MAX_FUTURE_MESSAGES: usize = 1024;
+
+/// `Tendermint` params.
+pub struct TendermintParams {
+	/// Valid validators.
+	pub validators: Box<dyn ValidatorSet>,
+	/// Timeouts of the round steps.
+	pub timeouts: Timeouts,
+	/// Reward per block.
+	pub block_reward: U256,
+	/// If set, this is the block number from which the validator set contracts are called as in
+	/// POSDAO.
+	pub posdao_transition: Option<BlockNumber>,
+}
+
+/// Time a validator waits in each step of a round.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Timeouts {
+	/// Wait for the proposal.
+	pub propose: Duration,
+	/// Wait for a quorum of prevotes.
+	pub prevote: Duration,
+	/// Wait for a quorum of precommits.
+	pub precommit: Duration,
+	/// Wait after a commit before starting the next height.
+	pub commit: Duration,
+}
+
+impl Default for Timeouts {
+	fn default() -> Self {
+		Timeouts {
+			propose: Duration::from_millis(1000),
+			prevote: Duration::from_millis(1000),
+			precommit: Duration::from_millis(1000),
+			commit: Duration::from_millis(1000),
+		}
+	}
+}
+
+impl From<ethjson::spec::TendermintParams> for TendermintParams {
+	fn from(p: ethjson::spec::TendermintParams) -> Self {
+		let defaults = Timeouts::default();
+		let to_duration = |ms: Option<ethjson::uint::Uint>, default: Duration| {
+			ms.map_or(default, |ms| Duration::from_millis(ms.into()))
+		};
+		let posdao_transition = p.posdao_transition.map(Into::into);
+		TendermintParams {
+			validators: new_validator_set_posdao(p.validators, posdao_transition),
+			timeouts: Timeouts {
+				propose: to_duration(p.timeout_propose, defaults.propose),
+				prevote: to_duration(p.timeout_prevote, defaults.prevote),
+				precommit: to_duration(p.timeout_precommit, defaults.precommit),
+				commit: to_duration(p.timeout_commit, defaults.commit),
+			},
+			block_reward: p.block_reward.map_or_else(Default::default, Into::into),
+			posdao_transition,
+		}
+	}
+}
+
+/// Whether `votes` out of `validators` are more than two thirds.
+fn is_quorum(votes: usize, validators: usize) -> bool {
+	votes * 3 > validators * 2
+}
+
+/// Check the seal of a committed block: the proposal must be signed by the proposer of the
+/// round and the precommits by more than two thirds of the validators.
+fn verify_commit(header: &Header, validators: &dyn ValidatorSet) -> Result<(), Error> {
+	let (round, proposer, precommitters) = seal_signers(header)?;
+	let parent_hash = header.parent_hash();
+
+	let expected = validators.get(parent_hash, (header.number() + round) as usize);
+	if proposer != expected {
+		return Err(EngineError::NotProposer(Mismatch { expected, found: proposer }).into());
+	}
+
+	let mut signers = HashSet::new();
+	for signer in precommitters {
+		if !validators.contains(parent_hash, &signer) {
+			return Err(EngineError::NotAuthorized(signer).into());
+		}
+		if !signers.insert(signer) {
+			return Err(EngineError::DoubleVote(signer).into());
+		}
+	}
+
+	let count = validators.count(parent_hash);
+	if !is_quorum(signers.len(), count) {
+		return Err(EngineError::InsufficientProof(
+			format!("{} of {} validators precommitted block {}", signers.len(), count, header.hash())
+		).into());
+	}
+	Ok(())
+}
+
+fn combine_proofs(signal_number: BlockNumber, set_proof: &[u8], finality_proof: &[u8]) -> Vec<u8> {
+	let mut stream = RlpStream::new_list(3);
+	stream.append(&signal_number).append(&set_proof).append(&finality_proof);
+	stream.out()
+}
+
+fn destructure_proofs(combined: &[u8]) -> Result<(BlockNumber, &[u8], &[u8]), Error> {
+	let rlp = Rlp::new(combined);
+	Ok((
+		rlp.at(0)?.as_val()?,
+		rlp.at(1)?.data()?,
+		rlp.at(2)?.data()?,
+	))
+}
+
+struct EpochVerifier {
+	list: SimpleList,
+	eip1559_transition: BlockNumber,
+}
+
+impl engine::EpochVerifier for EpochVerifier {
+	fn verify_light(&self, header: &Header) -> Result<(), Error> {
+		verify_commit(header, &self.list)
+	}
+
+	fn check_finality_proof(&self, proof: &[u8]) -> Option<Vec<H256>> {
+		// a committed block is final by itself.
+		let header = Header::decode_rlp(&Rlp::new(proof), self.eip1559_transition).ok()?;
+		verify_commit(&header, &self.list).ok()?;
+		Some(vec![header.hash()])
+	}
+}
+
+/// Side effects of the consensus state machine, carried out once the state is unlocked.
+enum Action {
+	/// Send a message to the other validators.
+	Broadcast(Bytes),
+	/// Import a committed block.
+	Import(Bytes),
+}
+
+/// Consensus state at the current height.
+#[derive(Default)]
+struct RoundState {
+	/// Number of the block being agreed on.
+	height: BlockNumber,
+	round: u64,
+	step: Step,
+	deadline: Option<Instant>,
+	parent_hash: H256,
+	/// Round and hash of the block this validator prevoted a quorum for.
+	lock: Option<(u64, H256)>,
+	/// Valid proposals of the current height by round, with the hashes of their blocks.
+	proposals: BTreeMap<u64, (H256, Proposal)>,
+	/// Whether this validator proposed in the current round.
+	proposed: bool,
+	/// Hash of the sealed block committed at the current height.
+	committed: Option<H256>,
+	votes: VoteCollector,
+	/// Messages for the next height.
+	future: Vec<Message>,
+}
+
+impl Default for Step {
+	fn default() -> Self {
+		Step::Commit
+	}
+}
+
+/// Engine using the Tendermint BFT algorithm.
+pub struct Tendermint {
+	machine: Machine,
+	validators: Box<dyn ValidatorSet>,
+	timeouts: Timeouts,
+	block_reward: U256,
+	posdao_transition: Option<BlockNumber>,
+	client: RwLock<Option<Weak<dyn EngineClient>>>,
+	signer: RwLock<Option<Box<dyn EngineSigner>>>,
+	state: Mutex<RoundState>,
+}
+
+impl Tendermint {
+	/// Create a new instance of the Tendermint engine and start stepping it.
+	pub fn new(params: TendermintParams, machine: Machine) -> Result<Arc<Self>, Error> {
+		/// Step Tendermint at most every 100 milliseconds
+		const STEP_FREQ: Duration = Duration::from_millis(100);
+
+		let engine = Arc::new(Tendermint::with_params(params, machine));
+		let weak_eng = Arc::downgrade(&engine);
+
+		thread::Builder::new().name("StepService".into())
+			.spawn(move || {
+				loop {
+					let next_step_at = Instant::now() + STEP_FREQ;
+					if let Some(eng) = weak_eng.upgrade() {
+						eng.step()
+					} else {
+						warn!(target: "shutdown", "StepService: engine is dropped; exiting.");
+						break;
+					}
+
+					let now = Instant::now();
+					if now < next_step_at {
+						thread::sleep(next_step_at - now);
+					}
+				}
+			})?;
+		Ok(engine)
+	}
+
+	fn with_params(params: TendermintParams, machine: Machine) -> Self {
+		Tendermint {
+			machine,
+			validators: params.validators,
+			timeouts: params.timeouts,
+			block_reward: params.block_reward,
+			posdao_transition: params.posdao_transition,
+			client: Default::default(),
+			signer: Default::default(),
+			state: Default::default(),
+		}
+	}
+
+	fn upgrade_client(&self) -> Option<Arc<dyn EngineClient>> {
+		self.client.read().as_ref().and_then(|weak| weak.upgrade())
+	}
+
+	fn our_address(&self) -> Option<Address> {
+		self.signer.read().as_ref().map(|signer| signer.address())
+	}
+
+	fn proposer(&self, state: &RoundState, round: u64) -> Address {
+		self.validators.get(&state.parent_hash, (state.height + round) as usize)
+	}
+
+	fn is_validator(&self, state: &RoundState) -> bool {
+		self.our_address().map_or(false, |address| self.validators.contains(&state.parent_hash, &address))
+	}
+
+	fn quorum_reached(&self, state: &RoundState, votes: usize) -> bool {
+		is_quorum(votes, self.validators.count(&state.parent_hash))
+	}
+
+	/// Smallest number of votes more than two thirds of the validators.
+	fn threshold(&self, state: &RoundState) -> usize {
+		self.validators.count(&state.parent_hash) * 2 / 3 + 1
+	}
+
+	fn execute(&self, actions: Vec<Action>) {
+		if actions.is_empty() {
+			return;
+		}
+		let client = match self.upgrade_client() {
+			Some(client) => client,
+			None => {
+				debug!(target: "engine", "Unable to execute consensus actions: missing client ref.");
+				return;
+			},
+		};
+		for action in actions {
+			match action {
+				Action::Broadcast(message) => client.broadcast_consensus_message(message),
+				Action::Import(block) => {
+					let full_client = match client.as_full_client() {
+						Some(full_client) => full_client,
+						None => {
+							warn!(target: "engine", "Unable to import a committed block: no full client.");
+							continue;
+						},
+					};
+					let result = Unverified::from_rlp(block, self.machine.params().eip1559_transition)
+						.map_err(Error::from)
+						.and_then(|block| full_client.import_block(block));
+					match result {
+						Ok(hash) => debug!(target: "engine", "Imported committed block {}.", hash),
+						Err(err) => warn!(target: "engine", "Failed to import a committed block: {}", err),
+					}
+				},
+			}
+		}
+	}
+
+	/// Start agreeing on the child of the given block.
+	fn start_height(&self, state: &mut RoundState, height: BlockNumber, parent_hash: H256, actions: &mut Vec<Action>) {
+		trace!(target: "engine", "Starting height {} on top of {}.", height, parent_hash);
+		state.height = height;
+		state.round = 0;
+		state.step = Step::Commit;
+		state.deadline = Some(Instant::now() + self.timeouts.commit);
+		state.parent_hash = parent_hash;
+		state.lock = None;
+		state.proposals.clear();
+		state.proposed = false;
+		state.committed = None;
+		state.votes.prune(height);
+
+		for message in std::mem::replace(&mut state.future, Vec::new()) {
+			if let Err(err) = self.handle(state, message, actions) {
+				trace!(target: "engine", "Dropped a message for height {}: {}", height, err);
+			}
+		}
+	}
+
+	fn enter_round(&self, state: &mut RoundState, round: u64, actions: &mut Vec<Action>) {
+		trace!(target: "engine", "Entering round {} at height {}.", round, state.height);
+		state.round = round;
+		state.step = Step::Propose;
+		state.deadline = Some(Instant::now() + self.timeouts.propose);
+		state.proposed = false;
+		state.committed = None;
+
+		let is_proposer = self.our_address() == Some(self.proposer(state, round));
+		match (state.lock, is_proposer) {
+			(Some((lock_round, hash)), true) => {
+				// re-propose the block we are locked on.
+				let block = match state.proposals.get(&lock_round) {
+					Some(&(_, ref proposal)) => proposal.block.clone(),
+					None => return,
+				};
+				let vote_step = VoteStep::new(state.height, round, Step::Propose);
+				match self.sign(message_hash(&vote_step, Some(hash))) {
+					Ok(signature) => {
+						let proposal = Proposal { vote_step, block, signature: signature.into() };
+						actions.push(Action::Broadcast(rlp::encode(&Message::Proposal(proposal.clone()))));
+						state.proposals.insert(round, (hash, proposal));
+						state.proposed = true;
+					},
+					Err(err) => warn!(target: "engine", "Failed to sign a proposal: {}", err),
+				}
+			},
+			// the block is proposed once the miner seals it.
+			(None, true) => return,
+			(_, false) => {},
+		}
+
+		if state.proposals.contains_key(&round) {
+			self.prevote(state, actions);
+		}
+		self.check_votes(state, VoteStep::new(state.height, round, Step::Prevote), actions);
+		self.check_votes(state, VoteStep::new(state.height, round, Step::Precommit), actions);
+	}
+
+	/// Release the lock on a committed block that the client rejected, and forget its proposals,
+	/// so that it isn't proposed and voted for again.
+	fn release_rejected(&self, state: &mut RoundState) {
+		if let Some((_, hash)) = state.lock.take() {
+			warn!(target: "engine", "Committed block #{} {} was rejected on import.", state.height, hash);
+			state.proposals.retain(|_, &mut (proposed, _)| proposed != hash);
+		}
+	}
+
+	fn on_timeout(&self, state: &mut RoundState, actions: &mut Vec<Action>) {
+		match state.step {
+			Step::Commit if state.committed.is_some() => {
+				warn!(target: "engine", "Committed block #{} wasn't imported in time.", state.height);
+				let round = state.round + 1;
+				self.enter_round(state, round, actions)
+			},
+			Step::Commit => self.enter_round(state, 0, actions),
+			Step::Propose => self.prevote(state, actions),
+			Step::Prevote => self.precommit(state, None, actions),
+			Step::Precommit => {
+				let round = state.round + 1;
+				self.enter_round(state, round, actions)
+			},
+		}
+	}
+
+	/// Sign and record a vote of ours, and send it to the other validators.
+	fn vote(&self, state: &mut RoundState, step: Step, block_hash: Option<H256>, actions: &mut Vec<Action>) {
+		if !self.is_validator(state) {
+			return;
+		}
+		let vote_step = VoteStep::new(state.height, state.round, step);
+		let signature = match self.sign(message_hash(&vote_step, block_hash)) {
+			Ok(signature) => signature.into(),
+			Err(err) => {
+				warn!(target: "engine", "Failed to sign a vote: {}", err);
+				return;
+			},
+		};
+		let address = self.our_address().expect("is_validator checked the signer is set; qed");
+		if let Ok(true) = state.votes.insert(vote_step, address, block_hash, signature) {
+			actions.push(Action::Broadcast(rlp::encode(&Message::Vote(Vote { vote_step, block_hash, signature }))));
+		}
+		self.check_votes(state, vote_step, actions);
+	}
+
+	/// Prevote for the block we're locked on, or else for the proposal of the round.
+	fn prevote(&self, state: &mut RoundState, actions: &mut Vec<Action>) {
+		let block_hash = match state.lock {
+			Some((_, hash)) => Some(hash),
+			None => state.proposals.get(&state.round).map(|&(hash, _)| hash),
+		};
+		state.step = Step::Prevote;
+		state.deadline = Some(Instant::now() + self.timeouts.prevote);
+		self.vote(state, Step::Prevote, block_hash, actions);
+	}
+
+	fn precommit(&self, state: &mut RoundState, block_hash: Option<H256>, actions: &mut Vec<Action>) {
+		state.step = Step::Precommit;
+		state.deadline = Some(Instant::now() + self.timeouts.precommit);
+		self.vote(state, Step::Precommit, block_hash, actions);
+	}
+
+	/// Act on the votes received at the given step.
+	fn check_votes(&self, state: &mut RoundState, vote_step: VoteStep, actions: &mut Vec<Action>) {
+		if state.committed.is_some() || vote_step.height != state.height {
+			return;
+		}
+		let threshold = self.threshold(state);
+		let majority = state.votes.majority(&vote_step, threshold);
+
+		match vote_step.step {
+			Step::Prevote => {
+				if vote_step.round > state.round && self.quorum_reached(state, state.votes.total(&vote_step)) {
+					// the other validators are ahead of us.
+					self.enter_round(state, vote_step.round, actions);
+					return;
+				}
+				if vote_step.round != state.round || state.step > Step::Prevote {
+					return;
+				}
+				match majority {
+					Some(Some(hash)) => {
+						if state.proposals.get(&vote_step.round).map_or(false, |&(proposed, _)| proposed == hash) {
+							state.lock = Some((vote_step.round, hash));
+						}
+						self.precommit(state, Some(hash), actions);
+					},
+					Some(None) => {
+						state.lock = None;
+						self.precommit(state, None, actions);
+					},
+					None => {},
+				}
+			},
+			Step::Precommit => {
+				if let Some(Some(hash)) = majority {
+					self.commit(state, vote_step.round, hash, actions);
+				} else if vote_step.round > state.round && self.quorum_reached(state, state.votes.total(&vote_step)) {
+					self.enter_round(state, vote_step.round, actions);
+				}
+			},
+			_ => {},
+		}
+	}
+
+	fn commit(&self, state: &mut RoundState, round: u64, hash: H256, actions: &mut Vec<Action>) {
+		let proposal = match state.proposals.get(&round) {
+			Some(&(proposed, ref proposal)) if proposed == hash => proposal,
+			// the block will arrive with the sync.
+			_ => return,
+		};
+		let precommits = state.votes.signatures(&VoteStep::new(state.height, round, Step::Precommit), &Some(hash));
+		let seal = encode_seal(round, &proposal.signature, &precommits);
+		match proposal.sealed_block(seal, self.machine.params().eip1559_transition) {
+			Ok((sealed_hash, block)) => {
+				debug!(target: "engine", "Committed block #{} {} at round {}.", state.height, hash, round);
+				actions.push(Action::Import(block));
+				state.lock = Some((round, hash));
+				state.round = round;
+				state.step = Step::Commit;
+				state.deadline = Some(Instant::now() + self.timeouts.commit + self.timeouts.propose);
+				state.committed = Some(sealed_hash);
+			},
+			Err(err) => warn!(target: "engine", "Failed to seal a committed block: {}", err),
+		}
+	}
+
+	/// Handle a consensus message. Returns whether it's new and should be relayed.
+	fn handle(&self, state: &mut RoundState, message: Message, actions: &mut Vec<Action>) -> Result<bool, EngineError> {
+		let height = message.vote_step().height;
+		if state.height == 0 || height < state.height {
+			return Ok(false);
+		}
+		if height > state.height {
+			if height == state.height + 1 && state.future.len() < MAX_FUTURE_MESSAGES {
+				state.future.push(message);
+			}
+			return Ok(false);
+		}
+
+		match message {
+			Message::Proposal(proposal) => self.handle_proposal(state, proposal, actions),
+			Message::Vote(vote) => self.handle_vote(state, vote, actions),
+		}
+	}
+
+	fn handle_proposal(&self, state: &mut RoundState, proposal: Proposal, actions: &mut Vec<Action>) -> Result<bool, EngineError> {
+		let round = proposal.vote_step.round;
+		if state.proposals.contains_key(&round) {
+			return Ok(false);
+		}
+
+		let signer = proposal.signer(self.machine.params().eip1559_transition).map_err(|err| EngineError::MalformedMessage(err.to_string()))?;
+		let expected = self.proposer(state, round);
+		if signer != expected {
+			return Err(EngineError::NotProposer(Mismatch { expected, found: signer }));
+		}
+		let header = proposal.header(self.machine.params().eip1559_transition).map_err(|err| EngineError::MalformedMessage(err.to_string()))?;
+		if header.number() != state.height || *header.parent_hash() != state.parent_hash {
+			return Err(EngineError::MalformedMessage(
+				format!("Proposal of block #{} doesn't extend block {}.", header.number(), state.parent_hash)
+			));
+		}
+		let client = self.upgrade_client().ok_or(EngineError::RequiresClient)?;
+		let block = Unverified::from_rlp(proposal.block.clone(), self.machine.params().eip1559_transition)
+			.map_err(|err| EngineError::MalformedMessage(err.to_string()))?;
+		if let Err(err) = client.verify_unsealed_block(block) {
+			return Err(EngineError::MalformedMessage(
+				format!("Proposal of block #{} is invalid: {}", header.number(), err)
+			));
+		}
+
+		trace!(target: "engine", "Received a proposal for round {} at height {}.", round, state.height);
+		state.proposals.insert(round, (header.bare_hash(), proposal));
+		if round == state.round && state.step == Step::Propose {
+			self.prevote(state, actions);
+		}
+		// a quorum may have precommitted it before it arrived.
+		self.check_votes(state, VoteStep::new(state.height, round, Step::Precommit), actions);
+		Ok(true)
+	}
+
+	fn handle_vote(&self, state: &mut RoundState, vote: Vote, actions: &mut Vec<Action>) -> Result<bool, EngineError> {
+		let signer = vote.signer().map_err(|err| EngineError::MalformedMessage(err.to_string()))?;
+		if !self.validators.contains(&state.parent_hash, &signer) {
+			return Err(EngineError::NotAuthorized(signer));
+		}
+		match state.votes.insert(vote.vote_step, signer, vote.block_hash, vote.signature) {
+			Ok(false) => Ok(false),
+			Ok(true) => {
+				self.check_votes(state, vote.vote_step, actions);
+				Ok(true)
+			},
+			Err(DoubleVote(address)) => Err(EngineError::DoubleVote(address)),
+		}
+	}
+
+	fn run_posdao(&self, block: &ExecutedBlock) -> Result<Vec<SignedTransaction>, Error> {
+		// Skip the rest of the function unless there has been a transition to POSDAO.
+		if self.posdao_transition.map_or(true, |posdao_block| block.header.number() < posdao_block) {
+			trace!(target: "engine", "Skipping POSDAO calls to validator set contracts");
+			return Ok(Vec::new());
+		}
+
+		let our_addr = match self.our_address() {
+			Some(address) => address,
+			None => return Ok(Vec::new()), // We are not a validator, so we shouldn't call the contracts.
+		};
+		let client = self.upgrade_client().ok_or(EngineError::RequiresClient)?;
+		let full_client = client.as_full_client().ok_or_else(|| {
+			EngineError::FailedSystemCall("Failed to upgrade to BlockchainClient.".to_string())
+		})?;
+
+		// Makes a constant contract call.
+		let mut call = |to: Address, data: Bytes| {
+			full_client.call_contract(BlockId::Latest, to, data).map_err(|e| format!("{}", e))
+		};
+
+		// Our current account nonce. The transactions must have consecutive nonces, starting with this one.
+		let mut tx_nonce = block.state.nonce(&our_addr)?;
+		let mut transactions = Vec::new();
+
+		let first = block.header.number() == 0;
+		for (to, data) in self.validators.generate_engine_transactions(first, &block.header, &mut call)? {
+			let tx_request = TransactionRequest::call(to, data).gas_price(U256::zero()).nonce(tx_nonce);
+			tx_nonce += U256::one();
+			transactions.push(full_client.create_transaction(tx_request)?);
+		}
+
+		Ok(transactions)
+	}
+}
+
+impl Engine for Tendermint {
+	fn name(&self) -> &str { "Tendermint" }
+
+	fn machine(&self) -> &Machine { &self.machine }
+
+	/// Round, proposal signature and precommit signatures.
+	fn seal_fields(&self, _header: &Header) -> usize { message::SEAL_FIELDS }
+
+	fn maximum_uncle_count(&self, _block: BlockNumber) -> usize { 0 }
+
+	fn populate_from_parent(&self, header: &mut Header, _parent: &Header) {
+		// every committed block weighs the same.
+		header.set_difficulty(U256::one());
+	}
+
+	fn sealing_state(&self) -> SealingState {
+		let our_address = match self.our_address() {
+			Some(address) => address,
+			None => return SealingState::NotReady,
+		};
+		let state = self.state.lock();
+		if state.height != 0 && state.step == Step::Propose && !state.proposed
+			&& self.proposer(&state, state.round) == our_address
+		{
+			SealingState::Ready
+		} else {
+			SealingState::NotReady
+		}
+	}
+
+	/// Propose the block to the other validators. It is sealed once they commit it.
+	fn generate_seal(&self, block: &ExecutedBlock, _parent: &Header) -> Seal {
+		let header = &block.header;
+		let mut actions = Vec::new();
+		{
+			let mut state = self.state.lock();
+			if header.number() != state.height || *header.parent_hash() != state.parent_hash
+				|| state.step != Step::Propose || state.proposed
+			{
+				return Seal::None;
+			}
+			if *header.author() != self.proposer(&state, state.round) {
+				trace!(target: "engine", "generate_seal: {} not the proposer of round {}.", header.author(), state.round);
+				return Seal::None;
+			}
+
+			let mut s = RlpStream::new_list(3);
+			s.append(header);
+			s.append_list(&block.transactions);
+			s.append_list(&block.uncles);
+
+			let vote_step = VoteStep::new(state.height, state.round, Step::Propose);
+			let hash = header.bare_hash();
+			let signature = match self.sign(message_hash(&vote_step, Some(hash))) {
+				Ok(signature) => signature.into(),
+				Err(err) => {
+					warn!(target: "engine", "generate_seal: FAIL: {}", err);
+					return Seal::None;
+				},
+			};
+			let proposal = Proposal { vote_step, block: s.out(), signature };
+			debug!(target: "engine", "Proposing block #{} {} at round {}.", state.height, hash, state.round);
+			actions.push(Action::Broadcast(rlp::encode(&Message::Proposal(proposal.clone()))));
+
+			let round = state.round;
+			state.proposals.insert(round, (hash, proposal));
+			state.proposed = true;
+			self.prevote(&mut state, &mut actions);
+		}
+		self.execute(actions);
+		Seal::None
+	}
+
+	fn verify_local_seal(&self, _header: &Header) -> Result<(), Error> {
+		Ok(())
+	}
+
+	fn verify_block_basic(&self, header: &Header) -> Result<(), Error> {
+		let seal_length = header.seal().len();
+		if seal_length != message::SEAL_FIELDS {
+			return Err(BlockError::InvalidSealArity(
+				Mismatch { expected: message::SEAL_FIELDS, found: seal_length }
+			).into());
+		}
+		if *header.difficulty() != U256::one() {
+			return Err(BlockError::InvalidDifficulty(
+				Mismatch { expected: U256::one(), found: *header.difficulty() }
+			).into());
+		}
+		Ok(())
+	}
+
+	fn verify_block_external(&self, header: &Header) -> Result<(), Error> {
+		verify_commit(header, &*self.validators)
+	}
+
+	fn on_new_block(&self, block: &mut ExecutedBlock, epoch_begin: bool) -> Result<(), Error> {
+		if !epoch_begin { return Ok(()) }
+
+		// genesis is never a new block, but might as well check.
+		let header = block.header.clone();
+		let first = header.number() == 0;
+
+		let mut call = |to, data| {
+			let result = self.machine.execute_as_system(
+				block,
+				to,
+				U256::max_value(), // unbounded gas? maybe make configurable.
+				Some(data),
+			);
+
+			result.map_err(|e| format!("{}", e))
+		};
+
+		self.validators.on_epoch_begin(first, &header, &mut call)
+	}
+
+	/// Apply the block reward on finalisation of the block.
+	fn on_close_block(&self, block: &mut ExecutedBlock, _parent: &Header) -> Result<(), Error> {
+		let author = *block.header.author();
+		block_reward::apply_block_rewards(&[(author, RewardKind::Author, self.block_reward)], block, &self.machine)
+	}
+
+	fn generate_engine_transactions(&self, block: &ExecutedBlock) -> Result<Vec<SignedTransaction>, Error> {
+		self.run_posdao(block)
+	}
+
+	fn genesis_epoch_data(&self, header: &Header, call: &Call) -> Result<Vec<u8>, String> {
+		self.validators.genesis_epoch_data(header, call)
+			.map(|set_proof| combine_proofs(0, &set_proof, &[]))
+	}
+
+	fn signals_epoch_end(&self, header: &Header, aux: AuxiliaryData) -> engine::EpochChange {
+		let first = header.number() == 0;
+		self.validators.signals_epoch_end(first, header, aux)
+	}
+
+	fn is_epoch_end(
+		&self,
+		chain_head: &Header,
+		_finalized: &[H256],
+		_chain: &Headers<Header>,
+		transition_store: &PendingTransitionStore,
+	) -> Option<Vec<u8>> {
+		let first = chain_head.number() == 0;
+
+		// Apply transitions that don't require finality and should be enacted immediately (e.g from chain spec)
+		if let Some(change) = self.validators.is_epoch_end(first, chain_head) {
+			return Some(combine_proofs(chain_head.number(), &change, &[]));
+		}
+
+		// the head is final, so a change it signals is enacted right away, with the head as proof.
+		transition_store(chain_head.hash()).map(|pending| {
+			combine_proofs(chain_head.number(), &pending.proof, &rlp::encode(chain_head))
+		})
+	}
+
+	fn is_epoch_end_light(
+		&self,
+		chain_head: &Header,
+		chain: &Headers<Header>,
+		transition_store: &PendingTransitionStore,
+	) -> Option<Vec<u8>> {
+		self.is_epoch_end(chain_head, &[], chain, transition_store)
+	}
+
+	fn epoch_verifier<'a>(&self, _header: &Header, proof: &'a [u8]) -> ConstructedVerifier<'a> {
+		let (signal_number, set_proof, finality_proof) = match destructure_proofs(proof) {
+			Ok(x) => x,
+			Err(e) => return ConstructedVerifier::Err(e),
+		};
+
+		let first = signal_number == 0;
+		match self.validators.epoch_set(first, &self.machine, signal_number, set_proof) {
+			Ok((list, finalize)) => {
+				let verifier = Box::new(EpochVerifier { list, eip1559_transition: self.machine.params().eip1559_transition });
+				match finalize {
+					Some(finalize) => ConstructedVerifier::Unconfirmed(verifier, finality_proof, finalize),
+					None => ConstructedVerifier::Trusted(verifier),
+				}
+			}
+			Err(e) => ConstructedVerifier::Err(e),
+		}
+	}
+
+	fn ancestry_actions(&self, header: &Header, _ancestry: &mut dyn Iterator<Item=ExtendedHeader>) -> Vec<AncestryAction> {
+		// a valid seal commits the block, finalizing it and its ancestors.
+		vec![AncestryAction::MarkFinalized(header.hash())]
+	}
+
+	fn handle_message(&self, rlp: &[u8]) -> Result<(), EngineError> {
+		let message: Message = rlp::decode(rlp).map_err(|err| EngineError::MalformedMessage(format!("{:?}", err)))?;
+		let mut actions = Vec::new();
+		let result = {
+			let mut state = self.state.lock();
+			self.handle(&mut state, message, &mut actions)
+		};
+		if let Ok(true) = result {
+			actions.push(Action::Broadcast(rlp.to_vec()));
+		}
+		self.execute(actions);
+		result.map(|_| ())
+	}
+
+	fn step(&self) {
+		let client = match self.upgrade_client() {
+			Some(client) => client,
+			None => return,
+		};
+		let best = match client.block_header(BlockId::Latest) {
+			Some(header) => header,
+			None => return,
+		};
+
+		let mut actions = Vec::new();
+		let propose = {
+			let mut state = self.state.lock();
+			if best.number() + 1 > state.height
+				|| (best.number() + 1 == state.height && best.hash() != state.parent_hash)
+			{
+				self.start_height(&mut state, best.number() + 1, best.hash(), &mut actions);
+			} else if state.deadline.map_or(false, |deadline| Instant::now() >= deadline) {
+				let rejected = state.committed.map_or(false, |hash| {
+					client.as_full_client().map_or(false, |full_client| {
+						match full_client.block_status(BlockId::Hash(hash)) {
+							BlockStatus::Bad | BlockStatus::Unknown => true,
+							BlockStatus::InChain | BlockStatus::Queued => false,
+						}
+					})
+				});
+				if rejected {
+					self.release_rejected(&mut state);
+				}
+				self.on_timeout(&mut state, &mut actions);
+			}
+			state.step == Step::Propose && !state.proposed
+				&& self.our_address() == Some(self.proposer(&state, state.round))
+		};
+		self.execute(actions);
+
+		if propose {
+			// keep asking the miner for a block until it's proposed or the round is over.
+			client.update_sealing(ForceUpdateSealing::No);
+		}
+	}
+
+	fn snapshot_mode(&self) -> Snapshotting {
+		Snapshotting::PoA
+	}
+
+	fn register_client(&self, client: Weak<dyn EngineClient>) {
+		*self.client.write() = Some(client.clone());
+		self.validators.register_client(client);
+	}
+
+	fn set_signer(&self, signer: Option<Box<dyn EngineSigner>>) {
+		*self.signer.write() = signer;
+	}
+
+	fn sign(&self, hash: H256) -> Result<Signature, Error> {
+		Ok(self.signer.read()
+			.as_ref()
+			.ok_or(parity_crypto::publickey::Error::InvalidAddress)?
+			.sign(hash)?
+		)
+	}
+
+	fn params(&self) -> &CommonParams {
+		self.machine.params()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::collections::{BTreeMap, HashSet};
+	use std::sync::{Arc, Weak};
+
+	use client_traits::{BlockChainClient, ChainInfo, EngineClient, ForceUpdateSealing};
+	use common_types::{
+		BlockNumber,
+		blockchain_info::BlockChainInfo,
+		encoded,
+		engines::{epoch::Transition as EpochTransition, params::CommonParams},
+		header::Header,
+		errors::{EngineError, EthcoreError as Error, EthcoreResult},
+		ids::BlockId,
+		verification::Unverified,
+	};
+	use engine::{Engine, signer::from_keypair};
+	use ethereum_types::{H256, H520};
+	use machine::Machine;
+	use parity_crypto::publickey::{Generator, KeyPair, Random, sign};
+	use rlp::{Rlp, RlpStream};
+	use validator_set::{SimpleList, ValidatorSet};
+
+	use super::{
+		Action, Message, Proposal, RoundState, Step, Tendermint, TendermintParams, Timeouts, Vote, VoteStep,
+		encode_seal, message_hash, verify_commit,
+	};
+
+	/// Client that finds every proposed block valid, except for the blocks with the given bare hashes.
+	struct TestClient {
+		invalid: HashSet<H256>,
+	}
+
+	impl ChainInfo for TestClient {
+		fn chain_info(&self) -> BlockChainInfo {
+			BlockChainInfo {
+				total_difficulty: Default::default(),
+				pending_total_difficulty: Default::default(),
+				genesis_hash: Default::default(),
+				best_block_hash: Default::default(),
+				best_block_number: 0,
+				best_block_timestamp: 0,
+				ancient_block_hash: None,
+				ancient_block_number: None,
+				first_block_hash: None,
+				first_block_number: None,
+			}
+		}
+	}
+
+	impl EngineClient for TestClient {
+		fn update_sealing(&self, _force: ForceUpdateSealing) {}
+
+		fn submit_seal(&self, _block_hash: H256, _seal: Vec<Vec<u8>>) {}
+
+		fn broadcast_consensus_message(&self, _message: Vec<u8>) {}
+
+		fn epoch_transition_for(&self, _parent_hash: H256) -> Option<EpochTransition> { None }
+
+		fn verify_unsealed_block(&self, block: Unverified) -> EthcoreResult<()> {
+			if self.invalid.contains(&block.header.bare_hash()) {
+				Err("invalid block".into())
+			} else {
+				Ok(())
+			}
+		}
+
+		fn as_full_client(&self) -> Option<&dyn BlockChainClient> { None }
+
+		fn block_number(&self, _id: BlockId) -> Option<BlockNumber> { None }
+
+		fn block_header(&self, _id: BlockId) -> Option<encoded::Header> { None }
+	}
+
+	/// Engine run by the last of the given validators, with a client rejecting the given blocks.
+	fn engine_with_client(keys: &[KeyPair], list: &SimpleList, invalid: HashSet<H256>) -> (Tendermint, Arc<TestClient>) {
+		let engine = Tendermint::with_params(TendermintParams {
+			validators: Box::new(list.clone()),
+			timeouts: Timeouts::default(),
+			block_reward: Default::default(),
+			posdao_transition: None,
+		}, Machine::regular(CommonParams::default(), BTreeMap::new()));
+		engine.set_signer(Some(from_keypair(keys[3].clone())));
+		let client = Arc::new(TestClient { invalid });
+		engine.register_client(Arc::downgrade(&client) as Weak<dyn EngineClient>);
+		(engine, client)
+	}
+
+	/// Proposal of the given empty block by its proposer.
+	fn proposal(key: &KeyPair, header: &Header, round: u64) -> Proposal {
+		let mut block = RlpStream::new_list(3);
+		block.append(header).begin_list(0).begin_list(0);
+		let vote_step = VoteStep::new(header.number(), round, Step::Propose);
+		Proposal { vote_step, block: block.out(), signature: signed(key, &vote_step, Some(header.bare_hash())) }
+	}
+
+	fn signed(key: &KeyPair, vote_step: &VoteStep, block_hash: Option<H256>) -> H520 {
+		sign(key.secret(), &message_hash(vote_step, block_hash)).unwrap().into()
+	}
+
+	/// Four validators, ordered as proposers of consecutive rounds starting at height 1 round 0.
+	fn validators(parent_hash: &H256) -> (Vec<KeyPair>, SimpleList) {
+		let keys: Vec<_> = (0..4).map(|_| Random.generate().unwrap()).collect();
+		let list = SimpleList::new(keys.iter().map(KeyPair::address).collect());
+		let mut ordered = Vec::new();
+		for nonce in 1..5 {
+			let address = list.get(parent_hash, nonce);
+			ordered.push(keys.iter().find(|key| key.address() == address).unwrap().clone());
+		}
+		(ordered, list)
+	}
+
+	fn child_of(parent_hash: H256) -> Header {
+		let mut header = Header::default();
+		header.set_number(1);
+		header.set_parent_hash(parent_hash);
+		header
+	}
+
+	#[test]
+	fn verifies_commits() {
+		let parent_hash = H256::from_low_u64_be(1);
+		let (keys, list) = validators(&parent_hash);
+		let mut header = child_of(parent_hash);
+		let hash = header.bare_hash();
+
+		let proposal = signed(&keys[1], &VoteStep::new(1, 1, Step::Propose), Some(hash));
+		let precommits = |signers: &[&KeyPair]| -> Vec<H520> {
+			signers.iter().map(|key| signed(key, &VoteStep::new(1, 1, Step::Precommit), Some(hash))).collect()
+		};
+
+		header.set_seal(encode_seal(1, &proposal, &precommits(&[&keys[0], &keys[1], &keys[2]])));
+		assert!(verify_commit(&header, &list).is_ok());
+
+		// two thirds are not enough.
+		header.set_seal(encode_seal(1, &proposal, &precommits(&[&keys[0], &keys[1]])));
+		match verify_commit(&header, &list) {
+			Err(Error::Engine(EngineError::InsufficientProof(_))) => {},
+			other => panic!("unexpected result: {:?}", other),
+		}
+
+		// a precommit counts once.
+		header.set_seal(encode_seal(1, &proposal, &precommits(&[&keys[0], &keys[1], &keys[1]])));
+		match verify_commit(&header, &list) {
+			Err(Error::Engine(EngineError::DoubleVote(_))) => {},
+			other => panic!("unexpected result: {:?}", other),
+		}
+
+		// the proposal of round 1 is only valid from its proposer.
+		let wrong_proposal = signed(&keys[0], &VoteStep::new(1, 1, Step::Propose), Some(hash));
+		header.set_seal(encode_seal(1, &wrong_proposal, &precommits(&[&keys[0], &keys[1], &keys[2]])));
+		match verify_commit(&header, &list) {
+			Err(Error::Engine(EngineError::NotProposer(_))) => {},
+			other => panic!("unexpected result: {:?}", other),
+		}
+
+		// precommits of outsiders don't count.
+		let outsider = Random.generate().unwrap();
+		header.set_seal(encode_seal(1, &proposal, &precommits(&[&keys[0], &keys[1], &outsider])));
+		match verify_commit(&header, &list) {
+			Err(Error::Engine(EngineError::NotAuthorized(address))) => assert_eq!(address, outsider.address()),
+			other => panic!("unexpected result: {:?}", other),
+		}
+	}
+
+	#[test]
+	fn commits_a_proposal_voted_by_a_quorum() {
+		let parent_hash = H256::from_low_u64_be(1);
+		let (keys, list) = validators(&parent_hash);
+		let (engine, _client) = engine_with_client(&keys, &list, HashSet::new());
+
+		let mut state = RoundState::default();
+		let mut actions = Vec::new();
+		engine.start_height(&mut state, 1, parent_hash, &mut actions);
+		engine.enter_round(&mut state, 0, &mut actions);
+		assert_eq!(state.step, Step::Propose);
+
+		// the proposal of round 0 comes from the first validator.
+		let header = child_of(parent_hash);
+		let hash = header.bare_hash();
+		let proposal = proposal(&keys[0], &header, 0);
+		assert!(engine.handle(&mut state, Message::Proposal(proposal.clone()), &mut actions).unwrap());
+		assert!(!engine.handle(&mut state, Message::Proposal(proposal), &mut actions).unwrap());
+		assert_eq!(state.step, Step::Prevote);
+
+		let vote = |key: &KeyPair, step: Step| {
+			let vote_step = VoteStep::new(1, 0, step);
+			Message::Vote(Vote { vote_step, block_hash: Some(hash), signature: signed(key, &vote_step, Some(hash)) })
+		};
+		for key in &keys[..2] {
+			assert!(engine.handle(&mut state, vote(key, Step::Prevote), &mut actions).unwrap());
+		}
+		assert_eq!(state.step, Step::Precommit);
+		assert_eq!(state.lock, Some((0, hash)));
+
+		let outsider = Random.generate().unwrap();
+		match engine.handle(&mut state, vote(&outsider, Step::Precommit), &mut actions) {
+			Err(EngineError::NotAuthorized(address)) => assert_eq!(address, outsider.address()),
+			other => panic!("unexpected result: {:?}", other),
+		}
+		for key in &keys[..2] {
+			assert!(engine.handle(&mut state, vote(key, Step::Precommit), &mut actions).unwrap());
+		}
+		assert_eq!(state.step, Step::Commit);
+
+		let imported = actions.iter().filter_map(|action| match *action {
+			Action::Import(ref block) => Some(block.clone()),
+			_ => None,
+		}).collect::<Vec<_>>();
+		assert_eq!(imported.len(), 1);
+		let header = Header::decode_rlp(&Rlp::new(&imported[0]).at(0).unwrap(), BlockNumber::max_value()).unwrap();
+		assert_eq!(header.bare_hash(), hash);
+		assert!(engine.verify_block_external(&header).is_ok());
+
+		// messages for the next height wait for the block to be imported.
+		let next_step = VoteStep::new(2, 0, Step::Prevote);
+		let next_vote = Vote { vote_step: next_step, block_hash: None, signature: signed(&keys[0], &next_step, None) };
+		assert!(!engine.handle(&mut state, Message::Vote(next_vote), &mut actions).unwrap());
+		engine.start_height(&mut state, 2, header.hash(), &mut actions);
+		assert_eq!(state.votes.count(&next_step, &None), 1);
+	}
+
+	#[test]
+	fn does_not_prevote_for_an_invalid_proposal() {
+		let parent_hash = H256::from_low_u64_be(1);
+		let (keys, list) = validators(&parent_hash);
+		let header = child_of(parent_hash);
+		let invalid = vec![header.bare_hash()].into_iter().collect();
+		let (engine, _client) = engine_with_client(&keys, &list, invalid);
+
+		let mut state = RoundState::default();
+		let mut actions = Vec::new();
+		engine.start_height(&mut state, 1, parent_hash, &mut actions);
+		engine.enter_round(&mut state, 0, &mut actions);
+
+		match engine.handle(&mut state, Message::Proposal(proposal(&keys[0], &header, 0)), &mut actions) {
+			Err(EngineError::MalformedMessage(_)) => {},
+			other => panic!("unexpected result: {:?}", other),
+		}
+		assert!(state.proposals.is_empty());
+		assert_eq!(state.step, Step::Propose);
+
+		// without a valid proposal the round times out with a prevote for nothing.
+		engine.on_timeout(&mut state, &mut actions);
+		assert_eq!(state.step, Step::Prevote);
+		assert_eq!(state.votes.count(&VoteStep::new(1, 0, Step::Prevote), &None), 1);
+	}
+
+	#[test]
+	fn releases_the_lock_on_a_rejected_commit() {
+		let parent_hash = H256::from_low_u64_be(1);
+		let (keys, list) = validators(&parent_hash);
+		let (engine, _client) = engine_with_client(&keys, &list, HashSet::new());
+
+		let mut state = RoundState::default();
+		let mut actions = Vec::new();
+		engine.start_height(&mut state, 1, parent_hash, &mut actions);
+		engine.enter_round(&mut state, 0, &mut actions);
+
+		let header = child_of(parent_hash);
+		let hash = header.bare_hash();
+		assert!(engine.handle(&mut state, Message::Proposal(proposal(&keys[0], &header, 0)), &mut actions).unwrap());
+		for &step in &[Step::Prevote, Step::Precommit] {
+			for key in &keys[..2] {
+				let vote_step = VoteStep::new(1, 0, step);
+				let vote = Vote { vote_step, block_hash: Some(hash), signature: signed(key, &vote_step, Some(hash)) };
+				assert!(engine.handle(&mut state, Message::Vote(vote), &mut actions).unwrap());
+			}
+		}
+		assert_eq!(state.step, Step::Commit);
+		assert_eq!(state.lock, Some((0, hash)));
+		assert!(state.committed.is_some());
+
+		// the client rejected the committed block: the next round starts without the lock on it.
+		engine.release_rejected(&mut state);
+		engine.on_timeout(&mut state, &mut actions);
+		assert_eq!(state.round, 1);
+		assert_eq!(state.step, Step::Propose);
+		assert_eq!(state.lock, None);
+		assert!(state.proposals.is_empty());
+		assert_eq!(state.committed, None);
+
+		engine.on_timeout(&mut state, &mut actions);
+		assert_eq!(state.votes.count(&VoteStep::new(1, 1, Step::Prevote), &None), 1);
+	}
+}
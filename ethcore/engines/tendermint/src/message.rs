@@ -0,0 +1,372 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Parity Ethereum.
+
+// Parity Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Tendermint consensus messages and seal.
+//!
+//! Every message is signed over the hash of its vote step and the bare hash of the block
+//! it's about. A proposal carries the whole unsealed block, a vote only its hash, or nothing
+//! for a vote for no block. The seal of a committed block holds the round, the signature of
+//! the proposal and the signatures of the precommits.
+
+use common_types::{
+	BlockNumber,
+	errors::{BlockError, EthcoreError as Error},
+	header::Header,
+};
+use ethereum_types::{Address, H256, H520};
+use keccak_hash::keccak;
+use parity_bytes::Bytes;
+use parity_crypto::publickey::{public_to_address, recover};
+use rlp::{Decodable, DecoderError, Encodable, Rlp, RlpStream};
+use unexpected::Mismatch;
+
+/// Number of seal fields: round, proposal signature and precommit signatures.
+pub const SEAL_FIELDS: usize = 3;
+
+/// Step of a consensus round.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Step {
+	/// Waiting for the proposal of the round.
+	Propose,
+	/// Voting for the proposal.
+	Prevote,
+	/// Committing to the proposal a quorum voted for.
+	Precommit,
+	/// A block was committed, waiting for the next height.
+	Commit,
+}
+
+impl Step {
+	fn number(&self) -> u8 {
+		match *self {
+			Step::Propose => 0,
+			Step::Prevote => 1,
+			Step::Precommit => 2,
+			Step::Commit => 3,
+		}
+	}
+}
+
+impl Encodable for Step {
+	fn rlp_append(&self, s: &mut RlpStream) {
+		s.append(&self.number());
+	}
+}
+
+impl Decodable for Step {
+	fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+		match rlp.as_val()? {
+			0u8 => Ok(Step::Propose),
+			1 => Ok(Step::Prevote),
+			2 => Ok(Step::Precommit),
+			_ => Err(DecoderError::Custom("Invalid step.")),
+		}
+	}
+}
+
+/// Position of a message in the consensus: height, round and step.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct VoteStep {
+	/// Number of the block being agreed on.
+	pub height: BlockNumber,
+	/// Round at the height.
+	pub round: u64,
+	/// Step of the round.
+	pub step: Step,
+}
+
+impl VoteStep {
+	/// Create a new instance.
+	pub fn new(height: BlockNumber, round: u64, step: Step) -> Self {
+		VoteStep { height, round, step }
+	}
+}
+
+impl Encodable for VoteStep {
+	fn rlp_append(&self, s: &mut RlpStream) {
+		s.begin_list(3)
+			.append(&self.height)
+			.append(&self.round)
+			.append(&self.step);
+	}
+}
+
+impl Decodable for VoteStep {
+	fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+		if rlp.item_count()? != 3 {
+			return Err(DecoderError::RlpIncorrectListLen);
+		}
+		Ok(VoteStep {
+			height: rlp.val_at(0)?,
+			round: rlp.val_at(1)?,
+			step: rlp.val_at(2)?,
+		})
+	}
+}
+
+/// Hash signed by a message at the given vote step about the given block.
+pub fn message_hash(vote_step: &VoteStep, block_hash: Option<H256>) -> H256 {
+	let mut s = RlpStream::new_list(2);
+	s.append(vote_step);
+	match block_hash {
+		Some(ref hash) => s.append(hash),
+		None => s.append_empty_data(),
+	};
+	keccak(s.out())
+}
+
+fn recover_signer(signature: &H520, hash: &H256) -> Result<Address, Error> {
+	let public = recover(&(*signature).into(), hash)?;
+	Ok(public_to_address(&public))
+}
+
+/// Prevote or precommit for a block, or for no block.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Vote {
+	/// Step of the vote.
+	pub vote_step: VoteStep,
+	/// Bare hash of the block voted for.
+	pub block_hash: Option<H256>,
+	/// Signature of the voter.
+	pub signature: H520,
+}
+
+impl Vote {
+	/// The validator which signed the vote.
+	pub fn signer(&self) -> Result<Address, Error> {
+		recover_signer(&self.signature, &message_hash(&self.vote_step, self.block_hash))
+	}
+}
+
+/// Proposal of a block for a round.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Proposal {
+	/// Step of the proposal.
+	pub vote_step: VoteStep,
+	/// The unsealed block: header, transactions and uncles.
+	pub block: Bytes,
+	/// Signature of the proposer.
+	pub signature: H520,
+}
+
+impl Proposal {
+	/// Header of the proposed block.
+	pub fn header(&self, eip1559_transition: BlockNumber) -> Result<Header, DecoderError> {
+		Header::decode_rlp(&Rlp::new(&self.block).at(0)?, eip1559_transition)
+	}
+
+	/// Bare hash of the proposed block.
+	pub fn block_hash(&self, eip1559_transition: BlockNumber) -> Result<H256, DecoderError> {
+		Ok(self.header(eip1559_transition)?.bare_hash())
+	}
+
+	/// The validator which signed the proposal.
+	pub fn signer(&self, eip1559_transition: BlockNumber) -> Result<Address, Error> {
+		recover_signer(&self.signature, &message_hash(&self.vote_step, Some(self.block_hash(eip1559_transition)?)))
+	}
+
+	/// The proposed block with the given seal, and its hash.
+	pub fn sealed_block(&self, seal: Vec<Bytes>, eip1559_transition: BlockNumber) -> Result<(H256, Bytes), DecoderError> {
+		let rlp = Rlp::new(&self.block);
+		let mut header = Header::decode_rlp(&rlp.at(0)?, eip1559_transition)?;
+		header.set_seal(seal);
+
+		let mut s = RlpStream::new_list(3);
+		s.append(&header);
+		s.append_raw(rlp.at(1)?.as_raw(), 1);
+		s.append_raw(rlp.at(2)?.as_raw(), 1);
+		Ok((header.hash(), s.out()))
+	}
+}
+
+/// A consensus message.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Message {
+	/// Proposal of a block.
+	Proposal(Proposal),
+	/// Vote for a block.
+	Vote(Vote),
+}
+
+impl Message {
+	/// Step of the message.
+	pub fn vote_step(&self) -> &VoteStep {
+		match *self {
+			Message::Proposal(ref proposal) => &proposal.vote_step,
+			Message::Vote(ref vote) => &vote.vote_step,
+		}
+	}
+}
+
+impl Encodable for Message {
+	fn rlp_append(&self, s: &mut RlpStream) {
+		s.begin_list(3);
+		match *self {
+			Message::Proposal(ref proposal) => {
+				s.append(&proposal.vote_step);
+				s.append_raw(&proposal.block, 1);
+				s.append(&proposal.signature);
+			},
+			Message::Vote(ref vote) => {
+				s.append(&vote.vote_step);
+				match vote.block_hash {
+					Some(ref hash) => s.append(hash),
+					None => s.append_empty_data(),
+				};
+				s.append(&vote.signature);
+			},
+		}
+	}
+}
+
+impl Decodable for Message {
+	fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+		if rlp.item_count()? != 3 {
+			return Err(DecoderError::RlpIncorrectListLen);
+		}
+		let vote_step: VoteStep = rlp.val_at(0)?;
+		let signature = rlp.val_at(2)?;
+		let payload = rlp.at(1)?;
+
+		Ok(match vote_step.step {
+			Step::Propose => Message::Proposal(Proposal {
+				vote_step,
+				block: payload.as_raw().to_vec(),
+				signature,
+			}),
+			_ => Message::Vote(Vote {
+				vote_step,
+				block_hash: if payload.is_empty() { None } else { Some(payload.as_val()?) },
+				signature,
+			}),
+		})
+	}
+}
+
+/// Seal of a block committed at the given round.
+pub fn encode_seal(round: u64, proposal_signature: &H520, precommits: &[H520]) -> Vec<Bytes> {
+	let mut s = RlpStream::new();
+	s.append_list(precommits);
+	vec![
+		rlp::encode(&round),
+		rlp::encode(proposal_signature),
+		s.out(),
+	]
+}
+
+/// The round, proposal signature and precommit signatures of a sealed header.
+pub fn decode_seal(header: &Header) -> Result<(u64, H520, Vec<H520>), Error> {
+	let seal = header.seal();
+	if seal.len() != SEAL_FIELDS {
+		return Err(BlockError::InvalidSealArity(Mismatch { expected: SEAL_FIELDS, found: seal.len() }).into());
+	}
+	Ok((
+		Rlp::new(&seal[0]).as_val()?,
+		Rlp::new(&seal[1]).as_val()?,
+		Rlp::new(&seal[2]).as_list()?,
+	))
+}
+
+/// The proposer of the block and the precommitting validators of a sealed header.
+pub fn seal_signers(header: &Header) -> Result<(u64, Address, Vec<Address>), Error> {
+	let (round, proposal_signature, precommits) = decode_seal(header)?;
+	let bare_hash = header.bare_hash();
+	let proposal_hash = message_hash(&VoteStep::new(header.number(), round, Step::Propose), Some(bare_hash));
+	let precommit_hash = message_hash(&VoteStep::new(header.number(), round, Step::Precommit), Some(bare_hash));
+
+	let proposer = recover_signer(&proposal_signature, &proposal_hash)?;
+	let precommitters = precommits.iter()
+		.map(|signature| recover_signer(signature, &precommit_hash))
+		.collect::<Result<_, _>>()?;
+	Ok((round, proposer, precommitters))
+}
+
+#[cfg(test)]
+mod tests {
+	use common_types::header::Header;
+	use ethereum_types::{H256, H520};
+	use parity_crypto::publickey::{Generator, KeyPair, Random, sign};
+	use rlp::{encode, decode};
+
+	use super::{Message, Proposal, Step, Vote, VoteStep, encode_seal, message_hash, seal_signers};
+
+	fn signed(key: &KeyPair, vote_step: &VoteStep, block_hash: Option<H256>) -> H520 {
+		sign(key.secret(), &message_hash(vote_step, block_hash)).unwrap().into()
+	}
+
+	#[test]
+	fn votes_roundtrip() {
+		let key = Random.generate().unwrap();
+		for &block_hash in &[Some(H256::from_low_u64_be(7)), None] {
+			let vote_step = VoteStep::new(5, 1, Step::Prevote);
+			let vote = Message::Vote(Vote { vote_step, block_hash, signature: signed(&key, &vote_step, block_hash) });
+			let decoded: Message = decode(&encode(&vote)).unwrap();
+			assert_eq!(decoded, vote);
+			match decoded {
+				Message::Vote(vote) => assert_eq!(vote.signer().unwrap(), key.address()),
+				_ => panic!("a vote was encoded"),
+			}
+		}
+	}
+
+	#[test]
+	fn proposals_roundtrip() {
+		let key = Random.generate().unwrap();
+		let mut header = Header::default();
+		header.set_number(5);
+		let mut s = rlp::RlpStream::new_list(3);
+		s.append(&header).begin_list(0).begin_list(0);
+
+		let vote_step = VoteStep::new(5, 0, Step::Propose);
+		let proposal = Message::Proposal(Proposal {
+			vote_step,
+			block: s.out(),
+			signature: signed(&key, &vote_step, Some(header.bare_hash())),
+		});
+		let decoded: Message = decode(&encode(&proposal)).unwrap();
+		assert_eq!(decoded, proposal);
+		match decoded {
+			Message::Proposal(proposal) => {
+				assert_eq!(proposal.signer(BlockNumber::max_value()).unwrap(), key.address());
+				assert_eq!(proposal.block_hash(BlockNumber::max_value()).unwrap(), header.bare_hash());
+			},
+			_ => panic!("a proposal was encoded"),
+		}
+	}
+
+	#[test]
+	fn recovers_seal_signers() {
+		let proposer = Random.generate().unwrap();
+		let voters = [Random.generate().unwrap(), Random.generate().unwrap()];
+		let mut header = Header::default();
+		header.set_number(3);
+		let hash = header.bare_hash();
+
+		let proposal = signed(&proposer, &VoteStep::new(3, 2, Step::Propose), Some(hash));
+		let precommits: Vec<_> = voters.iter()
+			.map(|key| signed(key, &VoteStep::new(3, 2, Step::Precommit), Some(hash)))
+			.collect();
+		header.set_seal(encode_seal(2, &proposal, &precommits));
+
+		let (round, author, signers) = seal_signers(&header).unwrap();
+		assert_eq!(round, 2);
+		assert_eq!(author, proposer.address());
+		assert_eq!(signers, voters.iter().map(KeyPair::address).collect::<Vec<_>>());
+
+		header.set_seal(vec![encode(&2u64)]);
+		assert!(seal_signers(&header).is_err());
+	}
+}
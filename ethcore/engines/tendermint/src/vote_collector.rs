@@ -0,0 +1,115 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Parity Ethereum.
+
+// Parity Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Collects the votes of validators, per vote step.
+
+use std::collections::{BTreeMap, HashMap};
+
+use common_types::BlockNumber;
+use ethereum_types::{Address, H256, H520};
+
+use crate::message::{Step, VoteStep};
+
+/// A validator voted twice, for different blocks, at the same step.
+#[derive(Debug, PartialEq)]
+pub struct DoubleVote(pub Address);
+
+/// Votes received, per step and voter.
+#[derive(Debug, Default)]
+pub struct VoteCollector {
+	votes: BTreeMap<VoteStep, HashMap<Address, (Option<H256>, H520)>>,
+}
+
+impl VoteCollector {
+	/// Record a vote. Returns whether the vote wasn't known yet.
+	pub fn insert(&mut self, vote_step: VoteStep, voter: Address, block_hash: Option<H256>, signature: H520) -> Result<bool, DoubleVote> {
+		let votes = self.votes.entry(vote_step).or_insert_with(HashMap::new);
+		match votes.get(&voter) {
+			Some(&(ref hash, _)) if *hash == block_hash => Ok(false),
+			Some(_) => Err(DoubleVote(voter)),
+			None => {
+				votes.insert(voter, (block_hash, signature));
+				Ok(true)
+			},
+		}
+	}
+
+	/// Number of votes for the given block at the given step.
+	pub fn count(&self, vote_step: &VoteStep, block_hash: &Option<H256>) -> usize {
+		self.votes.get(vote_step)
+			.map_or(0, |votes| votes.values().filter(|&&(ref hash, _)| hash == block_hash).count())
+	}
+
+	/// Number of votes, for any block, at the given step.
+	pub fn total(&self, vote_step: &VoteStep) -> usize {
+		self.votes.get(vote_step).map_or(0, |votes| votes.len())
+	}
+
+	/// Signatures of the votes for the given block at the given step.
+	pub fn signatures(&self, vote_step: &VoteStep, block_hash: &Option<H256>) -> Vec<H520> {
+		self.votes.get(vote_step)
+			.map_or_else(Vec::new, |votes| votes.values()
+				.filter(|&&(ref hash, _)| hash == block_hash)
+				.map(|&(_, signature)| signature)
+				.collect())
+	}
+
+	/// The block, or no block, at least `threshold` validators voted for at the given step.
+	pub fn majority(&self, vote_step: &VoteStep, threshold: usize) -> Option<Option<H256>> {
+		let votes = self.votes.get(vote_step)?;
+		let mut counts = HashMap::new();
+		for &(hash, _) in votes.values() {
+			*counts.entry(hash).or_insert(0) += 1;
+		}
+		counts.into_iter().find(|&(_, count)| count >= threshold).map(|(hash, _)| hash)
+	}
+
+	/// Forget the votes below the given height.
+	pub fn prune(&mut self, height: BlockNumber) {
+		self.votes = self.votes.split_off(&VoteStep::new(height, 0, Step::Propose));
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use ethereum_types::{Address, H256, H520};
+
+	use super::{DoubleVote, VoteCollector};
+	use crate::message::{Step, VoteStep};
+
+	#[test]
+	fn counts_votes_and_detects_double_votes() {
+		let mut collector = VoteCollector::default();
+		let step = VoteStep::new(1, 0, Step::Prevote);
+		let block = Some(H256::from_low_u64_be(1));
+		let (a, b, c) = (Address::from_low_u64_be(1), Address::from_low_u64_be(2), Address::from_low_u64_be(3));
+
+		assert_eq!(collector.insert(step, a, block, H520::repeat_byte(1)), Ok(true));
+		assert_eq!(collector.insert(step, a, block, H520::repeat_byte(1)), Ok(false));
+		assert_eq!(collector.insert(step, b, block, H520::repeat_byte(2)), Ok(true));
+		assert_eq!(collector.insert(step, c, None, H520::repeat_byte(3)), Ok(true));
+		assert_eq!(collector.insert(step, c, block, H520::repeat_byte(3)), Err(DoubleVote(c)));
+
+		assert_eq!(collector.count(&step, &block), 2);
+		assert_eq!(collector.count(&step, &None), 1);
+		assert_eq!(collector.signatures(&step, &None), vec![H520::repeat_byte(3)]);
+		assert_eq!(collector.majority(&step, 2), Some(block));
+		assert_eq!(collector.majority(&step, 3), None);
+
+		collector.prune(2);
+		assert_eq!(collector.count(&step, &block), 0);
+	}
+}
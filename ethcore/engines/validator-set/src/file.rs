@@ -0,0 +1,401 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Parity Ethereum.
+
+// Parity Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+/// Validator lists read from a local file.
+///
+/// The file holds either a single list of validators, or a map of the blocks signalling
+/// each list. The file is re-read whenever it changes, so new transitions can be scheduled
+/// without a restart. Every node must see the same file, and only transitions at future
+/// blocks may be added: the signalled lists are part of consensus.
+
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use std::sync::Weak;
+use std::time::{Duration, Instant, SystemTime};
+
+use client_traits::EngineClient;
+use common_types::{
+	BlockNumber,
+	header::Header,
+	ids::BlockId,
+	errors::EthcoreError,
+	engines::machine::{Call, AuxiliaryData},
+};
+use ethereum_types::{H256, Address};
+use ethjson::spec::ValidatorFile;
+use log::{debug, info, trace, warn};
+use machine::Machine;
+use parity_bytes::Bytes;
+use parking_lot::RwLock;
+use rlp::{Rlp, RlpStream};
+
+use super::{SimpleList, SystemCall, ValidatorSet};
+
+type BlockNumberLookup = Box<dyn Fn(BlockId) -> Result<BlockNumber, String> + Send + Sync + 'static>;
+
+/// Validator lists by the block signalling them.
+type Lists = BTreeMap<BlockNumber, SimpleList>;
+
+/// Minimal time between two checks of the file for changes.
+const RELOAD_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Validator set whose lists are read from a local file.
+pub struct FileValidatorSet {
+	path: PathBuf,
+	lists: RwLock<Lists>,
+	// modification time and length of the file when it was last read.
+	metadata: RwLock<Option<(SystemTime, u64)>>,
+	// when the file was last checked for changes.
+	last_check: RwLock<Instant>,
+	reload_interval: Duration,
+	block_number: RwLock<BlockNumberLookup>,
+}
+
+impl FileValidatorSet {
+	/// Create a new validator set from the given file.
+	///
+	/// Panics if the file can't be read, like an invalid spec would.
+	pub fn new(path: PathBuf) -> Self {
+		Self::with_reload_interval(path, RELOAD_INTERVAL)
+	}
+
+	fn with_reload_interval(path: PathBuf, reload_interval: Duration) -> Self {
+		let metadata = file_metadata(&path).ok();
+		let lists = load(&path)
+			.unwrap_or_else(|e| panic!("Invalid validator list file {}: {}", path.display(), e));
+
+		FileValidatorSet {
+			path,
+			lists: RwLock::new(lists),
+			metadata: RwLock::new(metadata),
+			last_check: RwLock::new(Instant::now()),
+			reload_interval,
+			block_number: RwLock::new(Box::new(move |_| Err("No client!".into()))),
+		}
+	}
+
+	// Re-read the file if it changed since it was last read. An invalid file is reported
+	// and ignored, keeping the lists read before. The file is checked at most once per
+	// reload interval.
+	fn reload(&self) {
+		{
+			let mut last_check = self.last_check.write();
+			if last_check.elapsed() < self.reload_interval {
+				return;
+			}
+			*last_check = Instant::now();
+		}
+
+		let metadata = match file_metadata(&self.path) {
+			Ok(metadata) => metadata,
+			Err(e) => {
+				debug!(target: "engine", "Could not check validator list file {}: {}", self.path.display(), e);
+				return;
+			}
+		};
+		if *self.metadata.read() == Some(metadata) {
+			return;
+		}
+		*self.metadata.write() = Some(metadata);
+
+		match load(&self.path) {
+			Ok(lists) => {
+				info!(target: "engine", "Reloaded validator list file {}: transitions at blocks {:?}",
+					self.path.display(), lists.keys().collect::<Vec<_>>());
+				*self.lists.write() = lists;
+			},
+			Err(e) => warn!(target: "engine", "Keeping the previous validator lists, invalid file {}: {}",
+				self.path.display(), e),
+		}
+	}
+
+	// The latest list signalled at or before the given block.
+	fn list_by_number(&self, number: BlockNumber) -> SimpleList {
+		self.reload();
+		let lists = self.lists.read();
+		let (block, list) = lists.range(..=number)
+			.next_back()
+			.expect("load ensures that there is a list for block 0; block 0 is less than any uint; qed");
+
+		trace!(target: "engine", "Validator list signalled at block {} retrieved for block {}.", block, number);
+		list.clone()
+	}
+
+	fn correct_list(&self, id: BlockId) -> Option<SimpleList> {
+		match self.block_number.read()(id) {
+			Ok(parent_block) => Some(self.list_by_number(parent_block)),
+			Err(e) => {
+				debug!(target: "engine", "Validator list could not be recovered: {}", e);
+				None
+			},
+		}
+	}
+
+	// The list signalled at exactly the given block, if any.
+	fn signalled_list(&self, number: BlockNumber) -> Option<SimpleList> {
+		self.reload();
+		self.lists.read().get(&number).cloned()
+	}
+}
+
+fn file_metadata(path: &Path) -> Result<(SystemTime, u64), std::io::Error> {
+	let metadata = fs::metadata(path)?;
+	Ok((metadata.modified()?, metadata.len()))
+}
+
+fn load(path: &Path) -> Result<Lists, String> {
+	let file = File::open(path).map_err(|e| e.to_string())?;
+	let lists: Lists = match ValidatorFile::load(file).map_err(|e| e.to_string())? {
+		ValidatorFile::List(list) => {
+			let mut lists = Lists::new();
+			lists.insert(0, SimpleList::new(list.into_iter().map(Into::into).collect()));
+			lists
+		},
+		ValidatorFile::Transitions(transitions) => transitions
+			.into_iter()
+			.map(|(block, list)| (block.into(), SimpleList::new(list.into_iter().map(Into::into).collect())))
+			.collect(),
+	};
+
+	if !lists.contains_key(&0) {
+		return Err("the validator list has to be specified from block 0".into());
+	}
+	if let Some(block) = lists.iter().find(|(_, list)| list.is_empty()).map(|(block, _)| block) {
+		return Err(format!("the validator list signalled at block {} is empty", block));
+	}
+	Ok(lists)
+}
+
+fn append_list(stream: &mut RlpStream, list: &SimpleList) {
+	stream.begin_list(list.len());
+	for address in list.iter() {
+		stream.append(address);
+	}
+}
+
+// Proof of the list of the first block of the set: the list itself.
+fn encode_first_proof(list: &SimpleList) -> Vec<u8> {
+	let mut stream = RlpStream::new();
+	append_list(&mut stream, list);
+	stream.out()
+}
+
+// Proof of a signalled list: the hash of the signalling block, and the list.
+fn encode_proof(hash: &H256, list: &SimpleList) -> Vec<u8> {
+	let mut stream = RlpStream::new_list(2);
+	stream.append(hash);
+	append_list(&mut stream, list);
+	stream.out()
+}
+
+fn decode_proof(first: bool, proof: &[u8]) -> Result<(SimpleList, Option<H256>), EthcoreError> {
+	let rlp = Rlp::new(proof);
+	if first {
+		Ok((SimpleList::new(rlp.as_list()?), None))
+	} else {
+		let hash = rlp.val_at(0)?;
+		let list = rlp.at(1)?.as_list()?;
+		Ok((SimpleList::new(list), Some(hash)))
+	}
+}
+
+impl ValidatorSet for FileValidatorSet {
+	fn default_caller(&self, _block_id: BlockId) -> Box<Call> {
+		Box::new(|_, _| Err("Validator list file doesn't require calls.".into()))
+	}
+
+	fn generate_engine_transactions(&self, _first: bool, _header: &Header, _call: &mut SystemCall)
+		-> Result<Vec<(Address, Bytes)>, EthcoreError>
+	{
+		Ok(Vec::new())
+	}
+
+	fn on_close_block(&self, _header: &Header, _address: &Address) -> Result<(), EthcoreError> {
+		Ok(())
+	}
+
+	fn genesis_epoch_data(&self, header: &Header, _call: &Call) -> Result<Vec<u8>, String> {
+		Ok(encode_first_proof(&self.list_by_number(header.number())))
+	}
+
+	fn is_epoch_end(&self, first: bool, chain_head: &Header) -> Option<Vec<u8>> {
+		match first {
+			true => Some(encode_first_proof(&self.list_by_number(chain_head.number()))),
+			false => None,
+		}
+	}
+
+	fn signals_epoch_end(&self, first: bool, header: &Header, _aux: AuxiliaryData)
+		-> engine::EpochChange
+	{
+		if first {
+			return engine::EpochChange::No;
+		}
+
+		match self.signalled_list(header.number()) {
+			Some(list) => {
+				trace!(target: "engine", "Validator list file signals {} validators at block {}", list.len(), header.number());
+				engine::EpochChange::Yes(engine::Proof::Known(encode_proof(&header.hash(), &list)))
+			},
+			None => engine::EpochChange::No,
+		}
+	}
+
+	fn epoch_set(&self, first: bool, _: &Machine, _: BlockNumber, proof: &[u8]) -> Result<(SimpleList, Option<H256>), EthcoreError> {
+		decode_proof(first, proof)
+	}
+
+	fn contains_with_caller(&self, bh: &H256, address: &Address, _: &Call) -> bool {
+		self.correct_list(BlockId::Hash(*bh))
+			.map_or(false, |list| list.iter().any(|a| a == address))
+	}
+
+	fn get_with_caller(&self, bh: &H256, nonce: usize, caller: &Call) -> Address {
+		self.correct_list(BlockId::Hash(*bh))
+			.map_or_else(Default::default, |list| list.get_with_caller(bh, nonce, caller))
+	}
+
+	fn count_with_caller(&self, bh: &H256, _: &Call) -> usize {
+		self.correct_list(BlockId::Hash(*bh))
+			.map_or_else(usize::max_value, |list| list.len())
+	}
+
+	fn register_client(&self, client: Weak<dyn EngineClient>) {
+		*self.block_number.write() = Box::new(move |id| client
+			.upgrade()
+			.ok_or_else(|| "No client!".into())
+			.and_then(|c| c.block_number(id).ok_or_else(|| "Unknown block".into())));
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::fs;
+	use std::str::FromStr;
+	use std::time::Duration;
+
+	use common_types::{engines::machine::AuxiliaryData, header::Header};
+	use engine::{EpochChange, Proof};
+	use ethereum_types::Address;
+	use tempdir::TempDir;
+
+	use crate::ValidatorSet;
+	use super::{FileValidatorSet, decode_proof, load};
+
+	fn no_aux() -> AuxiliaryData<'static> {
+		AuxiliaryData { bytes: None, receipts: None }
+	}
+
+	fn header(number: u64) -> Header {
+		let mut header = Header::default();
+		header.set_number(number);
+		header
+	}
+
+	fn signals(set: &FileValidatorSet, number: u64) -> bool {
+		match set.signals_epoch_end(false, &header(number), no_aux()) {
+			EpochChange::Yes(_) => true,
+			_ => false,
+		}
+	}
+
+	#[test]
+	fn signals_transitions_with_proofs() {
+		let dir = TempDir::new("validator-file").unwrap();
+		let path = dir.path().join("validators.json");
+		fs::write(&path, r#"{
+			"0": ["0x7d577a597b2742b498cb5cf0c26cdcd726d39e6e"],
+			"10": ["0x82a978b3f5962a5b0957d9ee9eef472ee55b42f1", "0x7d577a597b2742b498cb5cf0c26cdcd726d39e6e"]
+		}"#).unwrap();
+		let v1 = Address::from_str("7d577a597b2742b498cb5cf0c26cdcd726d39e6e").unwrap();
+		let v2 = Address::from_str("82a978b3f5962a5b0957d9ee9eef472ee55b42f1").unwrap();
+
+		let set = FileValidatorSet::new(path);
+		let genesis = set.genesis_epoch_data(&header(0), &|_, _| Ok((Vec::new(), Vec::new()))).unwrap();
+		assert_eq!(decode_proof(true, &genesis).unwrap(), (vec![v1].into(), None));
+		assert_eq!(set.is_epoch_end(true, &header(0)), Some(genesis));
+		assert_eq!(set.is_epoch_end(false, &header(10)), None);
+
+		assert!(!signals(&set, 9));
+		let signal = header(10);
+		match set.signals_epoch_end(false, &signal, no_aux()) {
+			EpochChange::Yes(Proof::Known(proof)) => {
+				assert_eq!(decode_proof(false, &proof).unwrap(), (vec![v2, v1].into(), Some(signal.hash())));
+			},
+			_ => panic!("Block 10 signals a transition."),
+		}
+	}
+
+	#[test]
+	fn reloads_changed_file() {
+		let dir = TempDir::new("validator-file").unwrap();
+		let path = dir.path().join("validators.json");
+		fs::write(&path, r#"["0x7d577a597b2742b498cb5cf0c26cdcd726d39e6e"]"#).unwrap();
+		let v1 = Address::from_str("7d577a597b2742b498cb5cf0c26cdcd726d39e6e").unwrap();
+		let v2 = Address::from_str("82a978b3f5962a5b0957d9ee9eef472ee55b42f1").unwrap();
+
+		let set = FileValidatorSet::with_reload_interval(path.clone(), Duration::from_secs(0));
+		assert_eq!(set.list_by_number(20), vec![v1].into());
+		assert!(!signals(&set, 20));
+
+		fs::write(&path, r#"{
+			"0": ["0x7d577a597b2742b498cb5cf0c26cdcd726d39e6e"],
+			"20": ["0x82a978b3f5962a5b0957d9ee9eef472ee55b42f1"]
+		}"#).unwrap();
+		assert_eq!(set.list_by_number(19), vec![v1].into());
+		assert_eq!(set.list_by_number(20), vec![v2].into());
+		assert!(signals(&set, 20));
+
+		// an invalid file keeps the previous lists.
+		fs::write(&path, r#"{ "20": ["0x7d577a597b2742b498cb5cf0c26cdcd726d39e6e"] }"#).unwrap();
+		assert_eq!(set.list_by_number(20), vec![v2].into());
+
+		// so does an empty list.
+		fs::write(&path, r#"{
+			"0": ["0x7d577a597b2742b498cb5cf0c26cdcd726d39e6e"],
+			"20": []
+		}"#).unwrap();
+		assert_eq!(set.list_by_number(20), vec![v2].into());
+	}
+
+	#[test]
+	fn rejects_empty_lists() {
+		let dir = TempDir::new("validator-file").unwrap();
+		let path = dir.path().join("validators.json");
+
+		fs::write(&path, "[]").unwrap();
+		assert!(load(&path).is_err());
+
+		fs::write(&path, r#"{
+			"0": ["0x7d577a597b2742b498cb5cf0c26cdcd726d39e6e"],
+			"0x10": []
+		}"#).unwrap();
+		assert!(load(&path).is_err());
+	}
+
+	#[test]
+	fn throttles_file_checks() {
+		let dir = TempDir::new("validator-file").unwrap();
+		let path = dir.path().join("validators.json");
+		fs::write(&path, r#"["0x7d577a597b2742b498cb5cf0c26cdcd726d39e6e"]"#).unwrap();
+		let v1 = Address::from_str("7d577a597b2742b498cb5cf0c26cdcd726d39e6e").unwrap();
+
+		let set = FileValidatorSet::with_reload_interval(path.clone(), Duration::from_secs(3600));
+		fs::write(&path, r#"["0x82a978b3f5962a5b0957d9ee9eef472ee55b42f1"]"#).unwrap();
+		assert_eq!(set.list_by_number(0), vec![v1].into());
+	}
+}
@@ -22,6 +22,7 @@ mod simple_list;
 mod safe_contract;
 mod contract;
 mod multi;
+mod file;
 
 use std::sync::Weak;
 
@@ -48,6 +49,7 @@ pub use self::simple_list::SimpleList;
 use self::contract::ValidatorContract;
 use self::safe_contract::ValidatorSafeContract;
 use self::multi::Multi;
+use self::file::FileValidatorSet;
 
 /// Creates a validator set from the given spec and initializes a transition to POSDAO AuRa consensus.
 pub fn new_validator_set_posdao(
@@ -70,6 +72,7 @@ pub fn new_validator_set_posdao(
 				))
 				.collect()
 		)),
+		ValidatorSpec::File(path) => Box::new(FileValidatorSet::new(path)),
 	}
 }
 
@@ -141,25 +141,39 @@ impl<Gas: evm::CostType> Gasometer<Gas> {
 						schedule.sstore_reset_gas
 					}
 				};
+				let gas = if schedule.eip2929 && !ext.is_storage_warm(&address) {
+					gas + schedule.cold_sload_gas
+				} else {
+					gas
+				};
 				Request::Gas(Gas::from(gas))
 			},
 			instructions::SLOAD => {
-				Request::Gas(Gas::from(schedule.sload_gas))
+				let gas = if schedule.eip2929 {
+					let key = BigEndianHash::from_uint(stack.peek(0));
+					if ext.is_storage_warm(&key) { schedule.warm_storage_read_gas } else { schedule.cold_sload_gas }
+				} else {
+					schedule.sload_gas
+				};
+				Request::Gas(Gas::from(gas))
 			},
 			instructions::BALANCE => {
-				Request::Gas(Gas::from(schedule.balance_gas))
+				Request::Gas(Gas::from(account_access_gas(ext, stack.peek(0), schedule.balance_gas)))
 			},
 			instructions::EXTCODESIZE => {
-				Request::Gas(Gas::from(schedule.extcodesize_gas))
+				Request::Gas(Gas::from(account_access_gas(ext, stack.peek(0), schedule.extcodesize_gas)))
 			},
 			instructions::EXTCODEHASH => {
-				Request::Gas(Gas::from(schedule.extcodehash_gas))
+				Request::Gas(Gas::from(account_access_gas(ext, stack.peek(0), schedule.extcodehash_gas)))
 			},
 			instructions::SUICIDE => {
 				let mut gas = Gas::from(schedule.suicide_gas);
 
 				let is_value_transfer = !ext.origin_balance()?.is_zero();
 				let address = u256_to_address(stack.peek(0));
+				if schedule.eip2929 && !ext.is_address_warm(&address) {
+					gas = overflowing!(gas.overflow_add(schedule.cold_account_access_gas.into()));
+				}
 				if (
 					!schedule.no_empty && !ext.exists(&address)?
 				) || (
@@ -188,7 +202,8 @@ impl<Gas: evm::CostType> Gasometer<Gas> {
 				Request::GasMemCopy(default_gas, mem_needed(stack.peek(0), stack.peek(2))?, Gas::from_u256(*stack.peek(2))?)
 			},
 			instructions::EXTCODECOPY => {
-				Request::GasMemCopy(schedule.extcodecopy_base_gas.into(), mem_needed(stack.peek(1), stack.peek(3))?, Gas::from_u256(*stack.peek(3))?)
+				let base = account_access_gas(ext, stack.peek(0), schedule.extcodecopy_base_gas);
+				Request::GasMemCopy(base.into(), mem_needed(stack.peek(1), stack.peek(3))?, Gas::from_u256(*stack.peek(3))?)
 			},
 			instructions::LOG0 | instructions::LOG1 | instructions::LOG2 | instructions::LOG3 | instructions::LOG4 => {
 				let no_of_topics = instruction.log_topics().expect("log_topics always return some for LOG* instructions; qed");
@@ -199,7 +214,7 @@ impl<Gas: evm::CostType> Gasometer<Gas> {
 				Request::GasMem(gas, mem_needed(stack.peek(0), stack.peek(1))?)
 			},
 			instructions::CALL | instructions::CALLCODE => {
-				let mut gas = Gas::from(schedule.call_gas);
+				let mut gas = Gas::from(account_access_gas(ext, stack.peek(1), schedule.call_gas));
 				let mem = cmp::max(
 					mem_needed(stack.peek(5), stack.peek(6))?,
 					mem_needed(stack.peek(3), stack.peek(4))?
@@ -225,7 +240,7 @@ impl<Gas: evm::CostType> Gasometer<Gas> {
 				Request::GasMemProvide(gas, mem, Some(requested))
 			},
 			instructions::DELEGATECALL | instructions::STATICCALL => {
-				let gas = Gas::from(schedule.call_gas);
+				let gas = Gas::from(account_access_gas(ext, stack.peek(1), schedule.call_gas));
 				let mem = cmp::max(
 					mem_needed(stack.peek(4), stack.peek(5))?,
 					mem_needed(stack.peek(2), stack.peek(3))?
@@ -371,6 +386,22 @@ fn to_word_size<Gas: evm::CostType>(value: Gas) -> (Gas, bool) {
 	(gas >> 5, false)
 }
 
+/// Cost of touching the account at `address`: the legacy `default` price, or the EIP-2929
+/// warm/cold price once that is enabled.
+#[inline]
+fn account_access_gas(ext: &dyn vm::Ext, address: &U256, default: usize) -> usize {
+	let schedule = ext.schedule();
+	if !schedule.eip2929 {
+		return default;
+	}
+
+	if ext.is_address_warm(&u256_to_address(address)) {
+		schedule.warm_storage_read_gas
+	} else {
+		schedule.cold_account_access_gas
+	}
+}
+
 #[inline]
 fn calculate_eip1283_sstore_gas<Gas: evm::CostType>(schedule: &Schedule, original: &U256, current: &U256, new: &U256) -> Gas {
 	Gas::from(
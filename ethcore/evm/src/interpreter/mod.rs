@@ -589,6 +589,9 @@ impl<Cost: CostType> Interpreter<Cost> {
 				let call_gas = provided.expect("`provided` comes through Self::exec from `Gasometer::get_gas_cost_mem`; `gas_gas_mem_cost` guarantees `Some` when instruction is `CALL`/`CALLCODE`/`DELEGATECALL`/`CREATE`; this is one of `CALL`/`CALLCODE`/`DELEGATECALL`; qed");
 				let code_address = self.stack.pop_back();
 				let code_address = u256_to_address(&code_address);
+				if ext.schedule().eip2929 {
+					ext.warm_address(code_address);
+				}
 
 				let value = if instruction == instructions::DELEGATECALL {
 					None
@@ -687,8 +690,11 @@ impl<Cost: CostType> Interpreter<Cost> {
 				return Ok(InstructionResult::StopExecution);
 			},
 			instructions::SUICIDE => {
-				let address = self.stack.pop_back();
-				ext.suicide(&u256_to_address(&address))?;
+				let address = u256_to_address(&self.stack.pop_back());
+				if ext.schedule().eip2929 {
+					ext.warm_address(address);
+				}
+				ext.suicide(&address)?;
 				return Ok(InstructionResult::StopExecution);
 			},
 			instructions::LOG0 | instructions::LOG1 | instructions::LOG2 | instructions::LOG3 | instructions::LOG4 => {
@@ -739,12 +745,18 @@ impl<Cost: CostType> Interpreter<Cost> {
 			},
 			instructions::SLOAD => {
 				let key = BigEndianHash::from_uint(&self.stack.pop_back());
+				if ext.schedule().eip2929 {
+					ext.warm_storage(key);
+				}
 				let word = ext.storage_at(&key)?.into_uint();
 				self.stack.push(word);
 			},
 			instructions::SSTORE => {
 				let address = BigEndianHash::from_uint(&self.stack.pop_back());
 				let val = self.stack.pop_back();
+				if ext.schedule().eip2929 {
+					ext.warm_storage(address);
+				}
 
 				let current_val = ext.storage_at(&address)?.into_uint();
 				// Increase refund for clear
@@ -773,6 +785,9 @@ impl<Cost: CostType> Interpreter<Cost> {
 			},
 			instructions::BALANCE => {
 				let address = u256_to_address(&self.stack.pop_back());
+				if ext.schedule().eip2929 {
+					ext.warm_address(address);
+				}
 				let balance = ext.balance(&address)?;
 				self.stack.push(balance);
 			},
@@ -812,11 +827,17 @@ impl<Cost: CostType> Interpreter<Cost> {
 			},
 			instructions::EXTCODESIZE => {
 				let address = u256_to_address(&self.stack.pop_back());
+				if ext.schedule().eip2929 {
+					ext.warm_address(address);
+				}
 				let len = ext.extcodesize(&address)?.unwrap_or(0);
 				self.stack.push(U256::from(len));
 			},
 			instructions::EXTCODEHASH => {
 				let address = u256_to_address(&self.stack.pop_back());
+				if ext.schedule().eip2929 {
+					ext.warm_address(address);
+				}
 				let hash = ext.extcodehash(&address)?.unwrap_or_else(H256::zero);
 				self.stack.push(hash.into_uint());
 			},
@@ -839,6 +860,9 @@ impl<Cost: CostType> Interpreter<Cost> {
 			},
 			instructions::EXTCODECOPY => {
 				let address = u256_to_address(&self.stack.pop_back());
+				if ext.schedule().eip2929 {
+					ext.warm_address(address);
+				}
 				let code = ext.extcode(&address)?;
 				Self::copy_data_to_memory(
 					&mut self.mem,
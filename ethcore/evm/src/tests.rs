@@ -195,6 +195,54 @@ fn test_chain_id(factory: super::Factory) {
 	assert_store(&ext, 0, "0000000000000000000000000000000000000000000000000000000000000009");
 }
 
+evm_test!{test_eip2929_sload_cold_then_warm: test_eip2929_sload_cold_then_warm_int}
+fn test_eip2929_sload_cold_then_warm(factory: super::Factory) {
+	// 60 00    PUSH 0
+	// 54       SLOAD (cold)
+	// 50       POP
+	// 60 00    PUSH 0
+	// 54       SLOAD (warm)
+	// 50       POP
+	let code = hex!("60 00 54 50 60 00 54 50").to_vec();
+
+	let mut params = ActionParams::default();
+	params.gas = U256::from(100_000);
+	params.code = Some(Arc::new(code));
+	let mut ext = FakeExt::new_berlin();
+
+	let gas_left = {
+		let vm = factory.create(params, ext.schedule(), ext.depth());
+		test_finalize(vm.exec(&mut ext).ok().unwrap()).unwrap()
+	};
+
+	assert_eq!(gas_left, U256::from(97_790));
+	assert!(ext.accessed_storage_keys.contains(&H256::zero()));
+}
+
+evm_test!{test_eip2929_balance_cold_then_warm: test_eip2929_balance_cold_then_warm_int}
+fn test_eip2929_balance_cold_then_warm(factory: super::Factory) {
+	// 60 01    PUSH 1
+	// 31       BALANCE (cold)
+	// 50       POP
+	// 60 01    PUSH 1
+	// 31       BALANCE (warm)
+	// 50       POP
+	let code = hex!("60 01 31 50 60 01 31 50").to_vec();
+
+	let mut params = ActionParams::default();
+	params.gas = U256::from(100_000);
+	params.code = Some(Arc::new(code));
+	let mut ext = FakeExt::new_berlin();
+
+	let gas_left = {
+		let vm = factory.create(params, ext.schedule(), ext.depth());
+		test_finalize(vm.exec(&mut ext).ok().unwrap()).unwrap()
+	};
+
+	assert_eq!(gas_left, U256::from(97_290));
+	assert!(ext.accessed_addresses.contains(&Address::from_low_u64_be(1)));
+}
+
 evm_test!{test_extcodecopy: test_extcodecopy_int}
 fn test_extcodecopy(factory: super::Factory) {
 		// 33 - sender
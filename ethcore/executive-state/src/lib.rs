@@ -221,7 +221,7 @@ impl<B: Backend> ExecutiveState for State<B> {
 		};
 
 		let output = e.output;
-		let receipt = Receipt::new(outcome, e.cumulative_gas_used, e.logs);
+		let receipt = Receipt::new_typed(t.tx_type(), outcome, e.cumulative_gas_used, e.logs);
 		trace!(target: "state", "Transaction receipt: {:?}", receipt);
 
 		Ok(ApplyOutcome {
@@ -1281,6 +1281,43 @@ mod tests {
 		assert_eq!(state.nonce(&a).unwrap(), U256::from(3u64));
 	}
 
+	#[test]
+	fn apply_state_override() {
+		use common_types::state_override::{AccountOverride, StorageOverride};
+
+		let mut state = get_temp_state();
+		let a = Address::zero();
+		let b = Address::from_low_u64_be(1u64);
+		let key = |k: u64| -> H256 { BigEndianHash::from_uint(&U256::from(k)) };
+		state.add_balance(&a, &U256::from(69u64), CleanupMode::NoEmpty).unwrap();
+		state.set_storage(&a, key(1), key(2)).unwrap();
+		state.set_storage(&b, key(1), key(2)).unwrap();
+		state.set_storage(&b, key(3), key(4)).unwrap();
+		state.commit().unwrap();
+
+		let overrides = vec![
+			(a, AccountOverride {
+				balance: Some(5.into()),
+				nonce: Some(7.into()),
+				code: Some(vec![1, 2, 3]),
+				storage: Some(StorageOverride::Diff(vec![(key(3), key(5))].into_iter().collect())),
+			}),
+			(b, AccountOverride {
+				storage: Some(StorageOverride::Replace(vec![(key(3), key(6))].into_iter().collect())),
+				..Default::default()
+			}),
+		].into_iter().collect();
+		state.apply_override(&overrides).unwrap();
+
+		assert_eq!(state.balance(&a).unwrap(), U256::from(5u64));
+		assert_eq!(state.nonce(&a).unwrap(), U256::from(7u64));
+		assert_eq!(state.code(&a).unwrap(), Some(Arc::new(vec![1u8, 2, 3])));
+		assert_eq!(state.storage_at(&a, &key(1)).unwrap(), key(2));
+		assert_eq!(state.storage_at(&a, &key(3)).unwrap(), key(5));
+		assert_eq!(state.storage_at(&b, &key(1)).unwrap(), H256::zero());
+		assert_eq!(state.storage_at(&b, &key(3)).unwrap(), key(6));
+	}
+
 	#[test]
 	fn balance_nonce() {
 		let mut state = get_temp_state();
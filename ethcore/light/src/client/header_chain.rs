@@ -31,6 +31,7 @@ use std::sync::Arc;
 use cache::Cache;
 use cht;
 use common_types::{
+	BlockNumber,
 	block_status::BlockStatus,
 	encoded,
 	engines::epoch::{
@@ -223,6 +224,7 @@ pub struct HeaderChain {
 	col: u32,
 	#[ignore_malloc_size_of = "ignored for performance reason"]
 	cache: Arc<Mutex<Cache>>,
+	eip1559_transition: BlockNumber,
 }
 
 impl HeaderChain {
@@ -291,6 +293,7 @@ impl HeaderChain {
 				db,
 				col,
 				cache,
+				eip1559_transition: spec.params().eip1559_transition,
 			}
 
 		} else {
@@ -306,6 +309,7 @@ impl HeaderChain {
 				db: db.clone(),
 				col,
 				cache,
+				eip1559_transition: spec.params().eip1559_transition,
 			};
 
 			// insert the hardcoded sync into the database.
@@ -317,7 +321,7 @@ impl HeaderChain {
 					batch.put(col, cht_key(cht_num as u64).as_bytes(), &::rlp::encode(cht_root));
 				}
 
-				let decoded_header = hardcoded_sync.header.decode()?;
+				let decoded_header = hardcoded_sync.header.decode(chain.eip1559_transition)?;
 				let decoded_header_num = decoded_header.number();
 
 				// write the block in the DB.
@@ -597,7 +601,7 @@ impl HeaderChain {
 						return Err(msg.into());
 					};
 
-					let decoded = header.decode().expect("decoding db value failed");
+					let decoded = header.decode(self.eip1559_transition).expect("decoding db value failed");
 
 					let entry: Entry = {
 						let bytes = self.db.get(self.col, era_key(h_num).as_bytes())?
@@ -827,7 +831,7 @@ impl HeaderChain {
 
 		for hdr in self.ancestry_iter(BlockId::Hash(parent_hash)) {
 			if let Some(transition) = live_proofs.get(&hdr.hash()).cloned() {
-				return hdr.decode().map(|decoded_hdr| {
+				return hdr.decode(self.eip1559_transition).map(|decoded_hdr| {
 					(decoded_hdr, transition.proof)
 				}).ok();
 			}
@@ -1232,7 +1236,7 @@ mod tests {
 		let hardcoded_sync = chain.read_hardcoded_sync().expect("failed reading hardcoded sync").expect("failed unwrapping hardcoded sync");
 		assert_eq!(hardcoded_sync.chts.len(), 3);
 		assert_eq!(hardcoded_sync.total_difficulty, total_difficulty);
-		let decoded: Header = hardcoded_sync.header.decode().expect("decoding failed");
+		let decoded: Header = hardcoded_sync.header.decode(spec.params().eip1559_transition).expect("decoding failed");
 		assert_eq!(decoded.number(), h_num);
 	}
 }
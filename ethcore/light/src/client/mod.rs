@@ -37,7 +37,7 @@ use common_types::{
 	header::Header,
 	ids::BlockId,
 	io_message::ClientIoMessage,
-	verification::VerificationQueueInfo as BlockQueueInfo,
+	verification::{Unverified, VerificationQueueInfo as BlockQueueInfo},
 };
 use kvdb::KeyValueDB;
 use vm::EnvInfo;
@@ -328,7 +328,7 @@ impl<T: ChainDataFetcher> Client<T> {
 
 			let epoch_proof = self.engine.is_epoch_end_light(
 				&verified_header,
-				&|h| self.chain.block_header(BlockId::Hash(h)).and_then(|hdr| hdr.decode().ok()),
+				&|h| self.chain.block_header(BlockId::Hash(h)).and_then(|hdr| hdr.decode(self.engine.params().eip1559_transition).ok()),
 				&|h| self.chain.pending_transition(h),
 			);
 
@@ -406,6 +406,7 @@ impl<T: ChainDataFetcher> Client<T> {
 			last_hashes: self.build_last_hashes(header.parent_hash()),
 			gas_used: Default::default(),
 			gas_limit: header.gas_limit(),
+			base_fee: header.base_fee_per_gas(self.engine.params().eip1559_transition),
 		})
 	}
 
@@ -446,7 +447,7 @@ impl<T: ChainDataFetcher> Client<T> {
 		// Verify Block Family
 
 		let verify_family_result = {
-			parent_header.decode()
+			parent_header.decode(self.engine.params().eip1559_transition)
 				.map_err(|dec_err| dec_err.into())
 				.and_then(|decoded| {
 					self.engine.verify_block_family(&verified_header, &decoded)
@@ -639,6 +640,10 @@ impl<T: ChainDataFetcher> client_traits::EngineClient for Client<T> {
 		})
 	}
 
+	fn verify_unsealed_block(&self, _block: Unverified) -> EthcoreResult<()> {
+		Err("Light clients don't execute blocks".into())
+	}
+
 	fn as_full_client(&self) -> Option<&dyn (client_traits::BlockChainClient)> {
 		None
 	}
@@ -16,7 +16,7 @@
 
 //! Transaction execution format module.
 
-use trace::{VMTrace, FlatTrace};
+use trace::{VMTrace, FlatTrace, StructLog};
 use common_types::{
 	engines::machine,
 	errors::ExecutionError,
@@ -25,5 +25,8 @@ use common_types::{
 /// /// Transaction execution receipt, parametrised with convenient defaults.
 pub type Executed = machine::Executed<FlatTrace, VMTrace>;
 
+/// Transaction execution receipt with Geth-style struct logs in place of the VM trace.
+pub type StructLogExecuted = machine::Executed<FlatTrace, Vec<StructLog>>;
+
 /// Transaction execution result.
 pub type ExecutionResult = Result<Box<Executed>, ExecutionError>;
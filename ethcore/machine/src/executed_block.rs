@@ -27,6 +27,7 @@ use ethereum_types::{H256, U256};
 
 use account_state::State;
 use common_types::{
+	block_reward::BlockReward,
 	header::Header,
 	receipt::Receipt,
 	transaction::SignedTransaction,
@@ -52,6 +53,8 @@ pub struct ExecutedBlock {
 	pub state: State<StateDB>,
 	/// Transaction traces.
 	pub traces: Tracing,
+	/// Rewards paid when closing the block.
+	pub rewards: Vec<BlockReward>,
 	/// Hashes of last 256 blocks.
 	pub last_hashes: Arc<LastHashes>,
 }
@@ -71,6 +74,7 @@ impl ExecutedBlock {
 			} else {
 				Tracing::Disabled
 			},
+			rewards: Default::default(),
 			last_hashes,
 		}
 	}
@@ -86,6 +90,7 @@ impl ExecutedBlock {
 			last_hashes: self.last_hashes.clone(),
 			gas_used: self.receipts.last().map_or(U256::zero(), |r| r.gas_used),
 			gas_limit: self.header.gas_limit().clone(),
+			base_fee: self.header.base_fee_per_gas(),
 		}
 	}
 
@@ -351,9 +351,11 @@ impl<'a> CallCreateExecutive<'a> {
 				| Err(vm::Error::Reverted)
 				| Ok(FinalizationResult { apply_state: false, .. }) => {
 					state.revert_to_checkpoint();
+					un_substate.access_list.revert_to_checkpoint();
 			},
 			Ok(_) | Err(vm::Error::Internal(_)) => {
 				state.discard_checkpoint();
+				un_substate.access_list.discard_checkpoint();
 				substate.accrue(un_substate);
 			}
 		}
@@ -465,6 +467,10 @@ impl<'a> CallCreateExecutive<'a> {
 					}
 				}
 
+				if self.schedule.eip2929 {
+					unconfirmed_substate.inherit_access_list(substate);
+				}
+
 				let origin_info = OriginInfo::from(&params);
 				let exec = self.factory.create(params, self.schedule, self.depth);
 
@@ -516,6 +522,11 @@ impl<'a> CallCreateExecutive<'a> {
 					}
 				}
 
+				if self.schedule.eip2929 {
+					unconfirmed_substate.inherit_access_list(substate);
+					unconfirmed_substate.access_list.insert_address(params.address);
+				}
+
 				let origin_info = OriginInfo::from(&params);
 				let exec = self.factory.create(params, self.schedule, self.depth);
 
@@ -871,10 +882,19 @@ impl<'a, B: 'a + StateBackend> Executive<'a, B> {
 			});
 		}
 
+		// EIP-1559: the max fee has to cover the base fee of the block.
+		if let Some(base_fee) = self.info.base_fee {
+			if t.max_fee_per_gas() < base_fee {
+				return Err(ExecutionError::GasPriceLowerThanBaseFee { gas_price: t.max_fee_per_gas(), base_fee });
+			}
+		}
+
 		// TODO: we might need bigints here, or at least check overflows.
 		let balance = self.state.balance(&sender)?;
-		let gas_cost = t.gas.full_mul(t.gas_price);
-		let total_cost = U512::from(t.value) + gas_cost;
+		let gas_price = t.effective_gas_price(self.info.base_fee);
+		let gas_cost = t.gas.full_mul(gas_price);
+		// the sender has to be able to afford the max fee even if it pays less.
+		let total_cost = U512::from(t.value) + t.gas.full_mul(t.gas_price);
 
 		// avoid unaffordable transactions
 		let balance512 = U512::from(balance);
@@ -884,6 +904,27 @@ impl<'a, B: 'a + StateBackend> Executive<'a, B> {
 
 		let mut substate = Substate::new();
 
+		// EIP-2929: sender, recipient, precompiles and the transaction access list start warm.
+		if schedule.eip2929 {
+			substate.access_list.insert_address(sender);
+			if let Action::Call(ref address) = t.action {
+				substate.access_list.insert_address(*address);
+			}
+			for (address, builtin) in self.machine.builtins().iter() {
+				if builtin.is_active(self.info.number) {
+					substate.access_list.insert_address(*address);
+				}
+			}
+			if let Some(access_list) = t.access_list() {
+				for item in access_list {
+					substate.access_list.insert_address(item.address);
+					for key in &item.storage_keys {
+						substate.access_list.insert_storage_key(item.address, *key);
+					}
+				}
+			}
+		}
+
 		// NOTE: there can be no invalid transactions from this point.
 		if !schedule.keep_unsigned_nonce || !t.is_unsigned() {
 			self.state.inc_nonce(&sender)?;
@@ -904,7 +945,7 @@ impl<'a, B: 'a + StateBackend> Executive<'a, B> {
 					sender: sender.clone(),
 					origin: sender.clone(),
 					gas: init_gas,
-					gas_price: t.gas_price,
+					gas_price,
 					value: ActionValue::Transfer(t.value),
 					code: Some(Arc::new(t.data.clone())),
 					code_version: schedule.latest_version,
@@ -926,7 +967,7 @@ impl<'a, B: 'a + StateBackend> Executive<'a, B> {
 					sender: sender.clone(),
 					origin: sender.clone(),
 					gas: init_gas,
-					gas_price: t.gas_price,
+					gas_price,
 					value: ActionValue::Transfer(t.value),
 					code: self.state.code(address)?,
 					code_hash: self.state.code_hash(address)?,
@@ -1154,9 +1195,11 @@ impl<'a, B: 'a + StateBackend> Executive<'a, B> {
 		let gas_left = gas_left_prerefund + refunded;
 
 		let gas_used = t.gas.saturating_sub(gas_left);
-		let (refund_value, overflow_1) = gas_left.overflowing_mul(t.gas_price);
-		let (fees_value, overflow_2) = gas_used.overflowing_mul(t.gas_price);
-		if overflow_1 || overflow_2 {
+		let gas_price = t.effective_gas_price(self.info.base_fee);
+		let (refund_value, overflow_1) = gas_left.overflowing_mul(gas_price);
+		let (fees_value, overflow_2) = gas_used.overflowing_mul(t.effective_priority_fee(self.info.base_fee));
+		let (base_fee_value, overflow_3) = gas_used.overflowing_mul(self.info.base_fee.unwrap_or_default());
+		if overflow_1 || overflow_2 || overflow_3 {
 			return Err(ExecutionError::TransactionMalformed("U256 Overflow".to_string()));
 		}
 
@@ -1171,6 +1214,15 @@ impl<'a, B: 'a + StateBackend> Executive<'a, B> {
 		trace!(target: "executive", "exec::finalize: Compensating author: fees_value={}, author={}\n", fees_value, &self.info.author);
 		self.state.add_balance(&self.info.author, &fees_value, cleanup_mode(&mut substate, &schedule))?;
 
+		// EIP-1559: the base fee is burnt, unless the chain collects it.
+		let params = self.machine.params();
+		if let Some(ref collector) = params.eip1559_fee_collector {
+			if !base_fee_value.is_zero() && self.info.number >= params.eip1559_fee_collector_transition {
+				trace!(target: "executive", "exec::finalize: Collecting base fee: base_fee_value={}, collector={}\n", base_fee_value, collector);
+				self.state.add_balance(collector, &base_fee_value, cleanup_mode(&mut substate, &schedule))?;
+			}
+		}
+
 		// perform suicides
 		for address in &substate.suicides {
 			self.state.kill_account(address);
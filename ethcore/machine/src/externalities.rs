@@ -438,6 +438,22 @@ impl<'a, T: 'a, V: 'a, B: 'a> Ext for Externalities<'a, T, V, B>
 		self.substate.sstore_clears_refund -= value as i128;
 	}
 
+	fn is_address_warm(&self, address: &Address) -> bool {
+		self.substate.access_list.contains_address(address)
+	}
+
+	fn warm_address(&mut self, address: Address) {
+		self.substate.access_list.insert_address(address);
+	}
+
+	fn is_storage_warm(&self, key: &H256) -> bool {
+		self.substate.access_list.contains_storage_key(&self.origin_info.address, key)
+	}
+
+	fn warm_storage(&mut self, key: H256) {
+		self.substate.access_list.insert_storage_key(self.origin_info.address, key);
+	}
+
 	fn trace_next_instruction(&mut self, pc: usize, instruction: u8, current_gas: U256) -> bool {
 		self.vm_tracer.trace_next_instruction(pc, instruction, current_gas)
 	}
@@ -493,6 +509,7 @@ mod tests {
 			last_hashes: Arc::new(vec![]),
 			gas_used: 0.into(),
 			gas_limit: 0.into(),
+			base_fee: None,
 		}
 	}
 
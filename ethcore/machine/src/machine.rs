@@ -21,7 +21,6 @@ use std::cmp;
 use std::sync::Arc;
 
 use ethereum_types::{U256, H256, Address};
-use rlp::Rlp;
 use log::debug;
 
 use common_types::{
@@ -32,7 +31,7 @@ use common_types::{
 		params::CommonParams,
 	},
 	errors::{EngineError, EthcoreError as Error},
-	transaction::{self, SYSTEM_ADDRESS, UNSIGNED_SENDER, UnverifiedTransaction, SignedTransaction},
+	transaction::{self, SYSTEM_ADDRESS, UNSIGNED_SENDER, TypedTxId, UnverifiedTransaction, SignedTransaction},
 };
 use vm::{ActionType, ActionParams, ActionValue, ParamsType};
 use vm::{EnvInfo, Schedule};
@@ -219,7 +218,8 @@ impl Machine {
 	/// The gas floor target must not be lower than the engine's minimum gas limit.
 	pub fn populate_from_parent(&self, header: &mut Header, parent: &Header, gas_floor_target: U256, gas_ceil_target: U256) {
 		header.set_difficulty(parent.difficulty().clone());
-		let gas_limit = parent.gas_limit().clone();
+		header.set_base_fee_per_gas(self.calc_base_fee(parent));
+		let gas_limit = self.parent_gas_limit(parent);
 		assert!(!gas_limit.is_zero(), "Gas limit should be > 0");
 
 		if let Some(ref ethash_params) = self.ethash_extensions {
@@ -264,6 +264,47 @@ impl Machine {
 		});
 	}
 
+	/// Gas limit of `parent` that the next block's gas limit is bounded by. At the EIP-1559
+	/// transition the parent's limit is scaled by the elasticity multiplier so that the gas
+	/// target of the first EIP-1559 block equals the parent's gas limit.
+	pub fn parent_gas_limit(&self, parent: &Header) -> U256 {
+		let params = self.params();
+		if parent.number() + 1 == params.eip1559_transition {
+			parent.gas_limit().saturating_mul(params.eip1559_elasticity_multiplier)
+		} else {
+			*parent.gas_limit()
+		}
+	}
+
+	/// Base fee of the block following `parent`, `None` before the EIP-1559 transition.
+	pub fn calc_base_fee(&self, parent: &Header) -> Option<U256> {
+		let params = self.params();
+		let number = parent.number() + 1;
+		if number < params.eip1559_transition {
+			return None;
+		}
+
+		let parent_base_fee = match parent.base_fee_per_gas() {
+			Some(base_fee) if number > params.eip1559_transition => base_fee,
+			_ => return Some(params.eip1559_base_fee_initial_value),
+		};
+
+		let gas_target = *parent.gas_limit() / params.eip1559_elasticity_multiplier;
+		let gas_used = *parent.gas_used();
+		let denominator = params.eip1559_base_fee_max_change_denominator;
+		if gas_target.is_zero() || gas_used == gas_target {
+			return Some(parent_base_fee);
+		}
+
+		Some(if gas_used > gas_target {
+			let delta = parent_base_fee.saturating_mul(gas_used - gas_target) / gas_target / denominator;
+			parent_base_fee.saturating_add(cmp::max(delta, U256::one()))
+		} else {
+			let delta = parent_base_fee.saturating_mul(gas_target - gas_used) / gas_target / denominator;
+			parent_base_fee.saturating_sub(delta)
+		})
+	}
+
 	/// Get the general parameters of the chain.
 	pub fn params(&self) -> &CommonParams {
 		&self.params
@@ -345,6 +386,23 @@ impl Machine {
 		};
 		t.verify_basic(check_low_s, chain_id)?;
 
+		let tx_type_enabled = match t.tx_type() {
+			TypedTxId::Legacy => true,
+			TypedTxId::AccessList =>
+				header.number() >= self.params().eip2718_transition &&
+				header.number() >= self.params().eip2930_transition,
+			TypedTxId::EIP1559Transaction =>
+				header.number() >= self.params().eip2718_transition &&
+				header.number() >= self.params().eip1559_transition,
+		};
+		if !tx_type_enabled {
+			return Err(transaction::Error::TransactionTypeNotEnabled);
+		}
+
+		if t.max_priority_fee_per_gas() > t.max_fee_per_gas() {
+			return Err(transaction::Error::TipAboveFeeCap);
+		}
+
 		Ok(())
 	}
 
@@ -366,12 +424,11 @@ impl Machine {
 
 	/// Performs pre-validation of RLP decoded transaction before other processing
 	pub fn decode_transaction(&self, transaction: &[u8]) -> Result<UnverifiedTransaction, transaction::Error> {
-		let rlp = Rlp::new(&transaction);
-		if rlp.as_raw().len() > self.params().max_transaction_size {
-			debug!("Rejected oversized transaction of {} bytes", rlp.as_raw().len());
+		if transaction.len() > self.params().max_transaction_size {
+			debug!("Rejected oversized transaction of {} bytes", transaction.len());
 			return Err(transaction::Error::TooBig)
 		}
-		rlp.as_val().map_err(|e| transaction::Error::InvalidRlp(e.to_string()))
+		UnverifiedTransaction::decode_raw(transaction).map_err(|e| transaction::Error::InvalidRlp(e.to_string()))
 	}
 
 	/// Get the balance, in base units, associated with an account.
@@ -438,6 +495,31 @@ mod tests {
 		assert_eq!(res, Err(transaction::Error::InvalidSignature("invalid EC signature".into())));
 	}
 
+	#[test]
+	fn should_disallow_access_list_transactions_before_transition() {
+		use common_types::transaction::{AccessListTx, Transaction, TypedTransaction};
+		use parity_crypto::publickey::{Random, Generator};
+
+		let spec = spec::new_test();
+		let mut params = spec.params().clone();
+		params.eip2718_transition = 10;
+		params.eip2930_transition = 10;
+		let machine = Machine::regular(params, Default::default());
+
+		let tx = TypedTransaction::AccessList(AccessListTx {
+			transaction: Transaction::default(),
+			access_list: vec![],
+		}).sign(Random.generate().unwrap().secret(), Some(spec.params().chain_id));
+
+		let mut header = Header::new();
+		header.set_number(9);
+		let res = machine.verify_transaction_basic(&tx, &header);
+		assert_eq!(res, Err(transaction::Error::TransactionTypeNotEnabled));
+
+		header.set_number(10);
+		assert!(machine.verify_transaction_basic(&tx, &header).is_ok());
+	}
+
 	#[test]
 	fn ethash_gas_limit_is_multiple_of_determinant() {
 		use ethereum_types::U256;
@@ -489,4 +571,29 @@ mod tests {
 		machine.populate_from_parent(&mut header, &parent, U256::from(150_000), U256::from(150_002));
 		assert_eq!(*header.gas_limit(), U256::from(150_002));
 	}
+
+	#[test]
+	fn parent_gas_limit_is_scaled_at_eip1559_transition() {
+		let spec = spec::new_test();
+		let mut params = spec.params().clone();
+		params.eip1559_transition = 2;
+		params.eip1559_elasticity_multiplier = U256::from(2);
+		let machine = Machine::regular(params, Default::default());
+
+		let mut parent = Header::new();
+		parent.set_gas_limit(U256::from(100_000));
+		assert_eq!(machine.parent_gas_limit(&parent), U256::from(100_000));
+
+		parent.set_number(1);
+		assert_eq!(machine.parent_gas_limit(&parent), U256::from(200_000));
+
+		let mut header = Header::new();
+		header.set_number(2);
+		machine.populate_from_parent(&mut header, &parent, U256::from(200_000), U256::from(300_000));
+		assert_eq!(*header.gas_limit(), U256::from(200_000));
+		assert_eq!(header.base_fee_per_gas(), Some(machine.params().eip1559_base_fee_initial_value));
+
+		parent.set_number(2);
+		assert_eq!(machine.parent_gas_limit(&parent), U256::from(100_000));
+	}
 }
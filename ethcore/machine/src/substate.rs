@@ -16,10 +16,92 @@
 
 //! Execution environment substate.
 
+use std::cell::RefCell;
 use std::collections::HashSet;
-use ethereum_types::Address;
+use std::rc::Rc;
+use ethereum_types::{Address, H256};
 use common_types::log_entry::LogEntry;
 
+/// Accounts and storage slots accessed during a transaction (EIP-2929).
+///
+/// A single set is shared by every frame of the call stack: clones refer to the same set.
+/// Each frame takes a checkpoint when it starts and, when it fails, reverts the accesses
+/// made since, like the checkpoints of the state.
+#[derive(Debug, Default, Clone)]
+pub struct AccessList {
+	inner: Rc<RefCell<AccessJournal>>,
+}
+
+#[derive(Debug, Default)]
+struct AccessJournal {
+	addresses: HashSet<Address>,
+	storage_keys: HashSet<(Address, H256)>,
+	// accesses which were new to the set, in order.
+	journal: Vec<Access>,
+	// journal lengths at the checkpoints.
+	checkpoints: Vec<usize>,
+}
+
+#[derive(Debug)]
+enum Access {
+	Address(Address),
+	StorageKey(Address, H256),
+}
+
+impl AccessList {
+	/// Whether the account at `address` was accessed.
+	pub fn contains_address(&self, address: &Address) -> bool {
+		self.inner.borrow().addresses.contains(address)
+	}
+
+	/// Note an access of the account at `address`.
+	pub fn insert_address(&self, address: Address) {
+		let mut inner = self.inner.borrow_mut();
+		if inner.addresses.insert(address) {
+			inner.journal.push(Access::Address(address));
+		}
+	}
+
+	/// Whether the storage slot `key` of the account at `address` was accessed.
+	pub fn contains_storage_key(&self, address: &Address, key: &H256) -> bool {
+		self.inner.borrow().storage_keys.contains(&(*address, *key))
+	}
+
+	/// Note an access of the storage slot `key` of the account at `address`.
+	pub fn insert_storage_key(&self, address: Address, key: H256) {
+		let mut inner = self.inner.borrow_mut();
+		if inner.storage_keys.insert((address, key)) {
+			inner.journal.push(Access::StorageKey(address, key));
+		}
+	}
+
+	/// Create a new checkpoint.
+	pub fn checkpoint(&self) {
+		let mut inner = self.inner.borrow_mut();
+		let len = inner.journal.len();
+		inner.checkpoints.push(len);
+	}
+
+	/// Keep the accesses made since the last checkpoint and remove the checkpoint.
+	pub fn discard_checkpoint(&self) {
+		self.inner.borrow_mut().checkpoints.pop();
+	}
+
+	/// Forget the accesses made since the last checkpoint and remove the checkpoint.
+	pub fn revert_to_checkpoint(&self) {
+		let mut inner = self.inner.borrow_mut();
+		if let Some(len) = inner.checkpoints.pop() {
+			let reverted = inner.journal.split_off(len);
+			for access in reverted {
+				match access {
+					Access::Address(address) => { inner.addresses.remove(&address); },
+					Access::StorageKey(address, key) => { inner.storage_keys.remove(&(address, key)); },
+				}
+			}
+		}
+	}
+}
+
 /// State changes which should be applied in finalize,
 /// after transaction is fully executed.
 #[derive(Debug, Default)]
@@ -38,6 +120,9 @@ pub struct Substate {
 
 	/// Created contracts.
 	pub contracts_created: Vec<Address>,
+
+	/// Accounts and storage slots accessed during the transaction (EIP-2929).
+	pub access_list: AccessList,
 }
 
 impl Substate {
@@ -54,11 +139,18 @@ impl Substate {
 		self.sstore_clears_refund += s.sstore_clears_refund;
 		self.contracts_created.extend(s.contracts_created);
 	}
+
+	/// Share the access list of the parent frame and checkpoint it, to be reverted if this
+	/// frame fails.
+	pub fn inherit_access_list(&mut self, parent: &Substate) {
+		self.access_list = parent.access_list.clone();
+		self.access_list.checkpoint();
+	}
 }
 
 #[cfg(test)]
 mod tests {
-	use ethereum_types::Address;
+	use ethereum_types::{Address, H256};
 	use common_types::log_entry::LogEntry;
 	use super::Substate;
 
@@ -94,4 +186,32 @@ mod tests {
 		assert_eq!(sub_state.sstore_clears_refund, (15000 * 12).into());
 		assert_eq!(sub_state.suicides.len(), 1);
 	}
+
+	#[test]
+	fn inherit_access_list() {
+		let parent = Substate::new();
+		parent.access_list.insert_address(Address::from_low_u64_be(1));
+		parent.access_list.insert_storage_key(Address::from_low_u64_be(1), H256::from_low_u64_be(2));
+
+		let mut child = Substate::new();
+		child.inherit_access_list(&parent);
+		assert!(child.access_list.contains_address(&Address::from_low_u64_be(1)));
+		assert!(child.access_list.contains_storage_key(&Address::from_low_u64_be(1), &H256::from_low_u64_be(2)));
+		assert!(child.suicides.is_empty());
+
+		// accesses of a frame are visible to its parent unless the frame is reverted.
+		child.access_list.insert_address(Address::from_low_u64_be(3));
+		let mut grandchild = Substate::new();
+		grandchild.inherit_access_list(&child);
+		grandchild.access_list.insert_address(Address::from_low_u64_be(4));
+		grandchild.access_list.insert_storage_key(Address::from_low_u64_be(1), H256::from_low_u64_be(5));
+		grandchild.access_list.revert_to_checkpoint();
+		child.access_list.discard_checkpoint();
+
+		assert!(parent.access_list.contains_address(&Address::from_low_u64_be(1)));
+		assert!(parent.access_list.contains_address(&Address::from_low_u64_be(3)));
+		assert!(!parent.access_list.contains_address(&Address::from_low_u64_be(4)));
+		assert!(!parent.access_list.contains_storage_key(&Address::from_low_u64_be(1), &H256::from_low_u64_be(5)));
+		assert!(parent.access_list.contains_storage_key(&Address::from_low_u64_be(1), &H256::from_low_u64_be(2)));
+	}
 }
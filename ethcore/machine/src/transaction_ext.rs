@@ -34,6 +34,16 @@ impl Transaction for transaction::Transaction {
 	}
 }
 
+impl Transaction for transaction::UnverifiedTransaction {
+	fn gas_required(&self, schedule: &Schedule) -> u64 {
+		let access_list_gas = self.access_list().map_or(0, |list| list.iter().fold(0, |g, item| {
+			g + schedule.tx_access_list_address_gas as u64 +
+				item.storage_keys.len() as u64 * schedule.tx_access_list_storage_key_gas as u64
+		}));
+		self.as_unsigned().gas_required(schedule) + access_list_gas
+	}
+}
+
 /// Get the transaction cost in gas for the given params.
 fn gas_required_for(is_create: bool, data: &[u8], schedule: &Schedule) -> u64 {
 	data.iter().fold(
@@ -21,6 +21,7 @@ use snapshot::test_helpers::AbridgedBlock;
 use bytes::Bytes;
 use ethereum_types::{H256, U256, Address};
 use common_types::{
+	BlockNumber,
 	transaction::{Action, Transaction},
 	block::Block,
 	view,
@@ -37,8 +38,8 @@ fn empty_block_abridging() {
 	let receipts_root = b.header.receipts_root().clone();
 	let encoded = encode_block(&b);
 
-	let abridged = AbridgedBlock::from_block_view(&view!(BlockView, &encoded));
-	assert_eq!(abridged.to_block(H256::zero(), 0, receipts_root).unwrap(), b);
+	let abridged = AbridgedBlock::from_block_view(&view!(BlockView, &encoded), BlockNumber::max_value());
+	assert_eq!(abridged.to_block(H256::zero(), 0, receipts_root, BlockNumber::max_value()).unwrap(), b);
 }
 
 #[test]
@@ -48,8 +49,8 @@ fn wrong_number() {
 	let receipts_root = b.header.receipts_root().clone();
 	let encoded = encode_block(&b);
 
-	let abridged = AbridgedBlock::from_block_view(&view!(BlockView, &encoded));
-	assert_eq!(abridged.to_block(H256::zero(), 2, receipts_root).unwrap(), b);
+	let abridged = AbridgedBlock::from_block_view(&view!(BlockView, &encoded), BlockNumber::max_value());
+	assert_eq!(abridged.to_block(H256::zero(), 2, receipts_root, BlockNumber::max_value()).unwrap(), b);
 }
 
 #[test]
@@ -84,6 +85,17 @@ fn with_transactions() {
 
 	let encoded = encode_block(&b);
 
-	let abridged = AbridgedBlock::from_block_view(&view!(BlockView, &encoded[..]));
-	assert_eq!(abridged.to_block(H256::zero(), 0, receipts_root).unwrap(), b);
+	let abridged = AbridgedBlock::from_block_view(&view!(BlockView, &encoded[..]), BlockNumber::max_value());
+	assert_eq!(abridged.to_block(H256::zero(), 0, receipts_root, BlockNumber::max_value()).unwrap(), b);
+}
+
+#[test]
+fn with_base_fee() {
+	let mut b = Block::default();
+	b.header.set_base_fee_per_gas(Some(U256::from(1_000_000_000)));
+	let receipts_root = b.header.receipts_root().clone();
+	let encoded = encode_block(&b);
+
+	let abridged = AbridgedBlock::from_block_view(&view!(BlockView, &encoded), 0);
+	assert_eq!(abridged.to_block(H256::zero(), 0, receipts_root, 0).unwrap(), b);
 }
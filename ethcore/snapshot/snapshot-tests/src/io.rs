@@ -16,13 +16,16 @@
 
 //! Tests for snapshot i/o.
 
+use std::fs::{self, File};
+use std::io;
+
 use tempdir::TempDir;
 use keccak_hash::keccak;
 
-use common_types::snapshot::ManifestData;
+use common_types::{errors::SnapshotError, snapshot::ManifestData};
 use snapshot::io::{
 	SnapshotWriter,SnapshotReader,
-	PackedWriter, PackedReader, LooseWriter, LooseReader,
+	PackedWriter, PackedReader, LooseWriter, LooseReader, TarWriter, TarReader,
 	SNAPSHOT_VERSION,
 };
 
@@ -107,3 +110,72 @@ fn loose_write_and_read() {
 		reader.chunk(hash.clone()).unwrap();
 	}
 }
+
+#[test]
+fn tar_write_and_read() {
+	let tempdir = TempDir::new("").unwrap();
+	let path = tempdir.path().join("snapshot.tar");
+	let mut writer = TarWriter::new(&path).unwrap();
+
+	let mut state_hashes = Vec::new();
+	let mut block_hashes = Vec::new();
+
+	for chunk in STATE_CHUNKS {
+		let hash = keccak(&chunk);
+		state_hashes.push(hash.clone());
+		writer.write_state_chunk(hash, chunk).unwrap();
+	}
+
+	for chunk in BLOCK_CHUNKS {
+		let hash = keccak(&chunk);
+		block_hashes.push(hash.clone());
+		writer.write_block_chunk(hash, chunk).unwrap();
+	}
+
+	let manifest = ManifestData {
+		version: SNAPSHOT_VERSION,
+		state_hashes,
+		block_hashes,
+		state_root: keccak(b"notarealroot"),
+		block_number: 12345678987654321,
+		block_hash: keccak(b"notarealblock"),
+	};
+
+	writer.finish(manifest.clone()).unwrap();
+	// only the archive is left behind.
+	assert_eq!(fs::read_dir(tempdir.path()).unwrap().count(), 1);
+
+	let mut reader = TarReader::new(File::open(&path).unwrap()).unwrap();
+	assert_eq!(reader.manifest(), &manifest);
+
+	let mut hashes = Vec::new();
+	while let Some((hash, chunk)) = reader.next_chunk().unwrap() {
+		assert_eq!(hash, keccak(&chunk));
+		hashes.push(hash);
+	}
+	assert_eq!(hashes, manifest.state_hashes.iter().chain(&manifest.block_hashes).cloned().collect::<Vec<_>>());
+
+	// corrupt the data of the first chunk, which comes after the manifest.
+	let mut archive = fs::read(&path).unwrap();
+	let manifest_len = manifest.into_rlp().len();
+	let chunk_offset = 512 + (manifest_len + 511) / 512 * 512 + 512;
+	archive[chunk_offset] ^= 0xff;
+	let mut reader = TarReader::new(&archive[..]).unwrap();
+	assert!(reader.next_chunk().is_err());
+}
+
+#[test]
+fn tar_rejects_oversized_entries() {
+	// a manifest entry claiming to be 8GB large.
+	let mut header = [0u8; 512];
+	header[..8].copy_from_slice(b"MANIFEST");
+	header[124..136].copy_from_slice(b"77777777777\0");
+	header[148..156].copy_from_slice(b"        ");
+	let checksum: u64 = header.iter().map(|b| *b as u64).sum();
+	header[148..156].copy_from_slice(format!("{:06o}\0 ", checksum).as_bytes());
+
+	match TarReader::new(&header[..]) {
+		Err(SnapshotError::Io(e)) => assert_eq!(e.kind(), io::ErrorKind::InvalidData),
+		_ => panic!("oversized entry should be rejected before it's read"),
+	}
+}
@@ -318,7 +318,7 @@ fn keep_ancient_blocks() {
 	for block_number in 1..50 {
 		let block_hash = bc.block_hash(block_number).unwrap();
 		let block = bc.block(&block_hash).unwrap();
-		client2.import_block(Unverified::from_rlp(block.into_inner()).unwrap()).unwrap();
+		client2.import_block(Unverified::from_rlp(block.into_inner(), client2.eip1559_transition()).unwrap()).unwrap();
 	}
 
 	client2.flush_queue();
@@ -106,6 +106,61 @@ fn snap_and_restore() {
 	}
 }
 
+#[test]
+fn merge_prepared_chunks_out_of_order() {
+	let mut producer = StateProducer::new();
+	let mut rng = XorShiftRng::from_seed(RNG_SEED);
+	let mut old_db = journaldb::new_memory_db();
+	let db_cfg = DatabaseConfig::with_columns(ethcore_db::NUM_COLUMNS);
+
+	for _ in 0..150 {
+		producer.tick(&mut rng, &mut old_db);
+	}
+
+	let tempdir = TempDir::new("").unwrap();
+	let snap_file = tempdir.path().join("SNAP");
+
+	let state_root = producer.state_root();
+	let writer = Mutex::new(PackedWriter::new(&snap_file).unwrap());
+
+	let mut state_hashes = Vec::new();
+	let progress = RwLock::new(Progress::new());
+	for part in 0..SNAPSHOT_SUBPARTS {
+		let mut hashes = chunk_state(&old_db, &state_root, &writer, &progress, Some(part), 0).unwrap();
+		state_hashes.append(&mut hashes);
+	}
+
+	writer.into_inner().finish(ManifestData {
+		version: 2,
+		state_hashes,
+		block_hashes: Vec::new(),
+		state_root,
+		block_number: 1000,
+		block_hash: H256::zero(),
+	}).unwrap();
+
+	let reader = PackedReader::new(&snap_file).unwrap().unwrap();
+	let flag = AtomicBool::new(true);
+
+	let prepared: Vec<_> = reader.manifest().state_hashes.iter()
+		.map(|hash| {
+			let chunk = snappy::decompress(&reader.chunk(*hash).unwrap()).unwrap();
+			StateRebuilder::prepare(&chunk, &flag).unwrap()
+		})
+		.collect();
+
+	let db_path = tempdir.path().join("db");
+	let new_db = Arc::new(Database::open(&db_cfg, &db_path.to_string_lossy()).unwrap());
+	let mut rebuilder = StateRebuilder::new(new_db, Algorithm::OverlayRecent);
+
+	for chunk in prepared.into_iter().rev() {
+		rebuilder.merge(chunk, &flag).unwrap();
+	}
+
+	assert_eq!(rebuilder.state_root(), state_root);
+	rebuilder.finalize(1000, H256::zero()).unwrap();
+}
+
 #[test]
 fn get_code_from_prev_chunk() {
 	use std::collections::HashSet;
@@ -18,8 +18,10 @@
 
 use bytes::Bytes;
 use common_types::{
+	BlockNumber,
 	block::Block,
 	header::Header,
+	transaction::typed_envelope_bytes,
 	views::BlockView,
 };
 use ethereum_types::H256;
@@ -49,16 +51,18 @@ impl AbridgedBlock {
 	}
 
 	/// Given a full block view, trim out the parent hash and block number,
-	/// producing new rlp.
-	pub fn from_block_view(block_view: &BlockView) -> Self {
+	/// producing new rlp. The base fee, if any, follows the seal fields.
+	pub fn from_block_view(block_view: &BlockView, eip1559_transition: BlockNumber) -> Self {
 		let header = block_view.header_view();
-		let seal_fields = header.seal();
+		let seal_fields = header.seal(eip1559_transition);
+		let base_fee = header.base_fee_per_gas(eip1559_transition);
 
-		// 10 header fields, unknown number of seal fields, and 2 block fields.
+		// 10 header fields, unknown number of seal fields, 2 block fields and the base fee.
 		let mut stream = RlpStream::new_list(
 			HEADER_FIELDS +
 			seal_fields.len() +
-			BLOCK_FIELDS
+			BLOCK_FIELDS +
+			base_fee.is_some() as usize
 		);
 
 		// write header values.
@@ -75,13 +79,17 @@ impl AbridgedBlock {
 		// write block values.
 		stream
 			.append_list(&block_view.transactions())
-			.append_list(&block_view.uncles());
+			.append_list(&block_view.uncles(eip1559_transition));
 
 		// write seal fields.
 		for field in seal_fields {
 			stream.append_raw(&field, 1);
 		}
 
+		if let Some(base_fee) = base_fee {
+			stream.append(&base_fee);
+		}
+
 		AbridgedBlock {
 			rlp: stream.out(),
 		}
@@ -90,7 +98,13 @@ impl AbridgedBlock {
 	/// Flesh out an abridged block view with the provided parent hash and block number.
 	///
 	/// Will fail if contains invalid rlp.
-	pub fn to_block(&self, parent_hash: H256, number: u64, receipts_root: H256) -> Result<Block, DecoderError> {
+	pub fn to_block(
+		&self,
+		parent_hash: H256,
+		number: u64,
+		receipts_root: H256,
+		eip1559_transition: BlockNumber,
+	) -> Result<Block, DecoderError> {
 		let rlp = Rlp::new(&self.rlp);
 
 		let mut header: Header = Default::default();
@@ -106,10 +120,10 @@ impl AbridgedBlock {
 		header.set_extra_data(rlp.val_at(7)?);
 
 		let transactions = rlp.list_at(8)?;
-		let uncles: Vec<Header> = rlp.list_at(9)?;
+		let uncles = Header::decode_rlp_list(&rlp.at(9)?, eip1559_transition)?;
 
 		header.set_transactions_root(ordered_trie_root(
-			rlp.at(8)?.iter().map(|r| r.as_raw())
+			rlp.at(8)?.iter().map(|r| typed_envelope_bytes(&r))
 		));
 		header.set_receipts_root(receipts_root);
 
@@ -117,8 +131,17 @@ impl AbridgedBlock {
 		uncles_rlp.append_list(&uncles);
 		header.set_uncles_hash(keccak(uncles_rlp.as_raw()));
 
+		let mut seal_end = rlp.item_count()?;
+		if number >= eip1559_transition {
+			if seal_end <= HEADER_FIELDS + BLOCK_FIELDS {
+				return Err(DecoderError::RlpIncorrectListLen);
+			}
+			seal_end -= 1;
+			header.set_base_fee_per_gas(Some(rlp.val_at(seal_end)?));
+		}
+
 		let mut seal_fields = Vec::new();
-		for i in (HEADER_FIELDS + BLOCK_FIELDS)..rlp.item_count()? {
+		for i in (HEADER_FIELDS + BLOCK_FIELDS)..seal_end {
 			let seal_rlp = rlp.at(i)?;
 			seal_fields.push(seal_rlp.as_raw().to_owned());
 		}
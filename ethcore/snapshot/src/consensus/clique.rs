@@ -0,0 +1,159 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Parity Ethereum.
+
+// Parity Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Secondary chunk creation and restoration, implementation for Clique chains.
+//!
+//! The secondary chunks have the format of the proof-of-work ones, but contain every
+//! block from the head of the chain back to the last checkpoint. The checkpoint header
+//! carries the signer set, from which the engine replays the signer votes up to the head.
+
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+use blockchain::{BlockChain, BlockChainDB, BlockProvider};
+use common_types::{
+	errors::{SnapshotError, EthcoreError},
+	snapshot::{ChunkSink, ManifestData, Progress},
+};
+use engine::Engine;
+use ethereum_types::H256;
+use log::trace;
+use parking_lot::RwLock;
+
+use crate::{SnapshotComponents, Rebuilder};
+use super::work::{PowSnapshot, PowRebuilder};
+
+/// Snapshot creation and restoration for Clique chains.
+#[derive(Clone, Copy, PartialEq)]
+pub struct CliqueSnapshot {
+	/// Number of blocks between checkpoints.
+	pub epoch_length: u64,
+}
+
+impl CliqueSnapshot {
+	/// Create a new instance.
+	pub fn new(epoch_length: u64) -> CliqueSnapshot {
+		CliqueSnapshot { epoch_length }
+	}
+
+	// Number of the last checkpoint at or before the given block.
+	fn checkpoint(&self, number: u64) -> u64 {
+		number - number % self.epoch_length
+	}
+
+	// Number of blocks in a snapshot at the given block. The genesis checkpoint is never
+	// included, as every chain starts with it.
+	fn snapshot_blocks(&self, number: u64) -> u64 {
+		match self.checkpoint(number) {
+			0 => number,
+			checkpoint => number - checkpoint + 1,
+		}
+	}
+}
+
+impl SnapshotComponents for CliqueSnapshot {
+	fn chunk_all(
+		&mut self,
+		chain: &BlockChain,
+		block_at: H256,
+		chunk_sink: &mut ChunkSink,
+		progress: &RwLock<Progress>,
+		preferred_size: usize,
+	) -> Result<(), SnapshotError> {
+		let number = chain.block_number(&block_at).ok_or_else(|| SnapshotError::BlockNotFound(block_at))?;
+		let blocks = self.snapshot_blocks(number);
+		trace!(target: "snapshot", "chunking {} blocks back to checkpoint #{}", blocks, self.checkpoint(number));
+
+		PowSnapshot::new(blocks, blocks).chunk_all(chain, block_at, chunk_sink, progress, preferred_size)
+	}
+
+	fn rebuilder(
+		&self,
+		chain: BlockChain,
+		db: Arc<dyn BlockChainDB>,
+		manifest: &ManifestData,
+	) -> Result<Box<dyn Rebuilder>, EthcoreError> {
+		let snapshot_blocks = self.snapshot_blocks(manifest.block_number);
+		let inner = PowRebuilder::new(chain, db.key_value().clone(), manifest, snapshot_blocks, false)?;
+
+		Ok(Box::new(CliqueRebuilder {
+			inner,
+			checkpoint: self.checkpoint(manifest.block_number),
+			best_hash: manifest.block_hash,
+			snapshot_blocks,
+			verified: false,
+		}))
+	}
+
+	fn min_supported_version(&self) -> u64 { crate::MIN_SUPPORTED_STATE_CHUNK_VERSION }
+	fn current_version(&self) -> u64 { crate::STATE_CHUNK_VERSION }
+}
+
+/// Rebuilder for Clique chains.
+///
+/// Blocks are restored like proof-of-work ones, checking each block on its own. Once every
+/// block is in, the headers from the checkpoint to the head are verified by the engine.
+pub struct CliqueRebuilder {
+	inner: PowRebuilder,
+	checkpoint: u64,
+	best_hash: H256,
+	snapshot_blocks: u64,
+	verified: bool,
+}
+
+impl CliqueRebuilder {
+	// Verify the restored headers from the checkpoint up to the head.
+	fn verify_headers(&mut self, engine: &dyn Engine) -> Result<(), EthcoreError> {
+		let chain = &self.inner.chain;
+		let mut headers = Vec::with_capacity(self.snapshot_blocks as usize + 1);
+		let mut hash = self.best_hash;
+		loop {
+			let header = chain.block_header_data(&hash)
+				.ok_or_else(|| SnapshotError::BlockNotFound(hash))?
+				.decode(engine.params().eip1559_transition)?;
+			let number = header.number();
+			hash = *header.parent_hash();
+			headers.push(header);
+
+			if number <= self.checkpoint { break }
+		}
+		headers.reverse();
+
+		trace!(target: "snapshot", "verifying {} headers from checkpoint #{}", headers.len(), self.checkpoint);
+		engine.verify_snapshot_headers(&headers)?;
+		self.verified = true;
+		Ok(())
+	}
+}
+
+impl Rebuilder for CliqueRebuilder {
+	fn feed(&mut self, chunk: &[u8], engine: &dyn Engine, abort_flag: &AtomicBool) -> Result<(), EthcoreError> {
+		self.inner.feed(chunk, engine, abort_flag)?;
+
+		// chunks may come in any order, so the chain is only complete after the last one.
+		if self.inner.fed_blocks == self.snapshot_blocks {
+			self.verify_headers(engine)?;
+		}
+		Ok(())
+	}
+
+	fn finalize(&mut self) -> Result<(), EthcoreError> {
+		if !self.verified {
+			return Err(SnapshotError::IncompleteChain.into())
+		}
+		self.inner.finalize()
+	}
+}
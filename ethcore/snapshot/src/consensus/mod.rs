@@ -18,9 +18,11 @@
 //! engines.
 
 mod authority;
+mod clique;
 mod work;
 
 pub use self::authority::*;
+pub use self::clique::*;
 pub use self::work::*;
 
 use crate::SnapshotComponents;
@@ -31,6 +33,7 @@ use common_types::snapshot::Snapshotting::{self, *};
 pub fn chunker(snapshot_type: Snapshotting) -> Option<Box<dyn SnapshotComponents>> {
 	match snapshot_type {
 		PoA => Some(Box::new(PoaSnapshot)),
+		Clique { epoch_length } => Some(Box::new(CliqueSnapshot::new(epoch_length))),
 		PoW { blocks, max_restore_blocks } => Some(Box::new(PowSnapshot::new(blocks, max_restore_blocks))),
 		Unsupported => None,
 	}
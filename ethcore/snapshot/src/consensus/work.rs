@@ -96,7 +96,8 @@ impl SnapshotComponents for PowSnapshot {
 			chain,
 			db.key_value().clone(),
 			manifest,
-			self.max_restore_blocks
+			self.max_restore_blocks,
+			true,
 		).map(|r| Box::new(r) as Box<_>)
 	}
 
@@ -131,7 +132,7 @@ impl<'a> PowWorker<'a> {
 				.and_then(|b| self.chain.block_receipts(&self.current_hash).map(|r| (b, r)))
 				.ok_or_else(||SnapshotError::BlockNotFound(self.current_hash))?;
 
-			let abridged_rlp = AbridgedBlock::from_block_view(&block.view()).into_inner();
+			let abridged_rlp = AbridgedBlock::from_block_view(&block.view(), self.chain.eip1559_transition()).into_inner();
 
 			let pair = {
 				let mut pair_stream = RlpStream::new_list(2);
@@ -206,20 +207,29 @@ impl<'a> PowWorker<'a> {
 ///
 /// After all chunks have been submitted, we "glue" the chunks together.
 pub struct PowRebuilder {
-	chain: BlockChain,
+	pub(super) chain: BlockChain,
 	db: Arc<dyn KeyValueDB>,
 	rng: OsRng,
 	disconnected: Vec<(u64, H256)>,
 	best_number: u64,
 	best_hash: H256,
 	best_root: H256,
-	fed_blocks: u64,
+	pub(super) fed_blocks: u64,
 	snapshot_blocks: u64,
+	verify_family: bool,
 }
 
 impl PowRebuilder {
 	/// Create a new PowRebuilder.
-	fn new(chain: BlockChain, db: Arc<dyn KeyValueDB>, manifest: &ManifestData, snapshot_blocks: u64) -> Result<Self, EthcoreError> {
+	/// Without `verify_family` blocks are checked on their own only, for engines whose family
+	/// verification needs the chain of the client.
+	pub(super) fn new(
+		chain: BlockChain,
+		db: Arc<dyn KeyValueDB>,
+		manifest: &ManifestData,
+		snapshot_blocks: u64,
+		verify_family: bool,
+	) -> Result<Self, EthcoreError> {
 		Ok(PowRebuilder {
 			chain,
 			db,
@@ -230,6 +240,7 @@ impl PowRebuilder {
 			best_root: manifest.state_root,
 			fed_blocks: 0,
 			snapshot_blocks,
+			verify_family,
 		})
 	}
 }
@@ -260,9 +271,9 @@ impl Rebuilder for PowRebuilder {
 			let abridged_rlp = pair.at(0)?.as_raw().to_owned();
 			let abridged_block = AbridgedBlock::from_raw(abridged_rlp);
 			let receipts: Vec<Receipt> = pair.list_at(1)?;
-			let receipts_root = ordered_trie_root(pair.at(1)?.iter().map(|r| r.as_raw()));
+			let receipts_root = ordered_trie_root(receipts.iter().map(|r| r.encode()));
 
-			let block = abridged_block.to_block(parent_hash, cur_number, receipts_root)?;
+			let block = abridged_block.to_block(parent_hash, cur_number, receipts_root, engine.params().eip1559_transition)?;
 			let block_bytes = encoded::Block::new(block.rlp_bytes());
 			let is_best = cur_number == self.best_number;
 
@@ -276,13 +287,18 @@ impl Rebuilder for PowRebuilder {
 				}
 			}
 
-			verify_old_block(
-				&mut self.rng,
-				&block.header,
-				engine,
-				&self.chain,
-				is_best
-			)?;
+			if self.verify_family {
+				verify_old_block(
+					&mut self.rng,
+					&block.header,
+					engine,
+					&self.chain,
+					is_best
+				)?;
+			} else {
+				engine.verify_block_basic(&block.header)?;
+				engine.verify_block_unordered(&block.header)?;
+			}
 
 			let mut batch = self.db.transaction();
 
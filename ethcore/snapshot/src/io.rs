@@ -16,14 +16,15 @@
 
 //! Snapshot i/o.
 //! Ways of writing and reading snapshots. This module supports writing and reading
-//! snapshots of two different formats: packed and loose.
-//! Packed snapshots are written to a single file, and loose snapshots are
+//! snapshots of three different formats: packed, tar and loose.
+//! Packed and tar snapshots are written to a single file, and loose snapshots are
 //! written to multiple files in one directory.
 
 use std::collections::HashMap;
 use std::io::{self, Read, Seek, SeekFrom, Write};
-use std::fs::{self, File};
+use std::fs::{self, File, OpenOptions};
 use std::path::{Path, PathBuf};
+use std::str;
 
 use bytes::Bytes;
 use common_types::{
@@ -31,9 +32,13 @@ use common_types::{
 	snapshot::ManifestData,
 };
 use ethereum_types::H256;
+use keccak_hash::keccak;
 use log::trace;
 use rlp::{RlpStream, Rlp};
 use rlp_derive::*;
+use snappy;
+
+use crate::MAX_CHUNK_SIZE;
 
 pub const SNAPSHOT_VERSION: u64 = 2;
 
@@ -173,6 +178,136 @@ impl SnapshotWriter for LooseWriter {
 	}
 }
 
+// size of tar headers, entries are padded to a multiple of it.
+const TAR_BLOCK_SIZE: usize = 512;
+// name of the tar entry holding the manifest.
+const TAR_MANIFEST: &str = "MANIFEST";
+
+// ustar header of a regular file entry.
+fn tar_header(name: &str, size: u64) -> [u8; TAR_BLOCK_SIZE] {
+	let mut header = [0u8; TAR_BLOCK_SIZE];
+	header[..name.len()].copy_from_slice(name.as_bytes());
+	header[100..108].copy_from_slice(b"0000644\0");
+	header[108..116].copy_from_slice(b"0000000\0");
+	header[116..124].copy_from_slice(b"0000000\0");
+	header[124..136].copy_from_slice(format!("{:011o}\0", size).as_bytes());
+	header[136..148].copy_from_slice(b"00000000000\0");
+	header[156] = b'0';
+	header[257..263].copy_from_slice(b"ustar\0");
+	header[263..265].copy_from_slice(b"00");
+
+	let checksum = tar_checksum(&header);
+	header[148..156].copy_from_slice(format!("{:06o}\0 ", checksum).as_bytes());
+	header
+}
+
+// sum of the header bytes, with the checksum field counted as spaces.
+fn tar_checksum(header: &[u8; TAR_BLOCK_SIZE]) -> u64 {
+	header.iter().enumerate()
+		.map(|(i, b)| if i >= 148 && i < 156 { b' ' as u64 } else { *b as u64 })
+		.sum()
+}
+
+fn tar_padding(size: u64) -> usize {
+	(TAR_BLOCK_SIZE - size as usize % TAR_BLOCK_SIZE) % TAR_BLOCK_SIZE
+}
+
+fn parse_octal(field: &[u8]) -> io::Result<u64> {
+	let field = str::from_utf8(field).ok()
+		.map(|f| f.trim_matches(|c| c == '\0' || c == ' '))
+		.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "invalid tar header field"))?;
+	u64::from_str_radix(field, 8)
+		.map_err(|_| io::Error::new(io::ErrorKind::InvalidData, format!("invalid tar header field: {}", field)))
+}
+
+fn write_tar_entry<W: Write>(out: &mut W, name: &str, data: &[u8]) -> io::Result<()> {
+	out.write_all(&tar_header(name, data.len() as u64))?;
+	out.write_all(data)?;
+	out.write_all(&[0u8; TAR_BLOCK_SIZE][..tar_padding(data.len() as u64)])
+}
+
+/// A tar snapshot writer. This writes snapshots to a single tar archive.
+///
+/// The archive starts with a `MANIFEST` entry holding the RLP of the `ManifestData`,
+/// followed by the state chunks and then the block chunks, each named after its hash.
+/// Since the manifest is only known once all chunks are written, chunks are kept in a
+/// temporary file next to the archive until `finish` is called.
+///
+/// As the manifest comes first, the archive can be restored while it's being read from
+/// a stream, e.g. straight from object storage.
+pub struct TarWriter {
+	file: File,
+	chunks_path: PathBuf,
+	chunks: File,
+	state_hashes: Vec<ChunkInfo>,
+	block_hashes: Vec<ChunkInfo>,
+	cur_len: u64,
+}
+
+impl TarWriter {
+	/// Create a new `TarWriter`, to write into the file at the given path.
+	pub fn new(path: &Path) -> io::Result<Self> {
+		let mut chunks_path = path.as_os_str().to_owned();
+		chunks_path.push(".chunks");
+		let chunks_path = PathBuf::from(chunks_path);
+
+		Ok(TarWriter {
+			file: File::create(path)?,
+			chunks: OpenOptions::new().read(true).write(true).create(true).truncate(true).open(&chunks_path)?,
+			chunks_path,
+			state_hashes: Vec::new(),
+			block_hashes: Vec::new(),
+			cur_len: 0,
+		})
+	}
+
+	fn write_chunk(&mut self, hash: H256, chunk: &[u8]) -> io::Result<ChunkInfo> {
+		self.chunks.write_all(chunk)?;
+
+		let len = chunk.len() as u64;
+		let info = ChunkInfo(hash, len, self.cur_len);
+		self.cur_len += len;
+		Ok(info)
+	}
+}
+
+impl SnapshotWriter for TarWriter {
+	fn write_state_chunk(&mut self, hash: H256, chunk: &[u8]) -> io::Result<()> {
+		let info = self.write_chunk(hash, chunk)?;
+		self.state_hashes.push(info);
+		Ok(())
+	}
+
+	fn write_block_chunk(&mut self, hash: H256, chunk: &[u8]) -> io::Result<()> {
+		let info = self.write_chunk(hash, chunk)?;
+		self.block_hashes.push(info);
+		Ok(())
+	}
+
+	fn finish(mut self, manifest: ManifestData) -> io::Result<()> {
+		let mut out = io::BufWriter::new(&self.file);
+		write_tar_entry(&mut out, TAR_MANIFEST, &manifest.into_rlp())?;
+
+		for &ChunkInfo(hash, len, off) in self.state_hashes.iter().chain(&self.block_hashes) {
+			let mut chunk = vec![0; len as usize];
+			self.chunks.seek(SeekFrom::Start(off))?;
+			self.chunks.read_exact(&mut chunk)?;
+			write_tar_entry(&mut out, &format!("{:x}", hash), &chunk)?;
+		}
+		trace!(target: "snapshot_io", "wrote {} state and {} block chunks to the archive", self.state_hashes.len(), self.block_hashes.len());
+
+		// end of archive marker.
+		out.write_all(&[0u8; 2 * TAR_BLOCK_SIZE])?;
+		out.flush()
+	}
+}
+
+impl Drop for TarWriter {
+	fn drop(&mut self) {
+		let _ = fs::remove_file(&self.chunks_path);
+	}
+}
+
 /// Something which can read compressed snapshots.
 pub trait SnapshotReader {
 	/// Get the manifest data for this snapshot.
@@ -294,6 +429,86 @@ impl SnapshotReader for PackedReader {
 	}
 }
 
+/// Tar snapshot reader. This reads the entries of an archive written by `TarWriter` in order,
+/// so the archive may be streamed instead of read from a file.
+///
+/// Each chunk is checked against the hash it's named after.
+pub struct TarReader<R> {
+	reader: R,
+	manifest: ManifestData,
+}
+
+impl<R: Read> TarReader<R> {
+	/// Create a new `TarReader` reading the archive from the given stream.
+	/// This reads the manifest, and will fail if the archive doesn't start with one
+	/// or its version isn't supported.
+	pub fn new(mut reader: R) -> Result<Self, SnapshotError> {
+		let (name, data) = Self::read_entry(&mut reader)?
+			.ok_or_else(|| io::Error::new(io::ErrorKind::UnexpectedEof, "empty snapshot archive"))?;
+		if name != TAR_MANIFEST {
+			return Err(io::Error::new(io::ErrorKind::InvalidData, format!("expected {}, found {}", TAR_MANIFEST, name)).into());
+		}
+
+		let manifest = ManifestData::from_rlp(&data)?;
+		if manifest.version > SNAPSHOT_VERSION {
+			return Err(SnapshotError::VersionNotSupported(manifest.version));
+		}
+
+		Ok(TarReader { reader, manifest })
+	}
+
+	/// Get the manifest data for this snapshot.
+	pub fn manifest(&self) -> &ManifestData {
+		&self.manifest
+	}
+
+	/// Read the next chunk of the archive, with its hash. Returns `None` at the end of the archive.
+	pub fn next_chunk(&mut self) -> io::Result<Option<(H256, Bytes)>> {
+		let (name, chunk) = match Self::read_entry(&mut self.reader)? {
+			Some(entry) => entry,
+			None => return Ok(None),
+		};
+
+		let hash: H256 = name.parse()
+			.map_err(|_| io::Error::new(io::ErrorKind::InvalidData, format!("unexpected archive entry {}", name)))?;
+		let actual = keccak(&chunk);
+		if actual != hash {
+			return Err(io::Error::new(io::ErrorKind::InvalidData, format!("mismatched chunk hash. Expected {:?}, got {:?}", hash, actual)));
+		}
+
+		Ok(Some((hash, chunk)))
+	}
+
+	// read the name and data of the next entry, `None` at the end of the archive.
+	fn read_entry(reader: &mut R) -> io::Result<Option<(String, Bytes)>> {
+		let mut header = [0u8; TAR_BLOCK_SIZE];
+		reader.read_exact(&mut header)?;
+		if header.iter().all(|b| *b == 0) {
+			return Ok(None);
+		}
+
+		if parse_octal(&header[148..156])? != tar_checksum(&header) {
+			return Err(io::Error::new(io::ErrorKind::InvalidData, "invalid tar header checksum"));
+		}
+
+		let name_len = header[..100].iter().position(|b| *b == 0).unwrap_or(100);
+		let name = str::from_utf8(&header[..name_len])
+			.map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "invalid archive entry name"))?
+			.to_owned();
+		let size = parse_octal(&header[124..136])?;
+		// chunks are compressed, so no entry is larger than a compressed chunk.
+		if size > snappy::max_compressed_len(MAX_CHUNK_SIZE) as u64 {
+			return Err(io::Error::new(io::ErrorKind::InvalidData, format!("archive entry {} is too large: {} bytes", name, size)));
+		}
+
+		let mut data = vec![0; size as usize];
+		reader.read_exact(&mut data)?;
+		reader.read_exact(&mut [0u8; TAR_BLOCK_SIZE][..tar_padding(size)])?;
+
+		Ok(Some((name, data)))
+	}
+}
+
 /// reader for "loose" snapshots
 pub struct LooseReader {
 	dir: PathBuf,
@@ -47,6 +47,7 @@ use keccak_hasher::KeccakHasher;
 use parking_lot::{Mutex, RwLock};
 use kvdb::{KeyValueDB, DBValue};
 use log::{debug, info, trace};
+use memory_db::{HashKey, MemoryDB};
 use num_cpus;
 use rand::{Rng, rngs::OsRng};
 use rlp::{RlpStream, Rlp};
@@ -372,6 +373,14 @@ pub fn chunk_state<'a>(
 	Ok(chunker.hashes)
 }
 
+/// A state chunk prepared by `StateRebuilder::prepare`, to be merged into a `StateRebuilder`.
+pub struct PreparedStateChunk {
+	db: MemoryDB<KeccakHasher, HashKey<KeccakHasher>, DBValue>, // storage and code of the inner accounts.
+	pairs: Vec<(H256, Bytes)>, // inner accounts mapped to their thin RLP.
+	status: RebuiltStatus,
+	boundary: Vec<Bytes>, // fat RLP of the first and last accounts.
+}
+
 /// Used to rebuild the state trie piece by piece.
 pub struct StateRebuilder {
 	db: Box<dyn JournalDB>,
@@ -397,21 +406,78 @@ impl StateRebuilder {
 
 	/// Feed an uncompressed state chunk into the rebuilder.
 	pub fn feed(&mut self, chunk: &[u8], flag: &AtomicBool) -> Result<(), EthcoreError> {
+		let prepared = Self::prepare(chunk, flag)?;
+		self.merge(prepared, flag)
+	}
+
+	/// Decode an uncompressed state chunk and rebuild the storage and code of its accounts,
+	/// without touching the rebuilder. Chunks can be prepared in parallel and merged in any order.
+	///
+	/// The first and last accounts of the chunk may have storage continued in other chunks, so
+	/// their storage is only rebuilt when merging.
+	pub fn prepare(chunk: &[u8], flag: &AtomicBool) -> Result<PreparedStateChunk, EthcoreError> {
 		let rlp = Rlp::new(chunk);
+		let count = rlp.item_count()?;
+
+		let mut boundary = Vec::new();
+		let mut inner = RlpStream::new_list(count.saturating_sub(2));
+		for (i, account_rlp) in rlp.iter().enumerate() {
+			if i == 0 || i + 1 == count {
+				boundary.push(account_rlp.as_raw().to_vec());
+			} else {
+				inner.append_raw(account_rlp.as_raw(), 1);
+			}
+		}
+
+		let inner = inner.out();
+		let inner = Rlp::new(&inner);
+		let mut db = journaldb::new_memory_db();
+		let mut pairs = vec![(H256::zero(), Vec::new()); inner.item_count()?];
+		let status = rebuild_accounts(&mut db, inner, &mut pairs, &HashMap::new(), &mut HashMap::new(), flag)?;
+
+		Ok(PreparedStateChunk { db, pairs, status, boundary })
+	}
+
+	/// Merge a prepared state chunk into the rebuilder.
+	pub fn merge(&mut self, prepared: PreparedStateChunk, flag: &AtomicBool) -> Result<(), EthcoreError> {
+		let PreparedStateChunk { db, mut pairs, mut status, boundary } = prepared;
 		let empty_rlp = StateAccount::new_basic(U256::zero(), U256::zero()).rlp();
-		let mut pairs = Vec::with_capacity(rlp.item_count()?);
 
-		// initialize the pairs vector with empty values so we have slots to write into.
-		pairs.resize(rlp.item_count()?, (H256::zero(), Vec::new()));
+		self.db.consolidate(db);
+
+		// code referenced by prepared accounts may have been inlined in an earlier chunk.
+		let mut missing_code = Vec::with_capacity(status.missing_code.len());
+		for (addr_hash, code_hash) in status.missing_code.drain(..) {
+			match self.known_code.get(&code_hash) {
+				Some(&first_with) => {
+					let code = AccountDB::from_hash(self.db.as_hash_db(), first_with)
+						.get(&code_hash, hash_db::EMPTY_PREFIX)
+						.ok_or_else(|| Error::MissingCode(vec![first_with]))?;
+					AccountDBMut::from_hash(self.db.as_hash_db_mut(), addr_hash).emplace(code_hash, hash_db::EMPTY_PREFIX, code);
+				},
+				None => missing_code.push((addr_hash, code_hash)),
+			}
+		}
+		status.missing_code = missing_code;
 
-		let status = rebuild_accounts(
+		// rebuild the boundary accounts, continuing storage known from other chunks.
+		let mut boundary_rlp = RlpStream::new_list(boundary.len());
+		for account_rlp in &boundary {
+			boundary_rlp.append_raw(account_rlp, 1);
+		}
+		let boundary_rlp = boundary_rlp.out();
+		let mut boundary_pairs = vec![(H256::zero(), Vec::new()); boundary.len()];
+		let boundary_status = rebuild_accounts(
 			self.db.as_hash_db_mut(),
-			rlp,
-			&mut pairs,
+			Rlp::new(&boundary_rlp),
+			&mut boundary_pairs,
 			&self.known_code,
 			&mut self.known_storage_roots,
 			flag
 		)?;
+		pairs.extend(boundary_pairs);
+		status.new_code.extend(boundary_status.new_code);
+		status.missing_code.extend(boundary_status.missing_code);
 
 		for (addr_hash, code_hash) in status.missing_code {
 			self.missing_code.entry(code_hash).or_insert_with(Vec::new).push(addr_hash);
@@ -556,7 +622,7 @@ pub fn verify_old_block(rng: &mut OsRng, header: &Header, engine: &dyn Engine, c
 	if always || rng.gen::<f32>() <= POW_VERIFY_RATE {
 		engine.verify_block_unordered(header)?;
 		match chain.block_header_data(header.parent_hash()) {
-			Some(parent) => engine.verify_block_family(header, &parent.decode()?).map_err(Into::into),
+			Some(parent) => engine.verify_block_family(header, &parent.decode(engine.params().eip1559_transition)?).map_err(Into::into),
 			None => Ok(()),
 		}
 	} else {
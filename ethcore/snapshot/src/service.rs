@@ -20,11 +20,11 @@ use std::collections::HashSet;
 use std::io::{self, Read, ErrorKind};
 use std::fs::{self, File};
 use std::path::PathBuf;
-use std::sync::Arc;
+use std::sync::{mpsc, Arc};
 use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::cmp;
 
-use blockchain::{BlockChain, BlockChainDB, BlockChainDBHandler};
+use blockchain::{BlockChain, BlockChainDB, BlockChainDBHandler, Config as BlockChainConfig};
 use bytes::Bytes;
 use common_types::{
 	io_message::ClientIoMessage,
@@ -33,6 +33,7 @@ use common_types::{
 	snapshot::{ManifestData, Progress, RestorationStatus},
 };
 use client_traits::ChainInfo;
+use crossbeam_utils::thread;
 use engine::Engine;
 use ethereum_types::H256;
 use ethcore_io::IoChannel;
@@ -48,6 +49,7 @@ use crate::{SnapshotClient, SnapshotWriter};
 
 use super::{
 	StateRebuilder,
+	PreparedStateChunk,
 	SnapshotService,
 	Rebuilder,
 	MAX_CHUNK_SIZE,
@@ -125,7 +127,11 @@ impl Restoration {
 
 		let raw_db = params.db;
 
-		let chain = BlockChain::new(Default::default(), params.genesis, raw_db.clone());
+		let chain_config = BlockChainConfig {
+			eip1559_transition: params.engine.params().eip1559_transition,
+			..Default::default()
+		};
+		let chain = BlockChain::new(chain_config, params.genesis, raw_db.clone());
 		let chunker = chunker(params.engine.snapshot_mode())
 			.ok_or_else(|| Error::Snapshot(SnapshotError::SnapshotsUnsupported))?;
 
@@ -150,18 +156,35 @@ impl Restoration {
 	/// Feeds a chunk of state data to the Restoration. Aborts early if `flag` becomes false.
 	pub fn feed_state(&mut self, hash: H256, chunk: &[u8], flag: &AtomicBool) -> Result<(), Error> {
 		if self.state_chunks_left.contains(&hash) {
-			let expected_len = snappy::decompressed_len(chunk)?;
-			if expected_len > MAX_CHUNK_SIZE {
-				trace!(target: "snapshot", "Discarding large chunk: {} vs {}", expected_len, MAX_CHUNK_SIZE);
-				return Err(SnapshotError::ChunkTooLarge.into());
-			}
-			let len = snappy::decompress_into(chunk, &mut self.snappy_buffer)?;
+			let prepared = Self::prepare_state(chunk, flag)?;
+			self.feed_prepared_state(hash, chunk, prepared, flag)?;
+		}
 
-			self.state.feed(&self.snappy_buffer[..len], flag)?;
+		Ok(())
+	}
+
+	/// Decompresses and prepares a chunk of state data without touching the Restoration, so that
+	/// chunks can be prepared in parallel. Aborts early if `flag` becomes false.
+	pub fn prepare_state(chunk: &[u8], flag: &AtomicBool) -> Result<PreparedStateChunk, Error> {
+		let expected_len = snappy::decompressed_len(chunk)?;
+		if expected_len > MAX_CHUNK_SIZE {
+			trace!(target: "snapshot", "Discarding large chunk: {} vs {}", expected_len, MAX_CHUNK_SIZE);
+			return Err(SnapshotError::ChunkTooLarge.into());
+		}
+		let chunk = snappy::decompress(chunk)?;
+
+		StateRebuilder::prepare(&chunk, flag)
+	}
+
+	/// Feeds a chunk of state data, prepared from the compressed `chunk`, to the Restoration.
+	/// Aborts early if `flag` becomes false.
+	pub fn feed_prepared_state(&mut self, hash: H256, chunk: &[u8], prepared: PreparedStateChunk, flag: &AtomicBool) -> Result<(), Error> {
+		if self.state_chunks_left.contains(&hash) {
+			self.state.merge(prepared, flag)?;
 
 			if let Some(ref mut writer) = self.writer.as_mut() {
 				writer.write_state_chunk(hash, chunk)?;
-				trace!(target: "snapshot", "Wrote {}/{} bytes of state to db/disk. Current state root: {:?}", len, chunk.len(), self.state.state_root());
+				trace!(target: "snapshot", "Wrote {} bytes of state to db/disk. Current state root: {:?}", chunk.len(), self.state.state_root());
 			}
 
 			self.state_chunks_left.remove(&hash);
@@ -259,6 +282,7 @@ pub struct Service<C: Send + Sync + 'static> {
 	genesis_block: Bytes,
 	state_chunks: AtomicUsize,
 	block_chunks: AtomicUsize,
+	state_workers: Mutex<Vec<usize>>,
 	client: Arc<C>,
 	progress: RwLock<Progress>,
 	taking_snapshot: AtomicBool,
@@ -280,6 +304,7 @@ impl<C> Service<C> where C: SnapshotClient + ChainInfo {
 			genesis_block: params.genesis_block,
 			state_chunks: AtomicUsize::new(0),
 			block_chunks: AtomicUsize::new(0),
+			state_workers: Mutex::new(Vec::new()),
 			client: params.client,
 			progress: RwLock::new(Progress::new()),
 			taking_snapshot: AtomicBool::new(false),
@@ -364,7 +389,11 @@ impl<C> Service<C> where C: SnapshotClient + ChainInfo {
 		let cur_chain_info = self.client.chain_info();
 
 		let next_db = self.restoration_db_handler.open(&rest_db)?;
-		let next_chain = BlockChain::new(Default::default(), &[], next_db.clone());
+		let chain_config = BlockChainConfig {
+			eip1559_transition: self.engine.params().eip1559_transition,
+			..Default::default()
+		};
+		let next_chain = BlockChain::new(chain_config, &[], next_db.clone());
 		let next_chain_info = next_chain.chain_info();
 
 		// The old database looks like this:
@@ -680,7 +709,7 @@ impl<C> Service<C> where C: SnapshotClient + ChainInfo {
 			return Ok(false);
 		};
 
-		self.feed_chunk_with_restoration(restoration, hash, &buffer, is_state)?;
+		self.feed_chunk_with_restoration(restoration, hash, &buffer, is_state, None)?;
 
 		trace!(target: "snapshot", "Fed chunk {:?}", hash);
 
@@ -732,10 +761,15 @@ impl<C> Service<C> where C: SnapshotClient + ChainInfo {
 	/// Feed a chunk of either kind (block or state). no-op if no restoration or status is wrong.
 	fn feed_chunk(&self, hash: H256, chunk: &[u8], is_state: bool) {
 		// TODO: be able to process block chunks and state chunks at same time?
-		let r = {
+		let r = match is_state {
+			// state chunks are prepared before locking the restoration, so that they are
+			// prepared in parallel when fed from several threads.
+			true => self.prepare_state_chunk(hash, chunk),
+			false => Ok(None),
+		}.and_then(|prepared| {
 			let mut restoration = self.restoration.lock();
-			self.feed_chunk_with_restoration(&mut restoration, hash, chunk, is_state)
-		};
+			self.feed_chunk_with_restoration(&mut restoration, hash, chunk, is_state, prepared)
+		});
 		match r {
 			Ok(()) |
 			Err(Error::Snapshot(SnapshotError::RestorationAborted)) => (),
@@ -752,7 +786,14 @@ impl<C> Service<C> where C: SnapshotClient + ChainInfo {
 	}
 
 	/// Feed a chunk with the Restoration
-	fn feed_chunk_with_restoration(&self, restoration: &mut Option<Restoration>, hash: H256, chunk: &[u8], is_state: bool) -> Result<(), Error> {
+	fn feed_chunk_with_restoration(
+		&self,
+		restoration: &mut Option<Restoration>,
+		hash: H256,
+		chunk: &[u8],
+		is_state: bool,
+		prepared: Option<PreparedStateChunk>,
+	) -> Result<(), Error> {
 		let (result, db) = {
 			match self.status() {
 				RestorationStatus::Inactive | RestorationStatus::Failed | RestorationStatus::Finalizing => {
@@ -766,9 +807,10 @@ impl<C> Service<C> where C: SnapshotClient + ChainInfo {
 							None => return Ok(()),
 						};
 
-						(match is_state {
-							true => rest.feed_state(hash, chunk, &self.restoring_snapshot),
-							false => rest.feed_blocks(hash, chunk, &*self.engine, &self.restoring_snapshot),
+						(match (is_state, prepared) {
+							(true, Some(prepared)) => rest.feed_prepared_state(hash, chunk, prepared, &self.restoring_snapshot),
+							(true, None) => rest.feed_state(hash, chunk, &self.restoring_snapshot),
+							(false, _) => rest.feed_blocks(hash, chunk, &*self.engine, &self.restoring_snapshot),
 						}.map(|_| rest.is_done()), rest.db.clone())
 					};
 
@@ -800,11 +842,72 @@ impl<C> Service<C> where C: SnapshotClient + ChainInfo {
 		Ok(())
 	}
 
+	// Prepare a state chunk, if the restoration still needs it.
+	fn prepare_state_chunk(&self, hash: H256, chunk: &[u8]) -> Result<Option<PreparedStateChunk>, Error> {
+		let needed = self.restoration.lock().as_ref().map_or(false, |rest| rest.state_chunks_left.contains(&hash));
+		if !needed {
+			return Ok(None);
+		}
+
+		Restoration::prepare_state(chunk, &self.restoring_snapshot).map(Some)
+	}
+
 	/// Feed a state chunk to be processed synchronously.
 	pub fn feed_state_chunk(&self, hash: H256, chunk: &[u8]) {
 		self.feed_chunk(hash, chunk, true);
 	}
 
+	/// Feed state chunks to be processed by a pool of `num_workers` threads. Chunks are prepared in
+	/// parallel and merged into the restoration one at a time. Returns once all chunks are processed.
+	pub fn feed_state_chunks<I>(&self, chunks: I, num_workers: usize) where I: IntoIterator<Item = (H256, Bytes)> {
+		let num_workers = cmp::max(num_workers, 1);
+		*self.state_workers.lock() = vec![0; num_workers];
+
+		// keep a few chunks in flight per worker at most.
+		let (tx, rx) = mpsc::sync_channel::<(H256, Bytes)>(num_workers);
+		let rx = Mutex::new(rx);
+
+		thread::scope(|scope| {
+			let mut spawned_workers = 0;
+			for worker in 0..num_workers {
+				let rx = &rx;
+				let tb = scope.builder().name(format!("Snapshot Restoration Worker #{}", worker));
+				let spawned = tb.spawn(move |_| loop {
+					let next = rx.lock().recv();
+					match next {
+						Ok((hash, chunk)) => {
+							self.feed_state_chunk(hash, &chunk);
+							self.state_workers.lock()[worker] += 1;
+							trace!(target: "snapshot", "Restoration worker #{} fed state chunk {:?}", worker, hash);
+						},
+						// all chunks were sent.
+						Err(_) => break,
+					}
+				});
+				match spawned {
+					Ok(_) => spawned_workers += 1,
+					Err(e) => warn!(target: "snapshot", "Failed to spawn restoration worker #{}: {}", worker, e),
+				}
+			}
+
+			for (hash, chunk) in chunks {
+				if spawned_workers == 0 {
+					self.feed_state_chunk(hash, &chunk);
+				} else if tx.send((hash, chunk)).is_err() {
+					break;
+				}
+			}
+			drop(tx);
+		}).expect("Sub-thread never panics; qed");
+
+		debug!(target: "snapshot", "State chunks fed per restoration worker: {:?}", *self.state_workers.lock());
+	}
+
+	/// Number of state chunks fed by each worker of the last `feed_state_chunks` call.
+	pub fn state_workers_progress(&self) -> Vec<usize> {
+		self.state_workers.lock().clone()
+	}
+
 	/// Feed a block chunk to be processed synchronously.
 	pub fn feed_block_chunk(&self, hash: H256, chunk: &[u8]) {
 		self.feed_chunk(hash, chunk, false);
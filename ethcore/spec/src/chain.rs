@@ -88,6 +88,7 @@ bundle_test_spec! {
 	"authority_round_block_reward_contract" => new_test_round_block_reward_contract,
 	"authority_round_empty_steps" => new_test_round_empty_steps,
 	"authority_round_randomness_contract" => new_test_round_randomness_contract,
+	"authority_round_step_duration_contract" => new_test_round_step_duration_contract,
 	"constructor" => new_test_constructor,
 	"ethereum/byzantium_test" => new_byzantium_test,
 	"ethereum/constantinople_test" => new_constantinople_test,
@@ -171,4 +172,15 @@ mod tests {
 		let genesis = frontier.genesis_block();
 		assert_eq!(view!(BlockView, &genesis).header_view().hash(), "d4e56740f876aef8c010b86a40d5f56745a118d0906a34e69aec8c0db1cb8fa3".parse().unwrap());
 	}
+
+	#[test]
+	fn foundation_hard_forks() {
+		let tempdir = TempDir::new("").unwrap();
+		let foundation = new_foundation(&tempdir.path());
+
+		assert_eq!(
+			foundation.hard_forks().into_iter().collect::<Vec<_>>(),
+			vec![1_150_000, 1_920_000, 2_463_000, 2_675_000, 4_370_000, 7_280_000, 9_069_000, 9_200_000, 12_244_000],
+		);
+	}
 }
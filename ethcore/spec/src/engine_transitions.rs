@@ -0,0 +1,487 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Parity Ethereum.
+
+// Parity Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Consensus engine switching to other engines at given blocks.
+//!
+//! Calls about a given block are routed to the engine active at its height. Calls which
+//! aren't about any block, like sealing and consensus messages, go to the engine of the
+//! block following the best block of the canonical chain.
+//!
+//! The first block of each new engine ends an epoch immediately. Its transition proof is
+//! the genesis epoch data of the new engine, generated from the state of that block.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::{Arc, Weak};
+
+use client_traits::EngineClient;
+use common_types::{
+	BlockNumber,
+	ancestry_action::AncestryAction,
+	header::{Header, ExtendedHeader},
+	engines::{
+		Seal, SealingState, Headers, PendingTransitionStore, ValidatorStats, RandomnessRound,
+		params::CommonParams,
+		machine::{AuxiliaryData, Call},
+	},
+	errors::{EthcoreError as Error, EngineError},
+	ids::BlockId,
+	snapshot::Snapshotting,
+	transaction::{self, SignedTransaction, UnverifiedTransaction},
+};
+use engine::{
+	Engine, ConstructedVerifier, EpochChange, Proof, StateDependentProof,
+	signer::EngineSigner,
+};
+use ethereum_types::{H256, U256, Address};
+use log::{debug, info};
+use machine::{Machine, executed_block::ExecutedBlock};
+use parity_crypto::publickey::{Error as CryptoError, Public, Signature};
+use parking_lot::RwLock;
+use stats::PrometheusRegistry;
+use vm::{EnvInfo, Schedule};
+
+/// Consensus engine delegating to the engine active at each block.
+pub struct EngineTransitions {
+	engines: BTreeMap<BlockNumber, Arc<dyn Engine>>,
+	client: RwLock<Option<Weak<dyn EngineClient>>>,
+}
+
+impl EngineTransitions {
+	/// Create a new instance from the engines by the first block they're active at.
+	pub fn new(engines: BTreeMap<BlockNumber, Arc<dyn Engine>>) -> Self {
+		assert!(engines.contains_key(&0), "Engine has to be specified from block 0.");
+		EngineTransitions {
+			engines,
+			client: RwLock::new(None),
+		}
+	}
+
+	// the engine active at the given block, along with the first block it's active at.
+	fn engine_by_number(&self, number: BlockNumber) -> (BlockNumber, &Arc<dyn Engine>) {
+		let (block, engine) = self.engines.range(..=number)
+			.next_back()
+			.expect("constructor validation ensures that there is an engine for block 0;
+					 block 0 is less than any uint;
+					 qed");
+		(*block, engine)
+	}
+
+	fn engine(&self, header: &Header) -> &dyn Engine {
+		&**self.engine_by_number(header.number()).1
+	}
+
+	// the engine of the block following the best block.
+	fn current(&self) -> &dyn Engine {
+		let best_block = self.client.read()
+			.as_ref()
+			.and_then(Weak::upgrade)
+			.and_then(|client| client.block_number(BlockId::Latest))
+			.unwrap_or(0);
+		&**self.engine_by_number(best_block + 1).1
+	}
+
+	// the engine whose first block is the given one, other than the genesis engine.
+	fn starting_engine(&self, header: &Header) -> Option<&Arc<dyn Engine>> {
+		match header.number() {
+			0 => None,
+			number => self.engines.get(&number),
+		}
+	}
+}
+
+/// Proof of the first epoch of an engine, generated from the state of its first block.
+struct FirstEpochProof {
+	engine: Arc<dyn Engine>,
+	header: Header,
+}
+
+impl StateDependentProof for FirstEpochProof {
+	fn generate_proof(&self, state: &Call) -> Result<Vec<u8>, String> {
+		self.engine.genesis_epoch_data(&self.header, state)
+	}
+
+	fn check_proof(&self, _machine: &Machine, proof: &[u8]) -> Result<(), String> {
+		match self.engine.epoch_verifier(&self.header, proof) {
+			ConstructedVerifier::Err(e) => Err(e.to_string()),
+			_ => Ok(()),
+		}
+	}
+}
+
+/// Signer shared by all engines, as each of them takes ownership of its signer.
+struct SharedSigner(Arc<dyn EngineSigner>);
+
+impl EngineSigner for SharedSigner {
+	fn sign(&self, hash: H256) -> Result<Signature, CryptoError> {
+		self.0.sign(hash)
+	}
+
+	fn sign_seal(&self, step: u64, hash: H256) -> Result<Signature, CryptoError> {
+		self.0.sign_seal(step, hash)
+	}
+
+	fn sign_data(&self, data: &[u8]) -> Result<Signature, CryptoError> {
+		self.0.sign_data(data)
+	}
+
+	fn sign_seal_data(&self, step: u64, data: &[u8]) -> Result<Signature, CryptoError> {
+		self.0.sign_seal_data(step, data)
+	}
+
+	fn address(&self) -> Address {
+		self.0.address()
+	}
+
+	fn decrypt(&self, auth_data: &[u8], cipher: &[u8]) -> Result<Vec<u8>, CryptoError> {
+		self.0.decrypt(auth_data, cipher)
+	}
+
+	fn public(&self) -> Option<Public> {
+		self.0.public()
+	}
+}
+
+impl Engine for EngineTransitions {
+	fn name(&self) -> &str {
+		self.current().name()
+	}
+
+	fn machine(&self) -> &Machine {
+		self.engine_by_number(0).1.machine()
+	}
+
+	fn seal_fields(&self, header: &Header) -> usize {
+		self.engine(header).seal_fields(header)
+	}
+
+	fn extra_info(&self, header: &Header) -> BTreeMap<String, String> {
+		self.engine(header).extra_info(header)
+	}
+
+	fn maximum_uncle_count(&self, block: BlockNumber) -> usize {
+		self.engine_by_number(block).1.maximum_uncle_count(block)
+	}
+
+	fn maximum_gas_limit(&self) -> Option<U256> {
+		self.current().maximum_gas_limit()
+	}
+
+	fn on_new_block(&self, block: &mut ExecutedBlock, epoch_begin: bool) -> Result<(), Error> {
+		let engine = self.engine_by_number(block.header.number()).1.clone();
+		engine.on_new_block(block, epoch_begin)
+	}
+
+	fn on_close_block(&self, block: &mut ExecutedBlock, parent_header: &Header) -> Result<(), Error> {
+		let engine = self.engine_by_number(block.header.number()).1.clone();
+		engine.on_close_block(block, parent_header)
+	}
+
+	fn on_seal_block(&self, block: &mut ExecutedBlock) -> Result<(), Error> {
+		let engine = self.engine_by_number(block.header.number()).1.clone();
+		engine.on_seal_block(block)
+	}
+
+	fn generate_engine_transactions(&self, block: &ExecutedBlock) -> Result<Vec<SignedTransaction>, Error> {
+		self.engine(&block.header).generate_engine_transactions(block)
+	}
+
+	fn sealing_state(&self) -> SealingState {
+		self.current().sealing_state()
+	}
+
+	fn should_reseal_on_update(&self) -> bool {
+		self.current().should_reseal_on_update()
+	}
+
+	fn generate_seal(&self, block: &ExecutedBlock, parent: &Header) -> Seal {
+		self.engine(&block.header).generate_seal(block, parent)
+	}
+
+	fn verify_local_seal(&self, header: &Header) -> Result<(), Error> {
+		self.engine(header).verify_local_seal(header)
+	}
+
+	fn verify_block_basic(&self, header: &Header) -> Result<(), Error> {
+		self.engine(header).verify_block_basic(header)
+	}
+
+	fn verify_block_unordered(&self, header: &Header) -> Result<(), Error> {
+		self.engine(header).verify_block_unordered(header)
+	}
+
+	fn verify_block_family(&self, header: &Header, parent: &Header) -> Result<(), Error> {
+		self.engine(header).verify_block_family(header, parent)
+	}
+
+	fn verify_block_external(&self, header: &Header) -> Result<(), Error> {
+		self.engine(header).verify_block_external(header)
+	}
+
+	fn genesis_epoch_data(&self, header: &Header, state: &Call) -> Result<Vec<u8>, String> {
+		self.engine(header).genesis_epoch_data(header, state)
+	}
+
+	fn signals_epoch_end(&self, header: &Header, aux: AuxiliaryData) -> EpochChange {
+		if let Some(engine) = self.starting_engine(header) {
+			debug!(target: "engine", "Block {} starts the first epoch of the {} engine", header.number(), engine.name());
+			return EpochChange::Yes(Proof::WithState(Arc::new(FirstEpochProof {
+				engine: engine.clone(),
+				header: header.clone(),
+			})));
+		}
+		self.engine(header).signals_epoch_end(header, aux)
+	}
+
+	fn is_epoch_end(
+		&self,
+		chain_head: &Header,
+		finalized: &[H256],
+		chain: &Headers<Header>,
+		transition_store: &PendingTransitionStore,
+	) -> Option<Vec<u8>> {
+		if let Some(engine) = self.starting_engine(chain_head) {
+			info!(target: "engine", "Switching to the {} engine at block {}", engine.name(), chain_head.number());
+			return transition_store(chain_head.hash()).map(|pending| pending.proof);
+		}
+		self.engine(chain_head).is_epoch_end(chain_head, finalized, chain, transition_store)
+	}
+
+	fn is_epoch_end_light(
+		&self,
+		chain_head: &Header,
+		chain: &Headers<Header>,
+		transition_store: &PendingTransitionStore,
+	) -> Option<Vec<u8>> {
+		if self.starting_engine(chain_head).is_some() {
+			return transition_store(chain_head.hash()).map(|pending| pending.proof);
+		}
+		self.engine(chain_head).is_epoch_end_light(chain_head, chain, transition_store)
+	}
+
+	fn epoch_verifier<'a>(&self, header: &Header, proof: &'a [u8]) -> ConstructedVerifier<'a> {
+		self.engine(header).epoch_verifier(header, proof)
+	}
+
+	fn populate_from_parent(&self, header: &mut Header, parent: &Header) {
+		self.engine_by_number(parent.number() + 1).1.populate_from_parent(header, parent)
+	}
+
+	fn handle_message(&self, message: &[u8]) -> Result<(), EngineError> {
+		self.current().handle_message(message)
+	}
+
+	fn set_signer(&self, signer: Option<Box<dyn EngineSigner>>) {
+		let signer: Option<Arc<dyn EngineSigner>> = signer.map(Into::into);
+		for engine in self.engines.values() {
+			engine.set_signer(signer.clone().map(|s| Box::new(SharedSigner(s)) as Box<dyn EngineSigner>));
+		}
+	}
+
+	fn sign(&self, hash: H256) -> Result<Signature, Error> {
+		self.current().sign(hash)
+	}
+
+	fn sign_data(&self, data: &[u8]) -> Result<Signature, Error> {
+		self.current().sign_data(data)
+	}
+
+	fn requires_signer_secret(&self) -> bool {
+		self.engines.values().any(|engine| engine.requires_signer_secret())
+	}
+
+	fn signs_data_only(&self) -> bool {
+		self.engines.values().all(|engine| engine.signs_data_only())
+	}
+
+	fn register_client(&self, client: Weak<dyn EngineClient>) {
+		*self.client.write() = Some(client.clone());
+		for engine in self.engines.values() {
+			engine.register_client(client.clone());
+		}
+	}
+
+	fn step(&self) {
+		self.current().step()
+	}
+
+	fn snapshot_mode(&self) -> Snapshotting {
+		self.current().snapshot_mode()
+	}
+
+	fn verify_snapshot_headers(&self, headers: &[Header]) -> Result<(), Error> {
+		match headers.last() {
+			Some(header) => self.engine(header).verify_snapshot_headers(headers),
+			None => Ok(()),
+		}
+	}
+
+	fn prometheus_metrics(&self, registry: &mut PrometheusRegistry) {
+		self.current().prometheus_metrics(registry)
+	}
+
+	fn validator_stats(&self, from: Option<BlockNumber>, to: BlockNumber) -> Result<ValidatorStats, Error> {
+		self.engine_by_number(to).1.validator_stats(from, to)
+	}
+
+	fn randomness_rounds(&self) -> Result<Vec<RandomnessRound>, Error> {
+		self.current().randomness_rounds()
+	}
+
+	fn set_data_dir(&self, dir: &Path) -> Result<(), Error> {
+		for engine in self.engines.values() {
+			engine.set_data_dir(dir)?;
+		}
+		Ok(())
+	}
+
+	fn open_block_header_timestamp(&self, parent: &Header) -> u64 {
+		self.engine_by_number(parent.number() + 1).1.open_block_header_timestamp(parent)
+	}
+
+	fn is_timestamp_valid(&self, header: &Header, parent: &Header) -> bool {
+		self.engine(header).is_timestamp_valid(header, parent)
+	}
+
+	fn ancestry_actions(&self, header: &Header, ancestry: &mut dyn Iterator<Item = ExtendedHeader>) -> Vec<AncestryAction> {
+		self.engine(header).ancestry_actions(header, ancestry)
+	}
+
+	fn executive_author(&self, header: &Header) -> Result<Address, Error> {
+		self.engine(header).executive_author(header)
+	}
+
+	fn gas_limit_override(&self, header: &Header) -> Option<U256> {
+		self.engine(header).gas_limit_override(header)
+	}
+
+	fn params(&self) -> &CommonParams {
+		self.engine_by_number(0).1.params()
+	}
+
+	fn schedule(&self, block_number: BlockNumber) -> Schedule {
+		self.engine_by_number(block_number).1.schedule(block_number)
+	}
+
+	fn account_start_nonce(&self, block: BlockNumber) -> U256 {
+		self.engine_by_number(block).1.account_start_nonce(block)
+	}
+
+	fn signing_chain_id(&self, env_info: &EnvInfo) -> Option<u64> {
+		self.engine_by_number(env_info.number).1.signing_chain_id(env_info)
+	}
+
+	fn verify_transaction_basic(&self, t: &UnverifiedTransaction, header: &Header) -> Result<(), transaction::Error> {
+		self.engine(header).verify_transaction_basic(t, header)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::collections::BTreeMap;
+	use std::sync::Arc;
+
+	use common_types::{
+		engines::{SealingState, epoch::PendingTransition, params::CommonParams},
+		header::Header,
+	};
+	use engine::{Engine, EpochChange};
+	use ethcore::test_helpers::{EachBlockWith, TestBlockChainClient};
+	use ethereum_types::H256;
+	use instant_seal::{InstantSeal, InstantSealParams};
+	use machine::Machine;
+	use null_engine::{NullEngine, NullEngineParams};
+
+	use super::EngineTransitions;
+
+	fn engine() -> EngineTransitions {
+		let machine = || Machine::regular(CommonParams::default(), BTreeMap::new());
+		let mut engines: BTreeMap<_, Arc<dyn Engine>> = BTreeMap::new();
+		engines.insert(0, Arc::new(NullEngine::new(NullEngineParams::default(), machine())));
+		engines.insert(10, Arc::new(InstantSeal::new(InstantSealParams::default(), machine())));
+		EngineTransitions::new(engines)
+	}
+
+	fn header(number: u64) -> Header {
+		let mut header = Header::default();
+		header.set_number(number);
+		header
+	}
+
+	#[test]
+	fn routes_to_engine_active_at_block() {
+		let engine = engine();
+		let client = Arc::new(TestBlockChainClient::new());
+		engine.register_client(Arc::downgrade(&client) as _);
+		assert_eq!(engine.name(), "NullEngine");
+		assert_eq!(engine.sealing_state(), SealingState::External);
+
+		client.add_blocks(8, EachBlockWith::Nothing);
+		assert_eq!(engine.name(), "NullEngine");
+
+		// importing a block that doesn't become the best block, e.g. on a side chain, doesn't switch.
+		assert_eq!(engine.is_epoch_end(&header(9), &[], &|_| None, &|_| None), None);
+		assert_eq!(engine.name(), "NullEngine");
+
+		// block 9 is followed by the first block of the next engine.
+		client.add_blocks(1, EachBlockWith::Nothing);
+		assert_eq!(engine.name(), "InstantSeal");
+		assert_eq!(engine.sealing_state(), SealingState::Ready);
+	}
+
+	#[test]
+	fn first_block_of_engine_ends_epoch() {
+		let engine = engine();
+		let no_aux = || common_types::engines::machine::AuxiliaryData { bytes: None, receipts: None };
+
+		match engine.signals_epoch_end(&header(9), no_aux()) {
+			EpochChange::No => {},
+			_ => panic!("Block 9 doesn't signal an epoch end."),
+		}
+		match engine.signals_epoch_end(&header(10), no_aux()) {
+			EpochChange::Yes(_) => {},
+			_ => panic!("Block 10 starts the epoch of the next engine."),
+		}
+
+		let transition = header(10);
+		let hash = transition.hash();
+		let store = move |h: H256| if h == hash { Some(PendingTransition { proof: vec![1, 2, 3] }) } else { None };
+		assert_eq!(engine.is_epoch_end(&transition, &[], &|_| None, &store), Some(vec![1, 2, 3]));
+		assert_eq!(engine.is_epoch_end(&header(11), &[], &|_| None, &store), None);
+	}
+
+	#[test]
+	fn checks_timestamps_by_engine_of_header() {
+		// no client is registered, so the best block can't be used to pick the engine.
+		let engine = engine();
+		let parent = |number: u64| {
+			let mut parent = header(number);
+			parent.set_timestamp(100);
+			parent
+		};
+		let child = |parent: &Header| {
+			let mut child = header(parent.number() + 1);
+			child.set_timestamp(parent.timestamp());
+			child.set_parent_hash(parent.hash());
+			child
+		};
+
+		// the null engine requires increasing timestamps, instant seal doesn't.
+		let (before, after) = (parent(4), parent(10));
+		assert!(!engine.is_timestamp_valid(&child(&before), &before));
+		assert!(engine.is_timestamp_valid(&child(&after), &after));
+	}
+}
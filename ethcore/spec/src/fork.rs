@@ -0,0 +1,147 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Parity Ethereum.
+
+// Parity Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Chain specification of a fork: a local instant-seal chain which continues from a
+//! pinned block of another chain, reusing that chain's state and parameters.
+//!
+//! The genesis block of the fork has the pinned block as its parent, the pinned state
+//! root as its state root and the number following the pinned block, so the block numbers
+//! of the original parameters apply unchanged.
+
+use ethereum_types::{Address, H256, U256};
+use serde_json::{Map, Value};
+
+/// The block of the original chain a fork continues from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ForkPoint {
+	/// Number of the pinned block.
+	pub number: u64,
+	/// Hash of the pinned block. Becomes the parent hash of the fork's genesis.
+	pub hash: H256,
+	/// State root of the pinned block. Becomes the state root of the fork's genesis.
+	pub state_root: H256,
+	/// Timestamp of the pinned block.
+	pub timestamp: u64,
+	/// Gas limit of the pinned block.
+	pub gas_limit: U256,
+	/// Author of the pinned block.
+	pub author: Address,
+	/// Base fee of the pinned block, if EIP-1559 is active there.
+	pub base_fee_per_gas: Option<U256>,
+}
+
+/// Rewrite a JSON chain specification into the specification of a fork at `fork`.
+///
+/// The engine is replaced with `instantSeal`, the genesis block is derived from the pinned
+/// block and bootnodes and hardcoded sync are dropped.
+pub fn fork_spec_json(spec: &mut Value, fork: &ForkPoint) -> Result<(), String> {
+	let spec = spec.as_object_mut().ok_or_else(|| "Chain specification is not a JSON object".to_owned())?;
+
+	let name = spec.get("name").and_then(Value::as_str).unwrap_or("chain").to_owned();
+	let data_dir = spec.get("dataDir").and_then(Value::as_str).unwrap_or(&name).to_owned();
+	spec.insert("name".into(), format!("{} fork at #{}", name, fork.number).into());
+	spec.insert("dataDir".into(), format!("{}-fork-{}", data_dir, fork.number).into());
+	spec.insert("engine".into(), json_object(vec![("instantSeal", json_object(vec![("params", json_object(vec![]))]))]));
+	spec.insert("nodes".into(), Value::Array(Vec::new()));
+	spec.remove("hardcodedSync");
+
+	let params = spec.get_mut("params")
+		.and_then(Value::as_object_mut)
+		.ok_or_else(|| "Chain specification has no params".to_owned())?;
+	params.remove("forkBlock");
+	params.remove("forkCanonHash");
+	if let Some(base_fee) = fork.base_fee_per_gas {
+		params.insert("eip1559BaseFeeInitialValue".into(), format!("0x{:x}", base_fee).into());
+	}
+
+	spec.insert("genesis".into(), json_object(vec![
+		("seal", json_object(vec![("generic", "0x0".into())])),
+		("difficulty", "0x20000".into()),
+		("author", format!("0x{:x}", fork.author).into()),
+		("timestamp", format!("0x{:x}", fork.timestamp).into()),
+		("parentHash", format!("0x{:x}", fork.hash).into()),
+		("gasLimit", format!("0x{:x}", fork.gas_limit).into()),
+		("stateRoot", format!("0x{:x}", fork.state_root).into()),
+	]));
+
+	Ok(())
+}
+
+fn json_object(fields: Vec<(&str, Value)>) -> Value {
+	Value::Object(fields.into_iter().map(|(key, value)| (key.to_owned(), value)).collect::<Map<_, _>>())
+}
+
+#[cfg(test)]
+mod tests {
+	use ethereum_types::{H256, U256};
+	use serde_json::Value;
+
+	use super::{ForkPoint, fork_spec_json};
+
+	fn fork_point() -> ForkPoint {
+		ForkPoint {
+			number: 10,
+			hash: H256::from_low_u64_be(1),
+			state_root: H256::from_low_u64_be(2),
+			timestamp: 1000,
+			gas_limit: U256::from(8_000_000),
+			author: Default::default(),
+			base_fee_per_gas: Some(U256::from(7)),
+		}
+	}
+
+	#[test]
+	fn keeps_transitions_and_builtins() {
+		let mut spec: Value = serde_json::from_str(r#"{
+			"name": "Test",
+			"engine": { "Ethash": { "params": {} } },
+			"params": {
+				"forkBlock": "0x5",
+				"eip150Transition": "5",
+				"eip1559Transition": 12,
+				"eip2929Transition": "0xffffffffffffffff"
+			},
+			"genesis": {},
+			"nodes": ["enode://a@127.0.0.1:30303"],
+			"accounts": {
+				"0000000000000000000000000000000000000001": { "builtin": { "name": "ecrecover", "activate_at": "0x14", "pricing": {} } },
+				"0000000000000000000000000000000000000002": { "builtin": { "name": "modexp", "pricing": {
+					"0": { "price": { "modexp": { "divisor": 20 } } },
+					"8": { "price": { "modexp": { "divisor": 10 } } },
+					"15": { "price": { "modexp": { "divisor": 5 } } }
+				} } }
+			}
+		}"#).unwrap();
+
+		fork_spec_json(&mut spec, &fork_point()).unwrap();
+
+		assert_eq!(spec["dataDir"], "Test-fork-10");
+		assert!(spec["engine"]["instantSeal"].is_object());
+		assert_eq!(spec["nodes"], Value::Array(vec![]));
+		assert!(spec["params"].get("forkBlock").is_none());
+		assert_eq!(spec["params"]["eip150Transition"], "5");
+		assert_eq!(spec["params"]["eip1559Transition"], 12);
+		assert_eq!(spec["params"]["eip2929Transition"], "0xffffffffffffffff");
+		assert_eq!(spec["params"]["eip1559BaseFeeInitialValue"], "0x7");
+		assert_eq!(spec["genesis"]["timestamp"], "0x3e8");
+		assert_eq!(spec["genesis"]["parentHash"], format!("0x{:x}", H256::from_low_u64_be(1)));
+
+		let ecrecover = &spec["accounts"]["0000000000000000000000000000000000000001"]["builtin"];
+		assert_eq!(ecrecover["activate_at"], "0x14");
+		let modexp = spec["accounts"]["0000000000000000000000000000000000000002"]["builtin"]["pricing"].as_object().unwrap();
+		assert_eq!(modexp.len(), 3);
+	}
+}
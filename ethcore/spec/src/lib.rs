@@ -17,10 +17,14 @@
 //! Blockchain params.
 
 mod chain;
+mod engine_transitions;
+mod fork;
 mod genesis;
 mod seal;
 mod spec;
 
 pub use self::chain::*;
+pub use self::engine_transitions::EngineTransitions;
+pub use self::fork::ForkPoint;
 pub use self::genesis::Genesis;
 pub use self::spec::{Spec, SpecHardcodedSync, SpecParams};
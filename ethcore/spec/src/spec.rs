@@ -17,7 +17,7 @@
 //! Parameters for a block chain.
 
 use std::{
-	collections::BTreeMap,
+	collections::{BTreeMap, BTreeSet},
 	convert::TryFrom,
 	fmt,
 	io::Read,
@@ -50,12 +50,15 @@ use machine::{executive::Executive, Machine, substate::Substate};
 use null_engine::NullEngine;
 use pod::PodState;
 use rlp::{Rlp, RlpStream};
+use tendermint::Tendermint;
 use trace::{NoopTracer, NoopVMTracer};
 use trie_vm_factories::Factories;
 use vm::{EnvInfo, ActionType, ActionValue, ActionParams, ParamsType};
 
 use crate::{
+	EngineTransitions,
 	Genesis,
+	fork::{ForkPoint, fork_spec_json},
 	seal::Generic as GenericSeal,
 };
 
@@ -69,6 +72,8 @@ pub struct SpecParams<'a> {
 	/// memory. This may get more fine-grained in the future but for now is simply a binary
 	/// option.
 	pub optimization_setting: Option<OptimizeFor>,
+	/// Load the chain as a fork continuing from this block of it, if set.
+	pub fork: Option<&'a ForkPoint>,
 }
 
 impl<'a> SpecParams<'a> {
@@ -77,6 +82,7 @@ impl<'a> SpecParams<'a> {
 		SpecParams {
 			cache_dir: path,
 			optimization_setting: None,
+			fork: None,
 		}
 	}
 
@@ -85,8 +91,15 @@ impl<'a> SpecParams<'a> {
 		SpecParams {
 			cache_dir: path,
 			optimization_setting: Some(optimization),
+			fork: None,
 		}
 	}
+
+	/// Load the chain as a fork continuing from the given block.
+	pub fn with_fork(mut self, fork: &'a ForkPoint) -> Self {
+		self.fork = Some(fork);
+		self
+	}
 }
 
 impl<'a, T: AsRef<Path>> From<&'a T> for SpecParams<'a> {
@@ -145,6 +158,7 @@ fn run_constructors<T: Backend>(
 			last_hashes: Default::default(),
 			gas_used: U256::zero(),
 			gas_limit: U256::max_value(),
+			base_fee: None,
 		};
 
 		let from = Address::zero();
@@ -198,6 +212,8 @@ pub struct Spec {
 	pub nodes: Vec<String>,
 	/// The genesis block's parent hash field.
 	pub parent_hash: H256,
+	/// The genesis block's number, only non-zero for a fork continuing another chain.
+	pub genesis_number: BlockNumber,
 	/// The genesis block's author field.
 	pub author: Address,
 	/// The genesis block's difficulty field.
@@ -267,20 +283,37 @@ fn convert_json_to_spec(
 
 /// Load from JSON object.
 fn load_from(spec_params: SpecParams, s: ethjson::spec::Spec) -> Result<Spec, Error> {
-	let builtins: Result<BTreeMap<Address, Builtin>, _> = s
-		.accounts
-		.builtins()
-		.into_iter()
-		.map(convert_json_to_spec)
-		.collect();
-	let builtins = builtins?;
+	let accounts = &s.accounts;
+	let builtins = || -> Result<BTreeMap<Address, Builtin>, Error> {
+		accounts
+			.builtins()
+			.into_iter()
+			.map(convert_json_to_spec)
+			.collect()
+	};
 	let g = Genesis::from(s.genesis);
 	let GenericSeal(seal_rlp) = g.seal.into();
-	let params = CommonParams::from(s.params);
+	let params = CommonParams::from(s.params.clone());
 
 	let hardcoded_sync = s.hardcoded_sync.map(Into::into);
 
-	let engine = Spec::engine(spec_params, s.engine, params, builtins);
+	let engine = Spec::engine(&spec_params, s.engine, params, builtins()?);
+	let engine: Arc<dyn Engine> = match s.engine_transitions {
+		Some(transitions) => {
+			let mut engines = BTreeMap::new();
+			engines.insert(0, engine);
+			for (block, engine_spec) in transitions {
+				let block: BlockNumber = block.into();
+				if block == 0 {
+					return Err(Error::Msg("Engine transitions must be after the genesis block".into()));
+				}
+				let params = CommonParams::from(s.params.clone());
+				engines.insert(block, Spec::engine(&spec_params, engine_spec, params, builtins()?));
+			}
+			Arc::new(EngineTransitions::new(engines))
+		},
+		None => engine,
+	};
 	let author = g.author;
 	let timestamp = g.timestamp;
 	let difficulty = g.difficulty;
@@ -308,6 +341,7 @@ fn load_from(spec_params: SpecParams, s: ethjson::spec::Spec) -> Result<Spec, Er
 		data_dir: s.data_dir.unwrap_or(s.name).into(),
 		nodes: s.nodes.unwrap_or_else(Vec::new),
 		parent_hash: g.parent_hash,
+		genesis_number: 0,
 		transactions_root: g.transactions_root,
 		receipts_root: g.receipts_root,
 		author,
@@ -343,7 +377,7 @@ impl Spec {
 	/// Convert engine spec into a arc'd Engine of the right underlying type.
 	/// TODO avoid this hard-coded nastiness - use dynamic-linked plugin framework instead.
 	fn engine(
-		spec_params: SpecParams,
+		spec_params: &SpecParams,
 		engine_spec: ethjson::spec::Engine,
 		params: CommonParams,
 		builtins: BTreeMap<Address, Builtin>,
@@ -360,6 +394,8 @@ impl Spec {
 								.expect("Failed to start Clique consensus engine."),
 			ethjson::spec::Engine::AuthorityRound(authority_round) => AuthorityRound::new(authority_round.params.into(), machine)
 				.expect("Failed to start AuthorityRound consensus engine."),
+			ethjson::spec::Engine::Tendermint(tendermint) => Tendermint::new(tendermint.params.into(), machine)
+				.expect("Failed to start Tendermint consensus engine."),
 		}
 	}
 
@@ -388,12 +424,58 @@ impl Spec {
 		self.params().fork_block
 	}
 
+	/// Get the block numbers of the hard forks of the chain, which make up its fork identifier
+	/// (EIP-2124). Transitions active from genesis or never active are left out.
+	pub fn hard_forks(&self) -> BTreeSet<BlockNumber> {
+		let params = self.params();
+		let mut forks: BTreeSet<_> = vec![
+			params.eip150_transition,
+			params.eip160_transition,
+			params.eip161abc_transition,
+			params.eip161d_transition,
+			params.eip98_transition,
+			params.eip658_transition,
+			params.eip155_transition,
+			params.eip140_transition,
+			params.eip210_transition,
+			params.eip211_transition,
+			params.eip214_transition,
+			params.eip145_transition,
+			params.eip1052_transition,
+			params.eip1283_transition,
+			params.eip1283_disable_transition,
+			params.eip1283_reenable_transition,
+			params.eip1014_transition,
+			params.eip1706_transition,
+			params.eip1344_transition,
+			params.eip1884_transition,
+			params.eip2028_transition,
+			params.eip2200_advance_transition,
+			params.eip2718_transition,
+			params.eip2930_transition,
+			params.eip2929_transition,
+			params.eip1559_transition,
+			params.eip1559_fee_collector_transition,
+			params.dust_protection_transition,
+			params.wasm_activation_transition,
+			params.kip4_transition,
+			params.kip6_transition,
+			params.max_code_size_transition,
+		].into_iter()
+			.chain(params.fork_block.map(|(number, _)| number))
+			.chain(self.engine.hard_forks())
+			.collect();
+		forks.remove(&0);
+		forks.remove(&BlockNumber::max_value());
+		forks
+	}
+
 	/// Get the header of the genesis block.
 	pub fn genesis_header(&self) -> Header {
 		let mut header: Header = Default::default();
 		header.set_parent_hash(self.parent_hash.clone());
 		header.set_timestamp(self.timestamp);
-		header.set_number(0);
+		header.set_number(self.genesis_number);
 		header.set_author(self.author.clone());
 		header.set_transactions_root(self.transactions_root.clone());
 		header.set_uncles_hash(keccak(RlpStream::new_list(0).out()));
@@ -408,6 +490,10 @@ impl Spec {
 			let r = Rlp::new(&self.seal_rlp);
 			r.iter().map(|f| f.as_raw().to_vec()).collect()
 		});
+		let params = self.engine.params();
+		if self.genesis_number >= params.eip1559_transition {
+			header.set_base_fee_per_gas(Some(params.eip1559_base_fee_initial_value));
+		}
 		trace!(target: "spec", "Header hash is {}", header.hash());
 		header
 	}
@@ -499,9 +585,28 @@ impl Spec {
 	/// Loads spec from json file. Provide factories for executing contracts and ensuring
 	/// storage goes to the right place.
 	pub fn load<'a, T: Into<SpecParams<'a>>, R: Read>(params: T, reader: R) -> Result<Self, Error> {
-		ethjson::spec::Spec::load(reader)
-			.map_err(|e| Error::Msg(e.to_string()))
-			.and_then(|x| load_from(params.into(), x))
+		let params = params.into();
+		match params.fork {
+			Some(fork) => Self::load_fork(params, fork, reader),
+			None => ethjson::spec::Spec::load(reader)
+				.map_err(|e| Error::Msg(e.to_string()))
+				.and_then(|x| load_from(params, x)),
+		}
+	}
+
+	/// Loads a spec as a fork at the given block. The genesis state of the fork is the state
+	/// of the pinned block, which is expected to be provided by the database.
+	fn load_fork<R: Read>(params: SpecParams, fork: &ForkPoint, reader: R) -> Result<Self, Error> {
+		let mut json = serde_json::from_reader(reader).map_err(|e| Error::Msg(e.to_string()))?;
+		fork_spec_json(&mut json, fork).map_err(Error::Msg)?;
+		let s = serde_json::from_value(json).map_err(|e| Error::Msg(e.to_string()))?;
+
+		let mut spec = load_from(params, s)?;
+		spec.genesis_number = fork.number + 1;
+		spec.state_root = fork.state_root;
+		spec.genesis_state = PodState::default();
+		spec.constructors.clear();
+		Ok(spec)
 	}
 
 	/// initialize genesis epoch data, using in-memory database for
@@ -529,6 +634,7 @@ impl Spec {
 				gas_limit: U256::max_value(),
 				last_hashes: Arc::new(Vec::new()),
 				gas_used: 0.into(),
+				base_fee: None,
 			};
 
 			let from = Address::zero();
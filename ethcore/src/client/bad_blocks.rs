@@ -21,25 +21,27 @@ use ethereum_types::H256;
 use itertools::Itertools;
 use memory_cache::MemoryLruCache;
 use parking_lot::RwLock;
+use types::BlockNumber;
 use types::verification::Unverified;
 
 /// Recently seen bad blocks.
 pub struct BadBlocks {
 	last_blocks: RwLock<MemoryLruCache<H256, (Unverified, String)>>,
+	eip1559_transition: BlockNumber,
 }
 
-impl Default for BadBlocks {
-	fn default() -> Self {
+impl BadBlocks {
+	/// Creates an empty store for a chain whose blocks carry a base fee from `eip1559_transition` on.
+	pub fn new(eip1559_transition: BlockNumber) -> Self {
 		BadBlocks {
 			last_blocks: RwLock::new(MemoryLruCache::new(8 * 1024 * 1024)),
+			eip1559_transition,
 		}
 	}
-}
 
-impl BadBlocks {
 	/// Reports given RLP as invalid block.
 	pub fn report(&self, raw: Bytes, message: String) {
-		match Unverified::from_rlp(raw) {
+		match Unverified::from_rlp(raw, self.eip1559_transition) {
 			Ok(unverified) => {
 				error!(
 					target: "client",
@@ -72,7 +74,7 @@ impl BadBlocks {
 			.backstore()
 			.iter()
 			.map(|(_k, (unverified, message))| (
-				Unverified::from_rlp(unverified.bytes.clone())
+				Unverified::from_rlp(unverified.bytes.clone(), self.eip1559_transition)
 					.expect("Bytes coming from UnverifiedBlock so decodable; qed"),
 				message.clone(),
 			))
@@ -33,13 +33,14 @@ use itertools::Itertools;
 use kvdb::{DBTransaction, DBValue, KeyValueDB};
 use parking_lot::{Mutex, RwLock};
 use rand::rngs::OsRng;
-use rlp::PayloadInfo;
+use rlp::{PayloadInfo, Rlp, RlpStream};
 use rustc_hex::FromHex;
 use trie::{Trie, TrieFactory, TrieSpec};
 
 use account_state::State;
 use account_state::state::StateInfo;
 use block::{ClosedBlock, Drain, enact_verified, LockedBlock, OpenBlock, SealedBlock};
+use block_reward::BlockRewardContract;
 use blockchain::{
 	BlockChain,
 	BlockChainDB,
@@ -53,8 +54,8 @@ use blockchain::{
 };
 use call_contract::CallContract;
 use client::{
-	bad_blocks, BlockProducer, BroadcastProposalBlock, Call,
-	ClientConfig, EngineInfo, ImportSealedBlock, PrepareOpenBlock,
+	bad_blocks, state_diff, BlockProducer, BroadcastProposalBlock, Call,
+	ChangedAccount, ClientConfig, EngineInfo, ImportSealedBlock, PrepareOpenBlock,
 	ReopenBlock, SealedBlockImporter,
 };
 use client::ancient_import::AncientVerifier;
@@ -83,7 +84,7 @@ use client_traits::{
 	ForceUpdateSealing
 };
 use db::{keys::BlockDetails, Readable, Writable};
-use engine::Engine;
+use engine::{default_system_or_code_call, Engine};
 use ethcore_miner::pool::VerifiedTransaction;
 use ethtrie::Layout;
 use evm::Schedule;
@@ -91,7 +92,8 @@ use executive_state;
 use io::IoChannel;
 use journaldb;
 use machine::{
-	executed::Executed,
+	executed::{Executed, StructLogExecuted},
+	executed_block::ExecutedBlock,
 	executive::{contract_address, Executive, TransactOptions},
 	transaction_ext::Transaction,
 };
@@ -99,12 +101,14 @@ use miner::{Miner, MinerService, PendingOrdering};
 use registrar::RegistrarClient;
 use snapshot::{self, SnapshotClient, SnapshotWriter};
 use spec::Spec;
-use state_db::StateDB;
+use state_db::{StateDB, StateFetcher};
+use stats::{PrometheusMetrics, PrometheusRegistry};
 use trace::{self, Database as TraceDatabase, ImportRequest as TraceImportRequest, LocalizedTrace, TraceDB};
 use trie_vm_factories::{Factories, VmFactory};
 use types::{
 	ancestry_action::AncestryAction,
 	block::PreverifiedBlock,
+	block_reward::{BlockReward, RewardKind},
 	block_status::BlockStatus,
 	blockchain_info::BlockChainInfo,
 	BlockNumber,
@@ -115,7 +119,7 @@ use types::{
 	engines::{
 		epoch::{PendingTransition, Transition as EpochTransition},
 		ForkChoice,
-		machine::{AuxiliaryData, Call as MachineCall},
+		machine::{AuxiliaryData, Call as MachineCall, Executed as RawExecuted},
 		MAX_UNCLE_AGE,
 		SealingState,
 	},
@@ -129,6 +133,7 @@ use types::{
 	pruning_info::PruningInfo,
 	receipt::{LocalizedReceipt, Receipt},
 	snapshot::{Progress, Snapshotting},
+	state_override::StateOverride,
 	trace_filter::Filter as TraceFilter,
 	transaction::{self, Action, CallError, LocalizedTransaction, SignedTransaction, UnverifiedTransaction},
 	verification::{Unverified, VerificationQueueInfo as BlockQueueInfo},
@@ -271,8 +276,8 @@ impl Importer {
 			block_queue,
 			miner,
 			ancient_verifier: AncientVerifier::new(engine.clone()),
+			bad_blocks: bad_blocks::BadBlocks::new(engine.params().eip1559_transition),
 			engine,
-			bad_blocks: Default::default(),
 		})
 	}
 
@@ -284,11 +289,12 @@ impl Importer {
 		}
 
 		let max_blocks_to_import = client.config.max_round_blocks_to_import;
-		let (imported_blocks, import_results, invalid_blocks, imported, proposed_blocks, duration, has_more_blocks_to_import) = {
+		let (imported_blocks, import_results, finalized_blocks, invalid_blocks, imported, proposed_blocks, duration, has_more_blocks_to_import) = {
 			let mut imported_blocks = Vec::with_capacity(max_blocks_to_import);
 			let mut invalid_blocks = HashSet::new();
 			let proposed_blocks = Vec::with_capacity(max_blocks_to_import);
 			let mut import_results = Vec::with_capacity(max_blocks_to_import);
+			let mut finalized_blocks = Vec::new();
 
 			let _import_lock = self.import_lock.lock();
 			let blocks = self.block_queue.drain(max_blocks_to_import);
@@ -313,8 +319,9 @@ impl Importer {
 					Ok((closed_block, pending)) => {
 						imported_blocks.push(hash);
 						let transactions_len = closed_block.transactions.len();
-						let route = self.commit_block(closed_block, &header, encoded::Block::new(bytes), pending, client);
+						let (route, finalized) = self.commit_block(closed_block, &header, encoded::Block::new(bytes), pending, client);
 						import_results.push(route);
+						finalized_blocks.extend(finalized);
 						client.report.write().accrue_block(&header, transactions_len);
 					},
 					Err(err) => {
@@ -331,7 +338,7 @@ impl Importer {
 				self.block_queue.mark_as_bad(&invalid_blocks);
 			}
 			let has_more_blocks_to_import = !self.block_queue.mark_as_good(&imported_blocks);
-			(imported_blocks, import_results, invalid_blocks, imported, proposed_blocks, start.elapsed(), has_more_blocks_to_import)
+			(imported_blocks, import_results, finalized_blocks, invalid_blocks, imported, proposed_blocks, start.elapsed(), has_more_blocks_to_import)
 		};
 
 		{
@@ -356,6 +363,10 @@ impl Importer {
 					);
 				});
 			}
+
+			if !finalized_blocks.is_empty() {
+				client.notify(|notify| notify.blocks_finalized(&finalized_blocks));
+			}
 		}
 
 		let db = client.db.read();
@@ -458,6 +469,53 @@ impl Importer {
 		Ok((locked_block, pending))
 	}
 
+	/// Check a block that isn't sealed yet, like a consensus proposal, the way it is checked on
+	/// import: against its parent, timestamp included, and by executing it on the parent state.
+	fn verify_unsealed_block(&self, unverified: Unverified, client: &Client) -> EthcoreResult<()> {
+		let engine = &*self.engine;
+		verification::verify_block_basic(&unverified, engine, false)?;
+		let block = verification::verify_block_unordered(unverified, engine, false)?;
+		let header = block.header.clone();
+
+		let parent = client.block_header_decoded(BlockId::Hash(*header.parent_hash()))
+			.ok_or_else(|| BlockError::UnknownParent(*header.parent_hash()))?;
+
+		let chain = client.chain.read();
+		verification::verify_block_family(
+			&header,
+			&parent,
+			engine,
+			verification::FullFamilyParams {
+				block: &block,
+				block_provider: &**chain,
+				client
+			},
+		)?;
+
+		let last_hashes = client.build_last_hashes(*header.parent_hash());
+		let db = client.state_db.read().boxed_clone_canon(header.parent_hash());
+		let is_epoch_begin = chain.epoch_transition(parent.number(), *header.parent_hash()).is_some();
+
+		let mut locked_block = enact_verified(
+			block,
+			engine,
+			false,
+			db,
+			&parent,
+			last_hashes,
+			client.factories.clone(),
+			is_epoch_begin,
+		)?;
+
+		if header.number() < engine.params().validate_receipts_transition
+			&& header.receipts_root() != locked_block.header.receipts_root()
+		{
+			locked_block.strip_receipts_outcomes();
+		}
+
+		verification::verify_block_final(&header, &locked_block.header)
+	}
+
 	/// Import a block with transaction receipts.
 	///
 	/// The block is guaranteed to be the next best blocks in the
@@ -487,6 +545,7 @@ impl Importer {
 	// it is for reconstructing the state transition.
 	//
 	// The header passed is from the original block data and is sealed.
+	// Returns the import route and the blocks the engine finalized with this one.
 	// TODO: should return an error if ImportRoute is none, issue #9910
 	fn commit_block<B>(
 		&self,
@@ -495,7 +554,7 @@ impl Importer {
 		block_data: encoded::Block,
 		pending: Option<PendingTransition>,
 		client: &Client
-	) -> ImportRoute
+	) -> (ImportRoute, Vec<H256>)
 		where B: Drain
 	{
 		let hash = &header.hash();
@@ -513,6 +572,7 @@ impl Importer {
 		let ancestry_actions = self.engine.ancestry_actions(&header, &mut chain.ancestry_with_metadata_iter(*parent));
 
 		let receipts = block.receipts;
+		let rewards = block.rewards;
 		let traces = block.traces.drain();
 		let best_hash = chain.best_block_hash();
 
@@ -562,6 +622,8 @@ impl Importer {
 			a
 		}).collect();
 
+		chain.insert_block_rewards(&mut batch, *hash, rewards);
+
 		let route = chain.insert_block(&mut batch, block_data, receipts, ExtrasInsert {
 			fork_choice,
 			is_finalized,
@@ -589,7 +651,7 @@ impl Importer {
 			warn!("Failed to prune ancient state data: {}", e);
 		}
 
-		route
+		(route, finalized)
 	}
 
 	// check for epoch end signal and write pending transition if it occurs.
@@ -625,6 +687,7 @@ impl Importer {
 							last_hashes: client.build_last_hashes(*header.parent_hash()),
 							gas_used: U256::default(),
 							gas_limit: u64::max_value().into(),
+							base_fee: header.base_fee_per_gas(),
 						};
 
 						let call = move |addr, data| {
@@ -711,12 +774,14 @@ impl Client {
 	/// Create a new client with given parameters.
 	/// The database is assumed to have been initialized with the correct columns.
 	pub fn new(
-		config: ClientConfig,
+		mut config: ClientConfig,
 		spec: &Spec,
 		db: Arc<dyn BlockChainDB>,
 		miner: Arc<Miner>,
 		message_channel: IoChannel<ClientIoMessage<Self>>,
 	) -> Result<Arc<Client>, EthcoreError> {
+		config.blockchain.eip1559_transition = spec.params().eip1559_transition;
+
 		let trie_spec = match config.fat_db {
 			true => TrieSpec::Fat,
 			false => TrieSpec::Secure,
@@ -887,6 +952,41 @@ impl Client {
 		}
 	}
 
+	/// Insert a block along with its receipts without executing it, like the blocks
+	/// queued through `queue_ancient_block`, but synchronously.
+	fn import_ancient_block(&self, unverified: Unverified, receipts_bytes: Bytes) -> EthcoreResult<()> {
+		let _lock = self.ancient_blocks_import_lock.lock();
+		{
+			let chain = self.chain.read();
+			if chain.is_known(&unverified.hash()) {
+				return Err(EthcoreError::Import(ImportError::AlreadyInChain));
+			}
+			let parent_hash = unverified.parent_hash();
+			if !chain.is_known(&parent_hash) {
+				return Err(EthcoreError::Block(BlockError::UnknownParent(parent_hash)));
+			}
+		}
+		self.importer.import_old_block(
+			unverified,
+			&receipts_bytes,
+			&**self.db.read().key_value(),
+			&*self.chain.read(),
+		)
+	}
+
+	/// Call `f` for every account which differs between the states of two blocks, skipping the
+	/// parts of the state tries they share. Requires a fat DB.
+	pub fn diff_states<F>(&self, from: BlockId, to: BlockId, f: F) -> Result<(), String> where
+		F: FnMut(ChangedAccount) -> Result<(), String>,
+	{
+		if !self.factories.trie.is_fat() {
+			return Err("Diffing states requires a fat DB".into());
+		}
+		let (from_root, from_db) = self.state_at(from).ok_or("State of the starting block is not available")?.drop();
+		let (to_root, to_db) = self.state_at(to).ok_or("State of the end block is not available")?.drop();
+		state_diff::diff_states(from_db.as_hash_db(), &from_root, to_db.as_hash_db(), &to_root, &self.factories, f)
+	}
+
 	/// The env info as of the best block.
 	pub fn latest_env_info(&self) -> EnvInfo {
 		self.env_info(BlockId::Latest).expect("Best block header always stored; qed")
@@ -904,6 +1004,7 @@ impl Client {
 				last_hashes: self.build_last_hashes(header.parent_hash()),
 				gas_used: U256::default(),
 				gas_limit: header.gas_limit(),
+				base_fee: header.base_fee_per_gas(self.engine.params().eip1559_transition),
 			}
 		})
 	}
@@ -1031,6 +1132,12 @@ impl Client {
 		self.chain.read().clone()
 	}
 
+	/// Attach a fetcher for state missing from the local database. Every state created
+	/// afterwards falls through to it on a trie lookup.
+	pub fn set_state_fetcher(&self, fetcher: Arc<dyn StateFetcher>) {
+		self.state_db.write().set_fetcher(Some(fetcher));
+	}
+
 	/// Replace io channel. Useful for testing.
 	pub fn set_io_channel(&self, io_channel: IoChannel<ClientIoMessage<Self>>) {
 		*self.io_channel.write() = io_channel;
@@ -1153,7 +1260,7 @@ impl Client {
 		match id {
 			BlockId::Hash(hash) => Some(hash),
 			BlockId::Number(number) => chain.block_hash(number),
-			BlockId::Earliest => chain.block_hash(0),
+			BlockId::Earliest => Some(chain.genesis_hash()),
 			BlockId::Latest => Some(chain.best_block_hash()),
 		}
 	}
@@ -1210,47 +1317,83 @@ impl Client {
 		t: &SignedTransaction,
 		analytics: CallAnalytics,
 	) -> Result<Executed, CallError> {
-		use types::engines::machine::Executed as RawExecuted;
-		fn call<V, T>(
-			state: &mut State<StateDB>,
-			env_info: &EnvInfo,
-			machine: &::machine::Machine,
-			state_diff: bool,
-			transaction: &SignedTransaction,
-			options: TransactOptions<T, V>,
-		) -> Result<RawExecuted<T::Output, V::Output>, CallError> where
-			T: trace::Tracer,
-			V: trace::VMTracer,
-		{
-			let options = options
-				.dont_check_nonce()
-				.save_output_from_contract();
-			let original_state = if state_diff { Some(state.clone()) } else { None };
-			let schedule = machine.schedule(env_info.number);
-
-			let mut ret = Executive::new(state, env_info, &machine, &schedule).transact_virtual(transaction, options)?;
+		let state_diff = analytics.state_diffing;
 
-			if let Some(original) = original_state {
-				ret.state_diff = Some(state.diff_from(original).map_err(ExecutionError::from)?);
-			}
-			Ok(ret)
+		match (analytics.transaction_tracing, analytics.vm_tracing) {
+			(true, true) => Self::do_virtual_call_with_options(state, env_info, machine, state_diff, t, TransactOptions::with_tracing_and_vm_tracing()),
+			(true, false) => Self::do_virtual_call_with_options(state, env_info, machine, state_diff, t, TransactOptions::with_tracing()),
+			(false, true) => Self::do_virtual_call_with_options(state, env_info, machine, state_diff, t, TransactOptions::with_vm_tracing()),
+			(false, false) => Self::do_virtual_call_with_options(state, env_info, machine, state_diff, t, TransactOptions::with_no_tracing()),
 		}
+	}
 
-		let state_diff = analytics.state_diffing;
+	fn do_virtual_call_with_options<T, V>(
+		state: &mut State<StateDB>,
+		env_info: &EnvInfo,
+		machine: &::machine::Machine,
+		state_diff: bool,
+		transaction: &SignedTransaction,
+		options: TransactOptions<T, V>,
+	) -> Result<RawExecuted<T::Output, V::Output>, CallError> where
+		T: trace::Tracer,
+		V: trace::VMTracer,
+	{
+		let options = options
+			.dont_check_nonce()
+			.save_output_from_contract();
+		let original_state = if state_diff { Some(state.clone()) } else { None };
+		let schedule = machine.schedule(env_info.number);
 
-		match (analytics.transaction_tracing, analytics.vm_tracing) {
-			(true, true) => call(state, env_info, machine, state_diff, t, TransactOptions::with_tracing_and_vm_tracing()),
-			(true, false) => call(state, env_info, machine, state_diff, t, TransactOptions::with_tracing()),
-			(false, true) => call(state, env_info, machine, state_diff, t, TransactOptions::with_vm_tracing()),
-			(false, false) => call(state, env_info, machine, state_diff, t, TransactOptions::with_no_tracing()),
+		let mut ret = Executive::new(state, env_info, &machine, &schedule).transact_virtual(transaction, options)?;
+
+		if let Some(original) = original_state {
+			ret.state_diff = Some(state.diff_from(original).map_err(ExecutionError::from)?);
 		}
+		Ok(ret)
+	}
+
+	fn do_virtual_call_with_struct_logger(
+		machine: &::machine::Machine,
+		env_info: &EnvInfo,
+		state: &mut State<StateDB>,
+		t: &SignedTransaction,
+		config: trace::StructLoggerConfig,
+	) -> Result<StructLogExecuted, CallError> {
+		let options = TransactOptions::new(trace::ExecutiveTracer::default(), trace::StructLogger::new(config));
+		Self::do_virtual_call_with_options(state, env_info, machine, false, t, options)
+	}
+
+	// Replays the transactions of a block on top of its parent state, executing each with `execute`.
+	fn replay_block_transactions_with<T, V, F>(&self, block: BlockId, execute: F) -> Result<Box<dyn Iterator<Item = (H256, RawExecuted<T, V>)>>, CallError> where
+		T: 'static,
+		V: 'static,
+		F: Fn(&::machine::Machine, &EnvInfo, &mut State<StateDB>, &SignedTransaction) -> Result<RawExecuted<T, V>, CallError> + 'static,
+	{
+		let mut env_info = self.env_info(block).ok_or_else(|| CallError::StatePruned)?;
+		let body = self.block_body(block).ok_or_else(|| CallError::StatePruned)?;
+		let mut state = self.state_at_beginning(block).ok_or_else(|| CallError::StatePruned)?;
+		let txs = body.transactions();
+		let engine = self.engine.clone();
+
+		const PROOF: &str = "Transactions fetched from blockchain; blockchain transactions are valid; qed";
+		const EXECUTE_PROOF: &str = "Transaction replayed; qed";
+
+		Ok(Box::new(txs.into_iter()
+			.map(move |t| {
+				let transaction_hash = t.hash();
+				let t = SignedTransaction::new(t).expect(PROOF);
+				let machine = engine.machine();
+				let x = execute(machine, &env_info, &mut state, &t).expect(EXECUTE_PROOF);
+				env_info.gas_used = env_info.gas_used + x.gas_used;
+				(transaction_hash, x)
+			})))
 	}
 
 	fn block_number_ref(&self, id: &BlockId) -> Option<BlockNumber> {
 		match *id {
 			BlockId::Number(number) => Some(number),
 			BlockId::Hash(ref hash) => self.chain.read().block_number(hash),
-			BlockId::Earliest => Some(0),
+			BlockId::Earliest => Some(self.chain.read().genesis_header().number()),
 			BlockId::Latest => Some(self.chain.read().best_block_number()),
 		}
 	}
@@ -1267,7 +1410,7 @@ impl Client {
 				=> Some(self.chain.read().best_block_header()),
 			BlockId::Number(number) if number == self.chain.read().best_block_number()
 				=> Some(self.chain.read().best_block_header()),
-			_   => self.block_header(id).and_then(|h| h.decode().ok())
+			_   => self.block_header(id).and_then(|h| h.decode(self.engine.params().eip1559_transition).ok())
 		}
 	}
 }
@@ -1412,6 +1555,10 @@ impl BlockInfo for Client {
 	fn code_hash(&self, address: &Address, id: BlockId) -> Option<H256> {
 		self.state_at(id).and_then(|s| s.code_hash(address).unwrap_or(None))
 	}
+
+	fn eip1559_transition(&self) -> BlockNumber {
+		self.engine.params().eip1559_transition
+	}
 }
 
 impl TransactionInfo for Client {
@@ -1509,12 +1656,31 @@ impl Call for Client {
 			last_hashes: self.build_last_hashes(*header.parent_hash()),
 			gas_used: U256::default(),
 			gas_limit: U256::max_value(),
+			// calls are not charged the base fee, like in other clients.
+			base_fee: None,
 		};
 		let machine = self.engine.machine();
 
 		Self::do_virtual_call(&machine, &env_info, state, transaction, analytics)
 	}
 
+	fn call_with_struct_logger(&self, transaction: &SignedTransaction, config: trace::StructLoggerConfig, state: &mut Self::State, header: &Header) -> Result<StructLogExecuted, CallError> {
+		let env_info = EnvInfo {
+			number: header.number(),
+			author: *header.author(),
+			timestamp: header.timestamp(),
+			difficulty: *header.difficulty(),
+			last_hashes: self.build_last_hashes(*header.parent_hash()),
+			gas_used: U256::default(),
+			gas_limit: U256::max_value(),
+			// calls are not charged the base fee, like in other clients.
+			base_fee: None,
+		};
+		let machine = self.engine.machine();
+
+		Self::do_virtual_call_with_struct_logger(&machine, &env_info, state, transaction, config)
+	}
+
 	fn call_many(&self, transactions: &[(SignedTransaction, CallAnalytics)], state: &mut Self::State, header: &Header) -> Result<Vec<Executed>, CallError> {
 		let mut env_info = EnvInfo {
 			number: header.number(),
@@ -1524,6 +1690,8 @@ impl Call for Client {
 			last_hashes: self.build_last_hashes(*header.parent_hash()),
 			gas_used: U256::default(),
 			gas_limit: U256::max_value(),
+			// calls are not charged the base fee, like in other clients.
+			base_fee: None,
 		};
 
 		let mut results = Vec::with_capacity(transactions.len());
@@ -1551,6 +1719,7 @@ impl Call for Client {
 				last_hashes: self.build_last_hashes(*header.parent_hash()),
 				gas_used: U256::default(),
 				gas_limit: max,
+				base_fee: None,
 			};
 
 			(init, max, env_info)
@@ -1621,6 +1790,13 @@ impl Call for Client {
 		trace!(target: "estimate_gas", "estimate_gas chopping {} .. {}", lower, upper);
 		binary_chop(lower, upper, cond)
 	}
+
+	fn apply_state_override(&self, state: &mut Self::State, overrides: &StateOverride) -> Result<(), CallError> {
+		state.apply_override(overrides).map_err(|e| {
+			warn!(target: "client", "Failed to apply state override: {}", e);
+			CallError::StateCorrupt
+		})
+	}
 }
 
 impl EngineInfo for Client {
@@ -1645,24 +1821,38 @@ impl BlockChainClient for Client {
 	}
 
 	fn replay_block_transactions(&self, block: BlockId, analytics: CallAnalytics) -> Result<Box<dyn Iterator<Item = (H256, Executed)>>, CallError> {
+		self.replay_block_transactions_with(block, move |machine, env_info, state, t| {
+			Self::do_virtual_call(machine, env_info, state, t, analytics)
+		})
+	}
+
+	fn replay_with_struct_logger(&self, id: TransactionId, config: trace::StructLoggerConfig) -> Result<StructLogExecuted, CallError> {
+		let address = self.transaction_address(id).ok_or_else(|| CallError::TransactionNotFound)?;
+		let block = BlockId::Hash(address.block_hash);
+
 		let mut env_info = self.env_info(block).ok_or_else(|| CallError::StatePruned)?;
 		let body = self.block_body(block).ok_or_else(|| CallError::StatePruned)?;
 		let mut state = self.state_at_beginning(block).ok_or_else(|| CallError::StatePruned)?;
-		let txs = body.transactions();
-		let engine = self.engine.clone();
+		let machine = self.engine.machine();
 
 		const PROOF: &str = "Transactions fetched from blockchain; blockchain transactions are valid; qed";
-		const EXECUTE_PROOF: &str = "Transaction replayed; qed";
+		const INDEX_PROOF: &str = "The transaction address contains a valid index within block; qed";
 
-		Ok(Box::new(txs.into_iter()
-			.map(move |t| {
-				let transaction_hash = t.hash();
-				let t = SignedTransaction::new(t).expect(PROOF);
-				let machine = engine.machine();
-				let x = Self::do_virtual_call(machine, &env_info, &mut state, &t, analytics).expect(EXECUTE_PROOF);
-				env_info.gas_used = env_info.gas_used + x.gas_used;
-				(transaction_hash, x)
-			})))
+		// only the requested transaction is logged, the ones before it just build up the state.
+		let mut txs = body.transactions().into_iter();
+		for t in txs.by_ref().take(address.index) {
+			let t = SignedTransaction::new(t).expect(PROOF);
+			let x = Self::do_virtual_call(machine, &env_info, &mut state, &t, CallAnalytics::default())?;
+			env_info.gas_used = env_info.gas_used + x.gas_used;
+		}
+		let t = SignedTransaction::new(txs.next().expect(INDEX_PROOF)).expect(PROOF);
+		Self::do_virtual_call_with_struct_logger(machine, &env_info, &mut state, &t, config)
+	}
+
+	fn replay_block_transactions_with_struct_logger(&self, block: BlockId, config: trace::StructLoggerConfig) -> Result<Box<dyn Iterator<Item = (H256, StructLogExecuted)>>, CallError> {
+		self.replay_block_transactions_with(block, move |machine, env_info, state, t| {
+			Self::do_virtual_call_with_struct_logger(machine, env_info, state, t, config)
+		})
 	}
 
 	fn mode(&self) -> Mode {
@@ -2106,6 +2296,29 @@ impl BlockChainClient for Client {
 			.and_then(|number| self.tracedb.read().block_traces(number))
 	}
 
+	fn block_rewards(&self, id: BlockId) -> Option<Vec<BlockReward>> {
+		let hash = self.block_hash(id)?;
+		self.chain.read().block_rewards(&hash)
+	}
+
+	fn dry_run_block_rewards(&self, id: BlockId, contract: Address, beneficiaries: Vec<(Address, RewardKind)>) -> Result<Vec<BlockReward>, CallError> {
+		let parent = self.block_header_decoded(id).ok_or(CallError::StatePruned)?;
+		let state = self.state_at(id).ok_or(CallError::StatePruned)?;
+
+		// rewards are paid when closing a block, so call the contract as if closing a child.
+		let mut block = ExecutedBlock::new(state, self.build_last_hashes(parent.hash()), false);
+		block.header.set_parent_hash(parent.hash());
+		block.header.set_number(parent.number() + 1);
+		block.header.set_timestamp(self.engine.open_block_header_timestamp(&parent));
+		block.header.set_difficulty(*parent.difficulty());
+		block.header.set_gas_limit(*parent.gas_limit());
+
+		let mut call = default_system_or_code_call(self.engine.machine(), &mut block);
+		let rewards = BlockRewardContract::new_from_address(contract).reward(beneficiaries, &mut call)
+			.map_err(|e| ExecutionError::Internal(format!("{}", e)))?;
+		Ok(rewards.into_iter().map(|(address, amount)| BlockReward { address, kind: RewardKind::External, amount }).collect())
+	}
+
 	fn last_hashes(&self) -> LastHashes {
 		self.build_last_hashes(self.chain.read().best_block_hash()).to_vec()
 	}
@@ -2132,6 +2345,10 @@ impl BlockChainClient for Client {
 		self.importer.miner.ready_transactions(self, max_len, PendingOrdering::Priority)
 	}
 
+	fn queued_transaction(&self, hash: &H256) -> Option<Arc<VerifiedTransaction>> {
+		self.importer.miner.transaction(hash)
+	}
+
 	fn signing_chain_id(&self) -> Option<u64> {
 		self.engine.signing_chain_id(&self.latest_env_info())
 	}
@@ -2144,7 +2361,7 @@ impl BlockChainClient for Client {
 	fn uncle_extra_info(&self, id: UncleId) -> Option<BTreeMap<String, String>> {
 		self.uncle(id)
 			.and_then(|h| {
-				h.decode().map(|dh| {
+				h.decode(self.engine.params().eip1559_transition).map(|dh| {
 					self.engine.extra_info(&dh)
 				}).ok()
 			})
@@ -2179,7 +2396,7 @@ impl BlockChainClient for Client {
 			data,
 		};
 		let chain_id = self.engine.signing_chain_id(&self.latest_env_info());
-		let signature = self.engine.sign(transaction.hash(chain_id))
+		let signature = self.engine.sign_data(&transaction.signing_data(chain_id))
 			.map_err(|e| transaction::Error::InvalidSignature(e.to_string()))?;
 		Ok(SignedTransaction::new(transaction.with_signature(signature, chain_id))?)
 	}
@@ -2314,7 +2531,7 @@ impl ReopenBlock for Client {
 			for h in uncles {
 				if !block.uncles.iter().any(|header| header.hash() == h) {
 					let uncle = chain.block_header_data(&h).expect("find_uncle_hashes only returns hashes for existing headers; qed");
-					let uncle = uncle.decode().expect("decoding failure");
+					let uncle = uncle.decode(engine.params().eip1559_transition).expect("decoding failure");
 					block.push_uncle(uncle).expect("pushing up to maximum_uncle_count;
 												push_uncle is not ok only if more than maximum_uncle_count is pushed;
 												so all push_uncle are Ok;
@@ -2356,7 +2573,7 @@ impl PrepareOpenBlock for Client {
 			.into_iter()
 			.take(engine.maximum_uncle_count(open_block.header.number()))
 			.foreach(|h| {
-				open_block.push_uncle(h.decode().expect("decoding failure")).expect("pushing maximum_uncle_count;
+				open_block.push_uncle(h.decode(engine.params().eip1559_transition).expect("decoding failure")).expect("pushing maximum_uncle_count;
 												open_block was just created;
 												push_uncle is not ok only if more than maximum_uncle_count is pushed;
 												so all push_uncle are Ok;
@@ -2385,7 +2602,7 @@ impl ImportSealedBlock for Client {
 			n.block_pre_import(&raw, &hash, header.difficulty())
 		});
 
-		let route = {
+		let (route, finalized) = {
 			// Do a super duper basic verification to detect potential bugs
 			if let Err(e) = self.engine.verify_block_basic(&header) {
 				self.importer.bad_blocks.report(
@@ -2408,7 +2625,7 @@ impl ImportSealedBlock for Client {
 				block.state.db(),
 				self
 			)?;
-			let route = self.importer.commit_block(
+			let (route, finalized) = self.importer.commit_block(
 				block,
 				&header,
 				encoded::Block::new(block_bytes),
@@ -2417,7 +2634,7 @@ impl ImportSealedBlock for Client {
 			);
 			trace!(target: "client", "Imported sealed block #{} ({})", header.number(), hash);
 			self.state_db.write().sync_cache(&route.enacted, &route.retracted, false);
-			route
+			(route, finalized)
 		};
 		let route = ChainRoute::from([route].as_ref());
 		self.importer.miner.chain_new_blocks(
@@ -2441,6 +2658,9 @@ impl ImportSealedBlock for Client {
 				)
 			);
 		});
+		if !finalized.is_empty() {
+			self.notify(|notify| notify.blocks_finalized(&finalized));
+		}
 		self.db.read().key_value().flush().expect("DB flush failed.");
 		Ok(hash)
 	}
@@ -2493,6 +2713,10 @@ impl client_traits::EngineClient for Client {
 		self.chain.read().epoch_transition_for(parent_hash)
 	}
 
+	fn verify_unsealed_block(&self, block: Unverified) -> EthcoreResult<()> {
+		self.importer.verify_unsealed_block(block, self)
+	}
+
 	fn as_full_client(&self) -> Option<&dyn BlockChainClient> { Some(self) }
 
 	fn block_number(&self, id: BlockId) -> Option<BlockNumber> {
@@ -2502,6 +2726,18 @@ impl client_traits::EngineClient for Client {
 	fn block_header(&self, id: BlockId) -> Option<encoded::Header> {
 		BlockChainClient::block_header(self, id)
 	}
+
+	fn engine_data(&self, key: &[u8]) -> Option<Bytes> {
+		self.db.read().key_value().get(::db::COL_NODE_INFO, key).expect("Low level database error. Some issue with disk?")
+	}
+
+	fn set_engine_data(&self, key: &[u8], value: &[u8]) {
+		let mut batch = DBTransaction::new();
+		batch.put(::db::COL_NODE_INFO, key, value);
+		if let Err(e) = self.db.read().key_value().write(batch) {
+			warn!(target: "client", "Failed to store engine data: {}", e);
+		}
+	}
 }
 
 impl ProvingBlockChainClient for Client {
@@ -2641,6 +2877,22 @@ impl ImportExportBlocks for Client {
 							format!("Couldn't write to stream. Cause: {}", e)
 						})?;
 				}
+				DataFormat::BinaryWithReceipts => {
+					let hash = self.block_hash(BlockId::Number(i))
+						.ok_or("Error exporting incomplete chain")?;
+					let receipts = self.block_receipts(&hash)
+						.ok_or("Error exporting incomplete chain")?;
+					let mut stream = RlpStream::new_list(2);
+					stream.append_raw(&b, 1);
+					stream.append(&receipts);
+					out.write_all(&stream.out())
+						.map_err(|e| {
+							format!("Couldn't write to stream. Cause: {}", e)
+						})?;
+				}
+				DataFormat::Json => {
+					return Err("Exporting blocks as JSON is not supported by the client".into());
+				}
 			}
 		}
 		Ok(())
@@ -2651,7 +2903,7 @@ impl ImportExportBlocks for Client {
 		mut source: Box<dyn std::io::Read + 'a>,
 		format: Option<DataFormat>
 	) -> Result<(), String> {
-		const READAHEAD_BYTES: usize = 8;
+		const READAHEAD_BYTES: usize = 32;
 
 		let mut first_bytes: Vec<u8> = vec![0; READAHEAD_BYTES];
 		let mut first_read = 0;
@@ -2663,15 +2915,11 @@ impl ImportExportBlocks for Client {
 					.map_err(|_| {
 						"Error reading from the file/stream."
 					})?;
-				match first_bytes[0] {
-					0xf9 => DataFormat::Binary,
-					_ => DataFormat::Hex,
-				}
+				DataFormat::detect(&first_bytes[..first_read])
 			}
 		};
 
-		let do_import = |bytes: Vec<u8>| {
-			let block = Unverified::from_rlp(bytes).map_err(|_| "Invalid block rlp")?;
+		let import_unverified = |block: Unverified| {
 			let number = block.header.number();
 			while self.queue_info().is_full() {
 				std::thread::sleep(Duration::from_secs(1));
@@ -2688,8 +2936,38 @@ impl ImportExportBlocks for Client {
 			Ok(())
 		};
 
+		let do_import = |bytes: Vec<u8>| {
+			let block = Unverified::from_rlp(bytes, self.engine.params().eip1559_transition).map_err(|_| "Invalid block rlp")?;
+			import_unverified(block)
+		};
+
+		// blocks paired with their receipts fill in the gap below a restored snapshot as ancient
+		// blocks, without being executed. Any other block is executed as usual.
+		let do_import_with_receipts = |bytes: Vec<u8>| {
+			let rlp = Rlp::new(&bytes);
+			let block = rlp.at(0)
+				.and_then(|block| Unverified::from_rlp(block.as_raw().to_vec(), self.engine.params().eip1559_transition))
+				.map_err(|_| "Invalid block rlp")?;
+			let number = block.header.number();
+			let below_snapshot = self.chain.read().first_block_number().map_or(false, |first| number < first);
+			if !below_snapshot {
+				return import_unverified(block);
+			}
+			let receipts = rlp.at(1).map_err(|_| "Invalid receipts rlp")?.as_raw().to_vec();
+			match self.import_ancient_block(block, receipts) {
+				Err(EthcoreError::Import(ImportError::AlreadyInChain)) => {
+					trace!("Skipping block #{}: already in chain.", number);
+				}
+				Err(e) => {
+					return Err(format!("Cannot import ancient block #{}: {:?}", number, e));
+				},
+				Ok(_) => {},
+			}
+			Ok(())
+		};
+
 		match format {
-			DataFormat::Binary => {
+			DataFormat::Binary | DataFormat::BinaryWithReceipts => {
 				loop {
 					let (mut bytes, n) = if first_read > 0 {
 						(first_bytes.clone(), first_read)
@@ -2712,7 +2990,11 @@ impl ImportExportBlocks for Client {
 						.map_err(|err| {
 							format!("Error reading from the file/stream: {:?}", err)
 						})?;
-					do_import(bytes)?;
+					if format == DataFormat::BinaryWithReceipts {
+						do_import_with_receipts(bytes)?;
+					} else {
+						do_import(bytes)?;
+					}
 				}
 			}
 			DataFormat::Hex => {
@@ -2738,6 +3020,38 @@ impl ImportExportBlocks for Client {
 					do_import(bytes)?;
 				}
 			}
+			DataFormat::Json => {
+				for line in BufReader::new(source).lines() {
+					let s = line
+						.map_err(|err| {
+							format!("Error reading from the file/stream: {:?}", err)
+						})?;
+					let s = if first_read > 0 {
+						from_utf8(&first_bytes[..first_read])
+							.map_err(|err| {
+								format!("Invalid UTF-8: {:?}", err)
+							})?
+							.to_owned() + &(s[..])
+					} else {
+						s
+					};
+					first_read = 0;
+					if s.trim().is_empty() {
+						continue;
+					}
+					let json: serde_json::Value = serde_json::from_str(&s)
+						.map_err(|err| {
+							format!("Invalid JSON in file/stream: {:?}", err)
+						})?;
+					let raw = json["raw"].as_str()
+						.ok_or("Block in file/stream has no raw RLP")?;
+					let bytes = raw.trim_start_matches("0x").from_hex()
+						.map_err(|err| {
+							format!("Invalid hex in file/stream: {:?}", err)
+						})?;
+					do_import_with_receipts(bytes)?;
+				}
+			}
 		};
 		self.flush_queue();
 		Ok(())
@@ -2788,6 +3102,54 @@ fn transaction_receipt(
 	}
 }
 
+impl PrometheusMetrics for Client {
+	fn prometheus_metrics(&self, r: &mut PrometheusRegistry) {
+		let chain = self.chain_info();
+		r.register_gauge("chain_block", "Best block number", chain.best_block_number as i64);
+		r.register_gauge(
+			"chain_ancient_block",
+			"Last imported ancient block number (0 if the ancient chain is complete)",
+			chain.ancient_block_number.unwrap_or(0) as i64,
+		);
+
+		let report = self.report();
+		r.register_counter("import_blocks", "Blocks imported since startup", report.blocks_imported as i64);
+		r.register_counter("import_transactions", "Transactions applied since startup", report.transactions_applied as i64);
+		r.register_counter(
+			"import_gas",
+			"Gas processed since startup",
+			cmp::min(report.gas_processed, U256::from(i64::max_value())).as_u64() as i64,
+		);
+
+		let queue = self.queue_info();
+		r.register_gauge("queue_unverified", "Blocks waiting for verification", queue.unverified_queue_size as i64);
+		r.register_gauge("queue_verifying", "Blocks being verified", queue.verifying_queue_size as i64);
+		r.register_gauge("queue_verified", "Verified blocks waiting for import", queue.verified_queue_size as i64);
+		r.register_gauge("queue_max_size", "Configured maximum number of queued blocks", queue.max_queue_size as i64);
+		r.register_gauge("queue_mem_used", "Heap memory used by the block queue in bytes", queue.mem_used as i64);
+
+		let cache = self.blockchain_cache_info();
+		for &(name, bytes) in &[
+			("state_db", report.state_db_mem),
+			("blocks", cache.blocks),
+			("block_details", cache.block_details),
+			("transaction_addresses", cache.transaction_addresses),
+			("block_receipts", cache.block_receipts),
+		] {
+			r.register_gauge_with_labels("cache_bytes", "Cache sizes in bytes", &[("cache", name)], bytes as i64);
+		}
+
+		let txpool = self.importer.miner.queue_status();
+		r.register_gauge("txpool_transactions", "Transactions in the pool", txpool.status.transaction_count as i64);
+		r.register_gauge("txpool_senders", "Distinct senders in the pool", txpool.status.senders as i64);
+		r.register_gauge("txpool_mem_used", "Memory used by the pool in bytes", txpool.status.mem_usage as i64);
+		r.register_gauge("txpool_max_transactions", "Configured maximum number of pooled transactions", txpool.limits.max_count as i64);
+		r.register_gauge("txpool_max_mem", "Configured maximum pool memory usage in bytes", txpool.limits.max_mem_usage as i64);
+
+		self.engine.prometheus_metrics(r);
+	}
+}
+
 /// Queue some items to be processed by IO client.
 struct IoChannelQueue {
 	/// Using a *signed* integer for counting currently queued messages since the
@@ -2944,6 +3306,7 @@ mod tests {
 			gas_used,
 			log_bloom: Default::default(),
 			logs: logs.clone(),
+			tx_type: Default::default(),
 		};
 
 		// when
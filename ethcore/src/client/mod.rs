@@ -20,10 +20,12 @@ mod ancient_import;
 mod bad_blocks;
 mod client;
 mod config;
+mod state_diff;
 mod traits;
 
 pub use self::client::Client;
 pub use self::config::{ClientConfig, DatabaseCompactionProfile};
+pub use self::state_diff::ChangedAccount;
 pub use self::traits::{
     ReopenBlock, PrepareOpenBlock, ImportSealedBlock, BroadcastProposalBlock,
     Call, EngineInfo, BlockProducer, SealedBlockImporter,
@@ -0,0 +1,341 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Parity Ethereum.
+
+// Parity Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Diff of two states, found by walking both state tries side by side.
+
+use std::collections::BTreeMap;
+
+use ethereum_types::{Address, BigEndianHash, H256, U256};
+use ethtrie::{Layout, RlpCodec};
+use hash::{keccak, KECCAK_NULL_RLP};
+use hash_db::{HashDB, EMPTY_PREFIX};
+use kvdb::DBValue;
+use trie::{NodeCodec, TrieLayout};
+use trie::node::{Node, NodeHandle};
+use trie_vm_factories::Factories;
+use types::basic_account::BasicAccount;
+
+type Hasher = <Layout as TrieLayout>::Hash;
+
+/// An account which differs between two states.
+#[derive(Debug, PartialEq)]
+pub struct ChangedAccount {
+	/// Address of the account.
+	pub address: Address,
+	/// The account in the first state, if it exists there.
+	pub pre: Option<BasicAccount>,
+	/// The account in the second state, if it exists there.
+	pub post: Option<BasicAccount>,
+	/// Storage slots which differ, with their values in the first and second state.
+	pub storage: BTreeMap<H256, (Option<H256>, Option<H256>)>,
+}
+
+/// Call `f` for every account which differs between the states with roots `from_root` and
+/// `to_root`. Subtries both states share are skipped, for the accounts as well as for their
+/// storage. Addresses and storage keys are read from the preimages stored by a fat DB.
+pub fn diff_states<F>(
+	from_db: &dyn HashDB<Hasher, DBValue>,
+	from_root: &H256,
+	to_db: &dyn HashDB<Hasher, DBValue>,
+	to_root: &H256,
+	factories: &Factories,
+	mut f: F,
+) -> Result<(), String> where
+	F: FnMut(ChangedAccount) -> Result<(), String>,
+{
+	diff_tries(from_db, from_root, to_db, to_root, &mut |hashed, pre, post| {
+		let address = Address::from_slice(&preimage(if pre.is_some() { from_db } else { to_db }, &hashed)?);
+		let pre = pre.map(|pre| ::rlp::decode::<BasicAccount>(pre)).transpose()
+			.map_err(|e| format!("Invalid account {:?}: {}", address, e))?;
+		let post = post.map(|post| ::rlp::decode::<BasicAccount>(post)).transpose()
+			.map_err(|e| format!("Invalid account {:?}: {}", address, e))?;
+
+		let from_storage_root = pre.as_ref().map_or(KECCAK_NULL_RLP, |a| a.storage_root);
+		let to_storage_root = post.as_ref().map_or(KECCAK_NULL_RLP, |a| a.storage_root);
+		let from_storage = factories.accountdb.readonly(from_db, hashed);
+		let to_storage = factories.accountdb.readonly(to_db, hashed);
+		let mut storage = BTreeMap::new();
+		diff_tries(&*from_storage, &from_storage_root, &*to_storage, &to_storage_root, &mut |hashed_key, pre, post| {
+			let key = H256::from_slice(&preimage(if pre.is_some() { &*from_storage } else { &*to_storage }, &hashed_key)?);
+			storage.insert(key, (storage_value(pre)?, storage_value(post)?));
+			Ok(())
+		})?;
+
+		f(ChangedAccount { address, pre, post, storage })
+	})
+}
+
+/// The key a fat DB stored under the hash `hashed`.
+fn preimage(db: &dyn HashDB<Hasher, DBValue>, hashed: &H256) -> Result<DBValue, String> {
+	db.get(&keccak(hashed), EMPTY_PREFIX)
+		.ok_or_else(|| format!("Missing preimage of {:?}, the state is not in a fat DB", hashed))
+}
+
+fn storage_value(value: Option<&[u8]>) -> Result<Option<H256>, String> {
+	value.map(|value| ::rlp::decode::<U256>(value).map(|v| H256::from_uint(&v)))
+		.transpose()
+		.map_err(|e| format!("Invalid storage value: {}", e))
+}
+
+/// A position in a trie: a node with the first `skip` nibbles of its partial key walked.
+#[derive(Clone)]
+struct Cursor {
+	/// How the parent refers to the node, a hash or the inline node, which is equal for
+	/// equal subtries.
+	handle: Vec<u8>,
+	node: DBValue,
+	skip: usize,
+}
+
+/// Call `f` with the hashed key and both values of every leaf which differs between the two
+/// tries. Keys of a secure trie all have the same length, so branches never hold values.
+fn diff_tries(
+	from_db: &dyn HashDB<Hasher, DBValue>,
+	from_root: &H256,
+	to_db: &dyn HashDB<Hasher, DBValue>,
+	to_root: &H256,
+	f: &mut dyn FnMut(H256, Option<&[u8]>, Option<&[u8]>) -> Result<(), String>,
+) -> Result<(), String> {
+	let from = root_cursor(from_db, from_root)?;
+	let to = root_cursor(to_db, to_root)?;
+	walk(from_db, from, to_db, to, &mut Vec::new(), f)
+}
+
+fn walk(
+	from_db: &dyn HashDB<Hasher, DBValue>,
+	from: Option<Cursor>,
+	to_db: &dyn HashDB<Hasher, DBValue>,
+	to: Option<Cursor>,
+	path: &mut Vec<u8>,
+	f: &mut dyn FnMut(H256, Option<&[u8]>, Option<&[u8]>) -> Result<(), String>,
+) -> Result<(), String> {
+	if let (Some(from), Some(to)) = (&from, &to) {
+		if from.handle == to.handle && from.skip == to.skip {
+			return Ok(());
+		}
+	}
+
+	// once either side reaches a leaf, the remaining keys of both subtries are compared.
+	if !is_inner(&from)? || !is_inner(&to)? {
+		let mut from_leaves = BTreeMap::new();
+		let mut to_leaves = BTreeMap::new();
+		if let Some(from) = from {
+			collect(from_db, from, &mut path.clone(), &mut from_leaves)?;
+		}
+		if let Some(to) = to {
+			collect(to_db, to, &mut path.clone(), &mut to_leaves)?;
+		}
+		for (key, pre) in &from_leaves {
+			let post = to_leaves.get(key);
+			if post != Some(pre) {
+				f(*key, Some(&pre[..]), post.map(|v| &v[..]))?;
+			}
+		}
+		for (key, post) in &to_leaves {
+			if !from_leaves.contains_key(key) {
+				f(*key, None, Some(&post[..]))?;
+			}
+		}
+		return Ok(());
+	}
+
+	for nibble in 0..16 {
+		let from_child = match from {
+			Some(ref from) => child(from_db, from, nibble)?,
+			None => None,
+		};
+		let to_child = match to {
+			Some(ref to) => child(to_db, to, nibble)?,
+			None => None,
+		};
+		if from_child.is_none() && to_child.is_none() {
+			continue;
+		}
+		path.push(nibble);
+		walk(from_db, from_child, to_db, to_child, path, f)?;
+		path.pop();
+	}
+	Ok(())
+}
+
+fn root_cursor(db: &dyn HashDB<Hasher, DBValue>, root: &H256) -> Result<Option<Cursor>, String> {
+	if *root == KECCAK_NULL_RLP {
+		return Ok(None);
+	}
+	resolve(db, NodeHandle::Hash(root.as_bytes()))
+}
+
+fn resolve(db: &dyn HashDB<Hasher, DBValue>, handle: NodeHandle) -> Result<Option<Cursor>, String> {
+	let (handle, node) = match handle {
+		NodeHandle::Hash(hash) => {
+			let node = db.get(&H256::from_slice(hash), EMPTY_PREFIX)
+				.ok_or_else(|| format!("Missing trie node {:?}", H256::from_slice(hash)))?;
+			(hash.to_vec(), node)
+		},
+		NodeHandle::Inline(node) => (node.to_vec(), node.to_vec()),
+	};
+	Ok(Some(Cursor { handle, node, skip: 0 }))
+}
+
+fn decode(node: &[u8]) -> Result<Node, String> {
+	RlpCodec::decode(node).map_err(|e| format!("Invalid trie node: {}", e))
+}
+
+/// Whether the cursor is at a branch or extension, which are walked a nibble at a time.
+fn is_inner(cursor: &Option<Cursor>) -> Result<bool, String> {
+	match cursor {
+		Some(cursor) => match decode(&cursor.node)? {
+			Node::Extension(..) | Node::Branch(..) => Ok(true),
+			_ => Ok(false),
+		},
+		None => Ok(false),
+	}
+}
+
+/// The position one nibble further down.
+fn child(db: &dyn HashDB<Hasher, DBValue>, cursor: &Cursor, nibble: u8) -> Result<Option<Cursor>, String> {
+	match decode(&cursor.node)? {
+		Node::Extension(partial, next) => {
+			if partial.at(cursor.skip) != nibble {
+				Ok(None)
+			} else if cursor.skip + 1 == partial.len() {
+				resolve(db, next)
+			} else {
+				Ok(Some(Cursor { skip: cursor.skip + 1, ..cursor.clone() }))
+			}
+		},
+		Node::Branch(children, _) => match children[nibble as usize].clone() {
+			Some(next) => resolve(db, next),
+			None => Ok(None),
+		},
+		_ => Ok(None),
+	}
+}
+
+/// Collect the leaves below the cursor, by hashed key.
+fn collect(
+	db: &dyn HashDB<Hasher, DBValue>,
+	cursor: Cursor,
+	path: &mut Vec<u8>,
+	leaves: &mut BTreeMap<H256, DBValue>,
+) -> Result<(), String> {
+	let depth = path.len();
+	match decode(&cursor.node)? {
+		Node::Empty => {},
+		Node::Leaf(partial, value) => {
+			path.extend((cursor.skip..partial.len()).map(|i| partial.at(i)));
+			leaves.insert(nibbles_to_key(path)?, value.to_vec());
+		},
+		Node::Extension(partial, child) => {
+			path.extend((cursor.skip..partial.len()).map(|i| partial.at(i)));
+			if let Some(child) = resolve(db, child)? {
+				collect(db, child, path, leaves)?;
+			}
+		},
+		Node::Branch(children, _) => {
+			for (nibble, child) in children.iter().enumerate() {
+				if let Some(child) = child {
+					if let Some(child) = resolve(db, child.clone())? {
+						path.push(nibble as u8);
+						collect(db, child, path, leaves)?;
+						path.pop();
+					}
+				}
+			}
+		},
+		_ => return Err("Unexpected trie node".into()),
+	}
+	path.truncate(depth);
+	Ok(())
+}
+
+fn nibbles_to_key(nibbles: &[u8]) -> Result<H256, String> {
+	if nibbles.len() != 64 {
+		return Err(format!("Unexpected trie key length of {} nibbles", nibbles.len()));
+	}
+	Ok(H256::from_slice(&nibbles.chunks(2).map(|n| (n[0] << 4) | n[1]).collect::<Vec<_>>()))
+}
+
+#[cfg(test)]
+mod tests {
+	use std::collections::BTreeMap;
+
+	use account_state::{CleanupMode, State};
+	use ethereum_types::{Address, H256, U256};
+	use ethtrie::Layout;
+	use trie::{TrieFactory, TrieSpec};
+	use trie_vm_factories::Factories;
+	use test_helpers::get_temp_state_db;
+
+	use super::{diff_states, ChangedAccount};
+
+	#[test]
+	fn diffs_accounts_and_storage() {
+		let factories = Factories {
+			trie: TrieFactory::new(TrieSpec::Fat, Layout),
+			..Default::default()
+		};
+		let unchanged = Address::from_low_u64_be(1);
+		let changed = Address::from_low_u64_be(2);
+		let died = Address::from_low_u64_be(3);
+		let born = Address::from_low_u64_be(4);
+		let slot = |n: u64| H256::from_low_u64_be(n);
+
+		let mut state = State::new(get_temp_state_db(), U256::zero(), factories.clone());
+		for account in &[unchanged, changed, died] {
+			state.add_balance(account, &U256::from(10), CleanupMode::NoEmpty).unwrap();
+		}
+		for n in 1..20 {
+			state.set_storage(&unchanged, slot(n), slot(n)).unwrap();
+			state.set_storage(&changed, slot(n), slot(n)).unwrap();
+		}
+		state.commit().unwrap();
+		let (from_root, db) = state.drop();
+
+		let mut state = State::from_existing(db, from_root, U256::zero(), factories.clone()).unwrap();
+		state.add_balance(&changed, &U256::from(5), CleanupMode::NoEmpty).unwrap();
+		state.set_storage(&changed, slot(3), slot(30)).unwrap();
+		state.set_storage(&changed, slot(4), H256::zero()).unwrap();
+		state.set_storage(&changed, slot(40), slot(40)).unwrap();
+		state.kill_account(&died);
+		state.add_balance(&born, &U256::from(7), CleanupMode::NoEmpty).unwrap();
+		state.commit().unwrap();
+		let (to_root, db) = state.drop();
+
+		let mut changes = BTreeMap::new();
+		diff_states(db.as_hash_db(), &from_root, db.as_hash_db(), &to_root, &factories, |change| {
+			changes.insert(change.address, change);
+			Ok(())
+		}).unwrap();
+
+		assert_eq!(changes.len(), 3);
+		assert!(!changes.contains_key(&unchanged));
+
+		let ChangedAccount { pre, post, storage, .. } = &changes[&changed];
+		assert_eq!(pre.as_ref().unwrap().balance, U256::from(10));
+		assert_eq!(post.as_ref().unwrap().balance, U256::from(15));
+		let mut expected = BTreeMap::new();
+		expected.insert(slot(3), (Some(slot(3)), Some(slot(30))));
+		expected.insert(slot(4), (Some(slot(4)), None));
+		expected.insert(slot(40), (None, Some(slot(40))));
+		assert_eq!(storage, &expected);
+
+		assert_eq!(changes[&died].pre.as_ref().unwrap().balance, U256::from(10));
+		assert!(changes[&died].post.is_none());
+		assert!(changes[&born].pre.is_none());
+		assert_eq!(changes[&born].post.as_ref().unwrap().balance, U256::from(7));
+	}
+}
@@ -19,6 +19,7 @@ use ethereum_types::{H256, U256, Address};
 use types::{
 	transaction::{SignedTransaction, CallError},
 	call_analytics::CallAnalytics,
+	state_override::StateOverride,
 	errors::EthcoreError as Error,
 	errors::EthcoreResult,
 	header::Header,
@@ -26,7 +27,8 @@ use types::{
 
 use block::{OpenBlock, SealedBlock, ClosedBlock};
 use engine::Engine;
-use machine::executed::Executed;
+use machine::executed::{Executed, StructLogExecuted};
+use trace::StructLoggerConfig;
 use account_state::state::StateInfo;
 
 /// Provides `call` and `call_many` methods
@@ -41,8 +43,14 @@ pub trait Call {
 	/// Returns a vector of successes or a failure if any of the transaction fails.
 	fn call_many(&self, txs: &[(SignedTransaction, CallAnalytics)], state: &mut Self::State, header: &Header) -> Result<Vec<Executed>, CallError>;
 
+	/// Makes a non-persistent transaction call, recording Geth-style struct logs.
+	fn call_with_struct_logger(&self, tx: &SignedTransaction, config: StructLoggerConfig, state: &mut Self::State, header: &Header) -> Result<StructLogExecuted, CallError>;
+
 	/// Estimates how much gas will be necessary for a call.
 	fn estimate_gas(&self, t: &SignedTransaction, state: &Self::State, header: &Header) -> Result<U256, CallError>;
+
+	/// Overrides balances, nonces, code or storage of a state before making non-persistent calls on it.
+	fn apply_state_override(&self, state: &mut Self::State, overrides: &StateOverride) -> Result<(), CallError>;
 }
 
 /// Provides `engine` method
@@ -233,6 +233,22 @@ impl<'a, T: 'a, V: 'a, B: 'a> Ext for TestExt<'a, T, V, B>
 	fn sub_sstore_refund(&mut self, value: usize) {
 		self.ext.sub_sstore_refund(value)
 	}
+
+	fn is_address_warm(&self, address: &Address) -> bool {
+		self.ext.is_address_warm(address)
+	}
+
+	fn warm_address(&mut self, address: Address) {
+		self.ext.warm_address(address)
+	}
+
+	fn is_storage_warm(&self, key: &H256) -> bool {
+		self.ext.is_storage_warm(key)
+	}
+
+	fn warm_storage(&mut self, key: H256) {
+		self.ext.warm_storage(key)
+	}
 }
 
 fn do_json_test<H: FnMut(&str, HookType)>(
@@ -20,6 +20,7 @@
 
 extern crate account_state;
 extern crate ansi_term;
+extern crate block_reward;
 extern crate client_traits;
 extern crate common_types as types;
 extern crate engine;
@@ -48,6 +49,7 @@ extern crate registrar;
 extern crate rlp;
 extern crate rustc_hex;
 extern crate serde;
+extern crate serde_json;
 extern crate snapshot;
 extern crate spec;
 extern crate state_db;
@@ -92,8 +94,6 @@ extern crate pod;
 extern crate blooms_db;
 #[cfg(feature = "env_logger")]
 extern crate env_logger;
-#[cfg(test)]
-extern crate serde_json;
 #[cfg(any(test, feature = "tempdir"))]
 extern crate tempdir;
 
@@ -579,6 +579,10 @@ impl Miner {
 				Err(Error::Execution(ExecutionError::InvalidNonce { expected, got })) => {
 					debug!(target: "miner", "Skipping adding transaction to block because of invalid nonce: {:?} (expected: {:?}, got: {:?})", hash, expected, got);
 				},
+				// The base fee may drop below the max fee of the transaction in one of the next blocks.
+				Err(Error::Execution(ExecutionError::GasPriceLowerThanBaseFee { gas_price, base_fee })) => {
+					debug!(target: "miner", "Skipping adding transaction to block because of base fee: {:?} (max fee: {:?}, base fee: {:?})", hash, gas_price, base_fee);
+				},
 				// already have transaction - ignore
 				Err(Error::Transaction(transaction::Error::AlreadyImported)) => {},
 				Err(Error::Transaction(transaction::Error::NotAllowed)) => {
@@ -702,7 +706,7 @@ impl Miner {
 
 		let parent_header = match chain.block_header(BlockId::Hash(*block.header.parent_hash())) {
 			Some(h) => {
-				match h.decode() {
+				match h.decode(self.engine.params().eip1559_transition) {
 					Ok(decoded_hdr) => decoded_hdr,
 					Err(e) => {
 						error!(target: "miner", "seal_block_internally: Block #{}, Could not decode header from parent block (hash={}): {:?}", block_number, block.header.parent_hash(), e);
@@ -1363,8 +1367,11 @@ impl miner::MinerService for Miner {
 		}
 
 		// First update gas limit in transaction queue and minimal gas price.
-		let gas_limit = *chain.best_block_header().gas_limit();
-		self.update_transaction_queue_limits(gas_limit);
+		let best_header = chain.best_block_header();
+		self.update_transaction_queue_limits(*best_header.gas_limit());
+
+		// Transactions are prioritized by the tip they pay on top of the next block's base fee.
+		self.transaction_queue.set_block_base_fee(self.engine.machine().calc_base_fee(&best_header));
 
 		// Then import all transactions from retracted blocks.
 		let client = self.pool_client(chain);
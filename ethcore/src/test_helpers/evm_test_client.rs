@@ -223,6 +223,7 @@ impl<'a> EvmTestClient<'a> {
 			last_hashes: Arc::new([H256::zero(); 256].to_vec()),
 			gas_used: 0.into(),
 			gas_limit: *genesis.gas_limit(),
+			base_fee: genesis.base_fee_per_gas(),
 		};
 		self.call_envinfo(params, tracer, vm_tracer, info)
 	}
@@ -58,7 +58,7 @@ use types::{
 
 use block::{OpenBlock, Drain};
 use client::{Client, ClientConfig, PrepareOpenBlock};
-use client_traits::{ChainInfo, ChainNotify, ImportBlock};
+use client_traits::{BlockInfo, ChainInfo, ChainNotify, ImportBlock};
 use trie_vm_factories::Factories;
 use miner::Miner;
 use spec::{Spec, self};
@@ -196,11 +196,11 @@ pub fn generate_dummy_client_with_spec_and_data<F>(
 
 		let b = b.close_and_lock().unwrap().seal(test_engine, vec![]).unwrap();
 
-		if let Err(e) = client.import_block(Unverified::from_rlp(b.rlp_bytes()).unwrap()) {
+		if let Err(e) = client.import_block(Unverified::from_rlp(b.rlp_bytes(), client.eip1559_transition()).unwrap()) {
 			panic!("error importing block which is valid by definition: {:?}", e);
 		}
 
-		last_header = view!(BlockView, &b.rlp_bytes()).header();
+		last_header = view!(BlockView, &b.rlp_bytes()).header(client.eip1559_transition());
 		db = b.drain().state.drop().1;
 	}
 	client.flush_queue();
@@ -231,7 +231,7 @@ pub fn push_blocks_to_client(client: &Arc<Client>, timestamp_salt: u64, starting
 		rolling_block_number = rolling_block_number + 1;
 		rolling_timestamp = rolling_timestamp + 10;
 
-		if let Err(e) = client.import_block(Unverified::from_rlp(create_test_block(&header)).unwrap()) {
+		if let Err(e) = client.import_block(Unverified::from_rlp(create_test_block(&header), client.eip1559_transition()).unwrap()) {
 			panic!("error importing block which is valid by definition: {:?}", e);
 		}
 	}
@@ -251,7 +251,7 @@ pub fn push_block_with_transactions(client: &Arc<Client>, transactions: &[Signed
 	}
 	let b = b.close_and_lock().unwrap().seal(test_engine, vec![]).unwrap();
 
-	if let Err(e) = client.import_block(Unverified::from_rlp(b.rlp_bytes()).unwrap()) {
+	if let Err(e) = client.import_block(Unverified::from_rlp(b.rlp_bytes(), client.eip1559_transition()).unwrap()) {
 		panic!("error importing block which is valid by definition: {:?}", e);
 	}
 
@@ -272,7 +272,7 @@ pub fn get_test_client_with_blocks(blocks: Vec<Bytes>) -> Arc<Client> {
 	).unwrap();
 
 	for block in blocks {
-		if let Err(e) = client.import_block(Unverified::from_rlp(block).unwrap()) {
+		if let Err(e) = client.import_block(Unverified::from_rlp(block, client.eip1559_transition()).unwrap()) {
 			panic!("error importing block which is well-formed: {:?}", e);
 		}
 	}
@@ -39,6 +39,7 @@ use rlp::{Rlp, RlpStream};
 use rustc_hex::FromHex;
 use types::{
 	BlockNumber,
+	block_reward::{BlockReward, RewardKind},
 	encoded,
 	engines::epoch::Transition as EpochTransition,
 	ids::{BlockId, TransactionId, UncleId, TraceId},
@@ -48,6 +49,7 @@ use types::{
 	filter::Filter,
 	trace_filter::Filter as TraceFilter,
 	call_analytics::CallAnalytics,
+	state_override::StateOverride,
 	header::Header,
 	log_entry::LocalizedLogEntry,
 	pruning_info::PruningInfo,
@@ -75,13 +77,13 @@ use client_traits::{
 	StateOrBlock, ForceUpdateSealing, TransactionRequest
 };
 use engine::Engine;
-use machine::executed::Executed;
+use machine::executed::{Executed, StructLogExecuted};
 use journaldb;
 use miner::{self, Miner, MinerService};
 use spec::{Spec, self};
 use account_state::state::StateInfo;
 use state_db::StateDB;
-use trace::LocalizedTrace;
+use trace::{LocalizedTrace, StructLoggerConfig};
 
 /// Test client.
 pub struct TestBlockChainClient {
@@ -127,6 +129,8 @@ pub struct TestBlockChainClient {
 	pub first_block: RwLock<Option<(H256, u64)>>,
 	/// Traces to return
 	pub traces: RwLock<Option<Vec<LocalizedTrace>>>,
+	/// Block rewards to return
+	pub rewards: RwLock<Option<Vec<BlockReward>>>,
 	/// Pruning history size to report.
 	pub history: RwLock<Option<u64>>,
 	/// Is disabled
@@ -197,6 +201,7 @@ impl TestBlockChainClient {
 			ancient_block: RwLock::new(None),
 			first_block: RwLock::new(None),
 			traces: RwLock::new(None),
+			rewards: RwLock::new(None),
 			history: RwLock::new(None),
 			disabled: AtomicBool::new(false),
 			error_on_logs: RwLock::new(None),
@@ -323,7 +328,7 @@ impl TestBlockChainClient {
 		rlp.append(&header);
 		rlp.append_raw(&txs, 1);
 		rlp.append_raw(uncles.as_raw(), 1);
-		let unverified = Unverified::from_rlp(rlp.out()).unwrap();
+		let unverified = Unverified::from_rlp(rlp.out(), self.eip1559_transition()).unwrap();
 		self.import_block(unverified).unwrap();
 	}
 
@@ -337,7 +342,7 @@ impl TestBlockChainClient {
 	/// Make a bad block by setting invalid parent hash.
 	pub fn corrupt_block_parent(&self, n: BlockNumber) {
 		let hash = self.block_hash(BlockId::Number(n)).unwrap();
-		let mut header: Header = self.block_header(BlockId::Number(n)).unwrap().decode().expect("decoding failed");
+		let mut header: Header = self.block_header(BlockId::Number(n)).unwrap().decode(self.eip1559_transition()).expect("decoding failed");
 		header.set_parent_hash(H256::from_low_u64_be(42));
 		let mut rlp = RlpStream::new_list(3);
 		rlp.append(&header);
@@ -514,7 +519,7 @@ impl BlockInfo for TestBlockChainClient {
 	fn best_block_header(&self) -> Header {
 		self.block_header(BlockId::Hash(self.chain_info().best_block_hash))
 			.expect("Best block always has header.")
-			.decode()
+			.decode(self.eip1559_transition())
 			.expect("decoding failed")
 	}
 
@@ -530,6 +535,10 @@ impl BlockInfo for TestBlockChainClient {
 			_ => None,
 		}
 	}
+
+	fn eip1559_transition(&self) -> BlockNumber {
+		self.spec.params().eip1559_transition
+	}
 }
 
 impl CallContract for TestBlockChainClient {
@@ -568,7 +577,7 @@ impl ImportBlock for TestBlockChainClient {
 		if number > 0 {
 			match self.blocks.read().get(header.parent_hash()) {
 				Some(parent) => {
-					let parent = view!(BlockView, parent).header();
+					let parent = view!(BlockView, parent).header_view();
 					if parent.number() != (header.number() - 1) {
 						panic!("Unexpected block parent");
 					}
@@ -593,7 +602,7 @@ impl ImportBlock for TestBlockChainClient {
 				while n > 0 && self.numbers.read()[&n] != parent_hash {
 					*self.numbers.write().get_mut(&n).unwrap() = parent_hash.clone();
 					n -= 1;
-					parent_hash = view!(BlockView, &self.blocks.read()[&parent_hash]).header().parent_hash().clone();
+					parent_hash = view!(BlockView, &self.blocks.read()[&parent_hash]).header_view().parent_hash();
 				}
 			}
 		}
@@ -624,9 +633,17 @@ impl Call for TestBlockChainClient {
 		Ok(res)
 	}
 
+	fn call_with_struct_logger(&self, _t: &SignedTransaction, _config: StructLoggerConfig, _state: &mut Self::State, _header: &Header) -> Result<StructLogExecuted, CallError> {
+		unimplemented!("TestClient does not implement call_with_struct_logger()")
+	}
+
 	fn estimate_gas(&self, _t: &SignedTransaction, _state: &Self::State, _header: &Header) -> Result<U256, CallError> {
 		Ok(21000.into())
 	}
+
+	fn apply_state_override(&self, _state: &mut Self::State, _overrides: &StateOverride) -> Result<(), CallError> {
+		Ok(())
+	}
 }
 
 /// NewType wrapper around `()` to impersonate `State` in trait impls. State will not be used by
@@ -677,6 +694,14 @@ impl BlockChainClient for TestBlockChainClient {
 		self.execution_result.read().clone().unwrap()
 	}
 
+	fn replay_with_struct_logger(&self, _id: TransactionId, _config: StructLoggerConfig) -> Result<StructLogExecuted, CallError> {
+		unimplemented!("TestClient does not implement replay_with_struct_logger()")
+	}
+
+	fn replay_block_transactions_with_struct_logger(&self, _block: BlockId, _config: StructLoggerConfig) -> Result<Box<dyn Iterator<Item = (H256, StructLogExecuted)>>, CallError> {
+		unimplemented!("TestClient does not implement replay_block_transactions_with_struct_logger()")
+	}
+
 	fn queue_info(&self) -> BlockQueueInfo {
 		BlockQueueInfo {
 			verified_queue_size: self.queue_size.load(AtomicOrder::Relaxed),
@@ -798,7 +823,7 @@ impl BlockChainClient for TestBlockChainClient {
 
 	fn block_extra_info(&self, id: BlockId) -> Option<BTreeMap<String, String>> {
 		self.block(id)
-			.map(|block| block.view().header())
+			.map(|block| block.view().header(self.eip1559_transition()))
 			.map(|header| self.spec.engine.extra_info(&header))
 	}
 
@@ -888,10 +913,22 @@ impl BlockChainClient for TestBlockChainClient {
 		self.traces.read().clone()
 	}
 
+	fn block_rewards(&self, _id: BlockId) -> Option<Vec<BlockReward>> {
+		self.rewards.read().clone()
+	}
+
+	fn dry_run_block_rewards(&self, _id: BlockId, _contract: Address, _beneficiaries: Vec<(Address, RewardKind)>) -> Result<Vec<BlockReward>, CallError> {
+		self.rewards.read().clone().ok_or(CallError::StatePruned)
+	}
+
 	fn transactions_to_propagate(&self) -> Vec<Arc<VerifiedTransaction>> {
 		self.miner.ready_transactions(self, 4096, miner::PendingOrdering::Priority)
 	}
 
+	fn queued_transaction(&self, hash: &H256) -> Option<Arc<VerifiedTransaction>> {
+		self.miner.transaction(hash)
+	}
+
 	fn signing_chain_id(&self) -> Option<u64> { None }
 
 	fn mode(&self) -> Mode { Mode::Active }
@@ -986,6 +1023,10 @@ impl client_traits::EngineClient for TestBlockChainClient {
 		None
 	}
 
+	fn verify_unsealed_block(&self, _block: Unverified) -> EthcoreResult<()> {
+		Ok(())
+	}
+
 	fn as_full_client(&self) -> Option<&dyn BlockChainClient> { Some(self) }
 
 	fn block_number(&self, id: BlockId) -> Option<BlockNumber> {
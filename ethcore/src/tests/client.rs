@@ -24,6 +24,7 @@ use hash::keccak;
 use io::IoChannel;
 use tempdir::TempDir;
 use types::{
+	block_reward::{BlockReward, RewardKind},
 	data_format::DataFormat,
 	ids::BlockId,
 	transaction::{PendingTransaction, Transaction, Action, Condition},
@@ -45,9 +46,10 @@ use miner::{Miner, PendingOrdering, MinerService};
 use account_state::{State, CleanupMode, backend};
 use test_helpers::{
 	self,
-	generate_dummy_client, push_blocks_to_client, get_test_client_with_blocks, get_good_dummy_block_seq,
+	generate_dummy_client, generate_dummy_client_with_spec, push_blocks_to_client, get_test_client_with_blocks, get_good_dummy_block_seq,
 	generate_dummy_client_with_data, get_good_dummy_block, get_bad_state_dummy_block
 };
+use rlp::Rlp;
 use rustc_hex::ToHex;
 use registrar::RegistrarClient;
 
@@ -105,7 +107,7 @@ fn imports_good_block() {
 		IoChannel::disconnected(),
 	).unwrap();
 	let good_block = get_good_dummy_block();
-	if client.import_block(Unverified::from_rlp(good_block).unwrap()).is_err() {
+	if client.import_block(Unverified::from_rlp(good_block, client.eip1559_transition()).unwrap()).is_err() {
 		panic!("error importing block being good by definition");
 	}
 	client.flush_queue();
@@ -144,7 +146,7 @@ fn returns_chain_info() {
 	let client = get_test_client_with_blocks(vec![dummy_block.clone()]);
 	let block = view!(BlockView, &dummy_block);
 	let info = client.chain_info();
-	assert_eq!(info.best_block_hash, block.header().hash());
+	assert_eq!(info.best_block_hash, block.header_view().hash());
 }
 
 #[test]
@@ -180,7 +182,7 @@ fn returns_block_body() {
 	let dummy_block = get_good_dummy_block();
 	let client = get_test_client_with_blocks(vec![dummy_block.clone()]);
 	let block = view!(BlockView, &dummy_block);
-	let body = client.block_body(BlockId::Hash(block.header().hash())).unwrap();
+	let body = client.block_body(BlockId::Hash(block.header_view().hash())).unwrap();
 	let body = body.rlp();
 	assert_eq!(body.item_count().unwrap(), 2);
 	assert_eq!(body.at(0).unwrap().as_raw()[..], block.rlp().at(1).as_raw()[..]);
@@ -468,3 +470,115 @@ fn import_export_binary() {
 	assert!(client.block_header(BlockId::Number(17)).is_some());
 	assert!(client.block_header(BlockId::Number(16)).is_some());
 }
+
+#[test]
+fn import_export_binary_with_receipts() {
+	let client = get_test_client_with_blocks(get_good_dummy_block_seq(19));
+
+	let mut out = Vec::new();
+
+	client.export_blocks(
+		Box::new(&mut out),
+		BlockId::Number(15),
+		BlockId::Number(20),
+		Some(DataFormat::BinaryWithReceipts)
+	).unwrap();
+
+	let first = Rlp::new(&out);
+	let first_hash = client.block_hash(BlockId::Number(15)).unwrap();
+	assert_eq!(first.at(0).unwrap().as_raw(), client.block(BlockId::Number(15)).unwrap().raw());
+	assert_eq!(first.at(1).unwrap().as_raw(), &::rlp::encode(&client.block_receipts(&first_hash).unwrap())[..]);
+
+	assert!(client.reset(5).is_ok());
+	client.chain().clear_cache();
+
+	assert!(client.block_header(BlockId::Number(20)).is_none());
+	assert!(client.block_header(BlockId::Number(16)).is_none());
+
+	client.import_blocks(Box::new(&*out), Some(DataFormat::BinaryWithReceipts)).unwrap();
+
+	assert!(client.block_header(BlockId::Number(20)).is_some());
+	assert!(client.block_header(BlockId::Number(19)).is_some());
+	assert!(client.block_header(BlockId::Number(18)).is_some());
+	assert!(client.block_header(BlockId::Number(17)).is_some());
+	assert!(client.block_header(BlockId::Number(16)).is_some());
+}
+
+#[test]
+fn import_with_receipts_executes_new_blocks() {
+	let source = get_test_client_with_blocks(get_good_dummy_block_seq(19));
+
+	let mut out = Vec::new();
+	source.export_blocks(
+		Box::new(&mut out),
+		BlockId::Number(1),
+		BlockId::Number(19),
+		Some(DataFormat::BinaryWithReceipts)
+	).unwrap();
+
+	let client = get_test_client_with_blocks(Vec::new());
+	// the format is detected from the stream.
+	client.import_blocks(Box::new(&*out), None).unwrap();
+
+	// without a restored snapshot there is no gap to fill, so the blocks are executed.
+	assert_eq!(client.chain_info().best_block_number, 19);
+	assert_eq!(client.chain_info().best_block_hash, source.chain_info().best_block_hash);
+	for number in 1..20 {
+		let hash = source.block_hash(BlockId::Number(number)).unwrap();
+		assert_eq!(client.block_hash(BlockId::Number(number)), Some(hash));
+		assert_eq!(
+			::rlp::encode(&client.block_receipts(&hash).unwrap()),
+			::rlp::encode(&source.block_receipts(&hash).unwrap()),
+		);
+	}
+}
+
+#[test]
+fn import_json_lines() {
+	let source = get_test_client_with_blocks(get_good_dummy_block_seq(5));
+
+	let mut out = Vec::new();
+	source.export_blocks(
+		Box::new(&mut out),
+		BlockId::Number(1),
+		BlockId::Number(5),
+		Some(DataFormat::BinaryWithReceipts)
+	).unwrap();
+
+	let mut lines = String::new();
+	let mut rest = &out[..];
+	while !rest.is_empty() {
+		let entry = Rlp::new(rest).as_raw();
+		lines.push_str(&format!("{{\"number\":\"0x0\",\"raw\":\"0x{}\"}}\n", entry.to_hex()));
+		rest = &rest[entry.len()..];
+	}
+
+	let client = get_test_client_with_blocks(Vec::new());
+	client.import_blocks(Box::new(lines.as_bytes()), None).unwrap();
+
+	assert_eq!(client.chain_info().best_block_number, 5);
+	assert_eq!(client.block_hash(BlockId::Number(5)), source.block_hash(BlockId::Number(5)));
+}
+
+#[test]
+fn dry_run_block_rewards() {
+	let client = generate_dummy_client_with_spec(spec::new_test_round_block_reward_contract);
+	// the spec has a block reward contract which rewards (1000 + kind) for each beneficiary.
+	let contract = Address::from_low_u64_be(0x42);
+	let author = Address::from_low_u64_be(0x33);
+	let uncle = Address::from_low_u64_be(0x34);
+
+	let rewards = client.dry_run_block_rewards(
+		BlockId::Latest,
+		contract,
+		vec![(author, RewardKind::Author), (uncle, RewardKind::Uncle(1))],
+	).unwrap();
+
+	assert_eq!(rewards, vec![
+		BlockReward { address: author, kind: RewardKind::External, amount: U256::from(1000) },
+		BlockReward { address: uncle, kind: RewardKind::External, amount: U256::from(1000 + 101) },
+	]);
+	// nothing is paid out.
+	assert_eq!(client.state().balance(&author).unwrap(), U256::zero());
+	assert!(client.dry_run_block_rewards(BlockId::Latest, contract, vec![]).unwrap().is_empty());
+}
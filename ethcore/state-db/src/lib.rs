@@ -21,10 +21,11 @@ use std::io;
 use std::sync::Arc;
 
 use ethereum_types::{Address, H256};
+use ethtrie::{Result as TrieResult, TrieError};
 use hash_db::HashDB;
 use keccak_hash::keccak;
 use kvdb::{DBTransaction, DBValue, KeyValueDB};
-use log::trace;
+use log::{debug, trace};
 use lru_cache::LruCache;
 use parking_lot::Mutex;
 
@@ -91,6 +92,23 @@ struct BlockChanges {
 	is_canon: bool,
 }
 
+/// Source of state which is not held in the local database yet.
+///
+/// Implementors write the trie nodes (and code) required to look up the requested item
+/// into the backing database of the `StateDB` they are attached to, so that the ordinary
+/// trie lookup which follows the call succeeds.
+pub trait StateFetcher: Send + Sync {
+	/// Fetch the account at `address`.
+	fn fetch_account(&self, address: &Address) -> Result<(), String>;
+
+	/// Fetch the value stored under `key` by the account at `address`.
+	fn fetch_storage(&self, address: &Address, key: &H256) -> Result<(), String>;
+
+	/// Fetch the trie node `hash`, held by the storage trie of `address` or, if `None`, by the
+	/// account trie.
+	fn fetch_node(&self, address: Option<&Address>, hash: &H256) -> Result<(), String>;
+}
+
 /// State database abstraction.
 /// Manages shared global state cache which reflects the canonical
 /// state as it is on the disk. All the entries in the cache are clean.
@@ -124,6 +142,8 @@ pub struct StateDB {
 	commit_hash: Option<H256>,
 	/// Number of the committing block or `None` if not committed yet.
 	commit_number: Option<BlockNumber>,
+	/// Fetcher for state missing from the backing database, if any.
+	fetcher: Option<Arc<dyn StateFetcher>>,
 }
 
 impl Clone for StateDB {
@@ -156,9 +176,17 @@ impl StateDB {
 			parent_hash: None,
 			commit_hash: None,
 			commit_number: None,
+			fetcher: None,
 		}
 	}
 
+	/// Attach a fetcher for state missing from the backing database. The fetcher is shared
+	/// with all clones created afterwards and disables the account bloom, since accounts
+	/// which were not fetched yet are not noted there.
+	pub fn set_fetcher(&mut self, fetcher: Option<Arc<dyn StateFetcher>>) {
+		self.fetcher = fetcher;
+	}
+
 	/// Loads accounts bloom from the database
 	/// This bloom is used to handle request for the non-existent account fast
 	pub fn load_bloom(db: &dyn KeyValueDB) -> Bloom {
@@ -341,6 +369,7 @@ impl StateDB {
 			parent_hash: None,
 			commit_hash: None,
 			commit_number: None,
+			fetcher: self.fetcher.clone(),
 		}
 	}
 
@@ -356,6 +385,7 @@ impl StateDB {
 			parent_hash: Some(parent.clone()),
 			commit_hash: None,
 			commit_number: None,
+			fetcher: self.fetcher.clone(),
 		}
 	}
 
@@ -469,11 +499,48 @@ impl account_state::Backend for StateDB {
 	}
 
 	fn is_known_null(&self, address: &Address) -> bool {
+		if self.fetcher.is_some() {
+			return false;
+		}
 		trace!(target: "account_bloom", "Check account bloom: {:?}", address);
 		let bloom = self.account_bloom.lock();
 		let is_null = !bloom.check(keccak(address).as_bytes());
 		is_null
 	}
+
+	fn prefetch_account(&self, address: &Address) -> TrieResult<()> {
+		match self.fetcher {
+			Some(ref fetcher) => fetcher.fetch_account(address).map_err(|e| {
+				debug!(target: "state_db", "Failed to fetch account {:?}: {}", address, e);
+				Box::new(TrieError::IncompleteDatabase(keccak(address)))
+			}),
+			None => Ok(()),
+		}
+	}
+
+	fn prefetch_storage(&self, address: &Address, key: &H256) -> TrieResult<()> {
+		match self.fetcher {
+			Some(ref fetcher) => fetcher.fetch_storage(address, key).map_err(|e| {
+				debug!(target: "state_db", "Failed to fetch storage {:?} of account {:?}: {}", key, address, e);
+				Box::new(TrieError::IncompleteDatabase(keccak(key)))
+			}),
+			None => Ok(()),
+		}
+	}
+
+	fn fetches_nodes(&self) -> bool {
+		self.fetcher.is_some()
+	}
+
+	fn prefetch_node(&self, address: Option<&Address>, hash: &H256) -> TrieResult<()> {
+		match self.fetcher {
+			Some(ref fetcher) => fetcher.fetch_node(address, hash).map_err(|e| {
+				debug!(target: "state_db", "Failed to fetch trie node {:?}: {}", hash, e);
+				Box::new(TrieError::IncompleteDatabase(*hash))
+			}),
+			None => Err(Box::new(TrieError::IncompleteDatabase(*hash))),
+		}
+	}
 }
 
 /// Sync wrapper for the account.
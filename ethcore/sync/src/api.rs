@@ -15,7 +15,7 @@
 // along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
 
 use std::sync::{Arc, mpsc, atomic};
-use std::collections::{HashMap, BTreeMap};
+use std::collections::{HashMap, BTreeMap, BTreeSet};
 use std::io;
 use std::ops::RangeInclusive;
 use std::time::Duration;
@@ -29,7 +29,7 @@ use crate::private_tx::PrivateTxHandler;
 use crate::chain::{
 	sync_packet::SyncPacket::{PrivateTransactionPacket, SignedPrivateTransactionPacket},
 	ChainSyncApi, SyncState, SyncStatus as EthSyncStatus, ETH_PROTOCOL_VERSION_62,
-	ETH_PROTOCOL_VERSION_63, PAR_PROTOCOL_VERSION_1, PAR_PROTOCOL_VERSION_2,
+	ETH_PROTOCOL_VERSION_63, ETH_PROTOCOL_VERSION_64, ETH_PROTOCOL_VERSION_65, PAR_PROTOCOL_VERSION_1, PAR_PROTOCOL_VERSION_2,
 	PAR_PROTOCOL_VERSION_3, PAR_PROTOCOL_VERSION_4,
 };
 
@@ -58,6 +58,7 @@ use network::{
 };
 use snapshot::SnapshotService;
 use parking_lot::{RwLock, Mutex};
+use stats::{PrometheusMetrics, PrometheusRegistry};
 use parity_runtime::Executor;
 use trace_time::trace_time;
 use common_types::{
@@ -279,6 +280,8 @@ pub struct Params {
 	pub provider: Arc<dyn (::light::Provider)>,
 	/// Network layer configuration.
 	pub network_config: NetworkConfiguration,
+	/// Hard fork block numbers of the chain, for the fork identifier.
+	pub forks: BTreeSet<BlockNumber>,
 }
 
 /// Ethereum network protocol handler
@@ -353,6 +356,7 @@ impl EthSync {
 		let sync = ChainSyncApi::new(
 			params.config,
 			&*params.chain,
+			params.forks,
 			params.private_tx_handler.as_ref().cloned(),
 			priority_tasks_rx,
 		);
@@ -456,6 +460,24 @@ impl SyncProvider for EthSync {
 	}
 }
 
+impl PrometheusMetrics for EthSync {
+	fn prometheus_metrics(&self, r: &mut PrometheusRegistry) {
+		let status = self.status();
+		let peers_range = self.num_peers_range();
+
+		r.register_gauge("sync_peers", "Connected peers", status.num_peers as i64);
+		r.register_gauge("sync_active_peers", "Peers participating in the sync", status.num_active_peers as i64);
+		r.register_gauge("sync_min_peers", "Configured minimum number of peers", *peers_range.start() as i64);
+		r.register_gauge("sync_max_peers", "Configured maximum number of peers", *peers_range.end() as i64);
+		r.register_gauge("sync_major_syncing", "Whether a major sync is in progress", self.is_major_syncing() as i64);
+		r.register_gauge("sync_highest_block", "Highest block number seen in the download queue", status.highest_block_number.unwrap_or(0) as i64);
+		r.register_counter("sync_blocks_received", "Blocks downloaded since the sync started", status.blocks_received as i64);
+		r.register_gauge("sync_mem_used", "Heap memory used by the sync in bytes", status.mem_used as i64);
+		r.register_gauge("sync_snapshot_chunks", "Chunks in the snapshot being downloaded", status.num_snapshot_chunks as i64);
+		r.register_gauge("sync_snapshot_chunks_done", "Snapshot chunks downloaded", status.snapshot_chunks_done as i64);
+	}
+}
+
 const PEERS_TIMER: TimerToken = 0;
 const MAINTAIN_SYNC_TIMER: TimerToken = 1;
 const CONTINUE_SYNC_TIMER: TimerToken = 2;
@@ -606,7 +628,7 @@ impl ChainNotify for EthSync {
 			_ => {},
 		}
 
-		self.network.register_protocol(self.eth_handler.clone(), self.subprotocol_name, &[ETH_PROTOCOL_VERSION_62, ETH_PROTOCOL_VERSION_63])
+		self.network.register_protocol(self.eth_handler.clone(), self.subprotocol_name, &[ETH_PROTOCOL_VERSION_62, ETH_PROTOCOL_VERSION_63, ETH_PROTOCOL_VERSION_64, ETH_PROTOCOL_VERSION_65])
 			.unwrap_or_else(|e| warn!("Error registering ethereum protocol: {:?}", e));
 		// register the warp sync subprotocol
 		self.network.register_protocol(self.eth_handler.clone(), WARP_SYNC_PROTOCOL_ID, &[PAR_PROTOCOL_VERSION_1, PAR_PROTOCOL_VERSION_2, PAR_PROTOCOL_VERSION_3, PAR_PROTOCOL_VERSION_4])
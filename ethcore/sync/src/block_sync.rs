@@ -263,7 +263,7 @@ impl BlockDownloader {
 		let mut hashes = Vec::new();
 		let mut last_header = None;
 		for i in 0..item_count {
-			let info = SyncHeader::from_rlp(r.at(i)?.as_raw().to_vec())?;
+			let info = SyncHeader::from_rlp(r.at(i)?.as_raw().to_vec(), io.chain().eip1559_transition())?;
 			let number = BlockNumber::from(info.header.number());
 			let hash = info.header.hash();
 
@@ -365,7 +365,7 @@ impl BlockDownloader {
 	}
 
 	/// Called by peer once it has new block bodies
-	pub fn import_bodies(&mut self, r: &Rlp, expected_hashes: &[H256]) -> Result<(), BlockDownloaderImportError> {
+	pub fn import_bodies(&mut self, r: &Rlp, expected_hashes: &[H256], eip1559_transition: BlockNumber) -> Result<(), BlockDownloaderImportError> {
 		let item_count = r.item_count().unwrap_or(0);
 		if item_count == 0 {
 			return Err(BlockDownloaderImportError::Useless);
@@ -374,7 +374,7 @@ impl BlockDownloader {
 		} else {
 			let mut bodies = Vec::with_capacity(item_count);
 			for i in 0..item_count {
-				let body = SyncBody::from_rlp(r.at(i)?.as_raw())?;
+				let body = SyncBody::from_rlp(r.at(i)?.as_raw(), eip1559_transition)?;
 				bodies.push(body);
 			}
 
@@ -655,6 +655,7 @@ mod tests {
 	use rlp::{encode_list, RlpStream};
 	use triehash_ethereum::ordered_trie_root;
 	use common_types::{
+		BlockNumber,
 		transaction::{Transaction, SignedTransaction},
 		header::Header as BlockHeader,
 	};
@@ -861,19 +862,19 @@ mod tests {
 		let mut rlp_data = RlpStream::new_list(1);
 		rlp_data.append_raw(&bodies[0], 1);
 		let bodies_rlp = Rlp::new(rlp_data.as_raw());
-		assert!(downloader.import_bodies(&bodies_rlp, &[headers[0].hash(), headers[1].hash()]).is_ok());
+		assert!(downloader.import_bodies(&bodies_rlp, &[headers[0].hash(), headers[1].hash()], BlockNumber::max_value()).is_ok());
 
 		// Import second body successfully.
 		let mut rlp_data = RlpStream::new_list(1);
 		rlp_data.append_raw(&bodies[1], 1);
 		let bodies_rlp = Rlp::new(rlp_data.as_raw());
-		assert!(downloader.import_bodies(&bodies_rlp, &[headers[0].hash(), headers[1].hash()]).is_ok());
+		assert!(downloader.import_bodies(&bodies_rlp, &[headers[0].hash(), headers[1].hash()], BlockNumber::max_value()).is_ok());
 
 		// Import unexpected third body.
 		let mut rlp_data = RlpStream::new_list(1);
 		rlp_data.append_raw(&bodies[2], 1);
 		let bodies_rlp = Rlp::new(rlp_data.as_raw());
-		match downloader.import_bodies(&bodies_rlp, &[headers[0].hash(), headers[1].hash()]) {
+		match downloader.import_bodies(&bodies_rlp, &[headers[0].hash(), headers[1].hash()], BlockNumber::max_value()) {
 			Err(BlockDownloaderImportError::Invalid) => (),
 			_ => panic!("expected BlockDownloaderImportError"),
 		};
@@ -932,7 +933,7 @@ mod tests {
 		let mut rlp_data = RlpStream::new_list(1);
 		rlp_data.append_raw(&receipts[3], 1);
 		let bodies_rlp = Rlp::new(rlp_data.as_raw());
-		match downloader.import_bodies(&bodies_rlp, &[headers[1].hash(), headers[2].hash()]) {
+		match downloader.import_bodies(&bodies_rlp, &[headers[1].hash(), headers[2].hash()], BlockNumber::max_value()) {
 			Err(BlockDownloaderImportError::Invalid) => (),
 			_ => panic!("expected BlockDownloaderImportError"),
 		};
@@ -0,0 +1,180 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Parity Ethereum.
+
+// Parity Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Fork identifier of EIP-2124, sent in the Status message from eth/64 on, and the filter
+//! checking the identifiers of peers against our own to reject peers on incompatible forks.
+
+use std::collections::BTreeSet;
+
+use common_types::BlockNumber;
+use ethereum_types::H256;
+use rlp::{Decodable, DecoderError, Encodable, Rlp, RlpStream};
+
+/// Reversed polynomial of the IEEE CRC32 checksum.
+const CRC32_POLYNOMIAL: u32 = 0xedb8_8320;
+
+/// Continues the IEEE CRC32 checksum `crc` of some data with `data`.
+fn crc32(crc: u32, data: &[u8]) -> u32 {
+	let mut crc = !crc;
+	for byte in data {
+		crc ^= u32::from(*byte);
+		for _ in 0..8 {
+			crc = if crc & 1 == 1 { (crc >> 1) ^ CRC32_POLYNOMIAL } else { crc >> 1 };
+		}
+	}
+	!crc
+}
+
+/// Identifies the chain and the forks a node has passed, and the fork it expects next.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ForkId {
+	/// CRC32 checksum of the genesis hash and the block numbers of the passed forks.
+	pub hash: u32,
+	/// Block number of the next fork, or 0 if no fork is scheduled.
+	pub next: BlockNumber,
+}
+
+impl Encodable for ForkId {
+	fn rlp_append(&self, s: &mut RlpStream) {
+		s.begin_list(2);
+		s.append(&self.hash.to_be_bytes().to_vec());
+		s.append(&self.next);
+	}
+}
+
+impl Decodable for ForkId {
+	fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+		if rlp.item_count()? != 2 {
+			return Err(DecoderError::RlpIncorrectListLen);
+		}
+		let hash: Vec<u8> = rlp.val_at(0)?;
+		if hash.len() != 4 {
+			return Err(DecoderError::RlpInvalidLength);
+		}
+		let mut bytes = [0u8; 4];
+		bytes.copy_from_slice(&hash);
+		Ok(ForkId {
+			hash: u32::from_be_bytes(bytes),
+			next: rlp.val_at(1)?,
+		})
+	}
+}
+
+/// Computes our fork identifier at a given head, and validates the ones of peers.
+#[derive(Debug)]
+pub struct ForkFilter {
+	/// Block numbers of the forks, in ascending order.
+	forks: Vec<BlockNumber>,
+	/// Fork hashes: the first one covers the genesis hash only, and each next one one more fork.
+	hashes: Vec<u32>,
+}
+
+impl ForkFilter {
+	/// Creates the filter for the chain with the given genesis hash and forks.
+	pub fn new(genesis_hash: H256, forks: BTreeSet<BlockNumber>) -> Self {
+		let forks: Vec<_> = forks.into_iter().filter(|&fork| fork != 0).collect();
+		let mut hashes = vec![crc32(0, genesis_hash.as_bytes())];
+		for fork in &forks {
+			let hash = crc32(hashes[hashes.len() - 1], &fork.to_be_bytes());
+			hashes.push(hash);
+		}
+		ForkFilter { forks, hashes }
+	}
+
+	/// Number of forks passed at `head`.
+	fn passed(&self, head: BlockNumber) -> usize {
+		self.forks.iter().take_while(|&&fork| fork <= head).count()
+	}
+
+	/// Our fork identifier at `head`.
+	pub fn current(&self, head: BlockNumber) -> ForkId {
+		let passed = self.passed(head);
+		ForkId {
+			hash: self.hashes[passed],
+			next: self.forks.get(passed).cloned().unwrap_or(0),
+		}
+	}
+
+	/// Whether a peer announcing `remote` may be on the same chain as us, at `head`.
+	pub fn is_compatible(&self, head: BlockNumber, remote: ForkId) -> bool {
+		let passed = self.passed(head);
+		if remote.hash == self.hashes[passed] {
+			// Same forks passed: the peer must not expect a fork we already passed without it.
+			return remote.next == 0 || head < remote.next;
+		}
+		if let Some(index) = self.hashes[..passed].iter().position(|&hash| hash == remote.hash) {
+			// The peer is behind us: the fork it expects next must be the one we passed.
+			return remote.next == self.forks[index];
+		}
+		// We are behind the peer: it must have passed forks we know of.
+		self.hashes[passed + 1..].contains(&remote.hash)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::str::FromStr;
+
+	use ethereum_types::H256;
+
+	use super::{ForkFilter, ForkId};
+
+	fn mainnet() -> ForkFilter {
+		let genesis = H256::from_str("d4e56740f876aef8c010b86a40d5f56745a118d0906a34e69aec8c0db1cb8fa3").unwrap();
+		let forks = [1_150_000, 1_920_000, 2_463_000, 2_675_000, 4_370_000, 7_280_000, 9_069_000, 9_200_000];
+		ForkFilter::new(genesis, forks.iter().cloned().collect())
+	}
+
+	#[test]
+	fn computes_mainnet_fork_ids() {
+		let filter = mainnet();
+		assert_eq!(filter.current(0), ForkId { hash: 0xfc64ec04, next: 1_150_000 });
+		assert_eq!(filter.current(1_149_999), ForkId { hash: 0xfc64ec04, next: 1_150_000 });
+		assert_eq!(filter.current(1_150_000), ForkId { hash: 0x97c2c34c, next: 1_920_000 });
+		assert_eq!(filter.current(2_463_000), ForkId { hash: 0x7a64da13, next: 2_675_000 });
+		assert_eq!(filter.current(4_370_000), ForkId { hash: 0xa00bc324, next: 7_280_000 });
+		assert_eq!(filter.current(7_987_396), ForkId { hash: 0x668db0af, next: 9_069_000 });
+		assert_eq!(filter.current(9_069_000), ForkId { hash: 0x879d6e30, next: 9_200_000 });
+		assert_eq!(filter.current(10_000_000), ForkId { hash: 0xe029e991, next: 0 });
+	}
+
+	#[test]
+	fn validates_remote_fork_ids() {
+		let filter = mainnet();
+		// same forks, same next fork.
+		assert!(filter.is_compatible(7_987_396, ForkId { hash: 0x668db0af, next: 9_069_000 }));
+		// same forks, no next fork known remotely.
+		assert!(filter.is_compatible(7_987_396, ForkId { hash: 0x668db0af, next: 0 }));
+		// the peer is syncing, and expects the fork we passed.
+		assert!(filter.is_compatible(7_987_396, ForkId { hash: 0xa00bc324, next: 7_280_000 }));
+		// the peer is syncing, but expects another fork.
+		assert!(!filter.is_compatible(7_987_396, ForkId { hash: 0xa00bc324, next: 7_279_999 }));
+		// we are syncing, and the peer passed forks we know of.
+		assert!(filter.is_compatible(7_279_999, ForkId { hash: 0x668db0af, next: 9_069_000 }));
+		// the peer passed a fork we don't know of.
+		assert!(!filter.is_compatible(7_987_396, ForkId { hash: 0x5cddc0e1, next: 0 }));
+		// the peer expects a fork we passed without it.
+		assert!(!filter.is_compatible(88_888_888, ForkId { hash: 0xe029e991, next: 88_888_888 }));
+	}
+
+	#[test]
+	fn fork_id_rlp_roundtrip() {
+		let fork_id = ForkId { hash: 0xe029e991, next: 0 };
+		let encoded = rlp::encode(&fork_id);
+		assert_eq!(encoded, vec![0xc6, 0x84, 0xe0, 0x29, 0xe9, 0x91, 0x80]);
+		assert_eq!(rlp::decode::<ForkId>(&encoded), Ok(fork_id));
+	}
+}
@@ -27,13 +27,15 @@ use crate::{
 			PacketInfo,
 			SyncPacket::{
 				self, BlockBodiesPacket, BlockHeadersPacket, NewBlockHashesPacket, NewBlockPacket,
-				PrivateStatePacket, PrivateTransactionPacket, ReceiptsPacket, SignedPrivateTransactionPacket,
-				SnapshotDataPacket, SnapshotManifestPacket, StatusPacket,
+				NewPooledTransactionHashesPacket, PrivateStatePacket, PrivateTransactionPacket, ReceiptsPacket,
+				SignedPrivateTransactionPacket, SnapshotDataPacket, SnapshotManifestPacket, StatusPacket,
 			}
 		},
+		fork_filter::ForkId,
 		BlockSet, ChainSync, ForkConfirmation, PacketDecodeError, PeerAsking, PeerInfo, SyncRequester,
-		SyncState, ETH_PROTOCOL_VERSION_62, ETH_PROTOCOL_VERSION_63, MAX_NEW_BLOCK_AGE, MAX_NEW_HASHES,
-		PAR_PROTOCOL_VERSION_1, PAR_PROTOCOL_VERSION_3, PAR_PROTOCOL_VERSION_4,
+		SyncState, ETH_PROTOCOL_VERSION_62, ETH_PROTOCOL_VERSION_64, ETH_PROTOCOL_VERSION_65, MAX_NEW_BLOCK_AGE,
+		MAX_NEW_HASHES, MAX_TRANSACTIONS_TO_REQUEST, PAR_PROTOCOL_VERSION_1, PAR_PROTOCOL_VERSION_3,
+		PAR_PROTOCOL_VERSION_4,
 	}
 };
 
@@ -70,6 +72,7 @@ impl SyncHandler {
 				ReceiptsPacket => SyncHandler::on_peer_block_receipts(sync, io, peer, &rlp),
 				NewBlockPacket => SyncHandler::on_peer_new_block(sync, io, peer, &rlp),
 				NewBlockHashesPacket => SyncHandler::on_peer_new_hashes(sync, io, peer, &rlp),
+				NewPooledTransactionHashesPacket => SyncHandler::on_peer_new_pooled_transaction_hashes(sync, io, peer, &rlp),
 				SnapshotManifestPacket => SyncHandler::on_snapshot_manifest(sync, io, peer, &rlp),
 				SnapshotDataPacket => SyncHandler::on_snapshot_data(sync, io, peer, &rlp),
 				PrivateTransactionPacket => SyncHandler::on_private_transaction(sync, io, peer, &rlp),
@@ -155,7 +158,7 @@ impl SyncHandler {
 				peer.difficulty = Some(difficulty);
 			}
 		}
-		let block = Unverified::from_rlp(r.at(0)?.as_raw().to_vec())?;
+		let block = Unverified::from_rlp(r.at(0)?.as_raw().to_vec(), io.chain().eip1559_transition())?;
 		let hash = block.header.hash();
 		let number = block.header.number();
 		trace!(target: "sync", "{} -> NewBlock ({})", peer_id, hash);
@@ -316,7 +319,7 @@ impl SyncHandler {
 						Some(ref mut blocks) => blocks,
 					}
 				};
-				downloader.import_bodies(r, expected_blocks.as_slice())?;
+				downloader.import_bodies(r, expected_blocks.as_slice(), io.chain().eip1559_transition())?;
 			}
 			sync.collect_blocks(io, block_set);
 			Ok(())
@@ -564,9 +567,13 @@ impl SyncHandler {
 	fn on_peer_status(sync: &mut ChainSync, io: &mut dyn SyncIo, peer_id: PeerId, r: &Rlp) -> Result<(), DownloaderImportError> {
 		sync.handshaking_peers.remove(&peer_id);
 		let protocol_version: u8 = r.val_at(0)?;
+		let eth_protocol_version = io.eth_protocol_version(peer_id);
 		let warp_protocol_version = io.protocol_version(&WARP_SYNC_PROTOCOL_ID, peer_id);
 		let warp_protocol = warp_protocol_version != 0;
 		let private_tx_protocol = warp_protocol_version >= PAR_PROTOCOL_VERSION_3.0;
+		// From eth/64 on, the fork identifier follows the genesis hash, before the warp sync fields.
+		let fork_id: Option<ForkId> = if eth_protocol_version >= ETH_PROTOCOL_VERSION_64.0 { Some(r.val_at(5)?) } else { None };
+		let warp_index = if fork_id.is_some() { 6 } else { 5 };
 		let peer = PeerInfo {
 			protocol_version,
 			network_id: r.val_at(1)?,
@@ -583,10 +590,10 @@ impl SyncHandler {
 			expired: false,
 			confirmation: if sync.fork_block.is_none() { ForkConfirmation::Confirmed } else { ForkConfirmation::Unconfirmed },
 			asking_snapshot_data: None,
-			snapshot_hash: if warp_protocol { Some(r.val_at(5)?) } else { None },
-			snapshot_number: if warp_protocol { Some(r.val_at(6)?) } else { None },
+			snapshot_hash: if warp_protocol { Some(r.val_at(warp_index)?) } else { None },
+			snapshot_number: if warp_protocol { Some(r.val_at(warp_index + 1)?) } else { None },
 			block_set: None,
-			private_tx_enabled: if private_tx_protocol { r.val_at(7).unwrap_or(false) } else { false },
+			private_tx_enabled: if private_tx_protocol { r.val_at(warp_index + 2).unwrap_or(false) } else { false },
 			client_version: ClientVersion::from(io.peer_version(peer_id)),
 		};
 
@@ -627,10 +634,16 @@ impl SyncHandler {
 			trace!(target: "sync", "Peer {} network id mismatch (ours: {}, theirs: {})", peer_id, sync.network_id, peer.network_id);
 			return Err(DownloaderImportError::Invalid);
 		}
+		if let Some(fork_id) = fork_id {
+			if !sync.fork_filter.is_compatible(chain_info.best_block_number, fork_id) {
+				trace!(target: "sync", "Peer {} fork id mismatch (ours: {:?}, theirs: {:?})", peer_id, sync.fork_filter.current(chain_info.best_block_number), fork_id);
+				return Err(DownloaderImportError::Invalid);
+			}
+		}
 
 		if false
 			|| (warp_protocol && (peer.protocol_version < PAR_PROTOCOL_VERSION_1.0 || peer.protocol_version > PAR_PROTOCOL_VERSION_4.0))
-			|| (!warp_protocol && (peer.protocol_version < ETH_PROTOCOL_VERSION_62.0 || peer.protocol_version > ETH_PROTOCOL_VERSION_63.0))
+			|| (!warp_protocol && (peer.protocol_version < ETH_PROTOCOL_VERSION_62.0 || peer.protocol_version > ETH_PROTOCOL_VERSION_65.0))
 		{
 			trace!(target: "sync", "Peer {} unsupported eth protocol ({})", peer_id, peer.protocol_version);
 			return Err(DownloaderImportError::Invalid);
@@ -677,6 +690,43 @@ impl SyncHandler {
 		Ok(())
 	}
 
+	/// Called when peer announces new transactions
+	fn on_peer_new_pooled_transaction_hashes(sync: &mut ChainSync, io: &mut dyn SyncIo, peer_id: PeerId, r: &Rlp) -> Result<(), DownloaderImportError> {
+		// Accept transactions only when fully synced
+		if !io.is_chain_queue_empty() || (sync.state != SyncState::Idle && sync.state != SyncState::NewBlocks) {
+			trace!(target: "sync", "{} Ignoring transaction announcements while syncing", peer_id);
+			return Ok(());
+		}
+		if !sync.peers.get(&peer_id).map_or(false, |p| p.can_sync()) {
+			trace!(target: "sync", "{} Ignoring transaction announcements from unconfirmed/unknown peer", peer_id);
+			return Ok(());
+		}
+
+		let hashes: Vec<H256> = r.as_list()?;
+		trace!(target: "sync", "{:02} -> NewPooledTransactionHashes ({} entries)", peer_id, hashes.len());
+		if let Some(peer) = sync.peers.get_mut(&peer_id) {
+			// the peer knows these, don't send them back.
+			peer.last_sent_transactions.extend(hashes.iter().cloned());
+		}
+
+		let now = Instant::now();
+		let mut to_request = Vec::new();
+		for hash in hashes {
+			if to_request.len() == MAX_TRANSACTIONS_TO_REQUEST {
+				break;
+			}
+			if sync.requested_transactions.contains_key(&hash) || io.chain().queued_transaction(&hash).is_some() {
+				continue;
+			}
+			sync.requested_transactions.insert(hash, now);
+			to_request.push(hash);
+		}
+		if !to_request.is_empty() {
+			SyncRequester::request_pooled_transactions(io, peer_id, &to_request);
+		}
+		Ok(())
+	}
+
 	/// Called when peer sends us signed private transaction packet
 	fn on_signed_private_transaction(sync: &mut ChainSync, _io: &mut dyn SyncIo, peer_id: PeerId, r: &Rlp) -> Result<(), DownloaderImportError> {
 		if !sync.peers.get(&peer_id).map_or(false, |p| p.can_sync()) {
@@ -799,8 +849,35 @@ mod tests {
 
 	use client_traits::ChainInfo;
 	use ethcore::test_helpers::{EachBlockWith, TestBlockChainClient};
+	use ethereum_types::H256;
 	use parking_lot::RwLock;
-	use rlp::Rlp;
+	use rlp::{Rlp, RlpStream};
+
+	#[test]
+	fn requests_announced_transactions_once() {
+		let mut client = TestBlockChainClient::new();
+		client.add_blocks(10, EachBlockWith::Uncle);
+		let queued = client.insert_transaction_to_queue();
+		let queue = RwLock::new(VecDeque::new());
+		let mut sync = dummy_sync_with_peer(client.block_hash_delta_minus(5), &client);
+		let ss = TestSnapshotService::new();
+		let mut io = TestIo::new(&mut client, &ss, &queue, None, None);
+
+		let unknown = H256::from_low_u64_be(1);
+		let mut announcement = RlpStream::new_list(2);
+		announcement.append(&queued);
+		announcement.append(&unknown);
+		let announcement = announcement.out();
+
+		SyncHandler::on_peer_new_pooled_transaction_hashes(&mut sync, &mut io, 0, &Rlp::new(&announcement)).unwrap();
+		// the same announcement doesn't trigger another request
+		SyncHandler::on_peer_new_pooled_transaction_hashes(&mut sync, &mut io, 0, &Rlp::new(&announcement)).unwrap();
+
+		assert_eq!(1, io.packets.len());
+		// GET_POOLED_TRANSACTIONS_PACKET
+		assert_eq!(0x09, io.packets[0].packet_id);
+		assert_eq!(vec![unknown], Rlp::new(&io.packets[0].data).as_list::<H256>().unwrap());
+	}
 
 	#[test]
 	fn handles_peer_new_hashes() {
@@ -16,7 +16,7 @@
 
 //! `BlockChain` synchronization strategy.
 //! Syncs to peers and keeps up to date.
-//! This implementation uses ethereum protocol v63
+//! This implementation uses ethereum protocol v62 to v65
 //!
 //! Syncing strategy summary.
 //! Split the chain into ranges of N blocks each. Download ranges sequentially. Split each range into subchains of M blocks. Download subchains in parallel.
@@ -87,6 +87,7 @@
 //!
 //! All other messages are ignored.
 
+mod fork_filter;
 mod handler;
 mod propagator;
 mod requester;
@@ -95,7 +96,7 @@ mod supplier;
 pub mod sync_packet;
 
 use std::sync::{Arc, mpsc};
-use std::collections::{HashSet, HashMap, BTreeMap};
+use std::collections::{HashSet, HashMap, BTreeMap, BTreeSet};
 use std::cmp;
 use std::time::{Duration, Instant};
 
@@ -132,6 +133,7 @@ use common_types::{
 	snapshot::RestorationStatus,
 };
 
+use self::fork_filter::ForkFilter;
 use self::handler::SyncHandler;
 use self::sync_packet::{PacketInfo, SyncPacket};
 use self::sync_packet::SyncPacket::{
@@ -147,6 +149,10 @@ malloc_size_of_is_0!(PeerInfo);
 
 pub type PacketDecodeError = DecoderError;
 
+/// 65 version of Ethereum protocol (transaction announcements added).
+pub const ETH_PROTOCOL_VERSION_65: (u8, u8) = (65, 0x11);
+/// 64 version of Ethereum protocol (fork identifier added to the status).
+pub const ETH_PROTOCOL_VERSION_64: (u8, u8) = (64, 0x11);
 /// 63 version of Ethereum protocol.
 pub const ETH_PROTOCOL_VERSION_63: (u8, u8) = (63, 0x11);
 /// 62 version of Ethereum protocol.
@@ -169,6 +175,12 @@ const MAX_NODE_DATA_TOTAL_DURATION: Duration = Duration::from_secs(2);
 /// Maximum allowed duration for serving a single GetNodeData request.
 const MAX_NODE_DATA_SINGLE_DURATION: Duration = Duration::from_millis(100);
 pub const MAX_RECEIPTS_HEADERS_TO_SEND: usize = 256;
+/// Maximum number of pooled transactions to include in a PooledTransactions response.
+pub const MAX_POOLED_TRANSACTIONS_TO_SEND: usize = 256;
+/// Maximum number of transaction hashes to include in a NewPooledTransactionHashes announcement.
+const MAX_TRANSACTION_HASHES_TO_ANNOUNCE: usize = 4096;
+/// Maximum number of transactions to ask for in a GetPooledTransactions request.
+const MAX_TRANSACTIONS_TO_REQUEST: usize = 256;
 const MIN_PEERS_PROPAGATION: usize = 4;
 const MAX_PEERS_PROPAGATION: usize = 128;
 const MAX_PEER_LAG_PROPAGATION: BlockNumber = 20;
@@ -203,6 +215,8 @@ const FORK_HEADER_TIMEOUT: Duration = Duration::from_secs(3);
 const SNAPSHOT_MANIFEST_TIMEOUT: Duration = Duration::from_secs(5);
 const SNAPSHOT_DATA_TIMEOUT: Duration = Duration::from_secs(120);
 const PRIVATE_STATE_TIMEOUT: Duration = Duration::from_secs(120);
+/// Time after which an announced transaction we asked for but didn't receive may be asked for again.
+const POOLED_TRANSACTIONS_TIMEOUT: Duration = Duration::from_secs(10);
 
 /// Defines how much time we have to complete priority transaction or block propagation.
 /// after the deadline is reached the task is considered finished
@@ -427,11 +441,12 @@ impl ChainSyncApi {
 	pub fn new(
 		config: SyncConfig,
 		chain: &dyn BlockChainClient,
+		forks: BTreeSet<BlockNumber>,
 		private_tx_handler: Option<Arc<dyn PrivateTxHandler>>,
 		priority_tasks: mpsc::Receiver<PriorityTask>,
 	) -> Self {
 		ChainSyncApi {
-			sync: RwLock::new(ChainSync::new(config, chain, private_tx_handler)),
+			sync: RwLock::new(ChainSync::new(config, chain, forks, private_tx_handler)),
 			priority_tasks: Mutex::new(priority_tasks),
 		}
 	}
@@ -662,6 +677,9 @@ pub struct ChainSync {
 	network_id: u64,
 	/// Optional fork block to check
 	fork_block: Option<(BlockNumber, H256)>,
+	/// Fork identifier filter of eth/64 peers.
+	#[ignore_malloc_size_of = "fork blocks, ignoring"]
+	fork_filter: ForkFilter,
 	/// Snapshot downloader.
 	snapshot: Snapshot,
 	/// Connected peers pending Status message.
@@ -671,6 +689,9 @@ pub struct ChainSync {
 	sync_start_time: Option<Instant>,
 	/// Transactions propagation statistics
 	transactions_stats: TransactionsStats,
+	/// Announced transactions asked for from peers, and when.
+	#[ignore_malloc_size_of = "Instant does not implement MallocSizeOf, ignoring"]
+	requested_transactions: H256FastMap<Instant>,
 	/// Enable ancient block downloading
 	download_old_blocks: bool,
 	/// Shared private tx service.
@@ -688,6 +709,7 @@ impl ChainSync {
 	pub fn new(
 		config: SyncConfig,
 		chain: &dyn BlockChainClient,
+		forks: BTreeSet<BlockNumber>,
 		private_tx_handler: Option<Arc<dyn PrivateTxHandler>>,
 	) -> Self {
 		let chain_info = chain.chain_info();
@@ -706,10 +728,12 @@ impl ChainSync {
 			last_sent_block_number: 0,
 			network_id: config.network_id,
 			fork_block: config.fork_block,
+			fork_filter: ForkFilter::new(chain_info.genesis_hash, forks),
 			download_old_blocks: config.download_old_blocks,
 			snapshot: Snapshot::new(),
 			sync_start_time: None,
 			transactions_stats: TransactionsStats::default(),
+			requested_transactions: Default::default(),
 			private_tx_handler,
 			warp_sync: config.warp_sync,
 			status_sinks: Vec::new()
@@ -723,7 +747,7 @@ impl ChainSync {
 		let last_imported_number = self.new_blocks.last_imported_block_number();
 		SyncStatus {
 			state: self.state.clone(),
-			protocol_version: ETH_PROTOCOL_VERSION_63.0,
+			protocol_version: ETH_PROTOCOL_VERSION_65.0,
 			network_id: self.network_id,
 			start_block_number: self.starting_block,
 			last_imported_block_number: Some(last_imported_number),
@@ -1240,10 +1264,11 @@ impl ChainSync {
 
 	/// Send Status message
 	fn send_status(&mut self, io: &mut dyn SyncIo, peer: PeerId) -> Result<(), network::Error> {
+		let eth_protocol_version = io.eth_protocol_version(peer);
 		let warp_protocol_version = io.protocol_version(&WARP_SYNC_PROTOCOL_ID, peer);
 		let warp_protocol = warp_protocol_version != 0;
 		let private_tx_protocol = warp_protocol_version >= PAR_PROTOCOL_VERSION_3.0;
-		let protocol = if warp_protocol { warp_protocol_version } else { ETH_PROTOCOL_VERSION_63.0 };
+		let protocol = if warp_protocol { warp_protocol_version } else { eth_protocol_version };
 		trace!(target: "sync", "Sending status to {}, protocol version {}", peer, protocol);
 		let mut packet = RlpStream::new();
 		packet.begin_unbounded_list();
@@ -1253,6 +1278,9 @@ impl ChainSync {
 		packet.append(&chain.total_difficulty);
 		packet.append(&chain.best_block_hash);
 		packet.append(&chain.genesis_hash);
+		if eth_protocol_version >= ETH_PROTOCOL_VERSION_64.0 {
+			packet.append(&self.fork_filter.current(chain.best_block_number));
+		}
 		if warp_protocol {
 			let manifest = io.snapshot_service().manifest();
 			let block_number = manifest.as_ref().map_or(0, |m| m.block_number);
@@ -1295,6 +1323,8 @@ impl ChainSync {
 			SyncHandler::on_peer_aborting(self, io, p);
 		}
 
+		self.requested_transactions.retain(|_, &mut ask_time| tick - ask_time < POOLED_TRANSACTIONS_TIMEOUT);
+
 		// Check for handshake timeouts
 		for (peer, &ask_time) in &self.handshaking_peers {
 			let elapsed = (tick - ask_time) / 1_000_000_000;
@@ -1490,7 +1520,7 @@ impl ChainSync {
 
 #[cfg(test)]
 pub mod tests {
-	use std::{collections::VecDeque, time::Instant};
+	use std::{collections::{BTreeSet, VecDeque}, time::Instant};
 
 	use super::{
 		BlockId, BlockQueueInfo, ChainSync, ClientVersion, PeerInfo, PeerAsking,
@@ -1595,7 +1625,7 @@ pub mod tests {
 
 	pub fn dummy_sync_with_peer(peer_latest_hash: H256, client: &dyn BlockChainClient) -> ChainSync {
 
-		let mut sync = ChainSync::new(SyncConfig::default(), client, None,);
+		let mut sync = ChainSync::new(SyncConfig::default(), client, BTreeSet::new(), None);
 		insert_dummy_peer(&mut sync, 0, peer_latest_hash);
 		sync
 	}
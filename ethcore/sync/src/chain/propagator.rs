@@ -32,6 +32,7 @@ use common_types::{blockchain_info::BlockChainInfo, transaction::SignedTransacti
 use super::sync_packet::SyncPacket::{
 	NewBlockHashesPacket,
 	TransactionsPacket,
+	NewPooledTransactionHashesPacket,
 	NewBlockPacket,
 	ConsensusDataPacket,
 };
@@ -43,6 +44,8 @@ use super::{
 	MAX_PEER_LAG_PROPAGATION,
 	MAX_PEERS_PROPAGATION,
 	MIN_PEERS_PROPAGATION,
+	MAX_TRANSACTION_HASHES_TO_ANNOUNCE,
+	ETH_PROTOCOL_VERSION_65,
 };
 
 /// The Chain Sync Propagator: propagates data to peers
@@ -178,6 +181,35 @@ impl SyncPropagator {
 			let peer_info = sync.peers.get_mut(&peer_id)
 				.expect("peer_id is form peers; peers is result of select_peers_for_transactions; select_peers_for_transactions selects peers from self.peers; qed");
 
+			// Only announce the hashes to eth/65 peers, they request the transactions they miss
+			if io.eth_protocol_version(peer_id) >= ETH_PROTOCOL_VERSION_65.0 {
+				let to_send = all_transactions_hashes.difference(&peer_info.last_sent_transactions)
+					.take(MAX_TRANSACTION_HASHES_TO_ANNOUNCE)
+					.cloned()
+					.collect::<H256FastSet>();
+				if to_send.is_empty() {
+					continue;
+				}
+
+				let id = io.peer_session_info(peer_id).and_then(|info| info.id);
+				let mut packet = RlpStream::new_list(to_send.len());
+				for hash in &to_send {
+					stats.propagated(hash, id, block_number);
+					packet.append(hash);
+				}
+
+				peer_info.last_sent_transactions = all_transactions_hashes
+					.intersection(&peer_info.last_sent_transactions)
+					.chain(&to_send)
+					.cloned()
+					.collect();
+				SyncPropagator::send_packet(io, peer_id, NewPooledTransactionHashesPacket, packet.out());
+				trace!(target: "sync", "{:02} <- NewPooledTransactionHashes ({} entries)", peer_id, to_send.len());
+				sent_to_peers.insert(peer_id);
+				max_sent = cmp::max(max_sent, to_send.len());
+				continue;
+			}
+
 			// Send all transactions, if the peer doesn't know about anything
 			if peer_info.last_sent_transactions.is_empty() {
 				// update stats
@@ -334,7 +366,7 @@ impl SyncPropagator {
 
 #[cfg(test)]
 mod tests {
-	use std::{collections::VecDeque, time::Instant};
+	use std::{collections::{BTreeSet, VecDeque}, time::Instant};
 
 	use crate::{
 		api::SyncConfig,
@@ -344,7 +376,7 @@ mod tests {
 
 	use super::{
 		super::tests::{dummy_sync_with_peer, insert_dummy_peer},
-		SyncPropagator,
+		SyncPropagator, ETH_PROTOCOL_VERSION_65,
 	};
 
 	use client_traits::{BlockChainClient, BlockInfo, ChainInfo};
@@ -423,7 +455,7 @@ mod tests {
 		client.add_blocks(2, EachBlockWith::Uncle);
 		let queue = RwLock::new(VecDeque::new());
 		let block = client.block(BlockId::Latest).unwrap().into_inner();
-		let mut sync = ChainSync::new(SyncConfig::default(), &client, None);
+		let mut sync = ChainSync::new(SyncConfig::default(), &client, BTreeSet::new(), None);
 		sync.peers.insert(0,
 			PeerInfo {
 				// Messaging protocol
@@ -485,6 +517,27 @@ mod tests {
 		assert_eq!(0x02, io.packets[0].packet_id);
 	}
 
+	#[test]
+	fn announces_transactions_to_eth65_peers() {
+		let mut client = TestBlockChainClient::new();
+		client.add_blocks(100, EachBlockWith::Uncle);
+		let hash = client.insert_transaction_to_queue();
+		let mut sync = dummy_sync_with_peer(client.block_hash_delta_minus(1), &client);
+		let queue = RwLock::new(VecDeque::new());
+		let ss = TestSnapshotService::new();
+		let mut io = TestIo::new(&mut client, &ss, &queue, None, None);
+		io.eth_protocol_version = ETH_PROTOCOL_VERSION_65.0;
+		let peer_count = SyncPropagator::propagate_new_transactions(&mut sync, &mut io, || true);
+		let peer_count2 = SyncPropagator::propagate_new_transactions(&mut sync, &mut io, || true);
+
+		assert_eq!(1, peer_count);
+		assert_eq!(0, peer_count2);
+		assert_eq!(1, io.packets.len());
+		// NEW_POOLED_TRANSACTION_HASHES_PACKET
+		assert_eq!(0x08, io.packets[0].packet_id);
+		assert_eq!(vec![hash], Rlp::new(&io.packets[0].data).as_list::<H256>().unwrap());
+	}
+
 	#[test]
 	fn does_not_propagate_new_transactions_after_new_block() {
 		let mut client = TestBlockChainClient::new();
@@ -514,7 +567,7 @@ mod tests {
 		client.add_blocks(100, EachBlockWith::Uncle);
 		client.insert_transaction_to_queue();
 		// Sync with no peers
-		let mut sync = ChainSync::new(SyncConfig::default(), &client, None);
+		let mut sync = ChainSync::new(SyncConfig::default(), &client, BTreeSet::new(), None);
 		let queue = RwLock::new(VecDeque::new());
 		let ss = TestSnapshotService::new();
 		let mut io = TestIo::new(&mut client, &ss, &queue, None, None);
@@ -584,7 +637,7 @@ mod tests {
 		let mut client = TestBlockChainClient::new();
 		client.insert_transaction_with_gas_price_to_queue(U256::zero());
 		let block_hash = client.block_hash_delta_minus(1);
-		let mut sync = ChainSync::new(SyncConfig::default(), &client, None);
+		let mut sync = ChainSync::new(SyncConfig::default(), &client, BTreeSet::new(), None);
 		let queue = RwLock::new(VecDeque::new());
 		let ss = TestSnapshotService::new();
 		let mut io = TestIo::new(&mut client, &ss, &queue, None, None);
@@ -614,7 +667,7 @@ mod tests {
 		let tx1_hash = client.insert_transaction_to_queue();
 		let tx2_hash = client.insert_transaction_with_gas_price_to_queue(U256::zero());
 		let block_hash = client.block_hash_delta_minus(1);
-		let mut sync = ChainSync::new(SyncConfig::default(), &client, None);
+		let mut sync = ChainSync::new(SyncConfig::default(), &client, BTreeSet::new(), None);
 		let queue = RwLock::new(VecDeque::new());
 		let ss = TestSnapshotService::new();
 		let mut io = TestIo::new(&mut client, &ss, &queue, None, None);
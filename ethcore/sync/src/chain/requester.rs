@@ -36,6 +36,7 @@ use super::sync_packet::SyncPacket::{
 	GetSnapshotManifestPacket,
 	GetSnapshotDataPacket,
 	GetPrivateStatePacket,
+	GetPooledTransactionsPacket,
 };
 
 use super::{
@@ -148,6 +149,20 @@ impl SyncRequester {
 		SyncRequester::send_request(sync, io, peer_id, PeerAsking::SnapshotData, GetSnapshotDataPacket, rlp.out());
 	}
 
+	/// Request announced transactions from a peer. This doesn't make the peer busy, so that the
+	/// request can be made alongside block downloads.
+	pub fn request_pooled_transactions(io: &mut dyn SyncIo, peer_id: PeerId, hashes: &[H256]) {
+		trace!(target: "sync", "{} <- GetPooledTransactions: {} entries", peer_id, hashes.len());
+		let mut rlp = RlpStream::new_list(hashes.len());
+		for h in hashes {
+			rlp.append(h);
+		}
+		if let Err(e) = io.send(peer_id, GetPooledTransactionsPacket, rlp.out()) {
+			debug!(target:"sync", "Error sending request: {:?}", e);
+			io.disconnect_peer(peer_id);
+		}
+	}
+
 	/// Generic request sender
 	fn send_request(sync: &mut ChainSync, io: &mut dyn SyncIo, peer_id: PeerId, asking: PeerAsking, packet_id: SyncPacket, packet: Bytes) {
 		if let Some(ref mut peer) = sync.peers.get_mut(&peer_id) {
@@ -47,6 +47,8 @@ use super::sync_packet::SyncPacket::{
 	ConsensusDataPacket,
 	GetPrivateStatePacket,
 	PrivateStatePacket,
+	GetPooledTransactionsPacket,
+	PooledTransactionsPacket,
 };
 
 use super::{
@@ -60,6 +62,7 @@ use super::{
 	MAX_NODE_DATA_TOTAL_DURATION,
 	MAX_NODE_DATA_SINGLE_DURATION,
 	MAX_RECEIPTS_HEADERS_TO_SEND,
+	MAX_POOLED_TRANSACTIONS_TO_SEND,
 };
 
 /// The Chain Sync Supplier: answers requests from peers with available data
@@ -109,6 +112,11 @@ impl SyncSupplier {
 					SyncSupplier::return_private_state,
 					|e| format!("Error sending private state data: {:?}", e)),
 
+				GetPooledTransactionsPacket => SyncSupplier::return_rlp(
+					io, &rlp, peer,
+					SyncSupplier::return_pooled_transactions,
+					|e| format!("Error sending pooled transactions: {:?}", e)),
+
 				StatusPacket => {
 					sync.write().on_packet(io, peer, packet_id, data);
 					Ok(())
@@ -125,7 +133,7 @@ impl SyncSupplier {
 						ConsensusDataPacket => {
 							SyncHandler::on_consensus_packet(io, peer, &rlp)
 						},
-						TransactionsPacket => {
+						TransactionsPacket | PooledTransactionsPacket => {
 							let res = {
 								let sync_ro = sync.read();
 								SyncHandler::on_peer_transactions(&*sync_ro, io, peer, &rlp)
@@ -390,6 +398,31 @@ impl SyncSupplier {
 		})
 	}
 
+	/// Respond to GetPooledTransactions request
+	fn return_pooled_transactions(io: &dyn SyncIo, r: &Rlp, peer_id: PeerId) -> RlpResponseResult {
+		let payload_soft_limit = io.payload_soft_limit();
+		let count = cmp::min(r.item_count().unwrap_or(0), MAX_POOLED_TRANSACTIONS_TO_SEND);
+		if count == 0 {
+			debug!(target: "sync", "Empty GetPooledTransactions request, ignoring.");
+			return Ok(None);
+		}
+		let mut added = 0usize;
+		let mut data = Bytes::new();
+		for i in 0..count {
+			if let Some(tx) = io.chain().queued_transaction(&r.val_at::<H256>(i)?) {
+				data.append(&mut rlp::encode(tx.signed()));
+				added += 1;
+				if data.len() > payload_soft_limit {
+					break;
+				}
+			}
+		}
+		let mut rlp = RlpStream::new_list(added);
+		rlp.append_raw(&data, added);
+		trace!(target: "sync", "{} -> GetPooledTransactions: returned {} entries", peer_id, added);
+		Ok(Some((PooledTransactionsPacket.id(), rlp)))
+	}
+
 	fn return_rlp<FRlp, FError>(io: &mut dyn SyncIo, rlp: &Rlp, peer: PeerId, rlp_func: FRlp, error_func: FError) -> Result<(), PacketDecodeError>
 		where FRlp : Fn(&dyn SyncIo, &Rlp, PeerId) -> RlpResponseResult,
 			FError : FnOnce(network::Error) -> String
@@ -418,7 +451,7 @@ mod test {
 	};
 
 	use super::{
-		SyncPacket::{GetReceiptsPacket, GetNodeDataPacket},
+		SyncPacket::{GetReceiptsPacket, GetNodeDataPacket, GetPooledTransactionsPacket},
 		BlockNumber, BlockId, SyncSupplier, PacketInfo
 	};
 
@@ -452,14 +485,14 @@ mod test {
 		}
 
 		fn to_header_vec(rlp: RlpResponseResult) -> Vec<SyncHeader> {
-			Rlp::new(&rlp.unwrap().unwrap().1.out()).iter().map(|r| SyncHeader::from_rlp(r.as_raw().to_vec()).unwrap()).collect()
+			Rlp::new(&rlp.unwrap().unwrap().1.out()).iter().map(|r| SyncHeader::from_rlp(r.as_raw().to_vec(), BlockNumber::max_value()).unwrap()).collect()
 		}
 
 		let mut client = TestBlockChainClient::new();
 		client.add_blocks(100, EachBlockWith::Nothing);
 		let blocks: Vec<_> = (0 .. 100)
 			.map(|i| (&client as &dyn BlockChainClient).block(BlockId::Number(i as BlockNumber)).map(|b| b.into_inner()).unwrap()).collect();
-		let headers: Vec<_> = blocks.iter().map(|b| SyncHeader::from_rlp(Rlp::new(b).at(0).unwrap().as_raw().to_vec()).unwrap()).collect();
+		let headers: Vec<_> = blocks.iter().map(|b| SyncHeader::from_rlp(Rlp::new(b).at(0).unwrap().as_raw().to_vec(), BlockNumber::max_value()).unwrap()).collect();
 		let hashes: Vec<_> = headers.iter().map(|h| h.header.hash()).collect();
 
 		let queue = RwLock::new(VecDeque::new());
@@ -606,4 +639,27 @@ mod test {
 		SyncSupplier::dispatch_packet(&RwLock::new(sync), &mut io, 0usize, GetReceiptsPacket.id(), &receipts_request);
 		assert_eq!(1, io.packets.len());
 	}
+
+	#[test]
+	fn return_pooled_transactions() {
+		let mut client = TestBlockChainClient::new();
+		let queue = RwLock::new(VecDeque::new());
+		let sync = dummy_sync_with_peer(H256::zero(), &client);
+		let queued = client.insert_transaction_to_queue();
+		let ss = TestSnapshotService::new();
+		let mut io = TestIo::new(&mut client, &ss, &queue, None, None);
+
+		let mut request = RlpStream::new_list(2);
+		request.append(&queued);
+		request.append(&H256::from_low_u64_be(1));
+		let request = request.out();
+
+		// only the queued transaction is returned
+		let result = SyncSupplier::return_pooled_transactions(&io, &Rlp::new(&request), 0).unwrap().unwrap();
+		assert_eq!(Rlp::new(&result.1.out()).item_count(), Ok(1));
+
+		io.sender = Some(2usize);
+		SyncSupplier::dispatch_packet(&RwLock::new(sync), &mut io, 0usize, GetPooledTransactionsPacket.id(), &request);
+		assert_eq!(1, io.packets.len());
+	}
 }
@@ -14,7 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
 
-use std::collections::{VecDeque, HashSet, HashMap};
+use std::collections::{BTreeSet, VecDeque, HashSet, HashMap};
 use std::sync::Arc;
 
 use crate::{
@@ -74,6 +74,7 @@ pub struct TestIo<'p, C> where C: FlushingBlockChainClient, C: 'p {
 	pub packets: Vec<TestPacket>,
 	pub peers_info: HashMap<PeerId, String>,
 	pub private_state_db: Option<Arc<PrivateStateDB>>,
+	pub eth_protocol_version: u8,
 	overlay: RwLock<HashMap<BlockNumber, Bytes>>,
 }
 
@@ -94,6 +95,7 @@ impl<'p, C> TestIo<'p, C> where C: FlushingBlockChainClient, C: 'p {
 			packets: Vec::new(),
 			peers_info: HashMap::new(),
 			private_state_db,
+			eth_protocol_version: ETH_PROTOCOL_VERSION_63.0,
 			overlay: RwLock::new(HashMap::new()),
 		}
 	}
@@ -156,7 +158,7 @@ impl<'p, C> SyncIo for TestIo<'p, C> where C: FlushingBlockChainClient, C: 'p {
 	}
 
 	fn eth_protocol_version(&self, _peer: PeerId) -> u8 {
-		ETH_PROTOCOL_VERSION_63.0
+		self.eth_protocol_version
 	}
 
 	fn protocol_version(&self, protocol: &ProtocolId, peer_id: PeerId) -> u8 {
@@ -384,7 +386,7 @@ impl TestNet<EthPeer<TestBlockChainClient>> {
 			let chain = TestBlockChainClient::new();
 			let ss = Arc::new(TestSnapshotService::new());
 			let private_tx_handler = Arc::new(SimplePrivateTxHandler::default());
-			let sync = ChainSync::new(config.clone(), &chain, Some(private_tx_handler.clone()));
+			let sync = ChainSync::new(config.clone(), &chain, BTreeSet::new(), Some(private_tx_handler.clone()));
 			net.peers.push(Arc::new(EthPeer {
 				sync: RwLock::new(sync),
 				snapshot_service: ss,
@@ -438,7 +440,7 @@ impl TestNet<EthPeer<EthcoreClient>> {
 
 		let private_tx_handler = Arc::new(SimplePrivateTxHandler::default());
 		let ss = Arc::new(TestSnapshotService::new());
-		let sync = ChainSync::new(config, &*client, Some(private_tx_handler.clone()));
+		let sync = ChainSync::new(config, &*client, BTreeSet::new(), Some(private_tx_handler.clone()));
 		let peer = Arc::new(EthPeer {
 			sync: RwLock::new(sync),
 			snapshot_service: ss,
@@ -27,6 +27,7 @@ mod db;
 mod executive_tracer;
 mod import;
 mod noop_tracer;
+mod struct_logger;
 mod types;
 
 pub use crate::{
@@ -36,6 +37,7 @@ pub use crate::{
 	executive_tracer::{ExecutiveTracer, ExecutiveVMTracer},
 	import::ImportRequest,
 	noop_tracer::{NoopTracer, NoopVMTracer},
+	struct_logger::{StructLog, StructLogger, StructLoggerConfig},
 	types::{
 		Tracing,
 		error::Error as TraceError,
@@ -0,0 +1,213 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Parity Ethereum.
+
+// Parity Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Geth-compatible struct logger.
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+
+use ethereum_types::U256;
+use evm::Instruction;
+use crate::VMTracer;
+
+/// Struct logger options.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct StructLoggerConfig {
+	/// Don't capture the stack.
+	pub disable_stack: bool,
+	/// Don't capture the memory.
+	pub disable_memory: bool,
+	/// Don't capture the storage.
+	pub disable_storage: bool,
+}
+
+/// A single executed instruction, as reported by Geth's struct logger.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StructLog {
+	/// Program counter.
+	pub pc: usize,
+	/// The instruction.
+	pub instruction: u8,
+	/// Gas left before the instruction.
+	pub gas: U256,
+	/// Gas cost of the instruction.
+	pub gas_cost: U256,
+	/// Call depth, starting at 1.
+	pub depth: usize,
+	/// Stack before the instruction, bottom first.
+	pub stack: Option<Vec<U256>>,
+	/// Memory before the instruction.
+	pub memory: Option<Vec<u8>>,
+	/// Storage of the executing contract known so far. Only captured for `SLOAD` and `SSTORE`.
+	pub storage: Option<BTreeMap<U256, U256>>,
+}
+
+impl StructLog {
+	/// Mnemonic of the instruction.
+	pub fn op_name(&self) -> Cow<'static, str> {
+		match Instruction::from_u8(self.instruction) {
+			Some(instruction) => Cow::Borrowed(instruction.info().name),
+			None => Cow::Owned(format!("opcode {:#04x} not defined", self.instruction)),
+		}
+	}
+}
+
+#[derive(Default)]
+struct Frame {
+	stack: Vec<U256>,
+	memory: Vec<u8>,
+	storage: BTreeMap<U256, U256>,
+	gas: U256,
+	// instruction being executed, the memory it writes and the index of its log
+	executing: Option<(u8, Option<(usize, usize)>, usize)>,
+}
+
+/// VM tracer emitting Geth's `structLogs`.
+///
+/// The interpreter only reports the items pushed by every instruction, so the stack is rebuilt
+/// using the number of arguments of each instruction. Storage is tracked per call frame.
+pub struct StructLogger {
+	config: StructLoggerConfig,
+	logs: Vec<StructLog>,
+	frames: Vec<Frame>,
+}
+
+impl StructLogger {
+	/// Create a new struct logger.
+	pub fn new(config: StructLoggerConfig) -> Self {
+		StructLogger {
+			config,
+			logs: Vec::new(),
+			frames: Vec::new(),
+		}
+	}
+
+	fn frame(&mut self) -> &mut Frame {
+		self.frames.last_mut().expect("prepare_subtrace is called before executing any code; qed")
+	}
+}
+
+impl VMTracer for StructLogger {
+	type Output = Vec<StructLog>;
+
+	fn trace_next_instruction(&mut self, _pc: usize, _instruction: u8, current_gas: U256) -> bool {
+		self.frame().gas = current_gas;
+		true
+	}
+
+	fn trace_prepare_execute(&mut self, pc: usize, instruction: u8, gas_cost: U256, mem_written: Option<(usize, usize)>, store_written: Option<(U256, U256)>) {
+		let config = self.config;
+		let index = self.logs.len();
+		let depth = self.frames.len();
+		let frame = self.frame();
+
+		if let Some((key, value)) = store_written {
+			frame.storage.insert(key, value);
+		}
+		let captures_storage = match Instruction::from_u8(instruction) {
+			Some(Instruction::SLOAD) | Some(Instruction::SSTORE) => !config.disable_storage,
+			_ => false,
+		};
+
+		let log = StructLog {
+			pc,
+			instruction,
+			gas: frame.gas,
+			gas_cost,
+			depth,
+			stack: if config.disable_stack { None } else { Some(frame.stack.clone()) },
+			memory: if config.disable_memory { None } else { Some(frame.memory.clone()) },
+			storage: if captures_storage { Some(frame.storage.clone()) } else { None },
+		};
+		frame.executing = Some((instruction, mem_written, index));
+		self.logs.push(log);
+	}
+
+	fn trace_failed(&mut self) {
+		self.frame().executing = None;
+	}
+
+	fn trace_executed(&mut self, _gas_used: U256, stack_push: &[U256], mem: &[u8]) {
+		let config = self.config;
+		let frame = self.frames.last_mut().expect("prepare_subtrace is called before executing any code; qed");
+		let (instruction, mem_written, index) = frame.executing.take().expect("trace_executed is always called after a trace_prepare_execute; qed");
+		let instruction = Instruction::from_u8(instruction);
+
+		if let (Some(Instruction::SLOAD), Some(&key), Some(&value)) = (instruction, frame.stack.last(), stack_push.first()) {
+			frame.storage.insert(key, value);
+			if let Some(ref mut storage) = self.logs[index].storage {
+				storage.insert(key, value);
+			}
+		}
+
+		let args = instruction.map_or(0, |i| i.info().args);
+		let len = frame.stack.len().saturating_sub(args);
+		frame.stack.truncate(len);
+		frame.stack.extend_from_slice(stack_push);
+
+		if !config.disable_memory && (mem_written.is_some() || mem.len() != frame.memory.len()) {
+			frame.memory = mem.to_vec();
+		}
+	}
+
+	fn prepare_subtrace(&mut self, _code: &[u8]) {
+		self.frames.push(Frame::default());
+	}
+
+	fn done_subtrace(&mut self) {
+		self.frames.pop();
+	}
+
+	fn drain(self) -> Option<Vec<StructLog>> {
+		Some(self.logs)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn rebuilds_stack_and_storage() {
+		let mut logger = StructLogger::new(StructLoggerConfig { disable_memory: true, ..Default::default() });
+
+		logger.prepare_subtrace(&[]);
+		// PUSH1 0x2a
+		logger.trace_next_instruction(0, 0x60, 100.into());
+		logger.trace_prepare_execute(0, 0x60, 3.into(), None, None);
+		logger.trace_executed(97.into(), &[42.into()], &[]);
+		// PUSH1 0x01
+		logger.trace_next_instruction(2, 0x60, 97.into());
+		logger.trace_prepare_execute(2, 0x60, 3.into(), None, None);
+		logger.trace_executed(94.into(), &[1.into()], &[]);
+		// SSTORE
+		logger.trace_next_instruction(4, 0x55, 94.into());
+		logger.trace_prepare_execute(4, 0x55, 20.into(), None, Some((1.into(), 42.into())));
+		logger.trace_executed(74.into(), &[], &[]);
+		logger.done_subtrace();
+
+		let logs = logger.drain().unwrap();
+		assert_eq!(logs.len(), 3);
+		assert_eq!(logs[1].stack, Some(vec![42.into()]));
+		assert_eq!(logs[2].op_name(), "SSTORE");
+		assert_eq!(logs[2].gas, 94.into());
+		assert_eq!(logs[2].depth, 1);
+		assert_eq!(logs[2].stack, Some(vec![42.into(), 1.into()]));
+		assert_eq!(logs[2].memory, None);
+		assert_eq!(logs[2].storage, Some(vec![(1.into(), 42.into())].into_iter().collect()));
+		assert_eq!(logs[0].storage, None);
+	}
+}
@@ -49,10 +49,9 @@ impl Block {
 		block_rlp.append_list(&self.uncles);
 		block_rlp.out()
 	}
-}
 
-impl Decodable for Block {
-	fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+	/// Decode a block whose headers carry a base fee from block `eip1559_transition` on.
+	pub fn decode_rlp(rlp: &Rlp, eip1559_transition: BlockNumber) -> Result<Self, DecoderError> {
 		if rlp.as_raw().len() != rlp.payload_info()?.total() {
 			return Err(DecoderError::RlpIsTooBig);
 		}
@@ -60,13 +59,20 @@ impl Decodable for Block {
 			return Err(DecoderError::RlpIncorrectListLen);
 		}
 		Ok(Block {
-			header: rlp.val_at(0)?,
+			header: Header::decode_rlp(&rlp.at(0)?, eip1559_transition)?,
 			transactions: rlp.list_at(1)?,
-			uncles: rlp.list_at(2)?,
+			uncles: Header::decode_rlp_list(&rlp.at(2)?, eip1559_transition)?,
 		})
 	}
 }
 
+/// Decodes a block without a base fee, see `Header`'s `Decodable` implementation.
+impl Decodable for Block {
+	fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+		Block::decode_rlp(rlp, BlockNumber::max_value())
+	}
+}
+
 /// Preprocessed block data gathered in `verify_block_unordered` call
 #[derive(MallocSizeOf)]
 pub struct PreverifiedBlock {
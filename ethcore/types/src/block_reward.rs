@@ -0,0 +1,117 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Parity Ethereum.
+
+// Parity Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Block rewards paid when closing a block.
+
+use ethereum_types::{Address, U256};
+use rlp::{Encodable, Decodable, DecoderError, RlpStream, Rlp};
+
+use BlockNumber;
+
+/// The kind of block reward.
+/// Depending on the consensus engine the allocated block reward might have
+/// different semantics which could lead e.g. to different reward values.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum RewardKind {
+	/// Reward attributed to the block author.
+	Author,
+	/// Reward attributed to the author(s) of empty step(s) included in the block (AuthorityRound engine).
+	EmptyStep,
+	/// Reward attributed by an external protocol (e.g. block reward contract).
+	External,
+	/// Reward attributed to the block uncle(s) with given difference.
+	Uncle(u8),
+}
+
+impl RewardKind {
+	/// Create `RewardKind::Uncle` from given current block number and uncle block number.
+	pub fn uncle(number: BlockNumber, uncle: BlockNumber) -> Self {
+		RewardKind::Uncle(if number > uncle && number - uncle <= u8::max_value().into() { (number - uncle) as u8 } else { 0 })
+	}
+
+	/// Decode the kind from its block reward contract code, see `From<RewardKind> for u16`.
+	pub fn from_code(code: u16) -> Option<Self> {
+		match code {
+			0 => Some(RewardKind::Author),
+			2 => Some(RewardKind::EmptyStep),
+			3 => Some(RewardKind::External),
+			100..=355 => Some(RewardKind::Uncle((code - 100) as u8)),
+			_ => None,
+		}
+	}
+}
+
+impl From<RewardKind> for u16 {
+	fn from(reward_kind: RewardKind) -> Self {
+		match reward_kind {
+			RewardKind::Author => 0,
+			RewardKind::EmptyStep => 2,
+			RewardKind::External => 3,
+
+			RewardKind::Uncle(depth) => 100 + depth as u16,
+		}
+	}
+}
+
+impl Encodable for RewardKind {
+	fn rlp_append(&self, s: &mut RlpStream) {
+		s.append(&u16::from(*self));
+	}
+}
+
+impl Decodable for RewardKind {
+	fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+		RewardKind::from_code(rlp.as_val()?).ok_or(DecoderError::Custom("Invalid reward kind"))
+	}
+}
+
+/// A reward paid to an address when closing a block.
+#[derive(Debug, Clone, PartialEq, RlpEncodable, RlpDecodable)]
+pub struct BlockReward {
+	/// Rewarded address.
+	pub address: Address,
+	/// Kind of the reward.
+	pub kind: RewardKind,
+	/// Rewarded amount.
+	pub amount: U256,
+}
+
+#[cfg(test)]
+mod tests {
+	use ethereum_types::{Address, U256};
+	use rlp;
+
+	use super::{BlockReward, RewardKind};
+
+	#[test]
+	fn reward_kind_codes_roundtrip() {
+		for kind in &[RewardKind::Author, RewardKind::EmptyStep, RewardKind::External, RewardKind::Uncle(0), RewardKind::Uncle(255)] {
+			assert_eq!(RewardKind::from_code((*kind).into()), Some(*kind));
+		}
+		assert_eq!(RewardKind::from_code(1), None);
+		assert_eq!(RewardKind::from_code(356), None);
+	}
+
+	#[test]
+	fn block_reward_rlp_roundtrip() {
+		let reward = BlockReward {
+			address: Address::from_low_u64_be(1),
+			kind: RewardKind::Uncle(2),
+			amount: U256::from(1000),
+		};
+		assert_eq!(rlp::decode::<BlockReward>(&rlp::encode(&reward)).unwrap(), reward);
+	}
+}
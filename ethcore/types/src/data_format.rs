@@ -17,6 +17,8 @@
 //! Data format for importing/exporting blocks from disk
 use std::str::FromStr;
 
+use rlp::PayloadInfo;
+
 /// Format for importing/exporting blocks
 #[derive(Debug, PartialEq)]
 pub enum DataFormat {
@@ -24,6 +26,10 @@ pub enum DataFormat {
 	Hex,
 	/// Binary format
 	Binary,
+	/// Binary format, each block paired with the RLP of its receipts
+	BinaryWithReceipts,
+	/// JSON lines format, one block with its transactions and receipts per line
+	Json,
 }
 
 impl Default for DataFormat {
@@ -32,6 +38,45 @@ impl Default for DataFormat {
 	}
 }
 
+impl DataFormat {
+	/// Guess the format of a stream from its first bytes.
+	///
+	/// Binary blocks and receipts archives both start with a long list, so they are told apart by
+	/// how deeply the first item is nested: a block is `[header, transactions, uncles]`, while an
+	/// archive entry is `[[header, transactions, uncles], receipts]`. Telling them apart takes the
+	/// headers of the outer two lists and the first byte of the third, up to 19 bytes.
+	pub fn detect(prefix: &[u8]) -> Self {
+		match prefix.first() {
+			Some(&byte) if is_long_list(byte) && list_depth(prefix) > 2 => DataFormat::BinaryWithReceipts,
+			Some(&byte) if is_long_list(byte) => DataFormat::Binary,
+			Some(b'{') => DataFormat::Json,
+			_ => DataFormat::Hex,
+		}
+	}
+}
+
+/// Whether `byte` starts the header of a list with a payload of 56 bytes or more.
+fn is_long_list(byte: u8) -> bool {
+	byte >= 0xf8
+}
+
+/// Number of lists opened at the start of `prefix`, following the first item of each.
+fn list_depth(prefix: &[u8]) -> usize {
+	let mut depth = 0;
+	let mut offset = 0;
+	while let Some(&byte) = prefix.get(offset) {
+		if byte < 0xc0 {
+			break;
+		}
+		depth += 1;
+		match PayloadInfo::from(&prefix[offset..]) {
+			Ok(info) => offset += info.header_len,
+			Err(_) => break,
+		}
+	}
+	depth
+}
+
 impl FromStr for DataFormat {
 	type Err = String;
 
@@ -39,7 +84,55 @@ impl FromStr for DataFormat {
 		match s {
 			"binary" | "bin" => Ok(DataFormat::Binary),
 			"hex" => Ok(DataFormat::Hex),
+			"binary-receipts" | "bin-receipts" => Ok(DataFormat::BinaryWithReceipts),
+			"json" | "jsonl" => Ok(DataFormat::Json),
 			x => Err(format!("Invalid format: {}", x))
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::DataFormat;
+	use rlp::RlpStream;
+
+	#[test]
+	fn detects_format() {
+		let mut header = RlpStream::new_list(2);
+		header.append(&vec![0u8; 32]).append(&vec![0u8; 256]);
+		let mut block = RlpStream::new_list(3);
+		block.append_raw(&header.out(), 1).begin_list(0).begin_list(0);
+		let block = block.out();
+		let mut entry = RlpStream::new_list(2);
+		entry.append_raw(&block, 1).begin_list(0);
+
+		assert_eq!(DataFormat::detect(&block[..8]), DataFormat::Binary);
+		assert_eq!(DataFormat::detect(&entry.out()[..8]), DataFormat::BinaryWithReceipts);
+		assert_eq!(DataFormat::detect(b"{\"hash\""), DataFormat::Json);
+		assert_eq!(DataFormat::detect(b"f90200f9"), DataFormat::Hex);
+	}
+
+	#[test]
+	fn detects_format_of_large_blocks() {
+		// the header alone takes more than 64 KiB, so every list length takes 3 bytes.
+		let mut header = RlpStream::new_list(2);
+		header.append(&vec![0u8; 32]).append(&vec![0u8; 70_000]);
+		let mut block = RlpStream::new_list(3);
+		block.append_raw(&header.out(), 1).begin_list(0).begin_list(0);
+		let block = block.out();
+		let mut entry = RlpStream::new_list(2);
+		entry.append_raw(&block, 1).begin_list(0);
+		let entry = entry.out();
+
+		assert_eq!(block[0], 0xfa);
+		assert_eq!(entry[0], 0xfa);
+		assert_eq!(DataFormat::detect(&block[..32]), DataFormat::Binary);
+		assert_eq!(DataFormat::detect(&entry[..32]), DataFormat::BinaryWithReceipts);
+
+		// lists of 16 MiB or more, given by their headers only.
+		let block = [0xfb, 0x01, 0x00, 0x00, 0x00, 0xf9, 0x02, 0x00, 0xa0];
+		let entry = [0xfb, 0x01, 0x00, 0x00, 0x10, 0xfb, 0x01, 0x00, 0x00, 0x00, 0xf9, 0x02, 0x00, 0xa0];
+		assert_eq!(DataFormat::detect(&block), DataFormat::Binary);
+		assert_eq!(DataFormat::detect(&entry), DataFormat::BinaryWithReceipts);
+	}
+}
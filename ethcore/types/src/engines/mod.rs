@@ -16,7 +16,9 @@
 
 //! Engine-specific types.
 
-use ethereum_types::{Address, H256, H64};
+use std::collections::BTreeMap;
+
+use ethereum_types::{Address, H256, H64, U256};
 use bytes::Bytes;
 use ethjson;
 use rlp::Rlp;
@@ -156,3 +158,54 @@ pub type Headers<'a, H> = dyn Fn(H256) -> Option<H> + 'a;
 
 /// Type alias for a function we can query pending transitions by block hash through.
 pub type PendingTransitionStore<'a> = dyn Fn(H256) -> Option<epoch::PendingTransition> + 'a;
+
+/// Liveness of a single validator over a range of blocks.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ValidatorLiveness {
+	/// Number of blocks authored by the validator.
+	pub produced_blocks: u64,
+	/// Number of steps in which the validator was the expected proposer but no block or empty
+	/// step was produced.
+	pub missed_steps: u64,
+	/// Number of empty steps signed by the validator and included in a block.
+	pub empty_steps_signed: u64,
+}
+
+/// Per-validator liveness statistics for a range of blocks.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ValidatorStats {
+	/// First block of the range (inclusive).
+	pub from_block: BlockNumber,
+	/// Last block of the range (inclusive).
+	pub to_block: BlockNumber,
+	/// Liveness of every validator seen in the range.
+	pub validators: BTreeMap<Address, ValidatorLiveness>,
+}
+
+/// Outcome of our validator's participation in a randomness round.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RandomnessRoundStatus {
+	/// The hash of the secret number was committed; it wasn't revealed yet.
+	Committed,
+	/// The secret number was revealed.
+	Revealed,
+	/// The round ended without the secret number being revealed.
+	Missed,
+}
+
+/// Our validator's participation in a round of a randomness contract.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RandomnessRound {
+	/// Address of the randomness contract.
+	pub contract: Address,
+	/// The contract's collection round.
+	pub round: U256,
+	/// The validator which committed the secret number.
+	pub validator: Address,
+	/// Hash of the committed secret number.
+	pub number_hash: H256,
+	/// Whether the number was revealed.
+	pub status: RandomnessRoundStatus,
+	/// Number of reveal transactions created for the round.
+	pub reveal_attempts: u64,
+}
@@ -102,6 +102,24 @@ pub struct CommonParams {
 	pub eip2028_transition: BlockNumber,
 	/// Number of first block where EIP-2200 advance transition begin.
 	pub eip2200_advance_transition: BlockNumber,
+	/// Number of first block where EIP-2718 typed transaction envelopes are accepted.
+	pub eip2718_transition: BlockNumber,
+	/// Number of first block where EIP-2930 access list transactions are accepted.
+	pub eip2930_transition: BlockNumber,
+	/// Number of first block where EIP-2929 cold/warm state access pricing begins.
+	pub eip2929_transition: BlockNumber,
+	/// Number of first block where the EIP-1559 fee market begins.
+	pub eip1559_transition: BlockNumber,
+	/// Bound divisor of the EIP-1559 base fee change between two blocks.
+	pub eip1559_base_fee_max_change_denominator: U256,
+	/// Ratio of the block gas limit to the EIP-1559 gas target.
+	pub eip1559_elasticity_multiplier: U256,
+	/// Base fee of the first EIP-1559 block.
+	pub eip1559_base_fee_initial_value: U256,
+	/// Account receiving the EIP-1559 base fee instead of burning it, if any.
+	pub eip1559_fee_collector: Option<Address>,
+	/// Number of first block where the base fee is paid to `eip1559_fee_collector`.
+	pub eip1559_fee_collector_transition: BlockNumber,
 	/// Number of first block where dust cleanup rules (EIP-168 and EIP169) begin.
 	pub dust_protection_transition: BlockNumber,
 	/// Nonce cap increase per block. Nonce cap is only checked if dust protection is enabled.
@@ -190,6 +208,12 @@ impl CommonParams {
 		if block_number >= self.eip2200_advance_transition {
 			schedule.sstore_dirty_gas = Some(800);
 		}
+		if block_number >= self.eip2929_transition {
+			schedule.enable_eip2929();
+		}
+		schedule.eip2718 = block_number >= self.eip2718_transition;
+		schedule.eip1559 = schedule.eip2718 && block_number >= self.eip1559_transition;
+		schedule.eip2930 = schedule.eip2718 && block_number >= self.eip2930_transition;
 		if block_number >= self.eip210_transition {
 			schedule.blockhash_gas = 800;
 		}
@@ -331,6 +355,27 @@ impl From<ethjson::spec::Params> for CommonParams {
 				BlockNumber::max_value,
 				Into::into,
 			),
+			eip2718_transition: p.eip2718_transition.map_or_else(
+				BlockNumber::max_value,
+				Into::into,
+			),
+			eip2930_transition: p.eip2930_transition.map_or_else(
+				BlockNumber::max_value,
+				Into::into,
+			),
+			eip2929_transition: p.eip2929_transition.map_or_else(
+				BlockNumber::max_value,
+				Into::into,
+			),
+			eip1559_transition: p.eip1559_transition.map_or_else(
+				BlockNumber::max_value,
+				Into::into,
+			),
+			eip1559_base_fee_max_change_denominator: p.eip1559_base_fee_max_change_denominator.map_or(U256::from(8), Into::into),
+			eip1559_elasticity_multiplier: p.eip1559_elasticity_multiplier.map_or(U256::from(2), Into::into),
+			eip1559_base_fee_initial_value: p.eip1559_base_fee_initial_value.map_or(U256::from(1_000_000_000), Into::into),
+			eip1559_fee_collector: p.eip1559_fee_collector.map(Into::into),
+			eip1559_fee_collector_transition: p.eip1559_fee_collector_transition.map_or(0, Into::into),
 			dust_protection_transition: p.dust_protection_transition.map_or_else(
 				BlockNumber::max_value,
 				Into::into,
@@ -86,6 +86,9 @@ pub enum BlockError {
 	/// Gas limit header field is invalid.
 	#[display(fmt = "Invalid gas limit: {}", _0)]
 	InvalidGasLimit(OutOfBounds<U256>),
+	/// EIP-1559 base fee header field is invalid.
+	#[display(fmt = "Invalid base fee: {}", _0)]
+	InvalidBaseFee(Mismatch<U256>),
 	/// Receipts trie root header field is invalid.
 	#[display(fmt = "Invalid receipts trie root in header: {}", _0)]
 	InvalidReceiptsRoot(Mismatch<H256>),
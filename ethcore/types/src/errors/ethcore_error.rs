@@ -152,6 +152,13 @@ pub enum ExecutionError {
 		/// Actual balance.
 		got: U512
 	},
+	/// Returned when the max fee per gas of the transaction is lower than the block base fee.
+	GasPriceLowerThanBaseFee {
+		/// Max fee per gas of the transaction.
+		gas_price: U256,
+		/// Base fee of the block.
+		base_fee: U256,
+	},
 	/// When execution tries to modify the state in static context
 	MutableCallInStaticContext,
 	/// Returned when transacting from a non-existing account with dust protection enabled.
@@ -194,6 +201,8 @@ impl fmt::Display for ExecutionError {
 			NotEnoughCash { ref required, ref got } =>
 				format!("Cost of transaction exceeds sender balance. {} is required \
 					but the sender only has {}", required, got),
+			GasPriceLowerThanBaseFee { ref gas_price, ref base_fee } =>
+				format!("Max fee per gas {} is lower than the block base fee {}", gas_price, base_fee),
 			MutableCallInStaticContext => "Mutable Call in static context".to_owned(),
 			SenderMustExist => "Transacting from an empty account".to_owned(),
 			Internal(ref msg) => msg.clone(),
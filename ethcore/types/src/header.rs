@@ -16,6 +16,7 @@
 
 //! Block header.
 
+
 use hash::{KECCAK_NULL_RLP, KECCAK_EMPTY_LIST_RLP, keccak};
 use parity_util_mem::MallocSizeOf;
 use ethereum_types::{H256, U256, Address, Bloom};
@@ -82,6 +83,8 @@ pub struct Header {
 	difficulty: U256,
 	/// Vector of post-RLP-encoded fields.
 	seal: Vec<Bytes>,
+	/// EIP-1559 base fee per gas, present from the EIP-1559 transition on.
+	base_fee_per_gas: Option<U256>,
 
 	/// Memoized hash of that header and the seal.
 	hash: Option<H256>,
@@ -108,7 +111,8 @@ impl PartialEq for Header {
 		self.gas_used == c.gas_used &&
 		self.gas_limit == c.gas_limit &&
 		self.difficulty == c.difficulty &&
-		self.seal == c.seal
+		self.seal == c.seal &&
+		self.base_fee_per_gas == c.base_fee_per_gas
 	}
 }
 
@@ -132,6 +136,7 @@ impl Default for Header {
 
 			difficulty: U256::default(),
 			seal: vec![],
+			base_fee_per_gas: None,
 			hash: None,
 		}
 	}
@@ -183,6 +188,9 @@ impl Header {
 	/// Get the seal field of the header.
 	pub fn seal(&self) -> &[Bytes] { &self.seal }
 
+	/// Get the EIP-1559 base fee per gas of the header, if any.
+	pub fn base_fee_per_gas(&self) -> Option<U256> { self.base_fee_per_gas }
+
 	/// Get the seal field with RLP-decoded values as bytes.
 	pub fn decode_seal<'a, T: ::std::iter::FromIterator<&'a [u8]>>(&'a self) -> Result<T, DecoderError> {
 		self.seal.iter().map(|rlp| {
@@ -260,6 +268,11 @@ impl Header {
 		change_field(&mut self.hash, &mut self.seal, a)
 	}
 
+	/// Set the EIP-1559 base fee per gas of the header.
+	pub fn set_base_fee_per_gas(&mut self, a: Option<U256>) {
+		change_field(&mut self.hash, &mut self.base_fee_per_gas, a)
+	}
+
 	/// Get & memoize the hash of this header (keccak of the RLP with seal).
 	pub fn compute_hash(&mut self) -> H256 {
 		let hash = self.hash();
@@ -274,7 +287,12 @@ impl Header {
 
 	/// Get the hash of the header excluding the seal
 	pub fn bare_hash(&self) -> H256 {
-		keccak(self.rlp(Seal::Without))
+		keccak(self.bare_rlp())
+	}
+
+	/// Get the RLP of the header excluding the seal, whose hash is `bare_hash`.
+	pub fn bare_rlp(&self) -> Bytes {
+		self.rlp(Seal::Without)
 	}
 
 	/// Encode the header, getting a type-safe wrapper around the RLP.
@@ -291,10 +309,11 @@ impl Header {
 
 	/// Place this header into an RLP stream `s`, optionally `with_seal`.
 	fn stream_rlp(&self, s: &mut RlpStream, with_seal: Seal) {
+		let base_fee_len = if self.base_fee_per_gas.is_some() { 1 } else { 0 };
 		if let Seal::With = with_seal {
-			s.begin_list(13 + self.seal.len());
+			s.begin_list(13 + self.seal.len() + base_fee_len);
 		} else {
-			s.begin_list(13);
+			s.begin_list(13 + base_fee_len);
 		}
 
 		s.append(&self.parent_hash);
@@ -316,6 +335,10 @@ impl Header {
 				s.append_raw(b, 1);
 			}
 		}
+
+		if let Some(ref base_fee) = self.base_fee_per_gas {
+			s.append(base_fee);
+		}
 	}
 }
 
@@ -327,8 +350,12 @@ fn change_field<T>(hash: &mut Option<H256>, field: &mut T, value: T) where T: Pa
 	}
 }
 
-impl Decodable for Header {
-	fn decode(r: &Rlp) -> Result<Self, DecoderError> {
+impl Header {
+	/// Decode a header whose base fee, from block `eip1559_transition` on, follows the seal fields.
+	///
+	/// The base fee can't be told apart from the engine-specific seal fields without knowing the
+	/// chain's transition, which comes from `CommonParams::eip1559_transition`.
+	pub fn decode_rlp(r: &Rlp, eip1559_transition: BlockNumber) -> Result<Self, DecoderError> {
 		let mut blockheader = Header {
 			parent_hash: r.val_at(0)?,
 			uncles_hash: r.val_at(1)?,
@@ -344,15 +371,41 @@ impl Decodable for Header {
 			timestamp: r.val_at(11)?,
 			extra_data: r.val_at(12)?,
 			seal: vec![],
+			base_fee_per_gas: None,
 			hash: keccak(r.as_raw()).into(),
 		};
 
-		for i in 13..r.item_count()? {
+		let mut seal_end = r.item_count()?;
+		if blockheader.number >= eip1559_transition {
+			if seal_end <= 13 {
+				return Err(DecoderError::RlpIncorrectListLen);
+			}
+			seal_end -= 1;
+			blockheader.base_fee_per_gas = Some(r.val_at(seal_end)?);
+		}
+
+		for i in 13..seal_end {
 			blockheader.seal.push(r.at(i)?.as_raw().to_vec())
 		}
 
 		Ok(blockheader)
 	}
+
+	/// Decode a list of headers, e.g. the uncles of a block.
+	pub fn decode_rlp_list(r: &Rlp, eip1559_transition: BlockNumber) -> Result<Vec<Self>, DecoderError> {
+		if !r.is_list() {
+			return Err(DecoderError::RlpExpectedToBeList);
+		}
+		r.iter().map(|header| Header::decode_rlp(&header, eip1559_transition)).collect()
+	}
+}
+
+/// Decodes a header without a base fee. Headers of chains with an EIP-1559 transition are decoded
+/// with `Header::decode_rlp`.
+impl Decodable for Header {
+	fn decode(r: &Rlp) -> Result<Self, DecoderError> {
+		Header::decode_rlp(r, BlockNumber::max_value())
+	}
 }
 
 impl Encodable for Header {
@@ -406,6 +459,48 @@ mod tests {
 		assert_eq!(header_rlp, encoded_header);
 	}
 
+	#[test]
+	fn decode_and_encode_header_with_base_fee() {
+		let mut header = Header::default();
+		header.set_number(10);
+		header.set_seal(vec![rlp::encode(&1u8), rlp::encode(&2u8)]);
+		header.set_base_fee_per_gas(Some(1_000_000_000.into()));
+
+		let encoded = rlp::encode(&header);
+		let decoded = Header::decode_rlp(&rlp::Rlp::new(&encoded), 10).expect("error decoding header");
+		assert_eq!(decoded.base_fee_per_gas(), Some(1_000_000_000.into()));
+		assert_eq!(decoded.seal().len(), 2);
+		assert_eq!(decoded.hash(), header.hash());
+
+		// before the transition the trailing item is a seal field
+		let decoded = Header::decode_rlp(&rlp::Rlp::new(&encoded), 11).expect("error decoding header");
+		assert_eq!(decoded.base_fee_per_gas(), None);
+		assert_eq!(decoded.seal().len(), 3);
+	}
+
+	#[test]
+	fn rejects_malformed_base_fee() {
+		let mut header = Header::default();
+		header.set_number(10);
+		let with_base_fee = |base_fee: &[u8]| {
+			let mut s = rlp::RlpStream::new_list(14);
+			let encoded = rlp::encode(&header);
+			for item in rlp::Rlp::new(&encoded).iter() {
+				s.append_raw(item.as_raw(), 1);
+			}
+			s.append_raw(base_fee, 1);
+			s.out()
+		};
+
+		// a list, a value with leading zeros and a value wider than 256 bits.
+		for base_fee in &[&[0xc0][..], &[0x82, 0x00, 0x01][..], &[&[0xa1][..], &[1u8; 33][..]].concat()[..]] {
+			let encoded = with_base_fee(base_fee);
+			assert!(Header::decode_rlp(&rlp::Rlp::new(&encoded), 10).is_err());
+		}
+		let encoded = with_base_fee(&rlp::encode(&7u8));
+		assert_eq!(Header::decode_rlp(&rlp::Rlp::new(&encoded), 10).unwrap().base_fee_per_gas(), Some(7.into()));
+	}
+
 	#[test]
 	fn reject_header_with_large_timestamp() {
 		// that's rlp of block header created with ethash engine.
@@ -61,6 +61,7 @@ pub mod account_diff;
 pub mod ancestry_action;
 pub mod basic_account;
 pub mod block;
+pub mod block_reward;
 pub mod block_status;
 pub mod blockchain_info;
 pub mod call_analytics;
@@ -80,6 +81,7 @@ pub mod receipt;
 pub mod security_level;
 pub mod snapshot;
 pub mod state_diff;
+pub mod state_override;
 pub mod trace_filter;
 pub mod transaction;
 pub mod tree_route;
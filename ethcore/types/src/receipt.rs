@@ -21,7 +21,9 @@ use parity_util_mem::MallocSizeOf;
 use rlp::{Rlp, RlpStream, Encodable, Decodable, DecoderError};
 
 use BlockNumber;
+use bytes::Bytes;
 use log_entry::{LogEntry, LocalizedLogEntry};
+use transaction::TypedTxId;
 
 /// Transaction outcome store in the receipt.
 #[derive(Debug, Clone, PartialEq, Eq, MallocSizeOf)]
@@ -45,12 +47,20 @@ pub struct Receipt {
 	pub logs: Vec<LogEntry>,
 	/// Transaction outcome.
 	pub outcome: TransactionOutcome,
+	/// EIP-2718 type of the transaction this receipt belongs to.
+	pub tx_type: TypedTxId,
 }
 
 impl Receipt {
-	/// Create a new receipt.
+	/// Create a new receipt of a legacy transaction.
 	pub fn new(outcome: TransactionOutcome, gas_used: U256, logs: Vec<LogEntry>) -> Self {
+		Self::new_typed(TypedTxId::Legacy, outcome, gas_used, logs)
+	}
+
+	/// Create a new receipt of a transaction of the given type.
+	pub fn new_typed(tx_type: TypedTxId, outcome: TransactionOutcome, gas_used: U256, logs: Vec<LogEntry>) -> Self {
 		Self {
+			tx_type,
 			gas_used,
 			log_bloom: logs.iter().fold(Bloom::default(), |mut b, l| {
 				b.accrue_bloom(&l.bloom());
@@ -60,10 +70,23 @@ impl Receipt {
 			outcome,
 		}
 	}
-}
 
-impl Encodable for Receipt {
-	fn rlp_append(&self, s: &mut RlpStream) {
+	/// Encode the receipt as committed to in the receipts trie: the RLP list for legacy receipts,
+	/// the type byte followed by the RLP list for typed ones.
+	pub fn encode(&self) -> Bytes {
+		let mut s = RlpStream::new();
+		self.rlp_append_fields(&mut s);
+		match self.tx_type.to_u8() {
+			None => s.out(),
+			Some(type_byte) => {
+				let mut bytes = vec![type_byte];
+				bytes.extend_from_slice(s.as_raw());
+				bytes
+			},
+		}
+	}
+
+	fn rlp_append_fields(&self, s: &mut RlpStream) {
 		match self.outcome {
 			TransactionOutcome::Unknown => {
 				s.begin_list(3);
@@ -81,12 +104,11 @@ impl Encodable for Receipt {
 		s.append(&self.log_bloom);
 		s.append_list(&self.logs);
 	}
-}
 
-impl Decodable for Receipt {
-	fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+	fn decode_fields(rlp: &Rlp, tx_type: TypedTxId) -> Result<Self, DecoderError> {
 		if rlp.item_count()? == 3 {
 			Ok(Receipt {
+				tx_type,
 				outcome: TransactionOutcome::Unknown,
 				gas_used: rlp.val_at(0)?,
 				log_bloom: rlp.val_at(1)?,
@@ -94,6 +116,7 @@ impl Decodable for Receipt {
 			})
 		} else {
 			Ok(Receipt {
+				tx_type,
 				gas_used: rlp.val_at(1)?,
 				log_bloom: rlp.val_at(2)?,
 				logs: rlp.list_at(3)?,
@@ -110,6 +133,27 @@ impl Decodable for Receipt {
 	}
 }
 
+/// Typed receipts are RLP strings holding the type byte followed by the receipt list.
+impl Encodable for Receipt {
+	fn rlp_append(&self, s: &mut RlpStream) {
+		match self.tx_type {
+			TypedTxId::Legacy => self.rlp_append_fields(s),
+			_ => { s.append(&self.encode()); },
+		}
+	}
+}
+
+impl Decodable for Receipt {
+	fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+		if rlp.is_list() {
+			return Receipt::decode_fields(rlp, TypedTxId::Legacy);
+		}
+		let (type_byte, payload) = rlp.data()?.split_first().ok_or(DecoderError::RlpIsTooShort)?;
+		let tx_type = TypedTxId::from_u8(*type_byte).ok_or(DecoderError::Custom("Unknown receipt type"))?;
+		Receipt::decode_fields(&Rlp::new(payload), tx_type)
+	}
+}
+
 /// Receipt with additional info.
 #[derive(Debug, Clone, PartialEq)]
 pub struct RichReceipt {
@@ -172,7 +216,7 @@ pub struct LocalizedReceipt {
 mod tests {
 	use std::str::FromStr;
 
-	use super::{Receipt, TransactionOutcome, Address, H256};
+	use super::{Receipt, TransactionOutcome, TypedTxId, Address, H256};
 	use log_entry::LogEntry;
 	use rustc_hex::FromHex;
 
@@ -226,4 +270,24 @@ mod tests {
 		let decoded: Receipt = rlp::decode(&encoded).expect("decoding receipt failed");
 		assert_eq!(decoded, r);
 	}
+
+	#[test]
+	fn test_typed_receipt() {
+		let r = Receipt::new_typed(
+			TypedTxId::AccessList,
+			TransactionOutcome::StatusCode(1),
+			0x40cae.into(),
+			vec![LogEntry {
+				address: Address::from_str("dcf421d093428b096ca501a7cd1a740855a7976f").unwrap(),
+				topics: vec![],
+				data: vec![0u8; 32]
+			}]
+		);
+		let trie_bytes = r.encode();
+		assert_eq!(trie_bytes[0], TypedTxId::ACCESS_LIST_BYTE);
+		let encoded = rlp::encode(&r);
+		assert_eq!(rlp::Rlp::new(&encoded).data().unwrap(), &trie_bytes[..]);
+		let decoded: Receipt = rlp::decode(&encoded).expect("decoding receipt failed");
+		assert_eq!(decoded, r);
+	}
 }
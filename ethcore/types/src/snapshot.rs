@@ -36,6 +36,12 @@ pub enum Snapshotting {
 	},
 	/// Snapshots for proof-of-authority chains
 	PoA,
+	/// Snapshots for Clique chains. These include all blocks from the head of the chain
+	/// back to the last checkpoint, whose extra data carries the signer set.
+	Clique {
+		/// Number of blocks between checkpoints.
+		epoch_length: u64,
+	},
 }
 
 /// A progress indicator for snapshots.
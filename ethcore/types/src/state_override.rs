@@ -0,0 +1,47 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Parity Ethereum.
+
+// Parity Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! State overrides applied before non-persistent calls.
+
+use std::collections::BTreeMap;
+
+use bytes::Bytes;
+use ethereum_types::{Address, H256, U256};
+
+/// Overrides of account fields, keyed by account address.
+pub type StateOverride = BTreeMap<Address, AccountOverride>;
+
+/// Overrides of a single account. Fields left as `None` keep their value from the underlying state.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct AccountOverride {
+	/// Balance to set.
+	pub balance: Option<U256>,
+	/// Nonce to set.
+	pub nonce: Option<U256>,
+	/// Code to set.
+	pub code: Option<Bytes>,
+	/// Storage to set.
+	pub storage: Option<StorageOverride>,
+}
+
+/// Override of account storage.
+#[derive(Debug, Clone, PartialEq)]
+pub enum StorageOverride {
+	/// Replace the whole storage; slots not listed read as zero.
+	Replace(BTreeMap<H256, H256>),
+	/// Set the listed slots, keeping all the others.
+	Diff(BTreeMap<H256, H256>),
+}
@@ -16,6 +16,7 @@
 
 //! Transaction data structure.
 
+use std::cmp;
 use std::ops::Deref;
 
 use ethereum_types::{H256, H160, Address, U256, BigEndianHash};
@@ -24,7 +25,7 @@ use parity_crypto::publickey::{Signature, Secret, Public, recover, public_to_add
 use hash::keccak;
 use parity_util_mem::MallocSizeOf;
 
-use rlp::{self, RlpStream, Rlp, DecoderError, Encodable};
+use rlp::{self, RlpStream, Rlp, DecoderError};
 
 use transaction::error;
 
@@ -74,6 +75,78 @@ impl rlp::Encodable for Action {
 	}
 }
 
+/// EIP-2718 transaction type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, MallocSizeOf)]
+pub enum TypedTxId {
+	/// Legacy transaction, encoded as a plain RLP list without a type byte.
+	Legacy,
+	/// EIP-2930 transaction with an access list.
+	AccessList,
+	/// EIP-1559 transaction with a max fee and a priority fee instead of a gas price.
+	EIP1559Transaction,
+}
+
+impl Default for TypedTxId {
+	fn default() -> TypedTxId { TypedTxId::Legacy }
+}
+
+impl TypedTxId {
+	/// Type byte of an EIP-2930 access list transaction.
+	pub const ACCESS_LIST_BYTE: u8 = 0x01;
+	/// Type byte of an EIP-1559 dynamic fee transaction.
+	pub const EIP1559_BYTE: u8 = 0x02;
+
+	/// Resolve the EIP-2718 type byte, `None` if the type is unknown.
+	pub fn from_u8(byte: u8) -> Option<TypedTxId> {
+		match byte {
+			Self::ACCESS_LIST_BYTE => Some(TypedTxId::AccessList),
+			Self::EIP1559_BYTE => Some(TypedTxId::EIP1559Transaction),
+			_ => None,
+		}
+	}
+
+	/// The EIP-2718 type byte, `None` for legacy transactions.
+	pub fn to_u8(self) -> Option<u8> {
+		match self {
+			TypedTxId::Legacy => None,
+			TypedTxId::AccessList => Some(Self::ACCESS_LIST_BYTE),
+			TypedTxId::EIP1559Transaction => Some(Self::EIP1559_BYTE),
+		}
+	}
+}
+
+/// Single entry of an EIP-2930 access list.
+#[derive(Default, Debug, Clone, PartialEq, Eq, MallocSizeOf)]
+pub struct AccessListItem {
+	/// Accessed account.
+	pub address: Address,
+	/// Accessed storage keys of the account.
+	pub storage_keys: Vec<H256>,
+}
+
+impl rlp::Encodable for AccessListItem {
+	fn rlp_append(&self, s: &mut RlpStream) {
+		s.begin_list(2);
+		s.append(&self.address);
+		s.append_list(&self.storage_keys);
+	}
+}
+
+impl rlp::Decodable for AccessListItem {
+	fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+		if rlp.item_count()? != 2 {
+			return Err(DecoderError::RlpIncorrectListLen);
+		}
+		Ok(AccessListItem {
+			address: rlp.val_at(0)?,
+			storage_keys: rlp.list_at(1)?,
+		})
+	}
+}
+
+/// EIP-2930 access list.
+pub type AccessList = Vec<AccessListItem>;
+
 /// Transaction activation condition.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Condition {
@@ -138,6 +211,221 @@ impl Transaction {
 	}
 }
 
+/// EIP-2930 transaction: a legacy transaction body extended with an access list.
+#[derive(Default, Debug, Clone, PartialEq, Eq, MallocSizeOf)]
+pub struct AccessListTx {
+	/// Legacy part of the transaction.
+	pub transaction: Transaction,
+	/// Accounts and storage keys the transaction plans to access.
+	pub access_list: AccessList,
+}
+
+impl AccessListTx {
+	/// Append the signed fields of the EIP-2930 payload (without the type byte) into RLP stream.
+	fn rlp_append_payload(&self, s: &mut RlpStream, chain_id: u64, signature: Option<(u8, &U256, &U256)>) {
+		s.begin_list(if signature.is_none() { 8 } else { 11 });
+		s.append(&chain_id);
+		s.append(&self.transaction.nonce);
+		s.append(&self.transaction.gas_price);
+		s.append(&self.transaction.gas);
+		s.append(&self.transaction.action);
+		s.append(&self.transaction.value);
+		s.append(&self.transaction.data);
+		s.append_list(&self.access_list);
+		if let Some((y_parity, r, s_)) = signature {
+			s.append(&y_parity);
+			s.append(r);
+			s.append(s_);
+		}
+	}
+}
+
+/// EIP-1559 transaction: an access list transaction paying a priority fee on top of the block's
+/// base fee. The `gas_price` of the inner transaction is the max fee per gas.
+#[derive(Default, Debug, Clone, PartialEq, Eq, MallocSizeOf)]
+pub struct EIP1559TransactionTx {
+	/// Access list part of the transaction; its `gas_price` is `max_fee_per_gas`.
+	pub transaction: AccessListTx,
+	/// Max tip per gas paid to the block author on top of the base fee.
+	pub max_priority_fee_per_gas: U256,
+}
+
+impl EIP1559TransactionTx {
+	/// Append the signed fields of the EIP-1559 payload (without the type byte) into RLP stream.
+	fn rlp_append_payload(&self, s: &mut RlpStream, chain_id: u64, signature: Option<(u8, &U256, &U256)>) {
+		let tx = &self.transaction.transaction;
+		s.begin_list(if signature.is_none() { 9 } else { 12 });
+		s.append(&chain_id);
+		s.append(&tx.nonce);
+		s.append(&self.max_priority_fee_per_gas);
+		s.append(&tx.gas_price);
+		s.append(&tx.gas);
+		s.append(&tx.action);
+		s.append(&tx.value);
+		s.append(&tx.data);
+		s.append_list(&self.transaction.access_list);
+		if let Some((y_parity, r, s_)) = signature {
+			s.append(&y_parity);
+			s.append(r);
+			s.append(s_);
+		}
+	}
+}
+
+/// Unsigned transaction of any EIP-2718 type.
+#[derive(Debug, Clone, PartialEq, Eq, MallocSizeOf)]
+pub enum TypedTransaction {
+	/// Legacy transaction.
+	Legacy(Transaction),
+	/// EIP-2930 access list transaction.
+	AccessList(AccessListTx),
+	/// EIP-1559 dynamic fee transaction.
+	EIP1559Transaction(EIP1559TransactionTx),
+}
+
+impl Default for TypedTransaction {
+	fn default() -> TypedTransaction { TypedTransaction::Legacy(Transaction::default()) }
+}
+
+impl From<Transaction> for TypedTransaction {
+	fn from(t: Transaction) -> Self {
+		TypedTransaction::Legacy(t)
+	}
+}
+
+impl From<AccessListTx> for TypedTransaction {
+	fn from(t: AccessListTx) -> Self {
+		TypedTransaction::AccessList(t)
+	}
+}
+
+impl From<EIP1559TransactionTx> for TypedTransaction {
+	fn from(t: EIP1559TransactionTx) -> Self {
+		TypedTransaction::EIP1559Transaction(t)
+	}
+}
+
+impl TypedTransaction {
+	/// EIP-2718 type of the transaction.
+	pub fn tx_type(&self) -> TypedTxId {
+		match *self {
+			TypedTransaction::Legacy(_) => TypedTxId::Legacy,
+			TypedTransaction::AccessList(_) => TypedTxId::AccessList,
+			TypedTransaction::EIP1559Transaction(_) => TypedTxId::EIP1559Transaction,
+		}
+	}
+
+	/// Fields shared by all transaction types.
+	pub fn tx(&self) -> &Transaction {
+		match *self {
+			TypedTransaction::Legacy(ref t) => t,
+			TypedTransaction::AccessList(ref t) => &t.transaction,
+			TypedTransaction::EIP1559Transaction(ref t) => &t.transaction.transaction,
+		}
+	}
+
+	/// The access list, if the transaction type has one.
+	pub fn access_list(&self) -> Option<&AccessList> {
+		match *self {
+			TypedTransaction::Legacy(_) => None,
+			TypedTransaction::AccessList(ref t) => Some(&t.access_list),
+			TypedTransaction::EIP1559Transaction(ref t) => Some(&t.transaction.access_list),
+		}
+	}
+
+	/// Max fee per gas the sender is willing to pay; the gas price for non EIP-1559 transactions.
+	pub fn max_fee_per_gas(&self) -> U256 {
+		self.tx().gas_price
+	}
+
+	/// Max tip per gas paid to the block author; the gas price for non EIP-1559 transactions.
+	pub fn max_priority_fee_per_gas(&self) -> U256 {
+		match *self {
+			TypedTransaction::EIP1559Transaction(ref t) => t.max_priority_fee_per_gas,
+			_ => self.tx().gas_price,
+		}
+	}
+
+	/// Price per gas actually paid by the sender in a block with the given base fee.
+	pub fn effective_gas_price(&self, base_fee: Option<U256>) -> U256 {
+		match (self, base_fee) {
+			(&TypedTransaction::EIP1559Transaction(ref t), Some(base_fee)) => {
+				let max_fee = t.transaction.transaction.gas_price;
+				cmp::min(max_fee, base_fee.saturating_add(t.max_priority_fee_per_gas))
+			},
+			_ => self.tx().gas_price,
+		}
+	}
+
+	/// Price per gas received by the block author in a block with the given base fee.
+	pub fn effective_priority_fee(&self, base_fee: Option<U256>) -> U256 {
+		self.effective_gas_price(base_fee).saturating_sub(base_fee.unwrap_or_default())
+	}
+
+	/// The message hash of the transaction.
+	///
+	/// Typed transactions always commit to a chain id; `None` is signed as chain id 0.
+	pub fn hash(&self, chain_id: Option<u64>) -> H256 {
+		match *self {
+			TypedTransaction::Legacy(ref t) => t.hash(chain_id),
+			TypedTransaction::AccessList(ref t) => {
+				let mut stream = RlpStream::new();
+				t.rlp_append_payload(&mut stream, chain_id.unwrap_or(0), None);
+				let mut bytes = vec![TypedTxId::ACCESS_LIST_BYTE];
+				bytes.extend_from_slice(stream.as_raw());
+				keccak(bytes)
+			},
+			TypedTransaction::EIP1559Transaction(ref t) => {
+				let mut stream = RlpStream::new();
+				t.rlp_append_payload(&mut stream, chain_id.unwrap_or(0), None);
+				let mut bytes = vec![TypedTxId::EIP1559_BYTE];
+				bytes.extend_from_slice(stream.as_raw());
+				keccak(bytes)
+			},
+		}
+	}
+
+	/// Signs the transaction as coming from `sender`.
+	pub fn sign(self, secret: &Secret, chain_id: Option<u64>) -> SignedTransaction {
+		let sig = parity_crypto::publickey::sign(secret, &self.hash(chain_id))
+			.expect("data is valid and context has signing capabilities; qed");
+		SignedTransaction::new(self.with_signature(sig, chain_id))
+			.expect("secret is valid so it's recoverable")
+	}
+
+	/// Signs the transaction with signature.
+	pub fn with_signature(self, sig: Signature, chain_id: Option<u64>) -> UnverifiedTransaction {
+		// Typed transactions carry the chain id and the y parity separately, but `v` is kept
+		// in the EIP-155 form internally so that chain id and signature checks are shared.
+		let chain_id = match self {
+			TypedTransaction::Legacy(_) => chain_id,
+			_ => Some(chain_id.unwrap_or(0)),
+		};
+		UnverifiedTransaction {
+			unsigned: self,
+			r: sig.r().into(),
+			s: sig.s().into(),
+			v: signature::add_chain_replay_protection(sig.v() as u64, chain_id),
+			hash: H256::zero(),
+		}.compute_hash()
+	}
+
+	/// Specify the sender; this won't survive the serialize/deserialize process, but can be cloned.
+	pub fn fake_sign(self, from: Address) -> SignedTransaction {
+		SignedTransaction {
+			transaction: UnverifiedTransaction {
+				unsigned: self,
+				r: U256::one(),
+				s: U256::one(),
+				v: 0,
+				hash: H256::zero(),
+			}.compute_hash(),
+			sender: from,
+			public: None,
+		}
+	}
+}
+
 #[cfg(any(test, feature = "test-helpers"))]
 impl From<ethjson::transaction::Transaction> for SignedTransaction {
 	fn from(t: ethjson::transaction::Transaction) -> Self {
@@ -165,7 +453,7 @@ impl From<ethjson::transaction::Transaction> for UnverifiedTransaction {
 	fn from(t: ethjson::transaction::Transaction) -> Self {
 		let to: Option<ethjson::hash::Address> = t.to.into();
 		UnverifiedTransaction {
-			unsigned: Transaction {
+			unsigned: TypedTransaction::Legacy(Transaction {
 				nonce: t.nonce.into(),
 				gas_price: t.gas_price.into(),
 				gas: t.gas_limit.into(),
@@ -175,7 +463,7 @@ impl From<ethjson::transaction::Transaction> for UnverifiedTransaction {
 				},
 				value: t.value.into(),
 				data: t.data.into(),
-			},
+			}),
 			r: t.r.into(),
 			s: t.s.into(),
 			v: t.v.into(),
@@ -187,35 +475,31 @@ impl From<ethjson::transaction::Transaction> for UnverifiedTransaction {
 impl Transaction {
 	/// The message hash of the transaction.
 	pub fn hash(&self, chain_id: Option<u64>) -> H256 {
+		keccak(self.signing_data(chain_id))
+	}
+
+	/// The data whose hash is signed, the RLP of the unsigned transaction.
+	pub fn signing_data(&self, chain_id: Option<u64>) -> Bytes {
 		let mut stream = RlpStream::new();
 		self.rlp_append_unsigned_transaction(&mut stream, chain_id);
-		keccak(stream.as_raw())
+		stream.out()
 	}
 
 	/// Signs the transaction as coming from `sender`.
 	pub fn sign(self, secret: &Secret, chain_id: Option<u64>) -> SignedTransaction {
-		let sig = parity_crypto::publickey::sign(secret, &self.hash(chain_id))
-			.expect("data is valid and context has signing capabilities; qed");
-		SignedTransaction::new(self.with_signature(sig, chain_id))
-			.expect("secret is valid so it's recoverable")
+		TypedTransaction::Legacy(self).sign(secret, chain_id)
 	}
 
 	/// Signs the transaction with signature.
 	pub fn with_signature(self, sig: Signature, chain_id: Option<u64>) -> UnverifiedTransaction {
-		UnverifiedTransaction {
-			unsigned: self,
-			r: sig.r().into(),
-			s: sig.s().into(),
-			v: signature::add_chain_replay_protection(sig.v() as u64, chain_id),
-			hash: H256::zero(),
-		}.compute_hash()
+		TypedTransaction::Legacy(self).with_signature(sig, chain_id)
 	}
 
 	/// Useful for test incorrectly signed transactions.
 	#[cfg(test)]
 	pub fn invalid_sign(self) -> UnverifiedTransaction {
 		UnverifiedTransaction {
-			unsigned: self,
+			unsigned: TypedTransaction::Legacy(self),
 			r: U256::one(),
 			s: U256::one(),
 			v: 0,
@@ -225,17 +509,7 @@ impl Transaction {
 
 	/// Specify the sender; this won't survive the serialize/deserialize process, but can be cloned.
 	pub fn fake_sign(self, from: Address) -> SignedTransaction {
-		SignedTransaction {
-			transaction: UnverifiedTransaction {
-				unsigned: self,
-				r: U256::one(),
-				s: U256::one(),
-				v: 0,
-				hash: H256::zero(),
-			}.compute_hash(),
-			sender: from,
-			public: None,
-		}
+		TypedTransaction::Legacy(self).fake_sign(from)
 	}
 
 	/// Legacy EIP-86 compatible empty signature.
@@ -245,7 +519,7 @@ impl Transaction {
 	pub fn null_sign(self, chain_id: u64) -> SignedTransaction {
 		SignedTransaction {
 			transaction: UnverifiedTransaction {
-				unsigned: self,
+				unsigned: TypedTransaction::Legacy(self),
 				r: U256::zero(),
 				s: U256::zero(),
 				v: chain_id,
@@ -261,9 +535,10 @@ impl Transaction {
 #[derive(Debug, Clone, Eq, PartialEq, MallocSizeOf)]
 pub struct UnverifiedTransaction {
 	/// Plain Transaction.
-	unsigned: Transaction,
+	unsigned: TypedTransaction,
 	/// The V field of the signature; the LS bit described which half of the curve our point falls
 	/// in. The MS bits describe which chain this transaction is for. If 27/28, its for all chains.
+	/// Typed transactions store their chain id and y parity in this EIP-155 form as well.
 	v: u64,
 	/// The R field of the signature; helps describe the point on the curve.
 	r: U256,
@@ -277,25 +552,30 @@ impl Deref for UnverifiedTransaction {
 	type Target = Transaction;
 
 	fn deref(&self) -> &Self::Target {
-		&self.unsigned
+		self.unsigned.tx()
 	}
 }
 
+/// Decodes a transaction from the RLP of a block body or a network message: legacy transactions
+/// are RLP lists, typed transactions are RLP strings holding the EIP-2718 envelope.
 impl rlp::Decodable for UnverifiedTransaction {
 	fn decode(d: &Rlp) -> Result<Self, DecoderError> {
+		if !d.is_list() {
+			return UnverifiedTransaction::decode_typed(d.data()?);
+		}
 		if d.item_count()? != 9 {
 			return Err(DecoderError::RlpIncorrectListLen);
 		}
 		let hash = keccak(d.as_raw());
 		Ok(UnverifiedTransaction {
-			unsigned: Transaction {
+			unsigned: TypedTransaction::Legacy(Transaction {
 				nonce: d.val_at(0)?,
 				gas_price: d.val_at(1)?,
 				gas: d.val_at(2)?,
 				action: d.val_at(3)?,
 				value: d.val_at(4)?,
 				data: d.val_at(5)?,
-			},
+			}),
 			v: d.val_at(6)?,
 			r: d.val_at(7)?,
 			s: d.val_at(8)?,
@@ -304,6 +584,16 @@ impl rlp::Decodable for UnverifiedTransaction {
 	}
 }
 
+/// Returns the bytes of a transaction (or receipt) as they are committed to in the block's trie:
+/// the raw list for legacy items and the EIP-2718 envelope for typed ones.
+pub fn typed_envelope_bytes<'a>(rlp: &Rlp<'a>) -> &'a [u8] {
+	if rlp.is_list() {
+		rlp.as_raw()
+	} else {
+		rlp.data().unwrap_or_else(|_| rlp.as_raw())
+	}
+}
+
 impl rlp::Encodable for UnverifiedTransaction {
 	fn rlp_append(&self, s: &mut RlpStream) { self.rlp_append_sealed_transaction(s) }
 }
@@ -311,11 +601,135 @@ impl rlp::Encodable for UnverifiedTransaction {
 impl UnverifiedTransaction {
 	/// Used to compute hash of created transactions
 	fn compute_hash(mut self) -> UnverifiedTransaction {
-		let hash = keccak(&*self.rlp_bytes());
+		let hash = keccak(&self.encode());
 		self.hash = hash;
 		self
 	}
 
+	/// Decode an EIP-2718 envelope: the type byte followed by the type-specific payload.
+	fn decode_typed(bytes: &[u8]) -> Result<Self, DecoderError> {
+		let (type_byte, payload) = bytes.split_first().ok_or(DecoderError::RlpIsTooShort)?;
+		let tx_type = TypedTxId::from_u8(*type_byte)
+			.ok_or(DecoderError::Custom("Unknown transaction type"))?;
+
+		let d = Rlp::new(payload);
+		let info = d.payload_info()?;
+		if info.header_len + info.value_len != payload.len() {
+			return Err(DecoderError::RlpIsTooBig);
+		}
+		// EIP-1559 payloads carry the priority fee in front of the max fee.
+		let offset = match tx_type {
+			TypedTxId::EIP1559Transaction => 1,
+			_ => 0,
+		};
+		if d.item_count()? != 11 + offset {
+			return Err(DecoderError::RlpIncorrectListLen);
+		}
+		let chain_id: u64 = d.val_at(0)?;
+		let y_parity: u8 = d.val_at(8 + offset)?;
+		if y_parity > 1 {
+			return Err(DecoderError::Custom("Invalid signature y parity"));
+		}
+		let v = chain_id.checked_mul(2)
+			.and_then(|v| v.checked_add(35 + y_parity as u64))
+			.ok_or(DecoderError::Custom("Chain id is too big"))?;
+
+		let access_list_tx = AccessListTx {
+			transaction: Transaction {
+				nonce: d.val_at(1)?,
+				gas_price: d.val_at(2 + offset)?,
+				gas: d.val_at(3 + offset)?,
+				action: d.val_at(4 + offset)?,
+				value: d.val_at(5 + offset)?,
+				data: d.val_at(6 + offset)?,
+			},
+			access_list: d.list_at(7 + offset)?,
+		};
+		let unsigned = match tx_type {
+			TypedTxId::EIP1559Transaction => TypedTransaction::EIP1559Transaction(EIP1559TransactionTx {
+				transaction: access_list_tx,
+				max_priority_fee_per_gas: d.val_at(2)?,
+			}),
+			_ => TypedTransaction::AccessList(access_list_tx),
+		};
+
+		Ok(UnverifiedTransaction {
+			unsigned,
+			v,
+			r: d.val_at(9 + offset)?,
+			s: d.val_at(10 + offset)?,
+			hash: keccak(bytes),
+		})
+	}
+
+	/// Decode a raw transaction as accepted by `eth_sendRawTransaction`: either a legacy RLP list,
+	/// a bare EIP-2718 envelope or an envelope wrapped in an RLP string.
+	pub fn decode_raw(bytes: &[u8]) -> Result<Self, DecoderError> {
+		match bytes.first() {
+			None => Err(DecoderError::RlpIsTooShort),
+			Some(&b) if b <= 0x7f => UnverifiedTransaction::decode_typed(bytes),
+			Some(_) => rlp::decode(bytes),
+		}
+	}
+
+	/// Encode the transaction as committed to in the transactions trie and as returned by
+	/// `eth_getRawTransaction`: the RLP list for legacy transactions, the EIP-2718 envelope otherwise.
+	pub fn encode(&self) -> Bytes {
+		match self.unsigned {
+			TypedTransaction::Legacy(_) => {
+				let mut s = RlpStream::new();
+				self.rlp_append_legacy_transaction(&mut s);
+				s.out()
+			},
+			TypedTransaction::AccessList(ref t) => {
+				let mut s = RlpStream::new();
+				let chain_id = self.chain_id().unwrap_or(0);
+				t.rlp_append_payload(&mut s, chain_id, Some((self.standard_v(), &self.r, &self.s)));
+				let mut bytes = vec![TypedTxId::ACCESS_LIST_BYTE];
+				bytes.extend_from_slice(s.as_raw());
+				bytes
+			},
+			TypedTransaction::EIP1559Transaction(ref t) => {
+				let mut s = RlpStream::new();
+				let chain_id = self.chain_id().unwrap_or(0);
+				t.rlp_append_payload(&mut s, chain_id, Some((self.standard_v(), &self.r, &self.s)));
+				let mut bytes = vec![TypedTxId::EIP1559_BYTE];
+				bytes.extend_from_slice(s.as_raw());
+				bytes
+			},
+		}
+	}
+
+	/// EIP-2718 type of the transaction.
+	pub fn tx_type(&self) -> TypedTxId {
+		self.unsigned.tx_type()
+	}
+
+	/// The access list, if the transaction type has one.
+	pub fn access_list(&self) -> Option<&AccessList> {
+		self.unsigned.access_list()
+	}
+
+	/// Max fee per gas the sender is willing to pay.
+	pub fn max_fee_per_gas(&self) -> U256 {
+		self.unsigned.max_fee_per_gas()
+	}
+
+	/// Max tip per gas paid to the block author.
+	pub fn max_priority_fee_per_gas(&self) -> U256 {
+		self.unsigned.max_priority_fee_per_gas()
+	}
+
+	/// Price per gas actually paid by the sender in a block with the given base fee.
+	pub fn effective_gas_price(&self, base_fee: Option<U256>) -> U256 {
+		self.unsigned.effective_gas_price(base_fee)
+	}
+
+	/// Price per gas received by the block author in a block with the given base fee.
+	pub fn effective_priority_fee(&self, base_fee: Option<U256>) -> U256 {
+		self.unsigned.effective_priority_fee(base_fee)
+	}
+
 	/// Checks if the signature is empty.
 	pub fn is_unsigned(&self) -> bool {
 		self.r.is_zero() && self.s.is_zero()
@@ -323,7 +737,7 @@ impl UnverifiedTransaction {
 
 	/// Returns transaction receiver, if any
 	pub fn receiver(&self) -> Option<Address> {
-		match self.unsigned.action {
+		match self.action {
 			Action::Create => None,
 			Action::Call(receiver) => Some(receiver),
 		}
@@ -331,6 +745,13 @@ impl UnverifiedTransaction {
 
 	/// Append object with a signature into RLP stream
 	fn rlp_append_sealed_transaction(&self, s: &mut RlpStream) {
+		match self.unsigned {
+			TypedTransaction::Legacy(_) => self.rlp_append_legacy_transaction(s),
+			_ => { s.append(&self.encode()); },
+		}
+	}
+
+	fn rlp_append_legacy_transaction(&self, s: &mut RlpStream) {
 		s.begin_list(9);
 		s.append(&self.nonce);
 		s.append(&self.gas_price);
@@ -345,6 +766,11 @@ impl UnverifiedTransaction {
 
 	///	Reference to unsigned part of this transaction.
 	pub fn as_unsigned(&self) -> &Transaction {
+		self.unsigned.tx()
+	}
+
+	/// Reference to unsigned part of this transaction, including its type-specific fields.
+	pub fn as_typed(&self) -> &TypedTransaction {
 		&self.unsigned
 	}
 
@@ -675,6 +1101,103 @@ mod tests {
 		assert_eq!(t.chain_id(), Some(69));
 	}
 
+	#[test]
+	fn access_list_transaction_roundtrip() {
+		use parity_crypto::publickey::{Random, Generator};
+
+		let key = Random.generate().unwrap();
+		let t = TypedTransaction::AccessList(AccessListTx {
+			transaction: Transaction {
+				action: Action::Call(Address::from_low_u64_be(0x42)),
+				nonce: U256::from(42),
+				gas_price: U256::from(3000),
+				gas: U256::from(50_000),
+				value: U256::from(1),
+				data: b"Hello!".to_vec()
+			},
+			access_list: vec![AccessListItem {
+				address: Address::from_low_u64_be(0x42),
+				storage_keys: vec![H256::from_low_u64_be(1), H256::from_low_u64_be(2)],
+			}],
+		}).sign(&key.secret(), Some(69));
+		assert_eq!(Address::from(keccak(key.public())), t.sender());
+		assert_eq!(t.chain_id(), Some(69));
+		assert_eq!(t.tx_type(), TypedTxId::AccessList);
+
+		let raw = t.encode();
+		assert_eq!(raw[0], TypedTxId::ACCESS_LIST_BYTE);
+		assert_eq!(keccak(&raw), t.hash());
+
+		let from_raw = UnverifiedTransaction::decode_raw(&raw).unwrap();
+		assert_eq!(&from_raw, &*t);
+		let from_rlp: UnverifiedTransaction = rlp::decode(&rlp::encode(&*t)).unwrap();
+		assert_eq!(&from_rlp, &*t);
+		assert_eq!(SignedTransaction::new(from_rlp).unwrap().sender(), t.sender());
+	}
+
+	#[test]
+	fn eip1559_transaction_roundtrip() {
+		use parity_crypto::publickey::{Random, Generator};
+
+		let key = Random.generate().unwrap();
+		let t = TypedTransaction::EIP1559Transaction(EIP1559TransactionTx {
+			transaction: AccessListTx {
+				transaction: Transaction {
+					action: Action::Call(Address::from_low_u64_be(0x42)),
+					nonce: U256::from(42),
+					gas_price: U256::from(3000),
+					gas: U256::from(50_000),
+					value: U256::from(1),
+					data: b"Hello!".to_vec()
+				},
+				access_list: vec![],
+			},
+			max_priority_fee_per_gas: U256::from(200),
+		}).sign(&key.secret(), Some(69));
+		assert_eq!(Address::from(keccak(key.public())), t.sender());
+		assert_eq!(t.tx_type(), TypedTxId::EIP1559Transaction);
+
+		let raw = t.encode();
+		assert_eq!(raw[0], TypedTxId::EIP1559_BYTE);
+		assert_eq!(keccak(&raw), t.hash());
+
+		let from_raw = UnverifiedTransaction::decode_raw(&raw).unwrap();
+		assert_eq!(&from_raw, &*t);
+		let from_rlp: UnverifiedTransaction = rlp::decode(&rlp::encode(&*t)).unwrap();
+		assert_eq!(SignedTransaction::new(from_rlp).unwrap().sender(), t.sender());
+
+		assert_eq!(t.max_fee_per_gas(), U256::from(3000));
+		assert_eq!(t.max_priority_fee_per_gas(), U256::from(200));
+		assert_eq!(t.effective_gas_price(None), U256::from(3000));
+		assert_eq!(t.effective_gas_price(Some(U256::from(1000))), U256::from(1200));
+		assert_eq!(t.effective_priority_fee(Some(U256::from(1000))), U256::from(200));
+		assert_eq!(t.effective_gas_price(Some(U256::from(2900))), U256::from(3000));
+		assert_eq!(t.effective_priority_fee(Some(U256::from(2900))), U256::from(100));
+	}
+
+	#[test]
+	fn should_reject_unknown_transaction_type() {
+		assert_eq!(UnverifiedTransaction::decode_raw(&[0x7f, 0xc0]), Err(DecoderError::Custom("Unknown transaction type")));
+	}
+
+	#[test]
+	fn should_reject_typed_transaction_with_trailing_bytes() {
+		use parity_crypto::publickey::{Random, Generator};
+
+		let key = Random.generate().unwrap();
+		let t = TypedTransaction::AccessList(AccessListTx {
+			transaction: Transaction::default(),
+			access_list: vec![],
+		}).sign(&key.secret(), Some(69));
+		let mut raw = t.encode();
+		raw.push(0x80);
+
+		assert_eq!(UnverifiedTransaction::decode_raw(&raw), Err(DecoderError::RlpIsTooBig));
+		let mut s = RlpStream::new();
+		s.append(&raw);
+		assert_eq!(rlp::decode::<UnverifiedTransaction>(&s.out()), Err(DecoderError::RlpIsTooBig));
+	}
+
 	#[test]
 	fn should_agree_with_vitalik() {
 		let test_vector = |tx_data: &str, address: &'static str| {
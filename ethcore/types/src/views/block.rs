@@ -23,6 +23,7 @@ use header::Header;
 use transaction::{UnverifiedTransaction, LocalizedTransaction};
 use views::{TransactionView, HeaderView};
 use super::ViewRlp;
+use BlockNumber;
 
 /// View onto block rlp.
 pub struct BlockView<'a> {
@@ -62,9 +63,9 @@ impl<'a> BlockView<'a> {
 		&self.rlp
 	}
 
-	/// Create new Header object from header rlp.
-	pub fn header(&self) -> Header {
-		self.rlp.val_at(0)
+	/// Create new Header object from header rlp, with a base fee from block `eip1559_transition` on.
+	pub fn header(&self, eip1559_transition: BlockNumber) -> Header {
+		self.header_view().decode(eip1559_transition)
 	}
 
 	/// Return header rlp.
@@ -116,7 +117,7 @@ impl<'a> BlockView<'a> {
 
 	/// Return transaction hashes.
 	pub fn transaction_hashes(&self) -> Vec<H256> {
-		self.transactions_rlp().iter().map(|rlp| keccak(rlp.as_raw())).collect()
+		self.transaction_views().iter().map(TransactionView::hash).collect()
 	}
 
 	/// Returns transaction at given index without deserializing unnecessary data.
@@ -144,8 +145,8 @@ impl<'a> BlockView<'a> {
 	}
 
 	/// Return list of uncles of given block.
-	pub fn uncles(&self) -> Vec<Header> {
-		self.rlp.list_at(2)
+	pub fn uncles(&self, eip1559_transition: BlockNumber) -> Vec<Header> {
+		self.uncles_rlp().decode_with(|rlp| Header::decode_rlp_list(rlp, eip1559_transition))
 	}
 
 	/// Return number of uncles in given block, without deserializing them.
@@ -164,8 +165,8 @@ impl<'a> BlockView<'a> {
 	}
 
 	/// Return nth uncle.
-	pub fn uncle_at(&self, index: usize) -> Option<Header> {
-		self.uncles_rlp().iter().nth(index).map(|rlp| rlp.as_val())
+	pub fn uncle_at(&self, index: usize, eip1559_transition: BlockNumber) -> Option<Header> {
+		self.uncles_rlp().iter().nth(index).map(|rlp| HeaderView::new(rlp).decode(eip1559_transition))
 	}
 
 	/// Return nth uncle rlp.
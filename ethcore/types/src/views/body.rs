@@ -93,7 +93,7 @@ impl<'a> BodyView<'a> {
 
 	/// Return transaction hashes.
 	pub fn transaction_hashes(&self) -> Vec<H256> {
-		self.transactions_rlp().iter().map(|rlp| keccak(rlp.as_raw())).collect()
+		self.transaction_views().iter().map(TransactionView::hash).collect()
 	}
 
 	/// Returns transaction at given index without deserializing unnecessary data.
@@ -118,8 +118,8 @@ impl<'a> BodyView<'a> {
 	}
 
 	/// Return list of uncles of given block.
-	pub fn uncles(&self) -> Vec<Header> {
-		self.rlp.list_at(1)
+	pub fn uncles(&self, eip1559_transition: BlockNumber) -> Vec<Header> {
+		self.uncles_rlp().decode_with(|rlp| Header::decode_rlp_list(rlp, eip1559_transition))
 	}
 
 	/// Return number of uncles in given block, without deserializing them.
@@ -138,8 +138,8 @@ impl<'a> BodyView<'a> {
 	}
 
 	/// Return nth uncle.
-	pub fn uncle_at(&self, index: usize) -> Option<Header> {
-		self.uncles_rlp().iter().nth(index).map(|rlp| rlp.as_val())
+	pub fn uncle_at(&self, index: usize, eip1559_transition: BlockNumber) -> Option<Header> {
+		self.uncles_rlp().iter().nth(index).map(|rlp| HeaderView::new(rlp).decode(eip1559_transition))
 	}
 
 	/// Return nth uncle rlp.
@@ -21,6 +21,7 @@ use ethereum_types::{H256, Bloom, U256, Address};
 use hash::keccak;
 use rlp::{self};
 use super::ViewRlp;
+use header::Header;
 use BlockNumber;
 
 /// View onto block header rlp.
@@ -98,18 +99,41 @@ impl<'a> HeaderView<'a> {
 	/// Returns block extra data.
 	pub fn extra_data(&self) -> Bytes { self.rlp.val_at(12) }
 
-	/// Returns a vector of post-RLP-encoded seal fields.
-	pub fn seal(&self) -> Vec<Bytes> {
+	/// Decode the header, with a base fee from block `eip1559_transition` on.
+	pub fn decode(&self, eip1559_transition: BlockNumber) -> Header {
+		self.rlp.decode_with(|rlp| Header::decode_rlp(rlp, eip1559_transition))
+	}
+
+	/// Returns a vector of post-RLP-encoded seal fields. From block `eip1559_transition` on, the
+	/// last field is the base fee rather than part of the seal.
+	pub fn seal(&self, eip1559_transition: BlockNumber) -> Vec<Bytes> {
 		let mut seal = vec![];
-		for i in 13..self.rlp.item_count() {
+		for i in 13..self.seal_end(eip1559_transition) {
 			seal.push(self.rlp.at(i).as_raw().to_vec());
 		}
 		seal
 	}
 
+	/// Returns the EIP-1559 base fee per gas, if the block is at or after `eip1559_transition`.
+	pub fn base_fee_per_gas(&self, eip1559_transition: BlockNumber) -> Option<U256> {
+		if self.number() >= eip1559_transition {
+			Some(self.rlp.val_at(self.rlp.item_count() - 1))
+		} else {
+			None
+		}
+	}
+
+	/// Index past the last seal field; the base fee follows the seal.
+	fn seal_end(&self, eip1559_transition: BlockNumber) -> usize {
+		match self.base_fee_per_gas(eip1559_transition) {
+			Some(_) => self.rlp.item_count() - 1,
+			None => self.rlp.item_count(),
+		}
+	}
+
 	/// Returns a vector of seal fields (RLP-decoded).
-	pub fn decode_seal(&self) -> Result<Vec<Bytes>, rlp::DecoderError> {
-		let seal = self.seal();
+	pub fn decode_seal(&self, eip1559_transition: BlockNumber) -> Result<Vec<Bytes>, rlp::DecoderError> {
+		let seal = self.seal(eip1559_transition);
 		seal.into_iter()
 			.map(|s| rlp::Rlp::new(&s).data().map(|x| x.to_vec()))
 			.collect()
@@ -122,6 +146,7 @@ mod tests {
 	use rustc_hex::FromHex;
 	use ethereum_types::{Bloom, H256, Address};
 	use super::HeaderView;
+	use BlockNumber;
 	use std::str::FromStr;
 
 	#[test]
@@ -146,6 +171,6 @@ mod tests {
 		assert_eq!(view.gas_used(), 0x524d.into());
 		assert_eq!(view.timestamp(), 0x56_8e_93_2a);
 		assert_eq!(view.extra_data(), vec![] as Vec<u8>);
-		assert_eq!(view.seal(), vec![mix_hash, nonce]);
+		assert_eq!(view.seal(BlockNumber::max_value()), vec![mix_hash, nonce]);
 	}
 }
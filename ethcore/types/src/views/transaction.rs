@@ -18,11 +18,15 @@
 use bytes::Bytes;
 use ethereum_types::{H256, U256};
 use hash::keccak;
+use transaction::TypedTxId;
 use super::ViewRlp;
 
 /// View onto transaction rlp.
 pub struct TransactionView<'a> {
-	rlp: ViewRlp<'a>
+	rlp: ViewRlp<'a>,
+	/// The signed fields; for typed transactions the payload following the type byte.
+	fields: ViewRlp<'a>,
+	tx_type: TypedTxId,
 }
 
 impl<'a> TransactionView<'a> {
@@ -43,8 +47,20 @@ impl<'a> TransactionView<'a> {
 	/// }
 	/// ```
 	pub fn new(rlp: ViewRlp<'a>) -> TransactionView<'a> {
+		let (fields, tx_type) = if rlp.is_list() {
+			(rlp.nested(rlp.as_raw()), TypedTxId::Legacy)
+		} else {
+			let envelope = rlp.data();
+			let tx_type = envelope.first()
+				.and_then(|b| TypedTxId::from_u8(*b))
+				.expect("View rlp is trusted and should be a known transaction type; qed");
+			(rlp.nested(&envelope[1..]), tx_type)
+		};
+
 		TransactionView {
-			rlp: rlp
+			rlp,
+			fields,
+			tx_type,
 		}
 	}
 
@@ -55,32 +71,63 @@ impl<'a> TransactionView<'a> {
 
 	/// Returns transaction hash.
 	pub fn hash(&self) -> H256 {
-		keccak(self.rlp.as_raw())
+		match self.tx_type {
+			TypedTxId::Legacy => keccak(self.rlp.as_raw()),
+			_ => keccak(self.rlp.data()),
+		}
+	}
+
+	/// Get the EIP-2718 type of the transaction.
+	pub fn tx_type(&self) -> TypedTxId { self.tx_type }
+
+	/// Index of the nonce field; typed transactions start with the chain id.
+	fn offset(&self) -> usize {
+		match self.tx_type {
+			TypedTxId::Legacy => 0,
+			_ => 1,
+		}
+	}
+
+	/// Index of the gas price (max fee) field; EIP-1559 transactions have the priority fee before it.
+	fn price_offset(&self) -> usize {
+		match self.tx_type {
+			TypedTxId::EIP1559Transaction => self.offset() + 2,
+			_ => self.offset() + 1,
+		}
+	}
+
+	/// Index of the first signature field; typed transactions have an access list before it.
+	fn signature_offset(&self) -> usize {
+		match self.tx_type {
+			TypedTxId::Legacy => 6,
+			TypedTxId::AccessList => 8,
+			TypedTxId::EIP1559Transaction => 9,
+		}
 	}
 
 	/// Get the nonce field of the transaction.
-	pub fn nonce(&self) -> U256 { self.rlp.val_at(0) }
+	pub fn nonce(&self) -> U256 { self.fields.val_at(self.offset()) }
 
-	/// Get the gas_price field of the transaction.
-	pub fn gas_price(&self) -> U256 { self.rlp.val_at(1) }
+	/// Get the gas_price field of the transaction; the max fee per gas for EIP-1559 transactions.
+	pub fn gas_price(&self) -> U256 { self.fields.val_at(self.price_offset()) }
 
 	/// Get the gas field of the transaction.
-	pub fn gas(&self) -> U256 { self.rlp.val_at(2) }
+	pub fn gas(&self) -> U256 { self.fields.val_at(self.price_offset() + 1) }
 
 	/// Get the value field of the transaction.
-	pub fn value(&self) -> U256 { self.rlp.val_at(4) }
+	pub fn value(&self) -> U256 { self.fields.val_at(self.price_offset() + 3) }
 
 	/// Get the data field of the transaction.
-	pub fn data(&self) -> Bytes { self.rlp.val_at(5) }
+	pub fn data(&self) -> Bytes { self.fields.val_at(self.price_offset() + 4) }
 
-	/// Get the v field of the transaction.
-	pub fn v(&self) -> u8 { let r: u16 = self.rlp.val_at(6); r as u8 }
+	/// Get the v field of the transaction; the y parity for typed transactions.
+	pub fn v(&self) -> u8 { let r: u16 = self.fields.val_at(self.signature_offset()); r as u8 }
 
 	/// Get the r field of the transaction.
-	pub fn r(&self) -> U256 { self.rlp.val_at(7) }
+	pub fn r(&self) -> U256 { self.fields.val_at(self.signature_offset() + 1) }
 
 	/// Get the s field of the transaction.
-	pub fn s(&self) -> U256 { self.rlp.val_at(8) }
+	pub fn s(&self) -> U256 { self.fields.val_at(self.signature_offset() + 2) }
 }
 
 #[cfg(test)]
@@ -77,6 +77,11 @@ impl<'a, 'view> ViewRlp<'a> where 'a : 'view {
 		self.expect_valid_rlp(self.rlp.as_val())
 	}
 
+	/// Returns the value decoded from this rlp with `decode`, panics if rlp not valid
+	pub fn decode_with<T, F>(&self, decode: F) -> T where F: FnOnce(&Rlp<'a>) -> Result<T, DecoderError> {
+		self.expect_valid_rlp(decode(&self.rlp))
+	}
+
 	/// Returns decoded value at the given index, panics not present or valid at that index
 	pub fn val_at<T>(&self, index: usize) -> T where T : Decodable {
 		self.expect_valid_rlp(self.rlp.val_at(index))
@@ -96,6 +101,21 @@ impl<'a, 'view> ViewRlp<'a> where 'a : 'view {
 	pub fn as_raw(&'view self) -> &'a [u8] {
 		self.rlp.as_raw()
 	}
+
+	/// Returns whether the rlp is a list
+	pub fn is_list(&self) -> bool {
+		self.rlp.is_list()
+	}
+
+	/// Returns the payload of a data item, panics if the rlp is not a valid data item
+	pub fn data(&self) -> &'a [u8] {
+		self.expect_valid_rlp(self.rlp.data())
+	}
+
+	/// Returns a view onto other rlp bytes, maintaining debug info
+	pub fn nested(&self, bytes: &'a [u8]) -> ViewRlp<'a> {
+		self.new_from_rlp(Rlp::new(bytes))
+	}
 }
 
 /// Iterator over rlp-slice list elements.
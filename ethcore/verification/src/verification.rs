@@ -39,6 +39,7 @@ use common_types::{
 	errors::{EthcoreError as Error, BlockError},
 	engines::MAX_UNCLE_AGE,
 	block::PreverifiedBlock,
+	transaction::typed_envelope_bytes,
 	verification::Unverified,
 };
 
@@ -228,7 +229,7 @@ fn verify_uncles(block: &PreverifiedBlock, bc: &dyn BlockProvider, engine: &dyn
 				return Err(From::from(BlockError::UncleParentNotInChain(uncle_parent.hash())));
 			}
 
-			let uncle_parent = uncle_parent.decode()?;
+			let uncle_parent = uncle_parent.decode(engine.params().eip1559_transition)?;
 			verify_parent(&uncle, &uncle_parent, engine)?;
 			engine.verify_block_family(&uncle, &uncle_parent)?;
 			verified.insert(uncle.hash());
@@ -368,7 +369,7 @@ fn verify_parent(header: &Header, parent: &Header, engine: &dyn Engine) -> Resul
 	assert!(header.parent_hash().is_zero() || &parent.hash() == header.parent_hash(),
 			"Parent hash should already have been verified; qed");
 
-	if !engine.is_timestamp_valid(header.timestamp(), parent.timestamp()) {
+	if !engine.is_timestamp_valid(header, parent) {
 		let now = SystemTime::now();
 		let min = CheckedSystemTime::checked_add(now, Duration::from_secs(parent.timestamp().saturating_add(1)))
 			.ok_or(BlockError::TimestampOverflow)?;
@@ -392,7 +393,7 @@ fn verify_parent(header: &Header, parent: &Header, engine: &dyn Engine) -> Resul
 	}
 	if engine.gas_limit_override(header).is_none() {
 		let gas_limit_divisor = engine.params().gas_limit_bound_divisor;
-		let parent_gas_limit = *parent.gas_limit();
+		let parent_gas_limit = engine.machine().parent_gas_limit(parent);
 		let min_gas = parent_gas_limit - parent_gas_limit / gas_limit_divisor;
 		let max_gas = parent_gas_limit + parent_gas_limit / gas_limit_divisor;
 		if header.gas_limit() <= &min_gas || header.gas_limit() >= &max_gas {
@@ -404,6 +405,14 @@ fn verify_parent(header: &Header, parent: &Header, engine: &dyn Engine) -> Resul
 		}
 	}
 
+	let expected_base_fee = engine.machine().calc_base_fee(parent);
+	if header.base_fee_per_gas() != expected_base_fee {
+		return Err(From::from(BlockError::InvalidBaseFee(Mismatch {
+			expected: expected_base_fee.unwrap_or_default(),
+			found: header.base_fee_per_gas().unwrap_or_default(),
+		})));
+	}
+
 	Ok(())
 }
 
@@ -411,7 +420,7 @@ fn verify_parent(header: &Header, parent: &Header, engine: &dyn Engine) -> Resul
 fn verify_block_integrity(block: &Unverified) -> Result<(), Error> {
 	let block_rlp = Rlp::new(&block.bytes);
 	let tx = block_rlp.at(1)?;
-	let expected_root = ordered_trie_root(tx.iter().map(|r| r.as_raw()));
+	let expected_root = ordered_trie_root(tx.iter().map(|r| typed_envelope_bytes(&r)));
 	if &expected_root != block.header.transactions_root() {
 		return Err(BlockError::InvalidTransactionsRoot(Mismatch {
 			expected: expected_root,
@@ -479,12 +488,12 @@ mod tests {
 	}
 
 	fn basic_test(bytes: &[u8], engine: &dyn Engine) -> Result<(), Error> {
-		let unverified = Unverified::from_rlp(bytes.to_vec())?;
+		let unverified = Unverified::from_rlp(bytes.to_vec(), engine.params().eip1559_transition)?;
 		verify_block_basic(&unverified, engine, true)
 	}
 
 	fn family_test<BC>(bytes: &[u8], engine: &dyn Engine, bc: &BC) -> Result<(), Error> where BC: BlockProvider {
-		let block = Unverified::from_rlp(bytes.to_vec()).unwrap();
+		let block = Unverified::from_rlp(bytes.to_vec(), engine.params().eip1559_transition).unwrap();
 		let header = block.header;
 		let transactions: Vec<_> = block.transactions
 			.into_iter()
@@ -498,7 +507,7 @@ mod tests {
 		let client = TestBlockChainClient::default();
 		let parent = bc.block_header_data(header.parent_hash())
 			.ok_or(BlockError::UnknownParent(*header.parent_hash()))?
-			.decode()?;
+			.decode(engine.params().eip1559_transition)?;
 
 		let block = PreverifiedBlock {
 			header,
@@ -516,7 +525,7 @@ mod tests {
 	}
 
 	fn unordered_test(bytes: &[u8], engine: &dyn Engine) -> Result<(), Error> {
-		let un = Unverified::from_rlp(bytes.to_vec())?;
+		let un = Unverified::from_rlp(bytes.to_vec(), engine.params().eip1559_transition)?;
 		verify_block_unordered(un, engine, false)?;
 		Ok(())
 	}
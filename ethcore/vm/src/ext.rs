@@ -160,6 +160,18 @@ pub trait Ext {
 	/// Decrements sstore refunds counter.
 	fn sub_sstore_refund(&mut self, value: usize);
 
+	/// Check whether the account was already accessed in the current transaction (EIP-2929).
+	fn is_address_warm(&self, address: &Address) -> bool;
+
+	/// Mark the account as accessed in the current transaction (EIP-2929).
+	fn warm_address(&mut self, address: Address);
+
+	/// Check whether the storage slot of the current account was already accessed in the current transaction (EIP-2929).
+	fn is_storage_warm(&self, key: &H256) -> bool;
+
+	/// Mark the storage slot of the current account as accessed in the current transaction (EIP-2929).
+	fn warm_storage(&mut self, key: H256);
+
 	/// Decide if any more operations should be traced. Passthrough for the VM trace.
 	fn trace_next_instruction(&mut self, _pc: usize, _instruction: u8, _current_gas: U256) -> bool { false }
 
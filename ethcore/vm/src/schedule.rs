@@ -97,6 +97,10 @@ pub struct Schedule {
 	pub tx_data_zero_gas: usize,
 	/// Additional cost for non-empty data transaction
 	pub tx_data_non_zero_gas: usize,
+	/// Additional cost for every address in a transaction access list
+	pub tx_access_list_address_gas: usize,
+	/// Additional cost for every storage key in a transaction access list
+	pub tx_access_list_storage_key_gas: usize,
 	/// Gas price for copying memory
 	pub copy_gas: usize,
 	/// Price of EXTCODESIZE
@@ -136,6 +140,20 @@ pub struct Schedule {
 	pub eip1283: bool,
 	/// Enable EIP-1706 rules
 	pub eip1706: bool,
+	/// Enable EIP-2718 typed transaction envelopes
+	pub eip2718: bool,
+	/// Enable EIP-2930 access list transactions
+	pub eip2930: bool,
+	/// Enable EIP-1559 dynamic fee transactions and the block base fee
+	pub eip1559: bool,
+	/// Enable EIP-2929 rules: cold/warm pricing of state access
+	pub eip2929: bool,
+	/// Gas for the first access to a storage slot in a transaction (EIP-2929)
+	pub cold_sload_gas: usize,
+	/// Gas for the first access to an account in a transaction (EIP-2929)
+	pub cold_account_access_gas: usize,
+	/// Gas for accessing an already accessed account or storage slot (EIP-2929)
+	pub warm_storage_read_gas: usize,
 	/// VM execution does not increase null signed address nonce if this field is true.
 	pub keep_unsigned_nonce: bool,
 	/// Latest VM version for contract creation transaction.
@@ -264,6 +282,8 @@ impl Schedule {
 			tx_create_gas: 53000,
 			tx_data_zero_gas: 4,
 			tx_data_non_zero_gas: 68,
+			tx_access_list_address_gas: 2400,
+			tx_access_list_storage_key_gas: 1900,
 			copy_gas: 3,
 			extcodesize_gas: 700,
 			extcodecopy_base_gas: 700,
@@ -279,6 +299,13 @@ impl Schedule {
 			kill_dust: CleanDustMode::Off,
 			eip1283: false,
 			eip1706: false,
+			eip2718: false,
+			eip2930: false,
+			eip1559: false,
+			eip2929: false,
+			cold_sload_gas: 2100,
+			cold_account_access_gas: 2600,
+			warm_storage_read_gas: 100,
 			keep_unsigned_nonce: false,
 			latest_version: U256::zero(),
 			versions: HashMap::new(),
@@ -315,6 +342,27 @@ impl Schedule {
 		schedule
 	}
 
+	/// Schedule for the Berlin fork of the Ethereum main net.
+	pub fn new_berlin() -> Schedule {
+		let mut schedule = Self::new_istanbul();
+		schedule.eip2718 = true; // EIP 2718
+		schedule.eip2930 = true; // EIP 2930
+		schedule.enable_eip2929();
+		schedule
+	}
+
+	/// Switch to EIP-2929 pricing: state access costs depend on whether the account or the storage
+	/// slot was already accessed in the current transaction.
+	pub fn enable_eip2929(&mut self) {
+		if self.eip2929 {
+			return;
+		}
+		self.eip2929 = true;
+		self.sload_gas = self.warm_storage_read_gas;
+		self.sstore_dirty_gas = Some(self.warm_storage_read_gas);
+		self.sstore_reset_gas -= self.cold_sload_gas;
+	}
+
 	fn new(efcd: bool, hdc: bool, tcg: usize) -> Schedule {
 		Schedule {
 			exceptional_failed_code_deposit: efcd,
@@ -356,6 +404,8 @@ impl Schedule {
 			tx_create_gas: tcg,
 			tx_data_zero_gas: 4,
 			tx_data_non_zero_gas: 68,
+			tx_access_list_address_gas: 2400,
+			tx_access_list_storage_key_gas: 1900,
 			copy_gas: 3,
 			extcodesize_gas: 20,
 			extcodecopy_base_gas: 20,
@@ -371,6 +421,13 @@ impl Schedule {
 			kill_dust: CleanDustMode::Off,
 			eip1283: false,
 			eip1706: false,
+			eip2718: false,
+			eip2930: false,
+			eip1559: false,
+			eip2929: false,
+			cold_sload_gas: 2100,
+			cold_account_access_gas: 2600,
+			warm_storage_read_gas: 100,
 			keep_unsigned_nonce: false,
 			latest_version: U256::zero(),
 			versions: HashMap::new(),
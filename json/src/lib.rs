@@ -21,6 +21,7 @@
 pub mod bytes;
 pub mod hash;
 pub mod maybe;
+pub mod slashing_protection;
 pub mod spec;
 pub mod uint;
 pub mod vm;
@@ -0,0 +1,91 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Parity Ethereum.
+
+// Parity Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Slashing-protection interchange format, for moving the signing history of validators
+//! between machines.
+
+use crate::{hash::Address, uint::Uint};
+use serde::{Deserialize, Serialize};
+
+/// Version of the interchange format.
+pub const INTERCHANGE_VERSION: u64 = 1;
+
+/// Highest steps signed by a validator.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[serde(rename_all = "camelCase")]
+pub struct SignedSteps {
+	/// Signer address.
+	pub address: Address,
+	/// Highest step a block was signed for.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub last_block_step: Option<Uint>,
+	/// Highest step an empty step message was signed for.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub last_empty_step: Option<Uint>,
+}
+
+/// Slashing-protection records of all validators.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Interchange {
+	/// Version of the format.
+	pub version: Uint,
+	/// Records of the validators.
+	pub signers: Vec<SignedSteps>,
+}
+
+#[cfg(test)]
+mod tests {
+	use std::str::FromStr;
+	use ethereum_types::{H160, U256};
+	use super::{Interchange, SignedSteps};
+	use crate::{hash::Address, uint::Uint};
+
+	#[test]
+	fn interchange_roundtrip() {
+		let s = r#"{
+			"version": 1,
+			"signers": [{
+				"address": "0xc6d9d2cd449a754c494264e1809c50e34d64562b",
+				"lastBlockStep": "0x10",
+				"lastEmptyStep": 15
+			}, {
+				"address": "0x0000000000000000000000000000000000000001"
+			}]
+		}"#;
+
+		let deserialized: Interchange = serde_json::from_str(s).unwrap();
+		assert_eq!(deserialized, Interchange {
+			version: Uint(U256::from(1)),
+			signers: vec![
+				SignedSteps {
+					address: Address(H160::from_str("c6d9d2cd449a754c494264e1809c50e34d64562b").unwrap()),
+					last_block_step: Some(Uint(U256::from(16))),
+					last_empty_step: Some(Uint(U256::from(15))),
+				},
+				SignedSteps {
+					address: Address(H160::from_low_u64_be(1)),
+					last_block_step: None,
+					last_empty_step: None,
+				},
+			],
+		});
+
+		let serialized = serde_json::to_string(&deserialized).unwrap();
+		assert_eq!(serde_json::from_str::<Interchange>(&serialized).unwrap(), deserialized);
+	}
+}
@@ -95,11 +95,16 @@ pub struct AuthorityRoundParams {
 	pub strict_empty_steps_transition: Option<Uint>,
 	/// First block for which a 2/3 quorum (instead of 1/2) is required.
 	pub two_thirds_majority_transition: Option<Uint>,
+	/// First block for which validators broadcast signed finality votes.
+	pub finality_votes_transition: Option<Uint>,
 	/// The random number contract's address, or a map of contract transitions.
 	pub randomness_contract_address: Option<BTreeMap<Uint, Address>>,
 	/// The addresses of contracts that determine the block gas limit starting from the block number
 	/// associated with each of those contracts.
 	pub block_gas_limit_contract_transitions: Option<BTreeMap<Uint, Address>>,
+	/// The addresses of contracts that schedule step duration changes starting from the block
+	/// number associated with each of those contracts.
+	pub step_duration_contract_transitions: Option<BTreeMap<Uint, Address>>,
 	/// The block number at which the consensus engine switches from AuRa to AuRa with POSDAO
 	/// modifications.
 	pub posdao_transition: Option<Uint>,
@@ -143,6 +148,9 @@ mod tests {
 				"blockGasLimitContractTransitions": {
 					"10": "0x1000000000000000000000000000000000000001",
 					"20": "0x2000000000000000000000000000000000000002"
+				},
+				"stepDurationContractTransitions": {
+					"30": "0x3000000000000000000000000000000000000003"
 				}
 			}
 		}"#;
@@ -167,5 +175,7 @@ mod tests {
 			 (Uint(20.into()), Address(H160::from_str("2000000000000000000000000000000000000002").unwrap()))];
 		assert_eq!(deserialized.params.block_gas_limit_contract_transitions,
 				   Some(expected_bglc.to_vec().into_iter().collect()));
+		assert_eq!(deserialized.params.step_duration_contract_transitions,
+				   Some(vec![(Uint(30.into()), Address(H160::from_str("3000000000000000000000000000000000000003").unwrap()))].into_iter().collect()));
 	}
 }
@@ -16,7 +16,7 @@
 
 //! Engine deserialization.
 
-use super::{Ethash, BasicAuthority, AuthorityRound, NullEngine, InstantSeal, Clique};
+use super::{Ethash, BasicAuthority, AuthorityRound, NullEngine, InstantSeal, Clique, Tendermint};
 use serde::Deserialize;
 
 /// Engine deserialization.
@@ -36,7 +36,9 @@ pub enum Engine {
 	/// AuthorityRound engine.
 	AuthorityRound(AuthorityRound),
 	/// Clique engine.
-	Clique(Clique)
+	Clique(Clique),
+	/// Tendermint engine.
+	Tendermint(Tendermint),
 }
 
 #[cfg(test)]
@@ -146,5 +148,20 @@ mod tests {
 			Engine::Clique(_) => {}, // Clique is unit tested in its own file.
 			_ => panic!(),
 		};
+
+		let s = r#"{
+			"tendermint": {
+				"params": {
+					"validators": {
+						"list" : ["0xc6d9d2cd449a754c494264e1809c50e34d64562b"]
+					}
+				}
+			}
+		}"#;
+		let deserialized: Engine = serde_json::from_str(s).unwrap();
+		match deserialized {
+			Engine::Tendermint(_) => {}, // Tendermint is unit tested in its own file.
+			_ => panic!(),
+		};
 	}
 }
@@ -32,6 +32,7 @@ pub mod null_engine;
 pub mod instant_seal;
 pub mod hardcoded_sync;
 pub mod clique;
+pub mod tendermint;
 pub mod step_duration;
 
 pub use self::account::Account;
@@ -43,10 +44,11 @@ pub use self::seal::{Seal, Ethereum, AuthorityRoundSeal, TendermintSeal};
 pub use self::engine::Engine;
 pub use self::state::{State, HashOrMap};
 pub use self::ethash::{Ethash, EthashParams, BlockReward};
-pub use self::validator_set::ValidatorSet;
+pub use self::validator_set::{ValidatorSet, ValidatorFile};
 pub use self::basic_authority::{BasicAuthority, BasicAuthorityParams};
 pub use self::authority_round::{AuthorityRound, AuthorityRoundParams};
 pub use self::clique::{Clique, CliqueParams};
+pub use self::tendermint::{Tendermint, TendermintParams};
 pub use self::null_engine::{NullEngine, NullEngineParams};
 pub use self::instant_seal::{InstantSeal, InstantSealParams};
 pub use self::hardcoded_sync::HardcodedSync;
@@ -24,7 +24,7 @@ use crate::{
 use serde::Deserialize;
 
 /// Spec params.
-#[derive(Debug, PartialEq, Deserialize)]
+#[derive(Debug, PartialEq, Deserialize, Clone)]
 #[serde(deny_unknown_fields)]
 #[serde(rename_all = "camelCase")]
 pub struct Params {
@@ -109,6 +109,26 @@ pub struct Params {
 	/// See `CommonParams` docs.
 	pub eip2200_advance_transition: Option<Uint>,
 	/// See `CommonParams` docs.
+	pub eip2718_transition: Option<Uint>,
+	/// See `CommonParams` docs.
+	pub eip2930_transition: Option<Uint>,
+	/// See `CommonParams` docs.
+	pub eip2929_transition: Option<Uint>,
+	/// See `CommonParams` docs.
+	pub eip1559_transition: Option<Uint>,
+	/// See `CommonParams` docs.
+	#[serde(default, deserialize_with="uint::validate_optional_non_zero")]
+	pub eip1559_base_fee_max_change_denominator: Option<Uint>,
+	/// See `CommonParams` docs.
+	#[serde(default, deserialize_with="uint::validate_optional_non_zero")]
+	pub eip1559_elasticity_multiplier: Option<Uint>,
+	/// See `CommonParams` docs.
+	pub eip1559_base_fee_initial_value: Option<Uint>,
+	/// See `CommonParams` docs.
+	pub eip1559_fee_collector: Option<Address>,
+	/// See `CommonParams` docs.
+	pub eip1559_fee_collector_transition: Option<Uint>,
+	/// See `CommonParams` docs.
 	pub dust_protection_transition: Option<Uint>,
 	/// See `CommonParams` docs.
 	pub nonce_cap_increment: Option<Uint>,
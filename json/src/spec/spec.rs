@@ -16,8 +16,10 @@
 
 //! Spec deserialization.
 
+use std::collections::BTreeMap;
 use std::io::Read;
 use crate::spec::{Params, Genesis, Engine, State, HardcodedSync};
+use crate::uint::Uint;
 use serde::Deserialize;
 use serde_json::Error;
 
@@ -61,6 +63,8 @@ pub struct Spec {
 	pub data_dir: Option<String>,
 	/// Engine.
 	pub engine: Engine,
+	/// Engines replacing the engine from the given blocks on.
+	pub engine_transitions: Option<BTreeMap<Uint, Engine>>,
 	/// Spec params.
 	pub params: Params,
 	/// Genesis header.
@@ -82,7 +86,8 @@ impl Spec {
 
 #[cfg(test)]
 mod tests {
-	use super::Spec;
+	use ethereum_types::U256;
+	use super::{Engine, Spec, Uint};
 
 	#[test]
 	fn should_error_on_unknown_fields() {
@@ -167,6 +172,11 @@ mod tests {
 				}
 			}
 		},
+		"engineTransitions": {
+			"0x10": {
+				"instantSeal": null
+			}
+		},
 		"params": {
 			"accountStartNonce": "0x0100000",
 			"maximumExtraDataSize": "0x20",
@@ -257,7 +267,9 @@ mod tests {
 			]
 		}
 		}"#;
-		let _deserialized: Spec = serde_json::from_str(s).unwrap();
+		let deserialized: Spec = serde_json::from_str(s).unwrap();
+		let transitions = deserialized.engine_transitions.unwrap();
+		assert_eq!(transitions.get(&Uint(U256::from(16))), Some(&Engine::InstantSeal(None)));
 		// TODO: validate all fields
 	}
 }
@@ -0,0 +1,79 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Parity Ethereum.
+
+// Parity Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Tendermint params deserialization.
+
+use crate::uint::Uint;
+use super::ValidatorSet;
+use serde::Deserialize;
+
+/// Tendermint params deserialization.
+#[derive(Debug, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[serde(rename_all = "camelCase")]
+pub struct TendermintParams {
+	/// Valid validators.
+	pub validators: ValidatorSet,
+	/// Propose step timeout in milliseconds.
+	pub timeout_propose: Option<Uint>,
+	/// Prevote step timeout in milliseconds.
+	pub timeout_prevote: Option<Uint>,
+	/// Precommit step timeout in milliseconds.
+	pub timeout_precommit: Option<Uint>,
+	/// Time to wait after a commit before proposing the next block, in milliseconds.
+	pub timeout_commit: Option<Uint>,
+	/// Reward per block in wei.
+	pub block_reward: Option<Uint>,
+	/// Block from which the validator set contracts are called as in POSDAO.
+	pub posdao_transition: Option<Uint>,
+}
+
+/// Tendermint engine deserialization.
+#[derive(Debug, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Tendermint {
+	/// Tendermint params.
+	pub params: TendermintParams,
+}
+
+#[cfg(test)]
+mod tests {
+	use std::str::FromStr;
+	use super::{Tendermint, Uint};
+	use ethereum_types::{U256, H160};
+	use crate::{hash::Address, spec::validator_set::ValidatorSet};
+
+	#[test]
+	fn tendermint_deserialization() {
+		let s = r#"{
+			"params": {
+				"validators": {
+					"list": ["0xc6d9d2cd449a754c494264e1809c50e34d64562b"]
+				},
+				"timeoutPropose": 3000,
+				"timeoutCommit": "0x1f4"
+			}
+		}"#;
+
+		let deserialized: Tendermint = serde_json::from_str(s).unwrap();
+		let vs = ValidatorSet::List(vec![Address(H160::from_str("c6d9d2cd449a754c494264e1809c50e34d64562b").unwrap())]);
+		assert_eq!(deserialized.params.validators, vs);
+		assert_eq!(deserialized.params.timeout_propose, Some(Uint(U256::from(3000))));
+		assert_eq!(deserialized.params.timeout_prevote, None);
+		assert_eq!(deserialized.params.timeout_commit, Some(Uint(U256::from(500))));
+		assert_eq!(deserialized.params.block_reward, None);
+	}
+}
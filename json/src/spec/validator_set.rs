@@ -17,6 +17,8 @@
 //! Validator set deserialization.
 
 use std::collections::BTreeMap;
+use std::io::Read;
+use std::path::PathBuf;
 use crate::{hash::Address, uint::Uint};
 use serde::Deserialize;
 
@@ -33,12 +35,32 @@ pub enum ValidatorSet {
 	Contract(Address),
 	/// A map of starting blocks for each validator set.
 	Multi(BTreeMap<Uint, ValidatorSet>),
+	/// Path of a local file with the list of authorities, re-read when it changes.
+	File(PathBuf),
+}
+
+/// Contents of a validator list file.
+#[derive(Debug, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum ValidatorFile {
+	/// A single list of authorities.
+	List(Vec<Address>),
+	/// A map of the blocks signalling each list of authorities.
+	Transitions(BTreeMap<Uint, Vec<Address>>),
+}
+
+impl ValidatorFile {
+	/// Loads a validator list file from json.
+	pub fn load<R>(reader: R) -> Result<Self, serde_json::Error> where R: Read {
+		serde_json::from_reader(reader)
+	}
 }
 
 #[cfg(test)]
 mod tests {
 	use std::str::FromStr;
-	use super::{Address, Uint, ValidatorSet};
+	use std::path::PathBuf;
+	use super::{Address, Uint, ValidatorFile, ValidatorSet};
 	use ethereum_types::{H160, U256};
 
 	#[test]
@@ -55,10 +77,12 @@ mod tests {
 				"10": { "list": ["0xd6d9d2cd449a754c494264e1809c50e34d64562b"] },
 				"20": { "contract": "0xc6d9d2cd449a754c494264e1809c50e34d64562b" }
 			}
+		}, {
+			"file": "/etc/parity/validators.json"
 		}]"#;
 
 		let deserialized: Vec<ValidatorSet> = serde_json::from_str(s).unwrap();
-		assert_eq!(deserialized.len(), 4);
+		assert_eq!(deserialized.len(), 5);
 
 		assert_eq!(deserialized[0], ValidatorSet::List(vec![Address(H160::from_str("c6d9d2cd449a754c494264e1809c50e34d64562b").unwrap())]));
 		assert_eq!(deserialized[1], ValidatorSet::SafeContract(Address(H160::from_str("c6d9d2cd449a754c494264e1809c50e34d64562b").unwrap())));
@@ -72,5 +96,26 @@ mod tests {
 			},
 			_ => assert!(false),
 		}
+		assert_eq!(deserialized[4], ValidatorSet::File(PathBuf::from("/etc/parity/validators.json")));
+	}
+
+	#[test]
+	fn validator_file_deserialization() {
+		let list = r#"["0xc6d9d2cd449a754c494264e1809c50e34d64562b"]"#;
+		let address = Address(H160::from_str("c6d9d2cd449a754c494264e1809c50e34d64562b").unwrap());
+		assert_eq!(ValidatorFile::load(list.as_bytes()).unwrap(), ValidatorFile::List(vec![address.clone()]));
+
+		let transitions = r#"{
+			"0": ["0xc6d9d2cd449a754c494264e1809c50e34d64562b"],
+			"0x10": ["0xd6d9d2cd449a754c494264e1809c50e34d64562b"]
+		}"#;
+		match ValidatorFile::load(transitions.as_bytes()).unwrap() {
+			ValidatorFile::Transitions(ref map) => {
+				assert_eq!(map.len(), 2);
+				assert_eq!(map[&Uint(U256::from(0))], vec![address]);
+				assert_eq!(map[&Uint(U256::from(16))], vec![Address(H160::from_str("d6d9d2cd449a754c494264e1809c50e34d64562b").unwrap())]);
+			},
+			_ => assert!(false),
+		}
 	}
 }
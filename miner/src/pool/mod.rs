@@ -117,6 +117,11 @@ pub trait ScoredTransaction {
 	/// Gets transaction gas price.
 	fn gas_price(&self) -> &U256;
 
+	/// Gets the gas price received by the block author in a block with the given base fee.
+	fn effective_priority_fee(&self, _block_base_fee: Option<U256>) -> U256 {
+		*self.gas_price()
+	}
+
 	/// Gets transaction nonce.
 	fn nonce(&self) -> U256;
 }
@@ -194,6 +199,10 @@ impl ScoredTransaction for VerifiedTransaction {
 		&self.transaction.gas_price
 	}
 
+	fn effective_priority_fee(&self, block_base_fee: Option<U256>) -> U256 {
+		self.transaction.effective_priority_fee(block_base_fee)
+	}
+
 	/// Gets transaction nonce.
 	fn nonce(&self) -> U256 {
 		self.transaction.nonce
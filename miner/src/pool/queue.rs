@@ -19,7 +19,7 @@
 use std::{cmp, fmt};
 use std::sync::Arc;
 use std::sync::atomic::{self, AtomicUsize};
-use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 
 use ethereum_types::{H256, U256, Address};
 use futures::sync::mpsc;
@@ -204,6 +204,8 @@ pub struct TransactionQueue {
 	options: RwLock<verifier::Options>,
 	cached_pending: RwLock<CachedPending>,
 	recently_rejected: RecentlyRejected,
+	block_base_fee: Arc<RwLock<Option<U256>>>,
+	penalties: Arc<RwLock<HashMap<Address, usize>>>,
 }
 
 impl TransactionQueue {
@@ -214,15 +216,40 @@ impl TransactionQueue {
 		strategy: PrioritizationStrategy,
 	) -> Self {
 		let max_count = limits.max_count;
+		let scoring = scoring::NonceAndGasPrice::new(strategy);
+		let block_base_fee = scoring.block_base_fee.clone();
+		let penalties = scoring.penalties.clone();
 		TransactionQueue {
 			insertion_id: Default::default(),
-			pool: RwLock::new(txpool::Pool::new(Default::default(), scoring::NonceAndGasPrice(strategy), limits)),
+			pool: RwLock::new(txpool::Pool::new(Default::default(), scoring, limits)),
 			options: RwLock::new(verification_options),
 			cached_pending: RwLock::new(CachedPending::none()),
 			recently_rejected: RecentlyRejected::new(cmp::max(MIN_REJECTED_CACHE_SIZE, max_count / 4)),
+			block_base_fee,
+			penalties,
 		}
 	}
 
+	/// Update the EIP-1559 base fee of the pending block.
+	///
+	/// Transactions are prioritized by the tip they pay on top of the base fee,
+	/// so all scores are recomputed whenever it changes.
+	pub fn set_block_base_fee(&self, block_base_fee: Option<U256>) {
+		if *self.block_base_fee.read() == block_base_fee {
+			return;
+		}
+		*self.block_base_fee.write() = block_base_fee;
+
+		{
+			let mut pool = self.pool.write();
+			let senders: Vec<_> = pool.senders().cloned().collect();
+			for sender in &senders {
+				pool.update_scores(sender, scoring::ScoringEvent::BlockBaseFeeChanged);
+			}
+		}
+		self.cached_pending.write().clear();
+	}
+
 	/// Update verification options
 	///
 	/// Some parameters of verification may vary in time (like block gas limit or minimal gas price).
@@ -458,6 +485,7 @@ impl TransactionQueue {
 			let state_readiness = ready::State::new(client.clone(), stale_id, nonce_cap);
 			removed += self.pool.write().cull(Some(chunk), state_readiness);
 		}
+		self.prune_penalties();
 		debug!(target: "txqueue", "Removed {} stalled transactions. {}", removed, self.status());
 	}
 
@@ -521,16 +549,24 @@ impl TransactionQueue {
 	/// Clear the entire pool.
 	pub fn clear(&self) {
 		self.pool.write().clear();
+		self.penalties.write().clear();
 	}
 
 	/// Penalize given senders.
 	pub fn penalize<'a, T: IntoIterator<Item = &'a Address>>(&self, senders: T) {
 		let mut pool = self.pool.write();
 		for sender in senders {
-			pool.update_scores(sender, ());
+			pool.update_scores(sender, scoring::ScoringEvent::Penalize);
 		}
 	}
 
+	// Forget the penalties of senders which have no transactions in the pool anymore.
+	fn prune_penalties(&self) {
+		let pool = self.pool.read();
+		let senders: HashSet<_> = pool.senders().collect();
+		self.penalties.write().retain(|sender, _| senders.contains(sender));
+	}
+
 	/// Returns gas price of currently the worst transaction in the pool.
 	pub fn current_worst_gas_price(&self) -> U256 {
 		match self.pool.read().worst_transaction() {
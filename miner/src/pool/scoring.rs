@@ -26,13 +26,22 @@
 //! Transactions between senders are prioritized using `gas price`. Higher `gas price`
 //! yields more profits for miners. Additionally we prioritize transactions that originate
 //! from our local node (own transactions).
+//!
+//! After the EIP-1559 transition the author only receives the part of the gas price above
+//! the block base fee, so transactions are prioritized by that effective tip instead.
 
 use std::cmp;
+use std::collections::HashMap;
+use std::sync::Arc;
 
-use ethereum_types::U256;
+use ethereum_types::{Address, U256};
+use parking_lot::RwLock;
 use txpool::{self, scoring};
 use super::{verifier, PrioritizationStrategy, VerifiedTransaction, ScoredTransaction};
 
+/// Every penalization divides the scores of the sender's transactions by `2^PENALTY_SHIFT`.
+const PENALTY_SHIFT: usize = 3;
+
 /// Transaction with the same (sender, nonce) can be replaced only if
 /// `new_gas_price >= old_gas_price + old_gas_price >> SHIFT`
 const GAS_PRICE_BUMP_SHIFT: usize = 3; // 2 = 25%, 3 = 12.5%, 4 = 6.25%
@@ -43,14 +52,58 @@ fn bump_gas_price(old_gp: U256) -> U256 {
 	old_gp.saturating_add(old_gp >> GAS_PRICE_BUMP_SHIFT)
 }
 
+/// Events altering the scores of already pooled transactions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScoringEvent {
+	/// Lower the priority of the sender's transactions.
+	Penalize,
+	/// The base fee of the pending block changed, scores have to be recomputed.
+	BlockBaseFeeChanged,
+}
+
 /// Simple, gas-price based scoring for transactions.
 ///
-/// NOTE: Currently penalization does not apply to new transactions that enter the pool.
-/// We might want to store penalization status in some persistent state.
+/// Penalization applies to all non-local transactions of the sender, including the ones
+/// which enter the pool later, for as long as the sender has transactions in the pool.
 #[derive(Debug, Clone)]
-pub struct NonceAndGasPrice(pub PrioritizationStrategy);
+pub struct NonceAndGasPrice {
+	/// Prioritization strategy.
+	pub strategy: PrioritizationStrategy,
+	/// Base fee of the pending block, shared with the queue that updates it on every new block.
+	pub block_base_fee: Arc<RwLock<Option<U256>>>,
+	/// Number of times each sender was penalized, shared with the queue that prunes senders
+	/// which left the pool.
+	pub penalties: Arc<RwLock<HashMap<Address, usize>>>,
+}
 
 impl NonceAndGasPrice {
+	/// Create a new scoring with no block base fee.
+	pub fn new(strategy: PrioritizationStrategy) -> Self {
+		NonceAndGasPrice {
+			strategy,
+			block_base_fee: Default::default(),
+			penalties: Default::default(),
+		}
+	}
+
+	fn score<P>(&self, tx: &txpool::Transaction<P>, block_base_fee: Option<U256>) -> U256 where
+		P: ScoredTransaction + txpool::VerifiedTransaction<Sender = Address>,
+	{
+		let score = tx.transaction.effective_priority_fee(block_base_fee);
+		let boost = match tx.priority() {
+			super::Priority::Local => 15,
+			super::Priority::Retracted => 10,
+			super::Priority::Regular => 0,
+		};
+		// Never penalize local transactions.
+		let penalties = if tx.priority().is_local() {
+			0
+		} else {
+			self.penalties.read().get(tx.transaction.sender()).cloned().unwrap_or(0)
+		};
+		(score << boost) >> cmp::min(penalties * PENALTY_SHIFT, 256)
+	}
+
 	/// Decide if the transaction should even be considered into the pool (if the pool is full).
 	///
 	/// Used by Verifier to quickly reject transactions that don't have any chance to get into the pool later on,
@@ -67,9 +120,9 @@ impl NonceAndGasPrice {
 	}
 }
 
-impl<P> txpool::Scoring<P> for NonceAndGasPrice where P: ScoredTransaction + txpool::VerifiedTransaction {
+impl<P> txpool::Scoring<P> for NonceAndGasPrice where P: ScoredTransaction + txpool::VerifiedTransaction<Sender = Address> {
 	type Score = U256;
-	type Event = ();
+	type Event = ScoringEvent;
 
 	fn compare(&self, old: &P, other: &P) -> cmp::Ordering {
 		old.nonce().cmp(&other.nonce())
@@ -101,22 +154,25 @@ impl<P> txpool::Scoring<P> for NonceAndGasPrice where P: ScoredTransaction + txp
 				assert!(i < txs.len());
 				assert!(i < scores.len());
 
-				scores[i] = *txs[i].transaction.gas_price();
-				let boost = match txs[i].priority() {
-					super::Priority::Local => 15,
-					super::Priority::Retracted => 10,
-					super::Priority::Regular => 0,
-				};
-				scores[i] = scores[i] << boost;
+				scores[i] = self.score(&txs[i], *self.block_base_fee.read());
 			},
-			// We are only sending an event in case of penalization.
-			// So just lower the priority of all non-local transactions.
-			Change::Event(_) => {
+			Change::Event(ScoringEvent::BlockBaseFeeChanged) => {
+				let block_base_fee = *self.block_base_fee.read();
 				for (score, tx) in scores.iter_mut().zip(txs) {
-					// Never penalize local transactions.
-					if !tx.priority().is_local() {
-						*score = *score >> 3;
-					}
+					*score = self.score(tx, block_base_fee);
+				}
+			},
+			// Lower the priority of all non-local transactions of the sender.
+			Change::Event(ScoringEvent::Penalize) => {
+				let sender = match txs.first() {
+					Some(tx) => *tx.transaction.sender(),
+					None => return,
+				};
+				*self.penalties.write().entry(sender).or_insert(0) += 1;
+
+				let block_base_fee = *self.block_base_fee.read();
+				for (score, tx) in scores.iter_mut().zip(txs) {
+					*score = self.score(tx, block_base_fee);
 				}
 			},
 		}
@@ -138,7 +194,7 @@ mod tests {
 	#[test]
 	fn should_calculate_score_correctly() {
 		// given
-		let scoring = NonceAndGasPrice(PrioritizationStrategy::GasPriceOnly);
+		let scoring = NonceAndGasPrice::new(PrioritizationStrategy::GasPriceOnly);
 		let (tx1, tx2, tx3) = Tx::default().signed_triple();
 		let transactions = vec![tx1, tx2, tx3].into_iter().enumerate().map(|(i, tx)| {
 			let mut verified = tx.verified();
@@ -184,7 +240,34 @@ mod tests {
 		assert_eq!(scores, vec![32768.into(), 1024.into(), 1.into()]);
 
 		// Check penalization
-		scoring.update_scores(&transactions, &mut *scores, scoring::Change::Event(()));
+		scoring.update_scores(&transactions, &mut *scores, scoring::Change::Event(ScoringEvent::Penalize));
+		assert_eq!(scores, vec![32768.into(), 128.into(), 0.into()]);
+
+		// Base fee changes keep the penalization
+		scoring.update_scores(&transactions, &mut *scores, scoring::Change::Event(ScoringEvent::BlockBaseFeeChanged));
 		assert_eq!(scores, vec![32768.into(), 128.into(), 0.into()]);
+
+		// and so do new transactions of the sender
+		let mut scores = initial_scores.clone();
+		scoring.update_scores(&transactions, &mut *scores, scoring::Change::InsertedAt(1));
+		assert_eq!(scores, vec![0.into(), 128.into(), 0.into()]);
+	}
+
+	#[test]
+	fn should_score_by_effective_tip() {
+		let scoring = NonceAndGasPrice::new(PrioritizationStrategy::GasPriceOnly);
+		let transactions = vec![txpool::Transaction {
+			insertion_id: 0,
+			transaction: Arc::new(Tx::gas_price(10).signed().verified()),
+		}];
+		let mut scores = vec![U256::from(0)];
+
+		*scoring.block_base_fee.write() = Some(7.into());
+		scoring.update_scores(&transactions, &mut *scores, scoring::Change::InsertedAt(0));
+		assert_eq!(scores, vec![3.into()]);
+
+		*scoring.block_base_fee.write() = Some(12.into());
+		scoring.update_scores(&transactions, &mut *scores, scoring::Change::Event(ScoringEvent::BlockBaseFeeChanged));
+		assert_eq!(scores, vec![0.into()]);
 	}
 }
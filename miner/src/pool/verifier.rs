@@ -105,11 +105,11 @@ impl Transaction {
 		}
 	}
 
-	fn transaction(&self) -> &transaction::Transaction {
+	fn transaction(&self) -> &transaction::UnverifiedTransaction {
 		match *self {
-			Transaction::Unverified(ref tx) => &*tx,
-			Transaction::Retracted(ref tx) => &*tx,
-			Transaction::Local(ref tx) => &*tx,
+			Transaction::Unverified(ref tx) => tx,
+			Transaction::Retracted(ref tx) => tx,
+			Transaction::Local(ref tx) => &**tx,
 		}
 	}
 
@@ -14,6 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
 
+use std::collections::BTreeMap;
 use std::str::from_utf8;
 use std::{io, fs};
 use std::io::{BufReader, BufRead};
@@ -25,13 +26,15 @@ use rustc_hex::FromHex;
 use hash::{keccak, KECCAK_NULL_RLP};
 use ethereum_types::{U256, H256, Address};
 use bytes::ToPretty;
-use rlp::PayloadInfo;
-use client_traits::{BlockChainReset, Nonce, Balance, BlockChainClient, ImportExportBlocks};
+use rlp::{PayloadInfo, RlpStream};
+use client_traits::{BlockChainReset, Nonce, Balance, BlockChainClient, BlockInfo, ImportExportBlocks};
 use ethcore::{
-	client::{DatabaseCompactionProfile},
+	client::{Client, DatabaseCompactionProfile},
 	miner::Miner,
 };
 use ethcore_service::ClientService;
+use parity_rpc::v1::{AccountDiff, Block, BlockTransactions, Bytes, Receipt, RichBlock, Transaction};
+use pod::PodAccount;
 use cache::CacheConfig;
 use informant::{Informant, FullNodeInformantData};
 use params::{SpecType, Pruning, Switch, tracing_switch_to_bool, fatdb_switch_to_bool};
@@ -42,6 +45,7 @@ use ethcore_private_tx;
 use db;
 use ansi_term::Colour;
 use types::{
+	basic_account::BasicAccount,
 	ids::BlockId,
 	errors::{ImportError, EthcoreError},
 	client_types::{Mode, StateResult},
@@ -55,6 +59,7 @@ pub enum BlockchainCmd {
 	Import(ImportBlockchain),
 	Export(ExportBlockchain),
 	ExportState(ExportState),
+	ExportStateDiff(ExportStateDiff),
 	Reset(ResetBlockchain)
 }
 
@@ -139,6 +144,23 @@ pub struct ExportState {
 	pub max_round_blocks_to_import: usize,
 }
 
+#[derive(Debug, PartialEq)]
+pub struct ExportStateDiff {
+	pub spec: SpecType,
+	pub cache_config: CacheConfig,
+	pub dirs: Directories,
+	pub file_path: Option<String>,
+	pub pruning: Pruning,
+	pub pruning_history: u64,
+	pub pruning_memory: usize,
+	pub compaction: DatabaseCompactionProfile,
+	pub fat_db: Switch,
+	pub tracing: Switch,
+	pub from: BlockId,
+	pub to: BlockId,
+	pub max_round_blocks_to_import: usize,
+}
+
 pub fn execute(cmd: BlockchainCmd) -> Result<(), String> {
 	match cmd {
 		BlockchainCmd::Kill(kill_cmd) => kill_db(kill_cmd),
@@ -151,6 +173,7 @@ pub fn execute(cmd: BlockchainCmd) -> Result<(), String> {
 		}
 		BlockchainCmd::Export(export_cmd) => execute_export(export_cmd),
 		BlockchainCmd::ExportState(export_cmd) => execute_export_state(export_cmd),
+		BlockchainCmd::ExportStateDiff(export_cmd) => execute_export_state_diff(export_cmd),
 		BlockchainCmd::Reset(reset_cmd) => execute_reset(reset_cmd),
 	}
 }
@@ -226,7 +249,7 @@ fn execute_import_light(cmd: ImportBlockchain) -> Result<(), String> {
 		None => Box::new(io::stdin()),
 	};
 
-	const READAHEAD_BYTES: usize = 8;
+	const READAHEAD_BYTES: usize = 32;
 
 	let mut first_bytes: Vec<u8> = vec![0; READAHEAD_BYTES];
 	let mut first_read = 0;
@@ -235,10 +258,7 @@ fn execute_import_light(cmd: ImportBlockchain) -> Result<(), String> {
 		Some(format) => format,
 		None => {
 			first_read = instream.read(&mut first_bytes).map_err(|_| "Error reading from the file/stream.")?;
-			match first_bytes[0] {
-				0xf9 => DataFormat::Binary,
-				_ => DataFormat::Hex,
-			}
+			DataFormat::detect(&first_bytes[..first_read])
 		}
 	};
 
@@ -267,7 +287,7 @@ fn execute_import_light(cmd: ImportBlockchain) -> Result<(), String> {
 	};
 
 	match format {
-		DataFormat::Binary => {
+		DataFormat::Binary | DataFormat::BinaryWithReceipts => {
 			loop {
 				let mut bytes = if first_read > 0 {first_bytes.clone()} else {vec![0; READAHEAD_BYTES]};
 				let n = if first_read > 0 {
@@ -280,6 +300,13 @@ fn execute_import_light(cmd: ImportBlockchain) -> Result<(), String> {
 				let s = PayloadInfo::from(&bytes).map_err(|e| format!("Invalid RLP in the file/stream: {:?}", e))?.total();
 				bytes.resize(s, 0);
 				instream.read_exact(&mut bytes[n..]).map_err(|_| "Error reading from the file/stream.")?;
+				// only the headers are imported, the receipts are dropped.
+				if format == DataFormat::BinaryWithReceipts {
+					bytes = ::rlp::Rlp::new(&bytes).at(0)
+						.map_err(|e| format!("Bad block: {}", e))?
+						.as_raw()
+						.to_vec();
+				}
 				do_import(bytes)?;
 			}
 		}
@@ -292,6 +319,23 @@ fn execute_import_light(cmd: ImportBlockchain) -> Result<(), String> {
 				do_import(bytes)?;
 			}
 		}
+		DataFormat::Json => {
+			for line in BufReader::new(instream).lines() {
+				let s = line.map_err(|_| "Error reading from the file/stream.")?;
+				let s = if first_read > 0 {from_utf8(&first_bytes[..first_read]).unwrap().to_owned() + &(s[..])} else {s};
+				first_read = 0;
+				if s.trim().is_empty() { continue; }
+				let json: ::serde_json::Value = ::serde_json::from_str(&s).map_err(|_| "Invalid JSON in file/stream.")?;
+				let raw = json["raw"].as_str().ok_or("Block in file/stream has no raw RLP.")?;
+				let bytes = raw.trim_start_matches("0x").from_hex().map_err(|_| "Invalid hex in file/stream.")?;
+				// only the headers are imported, the receipts are dropped.
+				let bytes = ::rlp::Rlp::new(&bytes).at(0)
+					.map_err(|e| format!("Bad block: {}", e))?
+					.as_raw()
+					.to_vec();
+				do_import(bytes)?;
+			}
+		}
 	}
 	client.flush_queue();
 
@@ -541,12 +585,75 @@ fn execute_export(cmd: ExportBlockchain) -> Result<(), String> {
 		None => Box::new(io::stdout()),
 	};
 
-	client.export_blocks(out, cmd.from_block, cmd.to_block, cmd.format)?;
+	match cmd.format {
+		Some(DataFormat::Json) => export_blocks_json(&client, out, cmd.from_block, cmd.to_block)?,
+		format => client.export_blocks(out, cmd.from_block, cmd.to_block, format)?,
+	}
 
 	info!("Export completed.");
 	Ok(())
 }
 
+/// Write each block as a line of JSON, shaped like the `eth_getBlockByNumber` RPC response with
+/// full transactions, plus the block's `receipts` and, as `raw`, the `[block, receipts]` RLP that
+/// `parity import` reads back.
+fn export_blocks_json(client: &Client, mut out: Box<dyn io::Write>, from: BlockId, to: BlockId) -> Result<(), String> {
+	let from = client.block_number(from).ok_or("Starting block could not be found")?;
+	let to = client.block_number(to).ok_or("End block could not be found")?;
+
+	for i in from..=to {
+		if i % 10000 == 0 {
+			info!("#{}", i);
+		}
+		let id = BlockId::Number(i);
+		let block = client.block(id).ok_or("Error exporting incomplete chain")?;
+		let total_difficulty = client.block_total_difficulty(id).ok_or("Error exporting incomplete chain")?;
+		let extra_info = client.block_extra_info(id).ok_or("Error exporting incomplete chain")?;
+		let receipts = client.localized_block_receipts(id).ok_or("Error exporting incomplete chain")?;
+
+		let mut raw = RlpStream::new_list(2);
+		raw.append_raw(block.raw(), 1);
+		raw.append(&client.block_receipts(&block.hash()).ok_or("Error exporting incomplete chain")?);
+
+		let view = block.header_view();
+		let rich_block = RichBlock {
+			inner: Block {
+				hash: Some(view.hash()),
+				size: Some(block.rlp().as_raw().len().into()),
+				parent_hash: view.parent_hash(),
+				uncles_hash: view.uncles_hash(),
+				author: view.author(),
+				miner: view.author(),
+				state_root: view.state_root(),
+				transactions_root: view.transactions_root(),
+				receipts_root: view.receipts_root(),
+				number: Some(view.number().into()),
+				gas_used: view.gas_used(),
+				gas_limit: view.gas_limit(),
+				logs_bloom: Some(view.log_bloom()),
+				timestamp: view.timestamp().into(),
+				difficulty: view.difficulty(),
+				total_difficulty: Some(total_difficulty),
+				seal_fields: view.seal(client.eip1559_transition()).into_iter().map(Into::into).collect(),
+				base_fee_per_gas: view.base_fee_per_gas(client.eip1559_transition()),
+				uncles: block.uncle_hashes(),
+				transactions: BlockTransactions::Full(block.view().localized_transactions().into_iter().map(Transaction::from_localized).collect()),
+				extra_data: Bytes::new(view.extra_data()),
+			},
+			extra_info,
+		};
+
+		let mut json = ::serde_json::to_value(&rich_block)
+			.map_err(|e| format!("Couldn't serialize block #{}: {}", i, e))?;
+		json["receipts"] = ::serde_json::to_value(receipts.into_iter().map(Receipt::from).collect::<Vec<_>>())
+			.map_err(|e| format!("Couldn't serialize receipts of block #{}: {}", i, e))?;
+		json["raw"] = ::serde_json::Value::String(format!("0x{}", raw.out().to_hex()));
+		out.write_fmt(format_args!("{}\n", json))
+			.map_err(|e| format!("Couldn't write to stream. Cause: {}", e))?;
+	}
+	Ok(())
+}
+
 fn execute_export_state(cmd: ExportState) -> Result<(), String> {
 	let service = start_client(
 		cmd.dirs,
@@ -637,6 +744,95 @@ fn execute_export_state(cmd: ExportState) -> Result<(), String> {
 	Ok(())
 }
 
+fn execute_export_state_diff(cmd: ExportStateDiff) -> Result<(), String> {
+	let service = start_client(
+		cmd.dirs,
+		cmd.spec,
+		cmd.pruning,
+		cmd.pruning_history,
+		cmd.pruning_memory,
+		cmd.tracing,
+		cmd.fat_db,
+		cmd.compaction,
+		cmd.cache_config,
+		true,
+		cmd.max_round_blocks_to_import,
+	)?;
+
+	let client = service.client();
+
+	let mut out: Box<dyn io::Write> = match cmd.file_path {
+		Some(f) => Box::new(fs::File::create(&f).map_err(|_| format!("Cannot write to file given: {}", f))?),
+		None => Box::new(io::stdout()),
+	};
+
+	let (from, to) = (cmd.from, cmd.to);
+	let from_hash = client.block_hash(from).ok_or("Starting block could not be found")?;
+	let to_hash = client.block_hash(to).ok_or("End block could not be found")?;
+
+	let mut changed = 0usize;
+
+	out.write_fmt(format_args!("{{ \"from\": \"0x{:x}\", \"to\": \"0x{:x}\", \"state\": {{", from_hash, to_hash)).expect("Write error");
+	client.diff_states(from, to, |change| {
+		// the code is only read when it changed.
+		let with_code = change.pre.as_ref().map(|a| a.code_hash) != change.post.as_ref().map(|a| a.code_hash);
+		let pre_storage = change.storage.iter().filter_map(|(k, &(v, _))| v.map(|v| (*k, v))).collect();
+		let post_storage = change.storage.iter().filter_map(|(k, &(_, v))| v.map(|v| (*k, v))).collect();
+		let pre = match change.pre {
+			Some(ref account) => Some(pod_account(&client, &change.address, account, from, pre_storage, with_code)?),
+			None => None,
+		};
+		let post = match change.post {
+			Some(ref account) => Some(pod_account(&client, &change.address, account, to, post_storage, with_code)?),
+			None => None,
+		};
+
+		if let Some(diff) = ::pod::account::diff_pod(pre.as_ref(), post.as_ref()) {
+			if changed != 0 {
+				out.write(b",").expect("Write error");
+			}
+			let diff = ::serde_json::to_string(&AccountDiff::from(diff))
+				.map_err(|e| format!("Couldn't serialize the diff of account {:?}: {}", change.address, e))?;
+			out.write_fmt(format_args!("\n\"0x{:x}\": {}", change.address, diff)).expect("Write error");
+			changed += 1;
+			if changed % 10000 == 0 {
+				info!("Account #{}", changed);
+			}
+		}
+		Ok(())
+	})?;
+	out.write_fmt(format_args!("\n}}}}")).expect("Write error");
+	info!("Export completed, {} accounts changed.", changed);
+	Ok(())
+}
+
+/// The account at the given block, with the given storage and, if `with_code` is set, its code.
+fn pod_account(
+	client: &Client,
+	address: &Address,
+	account: &BasicAccount,
+	at: BlockId,
+	storage: BTreeMap<H256, H256>,
+	with_code: bool,
+) -> Result<PodAccount, String> {
+	let code = if with_code {
+		match client.code(address, at.into()) {
+			StateResult::Missing => return Err(format!("State at block {:?} is not available", at)),
+			StateResult::Some(code) => Some(code.unwrap_or_else(Vec::new)),
+		}
+	} else {
+		None
+	};
+
+	Ok(PodAccount {
+		balance: account.balance,
+		nonce: account.nonce,
+		code,
+		storage,
+		version: account.code_version,
+	})
+}
+
 fn execute_reset(cmd: ResetBlockchain) -> Result<(), String> {
 	let service = start_client(
 		cmd.dirs,
@@ -683,5 +879,9 @@ mod test {
 		assert_eq!(DataFormat::Binary, "binary".parse().unwrap());
 		assert_eq!(DataFormat::Binary, "bin".parse().unwrap());
 		assert_eq!(DataFormat::Hex, "hex".parse().unwrap());
+		assert_eq!(DataFormat::BinaryWithReceipts, "binary-receipts".parse().unwrap());
+		assert_eq!(DataFormat::BinaryWithReceipts, "bin-receipts".parse().unwrap());
+		assert_eq!(DataFormat::Json, "json".parse().unwrap());
+		assert_eq!(DataFormat::Json, "jsonl".parse().unwrap());
 	}
 }
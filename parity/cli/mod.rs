@@ -80,7 +80,7 @@ usage! {
 
 			ARG arg_import_format: (Option<String>) = None,
 			"--format=[FORMAT]",
-			"Import in a given format. FORMAT must be either 'hex' or 'binary'. (default: auto)",
+			"Import in a given format. FORMAT must be either 'hex', 'binary' or 'binary-receipts'. Blocks with receipts filling the gap below a restored snapshot are imported without being executed. (default: auto)",
 
 			ARG arg_import_file: (Option<String>) = None,
 			"[FILE]",
@@ -97,7 +97,7 @@ usage! {
 
 				ARG arg_export_blocks_format: (Option<String>) = None,
 				"--format=[FORMAT]",
-				"Export in a given format. FORMAT must be either 'hex' or 'binary'. (default: binary)",
+				"Export in a given format. FORMAT must be either 'hex', 'binary', 'binary-receipts' or 'json'. Blocks are exported with their receipts in 'binary-receipts' and 'json' formats. (default: binary)",
 
 				ARG arg_export_blocks_from: (String) = "1",
 				"--from=[BLOCK]",
@@ -144,6 +144,36 @@ usage! {
 				"[FILE]",
 				"Path to the exported file",
 			}
+
+			CMD cmd_export_state_diff
+			{
+				"Export the changes to the balance, nonce, code and storage of accounts between two blocks of the given --chain (default: mainnet) into a JSON file. This command requires the chain to be synced with --fat-db on.",
+
+				ARG arg_export_state_diff_from: (Option<String>) = None,
+				"--from=[BLOCK]",
+				"Diff from the state at block BLOCK, which may be an index or hash. Note that diffing non-recent blocks will only work with --pruning archive",
+
+				ARG arg_export_state_diff_to: (String) = "latest",
+				"--to=[BLOCK]",
+				"Diff to the state at block BLOCK, which may be an index, hash or latest.",
+
+				ARG arg_export_state_diff_file: (Option<String>) = None,
+				"[FILE]",
+				"Path to the exported file",
+			}
+		}
+
+		CMD cmd_fork
+		{
+			"Run an instant-seal chain on top of the state of the given --chain (default: mainnet) at a pinned block. State is fetched lazily from a node of that chain with eth_getProof and verified against the pinned state root.",
+
+			ARG arg_fork_block: (String) = "latest",
+			"--block=[BLOCK]",
+			"Pin the fork to the given block of the remote node, which may be an index, hash, or latest.",
+
+			ARG arg_fork_url: (Option<String>) = None,
+			"<URL>",
+			"HTTP JSON-RPC endpoint of a node of the chain to fork",
 		}
 
 		CMD cmd_signer
@@ -185,6 +215,10 @@ usage! {
 			"--at=[BLOCK]",
 			"Take a snapshot at the given block, which may be an index, hash, or latest. Note that taking snapshots at non-recent blocks will only work with --pruning archive",
 
+			ARG arg_snapshot_format: (Option<String>) = None,
+			"--format=[FORMAT]",
+			"Write the snapshot in a given format. FORMAT must be either 'packed' or 'tar'. (default: packed)",
+
 			ARG arg_snapshot_file: (Option<String>) = None,
 			"<FILE>",
 			"Path to the file to export to",
@@ -194,6 +228,10 @@ usage! {
 		{
 			"Restore the database of the given --chain (default: mainnet) from a snapshot file",
 
+			ARG arg_restore_format: (Option<String>) = None,
+			"--format=[FORMAT]",
+			"Read the snapshot in a given format. FORMAT must be either 'packed' or 'tar'. A tar snapshot is read from the standard input if FILE is '-'. (default: packed)",
+
 			ARG arg_restore_file: (Option<String>) = None,
 			"[FILE]",
 			"Path to the file to restore from",
@@ -231,6 +269,29 @@ usage! {
 
 		}
 
+		CMD cmd_slashing_protection
+		{
+			"Manage the slashing-protection database of the validator of the given --chain (default: mainnet)",
+
+			CMD cmd_slashing_protection_export
+			{
+				"Export the highest steps signed by each validator key in a JSON format",
+
+				ARG arg_slashing_protection_export_file: (Option<String>) = None,
+				"[FILE]",
+				"Path to the file to export to, the standard output if omitted",
+			}
+
+			CMD cmd_slashing_protection_import
+			{
+				"Import the highest steps signed by each validator key, keeping the higher of the known and the imported steps",
+
+				ARG arg_slashing_protection_import_file: (Option<String>) = None,
+				"<FILE>",
+				"Path to the file to import from",
+			}
+		}
+
 		CMD cmd_export_hardcoded_sync
 		{
 			"Print the hashed light clients headers of the given --chain (default: mainnet) in a JSON format. To be used as hardcoded headers in a genesis file.",
@@ -597,6 +658,23 @@ usage! {
 			"--ipfs-api-cors=[URL]",
 			"Specify CORS header for IPFS API responses. Special options: \"all\", \"none\".",
 
+		["Metrics Options"]
+			FLAG flag_metrics: (bool) = false, or |c: &Config| c.metrics.as_ref()?.enable.clone(),
+			"--metrics",
+			"Enable the Prometheus metrics HTTP endpoint at /metrics.",
+
+			ARG arg_metrics_prefix: (String) = "", or |c: &Config| c.metrics.as_ref()?.prefix.clone(),
+			"--metrics-prefix=[PREFIX]",
+			"Prepend the specified prefix to the names of the exported metrics.",
+
+			ARG arg_metrics_port: (u16) = 3000u16, or |c: &Config| c.metrics.as_ref()?.port.clone(),
+			"--metrics-port=[PORT]",
+			"Specify the port portion of the metrics server.",
+
+			ARG arg_metrics_interface: (String) = "local", or |c: &Config| c.metrics.as_ref()?.interface.clone(),
+			"--metrics-interface=[IP]",
+			"Specify the hostname portion of the metrics server, IP should be an interface's IP address, or all (all interfaces) or local.",
+
 		["Light Client Options"]
 			ARG arg_on_demand_response_time_window: (Option<u64>) = None, or |c: &Config| c.light.as_ref()?.on_demand_response_time_window,
 			"--on-demand-time-window=[S]",
@@ -818,6 +896,10 @@ usage! {
 			"--engine-signer=[ADDRESS]",
 			"Specify the address which should be used to sign consensus messages and issue blocks. Relevant only to non-PoW chains.",
 
+			ARG arg_engine_signer_url: (Option<String>) = None, or |c: &Config| c.mining.as_ref()?.engine_signer_url.clone(),
+			"--engine-signer-url=[URL]",
+			"Specify the URL of a Web3Signer-compatible signing service holding the key of the --engine-signer address, instead of a local account. Only AuthorityRound chains without a randomness contract can be sealed this way.",
+
 			ARG arg_tx_gas_limit: (Option<String>) = None, or |c: &Config| c.mining.as_ref()?.tx_gas_limit.clone(),
 			"--tx-gas-limit=[GAS]",
 			"Apply a limit of GAS as the maximum amount of gas a single transaction may have for it to be mined.",
@@ -1164,6 +1246,7 @@ struct Config {
 	secretstore: Option<SecretStore>,
 	private_tx: Option<PrivateTransactions>,
 	ipfs: Option<Ipfs>,
+	metrics: Option<Metrics>,
 	mining: Option<Mining>,
 	footprint: Option<Footprint>,
 	snapshots: Option<Snapshots>,
@@ -1354,11 +1437,21 @@ struct Ipfs {
 	hosts: Option<Vec<String>>,
 }
 
+#[derive(Default, Debug, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Metrics {
+	enable: Option<bool>,
+	prefix: Option<String>,
+	port: Option<u16>,
+	interface: Option<String>,
+}
+
 #[derive(Default, Debug, PartialEq, Deserialize)]
 #[serde(deny_unknown_fields)]
 struct Mining {
 	author: Option<String>,
 	engine_signer: Option<String>,
+	engine_signer_url: Option<String>,
 	force_sealing: Option<bool>,
 	reseal_on_uncle: Option<bool>,
 	reseal_on_txs: Option<String>,
@@ -1458,7 +1551,7 @@ struct Light {
 mod tests {
 	use super::{
 		Args, ArgsError,
-		Config, Operating, Account, Ui, Network, Ws, Rpc, Ipc, Dapps, Ipfs, Mining, Footprint,
+		Config, Operating, Account, Ui, Network, Ws, Rpc, Ipc, Dapps, Ipfs, Metrics, Mining, Footprint,
 		Snapshots, Misc, Whisper, SecretStore, Light,
 	};
 	use toml;
@@ -1510,6 +1603,16 @@ mod tests {
 
 		let args = Args::parse(&["parity", "export", "state", "--min-balance","123"]).unwrap();
 		assert_eq!(args.arg_export_state_min_balance, Some("123".to_string()));
+
+		let args = Args::parse(&["parity", "export", "state-diff", "--from", "100", "--to", "200"]).unwrap();
+		assert_eq!(args.cmd_export_state_diff, true);
+		assert_eq!(args.arg_export_state_diff_from, Some("100".to_string()));
+		assert_eq!(args.arg_export_state_diff_to, "200");
+
+		let args = Args::parse(&["parity", "fork", "--block", "123", "http://localhost:8545"]).unwrap();
+		assert_eq!(args.cmd_fork, true);
+		assert_eq!(args.arg_fork_block, "123");
+		assert_eq!(args.arg_fork_url, Some("http://localhost:8545".to_string()));
 	}
 
 	#[test]
@@ -1712,6 +1815,8 @@ mod tests {
 			cmd_export: false,
 			cmd_export_blocks: false,
 			cmd_export_state: false,
+			cmd_export_state_diff: false,
+			cmd_fork: false,
 			cmd_signer: false,
 			cmd_signer_list: false,
 			cmd_signer_sign: false,
@@ -1724,6 +1829,9 @@ mod tests {
 			cmd_db: false,
 			cmd_db_kill: false,
 			cmd_db_reset: false,
+			cmd_slashing_protection: false,
+			cmd_slashing_protection_export: false,
+			cmd_slashing_protection_import: false,
 			cmd_export_hardcoded_sync: false,
 
 			// Arguments
@@ -1734,7 +1842,11 @@ mod tests {
 			arg_export_blocks_format: None,
 			arg_export_state_file: None,
 			arg_export_state_format: None,
+			arg_export_state_diff_file: None,
+			arg_fork_url: None,
+			arg_snapshot_format: None,
 			arg_snapshot_file: None,
+			arg_restore_format: None,
 			arg_restore_file: None,
 			arg_tools_hash_file: None,
 
@@ -1745,6 +1857,8 @@ mod tests {
 			arg_account_import_path: None,
 			arg_wallet_import_path: None,
 			arg_db_reset_num: 10,
+			arg_slashing_protection_export_file: None,
+			arg_slashing_protection_import_file: None,
 
 			// -- Operating Options
 			arg_mode: "last".into(),
@@ -1878,9 +1992,16 @@ mod tests {
 			arg_ipfs_api_cors: "null".into(),
 			arg_ipfs_api_hosts: "none".into(),
 
+			// -- Metrics Options
+			flag_metrics: false,
+			arg_metrics_prefix: "".into(),
+			arg_metrics_port: 3000u16,
+			arg_metrics_interface: "local".into(),
+
 			// -- Sealing/Mining Options
 			arg_author: Some("0xdeadbeefcafe0000000000000000000000000001".into()),
 			arg_engine_signer: Some("0xdeadbeefcafe0000000000000000000000000001".into()),
+			arg_engine_signer_url: None,
 			flag_force_sealing: true,
 			arg_reseal_on_txs: "all".into(),
 			arg_reseal_min_period: 4000u64,
@@ -1942,9 +2063,12 @@ mod tests {
 			flag_export_state_no_storage: false,
 			arg_export_state_min_balance: None,
 			arg_export_state_max_balance: None,
+			arg_export_state_diff_from: None,
+			arg_export_state_diff_to: "latest".into(),
 
 			// -- Snapshot Optons
 			arg_export_state_at: "latest".into(),
+			arg_fork_block: "latest".into(),
 			arg_snapshot_at: "latest".into(),
 			flag_no_periodic_snapshot: false,
 			arg_snapshot_threads: None,
@@ -2158,9 +2282,11 @@ mod tests {
 				cors: None,
 				hosts: None,
 			}),
+			metrics: None,
 			mining: Some(Mining {
 				author: Some("0xdeadbeefcafe0000000000000000000000000001".into()),
 				engine_signer: Some("0xdeadbeefcafe0000000000000000000000000001".into()),
+				engine_signer_url: None,
 				force_sealing: Some(true),
 				reseal_on_txs: Some("all".into()),
 				reseal_on_uncle: None,
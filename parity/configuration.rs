@@ -43,16 +43,19 @@ use params::{ResealPolicy, AccountsConfig, GasPricerConfig, MinerExtras, SpecTyp
 use ethcore_logger::Config as LogConfig;
 use dir::{self, Directories, default_hypervisor_path, default_local_path, default_data_path};
 use ipfs::Configuration as IpfsConfiguration;
+use metrics::Configuration as MetricsConfiguration;
 use ethcore_private_tx::{ProviderConfig, EncryptorConfig};
 use secretstore::{NodeSecretKey, Configuration as SecretStoreConfiguration, ContractAddress as SecretStoreContractAddress};
 use updater::{UpdatePolicy, UpdateFilter, ReleaseTrack};
 use run::RunCmd;
 use types::data_format::DataFormat;
-use blockchain::{BlockchainCmd, ImportBlockchain, ExportBlockchain, KillBlockchain, ExportState, ResetBlockchain};
+use blockchain::{BlockchainCmd, ImportBlockchain, ExportBlockchain, KillBlockchain, ExportState, ExportStateDiff, ResetBlockchain};
 use export_hardcoded_sync::ExportHsyncCmd;
+use fork::ForkConfig;
 use presale::ImportWallet;
 use account::{AccountCmd, NewAccount, ListAccounts, ImportAccounts, ImportFromGethAccounts};
 use snapshot_cmd::{self, SnapshotCommand};
+use slashing_protection_cmd::{SlashingProtectionCmd, SlashingProtectionFile};
 use network::{IpFilter, NatType};
 
 const DEFAULT_MAX_PEERS: u16 = 50;
@@ -83,6 +86,7 @@ pub enum Cmd {
 		authfile: PathBuf
 	},
 	Snapshot(SnapshotCommand),
+	SlashingProtection(SlashingProtectionCmd),
 	Hash(Option<String>),
 	ExportHardcodedSync(ExportHsyncCmd),
 }
@@ -140,6 +144,7 @@ impl Configuration {
 		let geth_compatibility = self.args.flag_geth;
 		let experimental_rpcs = self.args.flag_jsonrpc_experimental;
 		let ipfs_conf = self.ipfs_config();
+		let metrics_conf = self.metrics_config();
 		let secretstore_conf = self.secretstore_config()?;
 		let format = self.format()?;
 
@@ -200,6 +205,18 @@ impl Configuration {
 				dirs: dirs,
 				pruning: pruning,
 			}))
+		} else if self.args.cmd_slashing_protection && self.args.cmd_slashing_protection_export {
+			Cmd::SlashingProtection(SlashingProtectionCmd::Export(SlashingProtectionFile {
+				spec,
+				dirs,
+				file_path: self.args.arg_slashing_protection_export_file,
+			}))
+		} else if self.args.cmd_slashing_protection && self.args.cmd_slashing_protection_import {
+			Cmd::SlashingProtection(SlashingProtectionCmd::Import(SlashingProtectionFile {
+				spec,
+				dirs,
+				file_path: self.args.arg_slashing_protection_import_file,
+			}))
 		} else if self.args.cmd_account {
 			let account_cmd = if self.args.cmd_account_new {
 				let new_acc = NewAccount {
@@ -305,6 +322,25 @@ impl Configuration {
 					max_round_blocks_to_import: self.args.arg_max_round_blocks_to_import,
 				};
 				Cmd::Blockchain(BlockchainCmd::ExportState(export_cmd))
+			} else if self.args.cmd_export_state_diff {
+				let from = self.args.arg_export_state_diff_from.as_ref()
+					.ok_or("--from is required to export a state diff")?;
+				let export_cmd = ExportStateDiff {
+					spec: spec,
+					cache_config: cache_config,
+					dirs: dirs,
+					file_path: self.args.arg_export_state_diff_file.clone(),
+					pruning: pruning,
+					pruning_history: pruning_history,
+					pruning_memory: self.args.arg_pruning_memory,
+					compaction: compaction,
+					tracing: tracing,
+					fat_db: fat_db,
+					from: to_block_id(from)?,
+					to: to_block_id(&self.args.arg_export_state_diff_to)?,
+					max_round_blocks_to_import: self.args.arg_max_round_blocks_to_import,
+				};
+				Cmd::Blockchain(BlockchainCmd::ExportStateDiff(export_cmd))
 			} else {
 				unreachable!();
 			}
@@ -320,6 +356,7 @@ impl Configuration {
 				fat_db: fat_db,
 				compaction: compaction,
 				file_path: self.args.arg_snapshot_file.clone(),
+				format: self.snapshot_format()?,
 				kind: snapshot_cmd::Kind::Take,
 				block_at: to_block_id(&self.args.arg_snapshot_at)?,
 				max_round_blocks_to_import: self.args.arg_max_round_blocks_to_import,
@@ -338,6 +375,7 @@ impl Configuration {
 				fat_db: fat_db,
 				compaction: compaction,
 				file_path: self.args.arg_restore_file.clone(),
+				format: self.snapshot_format()?,
 				kind: snapshot_cmd::Kind::Restore,
 				block_at: to_block_id("latest")?, // unimportant.
 				max_round_blocks_to_import: self.args.arg_max_round_blocks_to_import,
@@ -397,6 +435,7 @@ impl Configuration {
 				experimental_rpcs,
 				net_settings: self.network_settings()?,
 				ipfs_conf,
+				metrics_conf,
 				secretstore_conf,
 				private_provider_conf,
 				private_encryptor_conf: private_enc_conf,
@@ -416,6 +455,7 @@ impl Configuration {
 				on_demand_request_backoff_max: self.args.arg_on_demand_request_backoff_max,
 				on_demand_request_backoff_rounds_max: self.args.arg_on_demand_request_backoff_rounds_max,
 				on_demand_request_consecutive_failures: self.args.arg_on_demand_request_consecutive_failures,
+				fork: self.fork_config()?,
 			};
 			Cmd::Run(run_cmd)
 		};
@@ -434,6 +474,7 @@ impl Configuration {
 			extra_data: self.extra_data()?,
 			gas_range_target: (floor, ceil),
 			engine_signer: self.engine_signer()?,
+			engine_signer_url: self.args.arg_engine_signer_url.clone(),
 			work_notify: self.work_notify(),
 			local_accounts: HashSet::from_iter(to_addresses(&self.args.arg_tx_queue_locals)?.into_iter()),
 		};
@@ -458,6 +499,13 @@ impl Configuration {
 		}
 	}
 
+	fn snapshot_format(&self) -> Result<snapshot_cmd::Format, String> {
+		match self.args.arg_snapshot_format.clone().or(self.args.arg_restore_format.clone()) {
+			Some(ref f) => f.parse(),
+			None => Ok(Default::default()),
+		}
+	}
+
 	fn cache_config(&self) -> CacheConfig {
 		match self.args.arg_cache_size.or(self.args.arg_cache) {
 			Some(size) => CacheConfig::new_with_total_cache_size(size),
@@ -548,6 +596,20 @@ impl Configuration {
 		} else { Ok(None) }
 	}
 
+	fn fork_config(&self) -> Result<Option<ForkConfig>, String> {
+		if !self.args.cmd_fork {
+			return Ok(None);
+		}
+		if self.is_dev_chain()? {
+			return Err("The dev chain cannot be forked".into());
+		}
+
+		Ok(Some(ForkConfig {
+			url: self.args.arg_fork_url.clone().expect("CLI argument is required; qed"),
+			block: to_block_id(&self.args.arg_fork_block)?,
+		}))
+	}
+
 	fn miner_options(&self) -> Result<MinerOptions, String> {
 		let is_dev_chain = self.is_dev_chain()?;
 		if is_dev_chain && self.args.flag_force_sealing && self.args.arg_reseal_min_period == 0 {
@@ -642,6 +704,15 @@ impl Configuration {
 		}
 	}
 
+	fn metrics_config(&self) -> MetricsConfiguration {
+		MetricsConfiguration {
+			enabled: self.args.flag_metrics,
+			prefix: self.args.arg_metrics_prefix.clone(),
+			port: self.args.arg_ports_shift + self.args.arg_metrics_port,
+			interface: self.metrics_interface(),
+		}
+	}
+
 	fn gas_pricer_config(&self) -> Result<GasPricerConfig, String> {
 		fn wei_per_gas(usd_per_tx: f32, usd_per_eth: f32) -> U256 {
 			let wei_per_usd: f32 = 1.0e18 / usd_per_eth;
@@ -1064,6 +1135,10 @@ impl Configuration {
 		self.interface(&self.args.arg_ipfs_api_interface)
 	}
 
+	fn metrics_interface(&self) -> String {
+		self.interface(&self.args.arg_metrics_interface)
+	}
+
 	fn secretstore_interface(&self) -> String {
 		self.interface(&self.args.arg_secretstore_interface)
 	}
@@ -1204,13 +1279,14 @@ mod tests {
 	use types::ids::BlockId;
 	use types::data_format::DataFormat;
 	use account::{AccountCmd, NewAccount, ImportAccounts, ListAccounts};
-	use blockchain::{BlockchainCmd, ImportBlockchain, ExportBlockchain, ExportState};
+	use blockchain::{BlockchainCmd, ImportBlockchain, ExportBlockchain, ExportState, ExportStateDiff};
 	use cli::Args;
 	use dir::{Directories, default_hypervisor_path};
 	use helpers::{default_network_config};
 	use params::SpecType;
 	use presale::ImportWallet;
 	use rpc::WsConfiguration;
+	use slashing_protection_cmd::{SlashingProtectionCmd, SlashingProtectionFile};
 	use rpc_apis::ApiSet;
 	use run::RunCmd;
 
@@ -1332,6 +1408,30 @@ mod tests {
 		})));
 	}
 
+	#[test]
+	fn test_command_snapshot_format() {
+		let args = vec!["parity", "snapshot", "--format", "tar", "snapshot.tar"];
+		match parse(&args).into_command().unwrap().cmd {
+			Cmd::Snapshot(cmd) => assert_eq!(cmd.format, snapshot_cmd::Format::Tar),
+			_ => panic!("Should be a snapshot command"),
+		}
+
+		let args = vec!["parity", "restore", "--format", "tar", "-"];
+		match parse(&args).into_command().unwrap().cmd {
+			Cmd::Snapshot(cmd) => {
+				assert_eq!(cmd.format, snapshot_cmd::Format::Tar);
+				assert_eq!(cmd.file_path, Some("-".into()));
+			},
+			_ => panic!("Should be a snapshot command"),
+		}
+
+		let args = vec!["parity", "restore", "snapshot"];
+		match parse(&args).into_command().unwrap().cmd {
+			Cmd::Snapshot(cmd) => assert_eq!(cmd.format, snapshot_cmd::Format::Packed),
+			_ => panic!("Should be a snapshot command"),
+		}
+	}
+
 	#[test]
 	fn test_command_state_export() {
 		let args = vec!["parity", "export", "state", "state.json"];
@@ -1357,6 +1457,30 @@ mod tests {
 		})));
 	}
 
+	#[test]
+	fn test_command_state_diff_export() {
+		let args = vec!["parity", "export", "state-diff", "--from", "100", "diff.json"];
+		let conf = parse(&args);
+		assert_eq!(conf.into_command().unwrap().cmd, Cmd::Blockchain(BlockchainCmd::ExportStateDiff(ExportStateDiff {
+			spec: Default::default(),
+			cache_config: Default::default(),
+			dirs: Default::default(),
+			file_path: Some("diff.json".into()),
+			pruning: Default::default(),
+			pruning_history: 64,
+			pruning_memory: 32,
+			compaction: Default::default(),
+			tracing: Default::default(),
+			fat_db: Default::default(),
+			from: BlockId::Number(100),
+			to: BlockId::Latest,
+			max_round_blocks_to_import: 12,
+		})));
+
+		let args = vec!["parity", "export", "state-diff", "diff.json"];
+		assert!(parse(&args).into_command().is_err());
+	}
+
 	#[test]
 	fn test_command_blockchain_export_with_custom_format() {
 		let args = vec!["parity", "export", "blocks", "--format", "hex", "blockchain.json"];
@@ -1380,6 +1504,17 @@ mod tests {
 		})));
 	}
 
+	#[test]
+	fn test_command_slashing_protection_export() {
+		let args = vec!["parity", "slashing-protection", "export", "protection.json"];
+		let conf = parse(&args);
+		assert_eq!(conf.into_command().unwrap().cmd, Cmd::SlashingProtection(SlashingProtectionCmd::Export(SlashingProtectionFile {
+			spec: Default::default(),
+			dirs: Default::default(),
+			file_path: Some("protection.json".into()),
+		})));
+	}
+
 	#[test]
 	fn test_command_signer_new_token() {
 		let args = vec!["parity", "signer", "new-token"];
@@ -1457,6 +1592,7 @@ mod tests {
 			experimental_rpcs: false,
 			net_settings: Default::default(),
 			ipfs_conf: Default::default(),
+			metrics_conf: Default::default(),
 			secretstore_conf: Default::default(),
 			private_provider_conf: Default::default(),
 			private_encryptor_conf: Default::default(),
@@ -1479,6 +1615,7 @@ mod tests {
 			on_demand_request_backoff_max: None,
 			on_demand_request_backoff_rounds_max: None,
 			on_demand_request_consecutive_failures: None,
+			fork: None,
 		};
 		expected.secretstore_conf.enabled = cfg!(feature = "secretstore");
 		expected.secretstore_conf.http_enabled = cfg!(feature = "secretstore");
@@ -1712,6 +1849,25 @@ mod tests {
 		}
 	}
 
+	#[test]
+	fn test_fork_command() {
+		let args = vec!["parity", "fork", "--block", "100", "http://localhost:8545"];
+		let conf = Configuration::parse_cli(&args).unwrap();
+		match conf.into_command().unwrap().cmd {
+			Cmd::Run(c) => {
+				assert_eq!(c.fork, Some(ForkConfig {
+					url: "http://localhost:8545".into(),
+					block: BlockId::Number(100),
+				}));
+			},
+			_ => panic!("Should be Cmd::Run"),
+		}
+
+		let args = vec!["parity", "--chain", "dev", "fork", "http://localhost:8545"];
+		let conf = Configuration::parse_cli(&args).unwrap();
+		assert!(conf.into_command().is_err());
+	}
+
 	#[test]
 	fn test_mining_preset() {
 		let args = vec!["parity", "--config", "mining"];
@@ -1831,6 +1987,7 @@ mod tests {
 		assert_eq!(conf0.secretstore_config().unwrap().port, 8084);
 		assert_eq!(conf0.secretstore_config().unwrap().http_port, 8083);
 		assert_eq!(conf0.ipfs_config().port, 5002);
+		assert_eq!(conf0.metrics_config().port, 3001);
 		assert_eq!(conf0.stratum_options().unwrap().unwrap().port, 8009);
 
 		assert_eq!(conf1.net_addresses().unwrap().0.port(), 30304);
@@ -1888,6 +2045,7 @@ mod tests {
 		assert_eq!(&conf0.secretstore_config().unwrap().http_interface, "0.0.0.0");
 		assert_eq!(&conf0.ipfs_config().interface, "0.0.0.0");
 		assert_eq!(conf0.ipfs_config().hosts, None);
+		assert_eq!(&conf0.metrics_config().interface, "0.0.0.0");
 	}
 
 	#[test]
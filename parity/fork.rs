@@ -0,0 +1,568 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Parity Ethereum.
+
+// Parity Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Running an instant-seal chain on top of the state of another chain.
+//!
+//! The state of the pinned block is not downloaded up front. Instead, whenever the local
+//! state misses an account or a storage slot, a Merkle proof for it is requested from a
+//! `ForkSource`, verified against the pinned state root and its nodes are written to the
+//! local state database, after which the regular trie lookup succeeds. Removing items from
+//! the state can also need trie nodes of siblings which no lookup has touched. These are
+//! requested from the `ForkSource` by their hash when the state is committed.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use account_db;
+use bytes::Bytes;
+use ethcore_db::COL_STATE;
+use ethereum_types::{Address, H256, U256};
+use ethtrie::TrieDB;
+use futures::Future;
+use hash::{keccak, KECCAK_EMPTY, KECCAK_NULL_RLP};
+use hash_db::{HashDB, EMPTY_PREFIX};
+use hash_fetch::fetch::{self, BodyReader, Fetch};
+use journaldb;
+use kvdb::{DBTransaction, DBValue, KeyValueDB};
+use parity_rpc::hyper::header::{self, HeaderValue};
+use parking_lot::Mutex;
+use rlp;
+use rustc_hex::FromHex;
+use serde_json::{self, Value};
+use spec::ForkPoint;
+use state_db::StateFetcher;
+use trie::Trie;
+use types::basic_account::BasicAccount;
+use types::ids::BlockId;
+
+/// Configuration of `parity fork`.
+#[derive(Debug, PartialEq)]
+pub struct ForkConfig {
+	/// JSON-RPC endpoint of a node of the forked chain.
+	pub url: String,
+	/// Block to pin the fork to.
+	pub block: BlockId,
+}
+
+/// Source of the state of the forked chain.
+pub trait ForkSource: Send + Sync {
+	/// Header fields of the block the fork continues from.
+	fn fork_point(&self, block: BlockId) -> Result<ForkPoint, String>;
+
+	/// Trie nodes proving the account at `address`, starting from the state root.
+	fn account_proof(&self, address: &Address, at: &ForkPoint) -> Result<Vec<Bytes>, String>;
+
+	/// Trie nodes proving `key` in the storage of the account at `address`, starting from
+	/// the storage root.
+	fn storage_proof(&self, address: &Address, key: &H256, at: &ForkPoint) -> Result<Vec<Bytes>, String>;
+
+	/// Code of the account at `address`.
+	fn code(&self, address: &Address, at: &ForkPoint) -> Result<Bytes, String>;
+
+	/// Trie node with the given hash, of either the account trie or a storage trie.
+	fn node(&self, hash: &H256) -> Result<Bytes, String>;
+}
+
+/// Fetches state from a remote node over HTTP JSON-RPC, using `eth_getProof`.
+pub struct RpcSource {
+	url: fetch::Url,
+	client: fetch::Client,
+}
+
+impl RpcSource {
+	/// Create a new source fetching from the node at `url`.
+	pub fn new(url: &str) -> Result<Self, String> {
+		Ok(RpcSource {
+			url: fetch::Url::parse(url).map_err(|e| format!("Invalid fork URL {}: {}", url, e))?,
+			client: fetch::Client::new(1).map_err(|e| format!("Error starting fetch client: {:?}", e))?,
+		})
+	}
+
+	fn call(&self, method: &str, params: Value) -> Result<Value, String> {
+		let body = json!({
+			"jsonrpc": "2.0",
+			"id": 1,
+			"method": method,
+			"params": params,
+		});
+		let request = fetch::Request::post(self.url.clone())
+			.with_header(header::CONTENT_TYPE, HeaderValue::from_static("application/json"))
+			.with_body(body.to_string());
+
+		let response = self.client.fetch(request, fetch::Abort::default())
+			.wait()
+			.map_err(|e| format!("{} request failed: {:?}", method, e))?;
+		if !response.is_success() {
+			return Err(format!("{} request failed: {}", method, response.status()));
+		}
+
+		let mut response: Value = serde_json::from_reader(BodyReader::new(response))
+			.map_err(|e| format!("Invalid response to {}: {}", method, e))?;
+		if let Some(error) = response.get("error") {
+			return Err(format!("{} failed: {}", method, error));
+		}
+		Ok(response["result"].take())
+	}
+
+	fn block_param(at: &ForkPoint) -> Value {
+		format!("0x{:x}", at.number).into()
+	}
+}
+
+impl ForkSource for RpcSource {
+	fn fork_point(&self, block: BlockId) -> Result<ForkPoint, String> {
+		let header = match block {
+			BlockId::Hash(hash) => self.call("eth_getBlockByHash", json!([hash, false]))?,
+			BlockId::Number(number) => self.call("eth_getBlockByNumber", json!([format!("0x{:x}", number), false]))?,
+			BlockId::Earliest => self.call("eth_getBlockByNumber", json!(["earliest", false]))?,
+			BlockId::Latest => self.call("eth_getBlockByNumber", json!(["latest", false]))?,
+		};
+		if header.is_null() {
+			return Err(format!("Block {:?} is not known to the fork source", block));
+		}
+
+		let header: RpcHeader = serde_json::from_value(header).map_err(|e| format!("Invalid block: {}", e))?;
+		Ok(ForkPoint {
+			number: header.number.low_u64(),
+			hash: header.hash,
+			state_root: header.state_root,
+			timestamp: header.timestamp.low_u64(),
+			gas_limit: header.gas_limit,
+			author: header.miner,
+			base_fee_per_gas: header.base_fee_per_gas,
+		})
+	}
+
+	fn account_proof(&self, address: &Address, at: &ForkPoint) -> Result<Vec<Bytes>, String> {
+		let proof = self.call("eth_getProof", json!([address, [], Self::block_param(at)]))?;
+		decode_proof(&proof["accountProof"])
+	}
+
+	fn storage_proof(&self, address: &Address, key: &H256, at: &ForkPoint) -> Result<Vec<Bytes>, String> {
+		let proof = self.call("eth_getProof", json!([address, [key], Self::block_param(at)]))?;
+		decode_proof(&proof["storageProof"][0]["proof"])
+	}
+
+	fn code(&self, address: &Address, at: &ForkPoint) -> Result<Bytes, String> {
+		let code = self.call("eth_getCode", json!([address, Self::block_param(at)]))?;
+		code.as_str().ok_or_else(|| "Invalid response to eth_getCode".to_owned()).and_then(decode_hex)
+	}
+
+	fn node(&self, hash: &H256) -> Result<Bytes, String> {
+		// trie nodes are not reachable through the `eth` API, but nodes keeping them by hash
+		// serve them from `debug_dbGet`.
+		let node = self.call("debug_dbGet", json!([hash]))?;
+		node.as_str().ok_or_else(|| "Invalid response to debug_dbGet".to_owned()).and_then(decode_hex)
+	}
+}
+
+/// Header fields of an `eth_getBlockBy*` response needed for the fork.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RpcHeader {
+	number: U256,
+	hash: H256,
+	state_root: H256,
+	timestamp: U256,
+	gas_limit: U256,
+	miner: Address,
+	base_fee_per_gas: Option<U256>,
+}
+
+fn decode_hex(s: &str) -> Result<Bytes, String> {
+	let s = if s.starts_with("0x") { &s[2..] } else { s };
+	s.from_hex().map_err(|e| format!("Invalid hex data: {}", e))
+}
+
+fn decode_proof(proof: &Value) -> Result<Vec<Bytes>, String> {
+	proof.as_array()
+		.ok_or_else(|| "Missing proof in eth_getProof response".to_owned())?
+		.iter()
+		.map(|node| node.as_str().ok_or_else(|| "Invalid proof node".to_owned()).and_then(decode_hex))
+		.collect()
+}
+
+/// Fills the local state database from a `ForkSource`, verifying everything against the
+/// state root of the pinned block.
+pub struct ForkFetcher {
+	source: Box<dyn ForkSource>,
+	fork: ForkPoint,
+	db: Arc<dyn KeyValueDB>,
+	accounts: Mutex<HashMap<Address, Option<BasicAccount>>>,
+	storage: Mutex<HashSet<(Address, H256)>>,
+}
+
+impl ForkFetcher {
+	/// Create a new fetcher writing into the state column of `db`.
+	pub fn new(source: Box<dyn ForkSource>, fork: ForkPoint, db: Arc<dyn KeyValueDB>) -> Self {
+		ForkFetcher {
+			source,
+			fork,
+			db,
+			accounts: Mutex::new(HashMap::new()),
+			storage: Mutex::new(HashSet::new()),
+		}
+	}
+
+	/// Fetch the root node of the pinned state, which the client needs before it starts.
+	pub fn fetch_root(&self) -> Result<(), String> {
+		self.account(&Address::zero())
+			.map(|_| ())
+			.map_err(|e| format!("Failed to fetch the state root of the fork: {}", e))
+	}
+
+	/// Fetch the account at `address` as of the pinned block, along with its code.
+	fn account(&self, address: &Address) -> Result<Option<BasicAccount>, String> {
+		if let Some(account) = self.accounts.lock().get(address) {
+			return Ok(account.clone());
+		}
+
+		let address_hash = keccak(address);
+		let proof = self.source.account_proof(address, &self.fork)?;
+		let account = match check_proof(&proof, &self.fork.state_root, &address_hash)? {
+			Some(rlp) => Some(rlp::decode::<BasicAccount>(&rlp).map_err(|e| format!("Invalid account {:?}: {}", address, e))?),
+			None => None,
+		};
+
+		let code = match account {
+			Some(ref account) if account.code_hash != KECCAK_EMPTY => {
+				let code = self.source.code(address, &self.fork)?;
+				if keccak(&code) != account.code_hash {
+					return Err(format!("Code of {:?} does not match its code hash", address));
+				}
+				Some(code)
+			},
+			_ => None,
+		};
+
+		let mut db = journaldb::new_memory_db();
+		for node in &proof {
+			db.insert(EMPTY_PREFIX, node);
+		}
+		if let Some(code) = code {
+			account_db::Factory::default().create(&mut db, address_hash).insert(EMPTY_PREFIX, &code);
+		}
+		self.write(db.drain())?;
+
+		self.accounts.lock().insert(*address, account.clone());
+		Ok(account)
+	}
+
+	/// Fetch the value under `key` in the storage of `address` as of the pinned block.
+	fn storage(&self, address: &Address, key: &H256) -> Result<(), String> {
+		if self.storage.lock().contains(&(*address, *key)) {
+			return Ok(());
+		}
+
+		let storage_root = match self.account(address)? {
+			Some(ref account) if account.storage_root != KECCAK_NULL_RLP => account.storage_root,
+			_ => return Ok(()),
+		};
+
+		let proof = self.source.storage_proof(address, key, &self.fork)?;
+		check_proof(&proof, &storage_root, &keccak(key))?;
+
+		let mut db = journaldb::new_memory_db();
+		{
+			let mut account_db = account_db::Factory::default().create(&mut db, keccak(address));
+			for node in &proof {
+				account_db.insert(EMPTY_PREFIX, node);
+			}
+		}
+		self.write(db.drain())?;
+
+		self.storage.lock().insert((*address, *key));
+		Ok(())
+	}
+
+	/// Fetch the trie node `hash` of the storage trie of `address`, or of the account trie.
+	fn node(&self, address: Option<&Address>, hash: &H256) -> Result<(), String> {
+		let node = self.source.node(hash)?;
+		if keccak(&node) != *hash {
+			return Err(format!("Trie node {:?} does not match its hash", hash));
+		}
+
+		let mut db = journaldb::new_memory_db();
+		match address {
+			Some(address) => {
+				account_db::Factory::default().create(&mut db, keccak(address)).insert(EMPTY_PREFIX, &node);
+			},
+			None => {
+				db.insert(EMPTY_PREFIX, &node);
+			},
+		}
+		self.write(db.drain())
+	}
+
+	fn write<I: IntoIterator<Item = (H256, (DBValue, i32))>>(&self, nodes: I) -> Result<(), String> {
+		let mut batch = DBTransaction::new();
+		for (key, (value, rc)) in nodes {
+			if rc > 0 {
+				batch.put(COL_STATE, key.as_bytes(), &value);
+			}
+		}
+		self.db.write(batch).map_err(|e| format!("Error writing fetched state: {}", e))
+	}
+}
+
+impl StateFetcher for ForkFetcher {
+	fn fetch_account(&self, address: &Address) -> Result<(), String> {
+		self.account(address).map(|_| ())
+	}
+
+	fn fetch_storage(&self, address: &Address, key: &H256) -> Result<(), String> {
+		self.storage(address, key)
+	}
+
+	fn fetch_node(&self, address: Option<&Address>, hash: &H256) -> Result<(), String> {
+		self.node(address, hash)
+	}
+}
+
+/// Look up `key` in the trie at `root` using only the nodes of `proof`.
+fn check_proof(proof: &[Bytes], root: &H256, key: &H256) -> Result<Option<DBValue>, String> {
+	let mut db = journaldb::new_memory_db();
+	for node in proof {
+		db.insert(EMPTY_PREFIX, node);
+	}
+
+	TrieDB::new(&db, root)
+		.and_then(|trie| trie.get(key.as_bytes()))
+		.map_err(|e| format!("Invalid proof: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+	use std::collections::HashMap;
+	use std::sync::Arc;
+
+	use account_state::State;
+	use bytes::Bytes;
+	use ethcore_db::{COL_STATE, NUM_COLUMNS};
+	use ethereum_types::{Address, H256, U256};
+	use ethtrie::{TrieDB, TrieDBMut};
+	use hash::{keccak, KECCAK_EMPTY, KECCAK_NULL_RLP};
+	use hash_db::{HashDB, EMPTY_PREFIX};
+	use journaldb;
+	use kvdb::KeyValueDB;
+	use kvdb_memorydb;
+	use rlp;
+	use spec::ForkPoint;
+	use state_db::StateDB;
+	use trie::{Recorder, Trie, TrieMut};
+	use types::basic_account::BasicAccount;
+	use types::ids::BlockId;
+
+	use super::{ForkFetcher, ForkSource};
+
+	/// Serves every node of a trie as the proof of any item in it.
+	struct TrieSource {
+		root: H256,
+		nodes: Vec<Bytes>,
+	}
+
+	impl ForkSource for TrieSource {
+		fn fork_point(&self, _block: BlockId) -> Result<ForkPoint, String> {
+			Ok(fork_point(self.root))
+		}
+
+		fn account_proof(&self, _address: &Address, _at: &ForkPoint) -> Result<Vec<Bytes>, String> {
+			Ok(self.nodes.clone())
+		}
+
+		fn storage_proof(&self, _address: &Address, _key: &H256, _at: &ForkPoint) -> Result<Vec<Bytes>, String> {
+			Ok(Vec::new())
+		}
+
+		fn code(&self, _address: &Address, _at: &ForkPoint) -> Result<Bytes, String> {
+			Ok(Vec::new())
+		}
+
+		fn node(&self, hash: &H256) -> Result<Bytes, String> {
+			self.nodes.iter().find(|node| keccak(node) == *hash).cloned().ok_or_else(|| "Unknown trie node".to_owned())
+		}
+	}
+
+	/// Serves proofs of the accounts and storage of a state, and its trie nodes by hash.
+	struct ProofSource {
+		root: H256,
+		storage_roots: HashMap<Address, H256>,
+		nodes: HashMap<H256, Bytes>,
+	}
+
+	impl ProofSource {
+		fn new(accounts: &[(Address, &[(H256, U256)])]) -> Self {
+			let mut db = journaldb::new_memory_db();
+			let mut storage_roots = HashMap::new();
+			for &(address, storage) in accounts {
+				let mut storage_root = H256::zero();
+				{
+					let mut trie = TrieDBMut::new(&mut db, &mut storage_root);
+					for (key, value) in storage {
+						trie.insert(keccak(key).as_bytes(), &rlp::encode(value)).unwrap();
+					}
+				}
+				storage_roots.insert(address, storage_root);
+			}
+
+			let mut root = H256::zero();
+			{
+				let mut trie = TrieDBMut::new(&mut db, &mut root);
+				for &(address, _) in accounts {
+					let account = BasicAccount {
+						nonce: U256::zero(),
+						balance: U256::one(),
+						storage_root: storage_roots[&address],
+						code_hash: KECCAK_EMPTY,
+						code_version: U256::zero(),
+					};
+					trie.insert(keccak(address).as_bytes(), &rlp::encode(&account)).unwrap();
+				}
+			}
+
+			let nodes = db.drain().into_iter().map(|(hash, (node, _))| (hash, node)).collect();
+			ProofSource { root, storage_roots, nodes }
+		}
+
+		fn proof(&self, root: &H256, key: &H256) -> Vec<Bytes> {
+			let mut db = journaldb::new_memory_db();
+			for node in self.nodes.values() {
+				db.insert(EMPTY_PREFIX, node);
+			}
+			let mut recorder = Recorder::new();
+			TrieDB::new(&db, root).unwrap().get_with(key.as_bytes(), &mut recorder).unwrap();
+			recorder.drain().into_iter().map(|record| record.data).collect()
+		}
+	}
+
+	impl ForkSource for ProofSource {
+		fn fork_point(&self, _block: BlockId) -> Result<ForkPoint, String> {
+			Ok(fork_point(self.root))
+		}
+
+		fn account_proof(&self, address: &Address, _at: &ForkPoint) -> Result<Vec<Bytes>, String> {
+			Ok(self.proof(&self.root, &keccak(address)))
+		}
+
+		fn storage_proof(&self, address: &Address, key: &H256, _at: &ForkPoint) -> Result<Vec<Bytes>, String> {
+			Ok(self.proof(&self.storage_roots[address], &keccak(key)))
+		}
+
+		fn code(&self, _address: &Address, _at: &ForkPoint) -> Result<Bytes, String> {
+			Ok(Vec::new())
+		}
+
+		fn node(&self, hash: &H256) -> Result<Bytes, String> {
+			self.nodes.get(hash).cloned().ok_or_else(|| "Unknown trie node".to_owned())
+		}
+	}
+
+	fn forked_state(source: ProofSource) -> State<StateDB> {
+		let db: Arc<dyn KeyValueDB> = Arc::new(kvdb_memorydb::create(NUM_COLUMNS));
+		let at = source.fork_point(BlockId::Latest).unwrap();
+		let root = at.state_root;
+		let fetcher = Arc::new(ForkFetcher::new(Box::new(source), at, db.clone()));
+		fetcher.fetch_root().unwrap();
+
+		let mut state_db = StateDB::new(journaldb::new(db, journaldb::Algorithm::Archive, COL_STATE), 1024 * 1024);
+		state_db.set_fetcher(Some(fetcher));
+		State::from_existing(state_db, root, U256::zero(), Default::default()).unwrap()
+	}
+
+	fn state_with(address: &Address, balance: u64) -> (H256, Vec<Bytes>) {
+		let mut db = journaldb::new_memory_db();
+		let mut root = H256::zero();
+		{
+			let mut trie = TrieDBMut::new(&mut db, &mut root);
+			let account = BasicAccount {
+				nonce: U256::zero(),
+				balance: balance.into(),
+				storage_root: KECCAK_NULL_RLP,
+				code_hash: KECCAK_EMPTY,
+				code_version: U256::zero(),
+			};
+			trie.insert(keccak(address).as_bytes(), &rlp::encode(&account)).unwrap();
+			trie.insert(keccak(Address::zero()).as_bytes(), &rlp::encode(&account)).unwrap();
+		}
+		let nodes = db.drain().into_iter().map(|(_, (value, _))| value).collect();
+		(root, nodes)
+	}
+
+	fn fork_point(state_root: H256) -> ForkPoint {
+		ForkPoint {
+			number: 1,
+			hash: H256::zero(),
+			state_root,
+			timestamp: 0,
+			gas_limit: 0.into(),
+			author: Address::zero(),
+			base_fee_per_gas: None,
+		}
+	}
+
+	#[test]
+	fn fetches_verified_accounts() {
+		let address = Address::from_low_u64_be(1);
+		let (root, nodes) = state_with(&address, 10);
+		let db: Arc<dyn KeyValueDB> = Arc::new(kvdb_memorydb::create(1));
+		let source = TrieSource { root, nodes };
+		let at = source.fork_point(BlockId::Latest).unwrap();
+		let fetcher = ForkFetcher::new(Box::new(source), at, db.clone());
+
+		let account = fetcher.account(&address).unwrap().unwrap();
+		assert_eq!(account.balance, 10.into());
+		assert!(db.get(COL_STATE, root.as_bytes()).unwrap().is_some());
+		assert!(fetcher.account(&Address::from_low_u64_be(2)).unwrap().is_none());
+	}
+
+	#[test]
+	fn rejects_proofs_of_another_state() {
+		let address = Address::from_low_u64_be(1);
+		let (root, _) = state_with(&address, 10);
+		let (other_root, other_nodes) = state_with(&address, 20);
+		let db: Arc<dyn KeyValueDB> = Arc::new(kvdb_memorydb::create(1));
+		let source = TrieSource { root, nodes: other_nodes };
+		let at = source.fork_point(BlockId::Latest).unwrap();
+		let fetcher = ForkFetcher::new(Box::new(source), at, db.clone());
+
+		assert!(fetcher.account(&address).is_err());
+		assert!(db.get(COL_STATE, other_root.as_bytes()).unwrap().is_none());
+		assert!(fetcher.storage(&address, &H256::zero()).is_err());
+	}
+
+	#[test]
+	fn removes_accounts_next_to_unfetched_ones() {
+		let first = Address::from_low_u64_be(1);
+		let second = Address::from_low_u64_be(2);
+		let mut state = forked_state(ProofSource::new(&[(first, &[]), (second, &[])]));
+
+		state.kill_account(&second);
+		state.commit().unwrap();
+		assert_eq!(*state.root(), ProofSource::new(&[(first, &[])]).root);
+	}
+
+	#[test]
+	fn clears_storage_next_to_unfetched_slots() {
+		let address = Address::from_low_u64_be(1);
+		let first = H256::from_low_u64_be(1);
+		let second = H256::from_low_u64_be(2);
+		let mut state = forked_state(ProofSource::new(&[(address, &[(first, U256::from(1)), (second, U256::from(2))])]));
+
+		state.set_storage(&address, first, H256::zero()).unwrap();
+		state.commit().unwrap();
+		assert_eq!(*state.root(), ProofSource::new(&[(address, &[(second, U256::from(2))])]).root);
+	}
+}
@@ -34,11 +34,14 @@ extern crate rpassword;
 extern crate rustc_hex;
 extern crate semver;
 extern crate serde;
+#[macro_use]
 extern crate serde_json;
 #[macro_use]
 extern crate serde_derive;
 extern crate toml;
 
+extern crate account_db;
+extern crate authority_round;
 extern crate blooms_db;
 extern crate cli_signer;
 
@@ -56,8 +59,10 @@ extern crate ethcore_private_tx;
 extern crate ethcore_service;
 extern crate ethcore_sync as sync;
 extern crate ethereum_types;
+extern crate ethjson;
 extern crate ethkey;
 extern crate ethstore;
+extern crate hash_db;
 extern crate journaldb;
 extern crate keccak_hash as hash;
 extern crate kvdb;
@@ -72,9 +77,14 @@ extern crate parity_rpc;
 extern crate parity_runtime;
 extern crate parity_updater as updater;
 extern crate parity_version;
+extern crate patricia_trie_ethereum as ethtrie;
+extern crate pod;
 extern crate registrar;
 extern crate snapshot;
 extern crate spec;
+extern crate state_db;
+extern crate stats;
+extern crate trie_db as trie;
 extern crate verification;
 
 #[macro_use]
@@ -99,6 +109,12 @@ extern crate pretty_assertions;
 #[cfg(test)]
 extern crate tempdir;
 
+#[cfg(test)]
+extern crate kvdb_memorydb;
+
+#[cfg(test)]
+extern crate account_state;
+
 mod account;
 mod account_utils;
 mod blockchain;
@@ -106,11 +122,13 @@ mod cache;
 mod cli;
 mod configuration;
 mod export_hardcoded_sync;
+mod fork;
 mod ipfs;
 mod deprecated;
 mod helpers;
 mod informant;
 mod light_helpers;
+mod metrics;
 mod modules;
 mod params;
 mod presale;
@@ -119,6 +137,7 @@ mod rpc_apis;
 mod run;
 mod secretstore;
 mod signer;
+mod slashing_protection_cmd;
 mod snapshot_cmd;
 mod upgrade;
 mod user_defaults;
@@ -223,6 +242,7 @@ fn execute<Cr, Rr>(
 		Cmd::SignerSign { id, pwfile, port, authfile } => cli_signer::signer_sign(id, pwfile, port, authfile).map(|s| ExecutionAction::Instant(Some(s))),
 		Cmd::SignerList { port, authfile } => cli_signer::signer_list(port, authfile).map(|s| ExecutionAction::Instant(Some(s))),
 		Cmd::SignerReject { id, port, authfile } => cli_signer::signer_reject(id, port, authfile).map(|s| ExecutionAction::Instant(Some(s))),
+		Cmd::SlashingProtection(cmd) => slashing_protection_cmd::execute(cmd).map(|s| ExecutionAction::Instant(Some(s))),
 		Cmd::Snapshot(snapshot_cmd) => snapshot_cmd::execute(snapshot_cmd).map(|s| ExecutionAction::Instant(Some(s))),
 		Cmd::ExportHardcodedSync(export_hs_cmd) => export_hardcoded_sync::execute(export_hs_cmd).map(|s| ExecutionAction::Instant(Some(s))),
 	}
@@ -0,0 +1,192 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Parity Ethereum.
+
+// Parity Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Prometheus metrics HTTP endpoint.
+
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{mpsc, Arc};
+use std::thread;
+
+use futures::{self, Future};
+use parity_rpc::hyper::{self, header::HeaderValue, service::service_fn_ok, Body, Method, Request, Response, StatusCode};
+use parity_rpc::informant::RpcStats;
+use stats::{PrometheusMetrics, PrometheusRegistry};
+
+/// Metrics server configuration.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Configuration {
+	/// Whether the endpoint is enabled.
+	pub enabled: bool,
+	/// Prefix prepended to every metric name.
+	pub prefix: String,
+	/// Port to listen on.
+	pub port: u16,
+	/// Interface to listen on.
+	pub interface: String,
+}
+
+impl Default for Configuration {
+	fn default() -> Self {
+		Configuration {
+			enabled: false,
+			prefix: "".into(),
+			port: 3000,
+			interface: "127.0.0.1".into(),
+		}
+	}
+}
+
+/// Sources of metrics exposed by the endpoint.
+pub struct Dependencies {
+	/// Components reporting their own metrics, e.g. the client and the sync.
+	pub sources: Vec<Arc<dyn PrometheusMetrics + Send + Sync>>,
+	/// RPC statistics.
+	pub rpc_stats: Option<Arc<RpcStats>>,
+}
+
+impl PrometheusMetrics for Dependencies {
+	fn prometheus_metrics(&self, r: &mut PrometheusRegistry) {
+		for source in &self.sources {
+			source.prometheus_metrics(r);
+		}
+
+		if let Some(ref rpc_stats) = self.rpc_stats {
+			r.register_gauge("rpc_sessions", "Open RPC sessions", rpc_stats.sessions() as i64);
+			r.register_gauge("rpc_requests_rate", "RPC requests per second", rpc_stats.requests_rate() as i64);
+			r.register_gauge("rpc_roundtrip_micros", "Approximated RPC roundtrip in microseconds", rpc_stats.approximated_roundtrip() as i64);
+		}
+	}
+}
+
+fn handle_request(req: Request<Body>, prefix: &str, deps: &Dependencies) -> Response<Body> {
+	match (req.method(), req.uri().path()) {
+		(&Method::GET, "/metrics") => {
+			let mut registry = PrometheusRegistry::new(prefix.to_owned());
+			deps.prometheus_metrics(&mut registry);
+
+			Response::builder()
+				.status(StatusCode::OK)
+				.header("content-type", HeaderValue::from_static("text/plain; version=0.0.4"))
+				.body(registry.render().into())
+		},
+		(&Method::GET, _) => {
+			Response::builder()
+				.status(StatusCode::NOT_FOUND)
+				.body(Body::empty())
+		},
+		_ => {
+			Response::builder()
+				.status(StatusCode::METHOD_NOT_ALLOWED)
+				.body(Body::empty())
+		},
+	}.expect("Response builder: Parsing 'content-type' header name will not fail; qed")
+}
+
+/// Handle to a running metrics server; the server is stopped when this is dropped.
+pub struct MetricsServer {
+	close: Option<futures::sync::oneshot::Sender<()>>,
+	thread: Option<thread::JoinHandle<()>>,
+}
+
+impl Drop for MetricsServer {
+	fn drop(&mut self) {
+		if let Some(close) = self.close.take() {
+			let _ = close.send(());
+		}
+		if let Some(thread) = self.thread.take() {
+			let _ = thread.join();
+		}
+	}
+}
+
+/// Start the metrics server if enabled in `conf`.
+pub fn start_server(conf: Configuration, deps: Dependencies) -> Result<Option<MetricsServer>, String> {
+	if !conf.enabled {
+		return Ok(None);
+	}
+
+	let ip: IpAddr = conf.interface.parse().map_err(|_| format!("Invalid --metrics-interface parameter: {}", conf.interface))?;
+	let addr = SocketAddr::new(ip, conf.port);
+	let deps = Arc::new(deps);
+	let prefix = Arc::new(conf.prefix);
+
+	let (close, shutdown_signal) = futures::sync::oneshot::channel::<()>();
+	let (tx, rx) = mpsc::sync_channel::<Result<(), String>>(1);
+	let thread = thread::Builder::new().name("metrics".into()).spawn(move || {
+		let send = |res| tx.send(res).expect("rx end is never dropped; qed");
+
+		let server_bldr = match hyper::Server::try_bind(&addr) {
+			Ok(s) => s,
+			Err(err) => {
+				send(Err(format!("Metrics server error: {}", err)));
+				return;
+			}
+		};
+
+		let new_service = move || {
+			let (deps, prefix) = (deps.clone(), prefix.clone());
+			service_fn_ok(move |req| handle_request(req, &prefix, &deps))
+		};
+
+		let server = server_bldr
+			.serve(new_service)
+			.map_err(|e| warn!("Metrics server error: {}", e))
+			.select(shutdown_signal.map_err(|_| ()))
+			.then(|_| Ok(()));
+
+		send(Ok(()));
+		hyper::rt::run(server);
+	}).map_err(|e| format!("Unable to spawn metrics thread: {}", e))?;
+
+	rx.recv().expect("tx end is never dropped; qed")?;
+	info!("Prometheus metrics available at http://{}/metrics", addr);
+
+	Ok(Some(MetricsServer {
+		close: Some(close),
+		thread: Some(thread),
+	}))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use futures::Stream;
+
+	struct Fixed;
+
+	impl PrometheusMetrics for Fixed {
+		fn prometheus_metrics(&self, r: &mut PrometheusRegistry) {
+			r.register_gauge("answer", "The answer", 42);
+		}
+	}
+
+	#[test]
+	fn serves_metrics_path_only() {
+		let deps = Dependencies { sources: vec![Arc::new(Fixed)], rpc_stats: None };
+
+		let req = Request::get("/metrics").body(Body::empty()).unwrap();
+		let res = handle_request(req, "parity_", &deps);
+		assert_eq!(res.status(), StatusCode::OK);
+		let body = res.into_body().concat2().wait().unwrap();
+		assert_eq!(&body[..], &b"# HELP parity_answer The answer\n# TYPE parity_answer gauge\nparity_answer 42\n"[..]);
+
+		let req = Request::get("/").body(Body::empty()).unwrap();
+		assert_eq!(handle_request(req, "parity_", &deps).status(), StatusCode::NOT_FOUND);
+
+		let req = Request::post("/metrics").body(Body::empty()).unwrap();
+		assert_eq!(handle_request(req, "parity_", &deps).status(), StatusCode::METHOD_NOT_ALLOWED);
+	}
+}
@@ -14,6 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
 
+use std::collections::BTreeSet;
 use std::sync::{Arc, mpsc};
 
 use client_traits::{BlockChainClient, ChainNotify};
@@ -22,6 +23,8 @@ use snapshot::SnapshotService;
 use ethcore_private_tx::PrivateStateDB;
 use light::Provider;
 use parity_runtime::Executor;
+use types::BlockNumber;
+use stats::PrometheusMetrics;
 
 pub use sync::{EthSync, SyncProvider, ManageNetwork, PrivateTxHandler};
 use ethcore_logger::Config as LogConfig;
@@ -31,6 +34,7 @@ pub type SyncModules = (
 	Arc<dyn ManageNetwork>,
 	Arc<dyn ChainNotify>,
 	mpsc::Sender<sync::PriorityTask>,
+	Arc<dyn PrometheusMetrics + Send + Sync>,
 );
 
 pub fn sync(
@@ -44,6 +48,7 @@ pub fn sync(
 	provider: Arc<dyn Provider>,
 	_log_settings: &LogConfig,
 	connection_filter: Option<Arc<dyn ConnectionFilter>>,
+	forks: BTreeSet<BlockNumber>,
 ) -> Result<SyncModules, sync::Error> {
 	let eth_sync = EthSync::new(Params {
 		config,
@@ -54,6 +59,7 @@ pub fn sync(
 		private_tx_handler,
 		private_state,
 		network_config,
+		forks,
 	},
 	connection_filter)?;
 
@@ -61,6 +67,7 @@ pub fn sync(
 		eth_sync.clone() as Arc<dyn SyncProvider>,
 		eth_sync.clone() as Arc<dyn ManageNetwork>,
 		eth_sync.clone() as Arc<dyn ChainNotify>,
-		eth_sync.priority_tasks()
+		eth_sync.priority_tasks(),
+		eth_sync.clone() as Arc<dyn PrometheusMetrics + Send + Sync>,
 	))
 }
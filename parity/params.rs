@@ -305,6 +305,7 @@ impl GasPricerConfig {
 pub struct MinerExtras {
 	pub author: Address,
 	pub engine_signer: Address,
+	pub engine_signer_url: Option<String>,
 	pub extra_data: Vec<u8>,
 	pub gas_range_target: (U256, U256),
 	pub work_notify: Vec<String>,
@@ -316,6 +317,7 @@ impl Default for MinerExtras {
 		MinerExtras {
 			author: Default::default(),
 			engine_signer: Default::default(),
+			engine_signer_url: None,
 			extra_data: version_data(),
 			gas_range_target: (8_000_000.into(), 10_000_000.into()),
 			work_notify: Default::default(),
@@ -23,7 +23,7 @@ pub use parity_rpc::signer::SignerService;
 
 use account_utils::{self, AccountProvider};
 use ethcore::client::Client;
-use ethcore::miner::Miner;
+use ethcore::miner::{Miner, MinerService};
 use snapshot::SnapshotService;
 use client_traits::BlockChainClient;
 use sync::SyncState;
@@ -341,6 +341,11 @@ impl FullDependencies {
 							})
 						});
 
+						let miner = self.miner.clone();
+						client.add_transactions_notifier(self.miner.full_transactions_receiver(), move |hash| {
+							miner.transaction(hash).map(|transaction| transaction.signed().clone())
+						});
+
 						if let Some(h) = client.handler().upgrade() {
 							self.client.add_notify(h);
 						}
@@ -581,6 +586,10 @@ impl<C: LightChainClient + 'static> LightDependencies<C> {
 						})
 					});
 
+					let transaction_queue = self.transaction_queue.clone();
+					let receiver = self.transaction_queue.write().full_transactions_receiver();
+					client.add_transactions_notifier(receiver, move |hash| transaction_queue.read().transaction(hash));
+
 					self.client.add_listener(client.handler() as Weak<_>);
 					handler.extend_with(EthPubSub::to_delegate(client));
 				}
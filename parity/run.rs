@@ -25,6 +25,7 @@ use ethcore::client::{Client, DatabaseCompactionProfile};
 use ethcore::miner::{self, stratum, Miner, MinerService, MinerOptions};
 use snapshot::{self, SnapshotConfiguration};
 use spec::SpecParams;
+use stats::PrometheusMetrics;
 use verification::queue::VerifierSettings;
 use ethcore_logger::{Config as LogConfig, RotatingLogger};
 use ethcore_service::ClientService;
@@ -59,6 +60,7 @@ use dir::{Directories, DatabaseDirectories};
 use cache::CacheConfig;
 use user_defaults::UserDefaults;
 use ipfs;
+use metrics;
 use jsonrpc_core;
 use modules;
 use rpc;
@@ -66,6 +68,7 @@ use rpc_apis;
 use secretstore;
 use signer;
 use db;
+use fork::{ForkConfig, ForkFetcher, ForkSource, RpcSource};
 use registrar::RegistrarClient;
 
 // How often we attempt to take a snapshot: only snapshot on blocknumbers that are multiples of this.
@@ -117,6 +120,7 @@ pub struct RunCmd {
 	pub experimental_rpcs: bool,
 	pub net_settings: NetworkSettings,
 	pub ipfs_conf: ipfs::Configuration,
+	pub metrics_conf: metrics::Configuration,
 	pub secretstore_conf: secretstore::Configuration,
 	pub private_provider_conf: ProviderConfig,
 	pub private_encryptor_conf: EncryptorConfig,
@@ -139,6 +143,7 @@ pub struct RunCmd {
 	pub on_demand_request_backoff_max: Option<u64>,
 	pub on_demand_request_backoff_rounds_max: Option<usize>,
 	pub on_demand_request_consecutive_failures: Option<usize>,
+	pub fork: Option<ForkConfig>,
 }
 
 // node info fetcher for the local store.
@@ -373,8 +378,21 @@ fn execute_impl<Cr, Rr>(
 		Cr: Fn(String) + 'static + Send,
 		Rr: Fn() + 'static + Send
 {
+	// pin the block to fork from, if running a fork
+	let fork = match cmd.fork {
+		Some(ref fork) => {
+			let source = RpcSource::new(&fork.url)?;
+			let point = source.fork_point(fork.block)?;
+			Some((source, point))
+		},
+		None => None,
+	};
+
 	// load spec
-	let spec = cmd.spec.spec(&cmd.dirs.cache)?;
+	let spec = match fork {
+		Some((_, ref point)) => cmd.spec.spec(SpecParams::from(&cmd.dirs.cache).with_fork(point))?,
+		None => cmd.spec.spec(&cmd.dirs.cache)?,
+	};
 
 	// load genesis hash
 	let genesis_hash = spec.genesis_header().hash();
@@ -388,8 +406,12 @@ fn execute_impl<Cr, Rr>(
 	// load user defaults
 	let mut user_defaults = UserDefaults::load(&user_defaults_path)?;
 
-	// select pruning algorithm
-	let algorithm = cmd.pruning.to_algorithm(&user_defaults);
+	// select pruning algorithm; the state of a fork is written to the database as it
+	// is fetched, bypassing the journal, so it must never be pruned.
+	let algorithm = match fork {
+		Some(_) => Algorithm::Archive,
+		None => cmd.pruning.to_algorithm(&user_defaults),
+	};
 
 	// check if tracing is on
 	let tracing = tracing_switch_to_bool(cmd.tracing, &user_defaults)?;
@@ -450,6 +472,7 @@ fn execute_impl<Cr, Rr>(
 	}
 
 	sync_config.fork_block = spec.fork_block();
+	let hard_forks = spec.hard_forks();
 	let snapshot_supported =
 		if let Snapshotting::Unsupported = spec.engine.snapshot_mode() {
 			false
@@ -512,9 +535,24 @@ fn execute_impl<Cr, Rr>(
 		));
 	}
 
+	// the engine's slashing protection has to be in place before it gets a signer.
+	spec.engine.set_data_dir(&db_dirs.engine_path())
+		.map_err(|e| format!("Failed to open the engine data directory: {}", e))?;
+
 	let engine_signer = cmd.miner_extras.engine_signer;
 	if engine_signer != Default::default() {
-		if let Some(author) = account_utils::miner_author(&cmd.spec, &cmd.dirs, &account_provider, engine_signer, &passwords)? {
+		if let Some(ref url) = cmd.miner_extras.engine_signer_url {
+			if !spec.engine.signs_data_only() {
+				return Err("The remote engine signer can't sign bare hashes, which the engine signs. \
+					Only AuthorityRound can be used with a remote engine signer.".into());
+			}
+			if spec.engine.requires_signer_secret() {
+				return Err("The remote engine signer can't decrypt messages or provide its public key, \
+					which the engine's randomness protocol requires.".into());
+			}
+			let signer = parity_rpc::signer::RemoteSigner::new(url, engine_signer)?;
+			miner.set_author(miner::Author::Sealer(Box::new(signer)));
+		} else if let Some(author) = account_utils::miner_author(&cmd.spec, &cmd.dirs, &account_provider, engine_signer, &passwords)? {
 			miner.set_author(author);
 		}
 	}
@@ -557,6 +595,16 @@ fn execute_impl<Cr, Rr>(
 	let client_db = restoration_db_handler.open(&client_path)
 		.map_err(|e| format!("Failed to open database {:?}", e))?;
 
+	let fork_fetcher = match fork {
+		Some((source, point)) => {
+			info!("Forking block #{} ({:?}) from {}", point.number, point.hash, cmd.fork.as_ref().map_or("", |f| &f.url));
+			let fetcher = Arc::new(ForkFetcher::new(Box::new(source), point, client_db.key_value().clone()));
+			fetcher.fetch_root()?;
+			Some(fetcher)
+		},
+		None => None,
+	};
+
 	let private_tx_signer = account_utils::private_tx_signer(account_provider.clone(), &passwords)?;
 
 	// create client service.
@@ -580,6 +628,9 @@ fn execute_impl<Cr, Rr>(
 
 	// take handle to client
 	let client = service.client();
+	if let Some(fetcher) = fork_fetcher {
+		client.set_state_fetcher(fetcher);
+	}
 	// Update miners block gas limit
 	miner.update_transaction_queue_limits(*client.best_block_header().gas_limit());
 
@@ -644,7 +695,7 @@ fn execute_impl<Cr, Rr>(
 	};
 
 	// create sync object
-	let (sync_provider, manage_network, chain_notify, priority_tasks) = modules::sync(
+	let (sync_provider, manage_network, chain_notify, priority_tasks, sync_metrics) = modules::sync(
 		sync_config,
 		runtime.executor(),
 		net_conf.clone().into(),
@@ -655,6 +706,7 @@ fn execute_impl<Cr, Rr>(
 		client.clone(),
 		&cmd.logger_config,
 		connection_filter.clone().map(|f| f as Arc<dyn sync::ConnectionFilter + 'static>),
+		hard_forks,
 	).map_err(|e| format!("Sync error: {}", e))?;
 
 	service.add_notify(chain_notify.clone());
@@ -760,6 +812,12 @@ fn execute_impl<Cr, Rr>(
 	// the ipfs server
 	let ipfs_server = ipfs::start_server(cmd.ipfs_conf.clone(), client.clone())?;
 
+	// the prometheus metrics server
+	let metrics_server = metrics::start_server(cmd.metrics_conf.clone(), metrics::Dependencies {
+		sources: vec![client.clone() as Arc<dyn PrometheusMetrics + Send + Sync>, sync_metrics],
+		rpc_stats: Some(rpc_stats.clone()),
+	})?;
+
 	// the informant
 	let informant = Arc::new(Informant::new(
 		FullNodeInformantData {
@@ -817,7 +875,7 @@ fn execute_impl<Cr, Rr>(
 			informant,
 			client,
 			client_service: Arc::new(service),
-			keep_alive: Box::new((watcher, updater, ws_server, http_server, ipc_server, secretstore_key_server, ipfs_server, runtime)),
+			keep_alive: Box::new((watcher, updater, ws_server, http_server, ipc_server, secretstore_key_server, ipfs_server, metrics_server, runtime)),
 		}
 	})
 }
@@ -927,7 +985,9 @@ pub fn execute<Cr, Rr>(
 		Cr: Fn(String) + 'static + Send,
 		Rr: Fn() + 'static + Send
 {
-	if cmd.light {
+	if cmd.light && cmd.fork.is_some() {
+		Err("A fork cannot be run as a light client".into())
+	} else if cmd.light {
 		execute_light_impl(cmd, logger, on_client_rq)
 	} else {
 		execute_impl(cmd, logger, on_client_rq, on_updater_rq)
@@ -0,0 +1,78 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Parity Ethereum.
+
+// Parity Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Export and import of the slashing-protection database of the validator.
+
+use std::fs;
+use std::io;
+
+use authority_round::SlashingProtection;
+use dir::Directories;
+use ethjson::slashing_protection::Interchange;
+use spec::SpecParams;
+use types::engines::OptimizeFor;
+use params::SpecType;
+
+/// Slashing-protection command.
+#[derive(Debug, PartialEq)]
+pub enum SlashingProtectionCmd {
+	/// Export the database, to the given file or the standard output.
+	Export(SlashingProtectionFile),
+	/// Merge the given file into the database.
+	Import(SlashingProtectionFile),
+}
+
+#[derive(Debug, PartialEq)]
+pub struct SlashingProtectionFile {
+	pub spec: SpecType,
+	pub dirs: Directories,
+	pub file_path: Option<String>,
+}
+
+pub fn execute(cmd: SlashingProtectionCmd) -> Result<String, String> {
+	match cmd {
+		SlashingProtectionCmd::Export(cmd) => {
+			let db = open(&cmd)?;
+			let json = serde_json::to_string_pretty(&db.export())
+				.map_err(|e| format!("Failed to serialize the slashing-protection records: {}", e))?;
+			match cmd.file_path {
+				Some(path) => {
+					fs::write(&path, json).map_err(|e| format!("Failed to write {}: {}", path, e))?;
+					Ok(format!("Slashing-protection records exported to {}", path))
+				}
+				None => Ok(json),
+			}
+		}
+		SlashingProtectionCmd::Import(cmd) => {
+			let mut db = open(&cmd)?;
+			let path = cmd.file_path.ok_or("Missing the file to import from")?;
+			let file = fs::File::open(&path).map_err(|e| format!("Failed to open {}: {}", path, e))?;
+			let interchange: Interchange = serde_json::from_reader(io::BufReader::new(file))
+				.map_err(|e| format!("Invalid slashing-protection file {}: {}", path, e))?;
+			let signers = interchange.signers.len();
+			db.import(interchange).map_err(|e| format!("Failed to import slashing-protection records: {}", e))?;
+			Ok(format!("Imported slashing-protection records of {} signers", signers))
+		}
+	}
+}
+
+fn open(cmd: &SlashingProtectionFile) -> Result<SlashingProtection, String> {
+	let spec = cmd.spec.spec(SpecParams::new(cmd.dirs.cache.as_ref(), OptimizeFor::Memory))?;
+	let genesis_hash = spec.genesis_header().hash();
+	let db_dirs = cmd.dirs.database(genesis_hash, cmd.spec.legacy_fork_name(), spec.data_dir);
+	let path = db_dirs.engine_path().join("slashing_protection");
+	SlashingProtection::open(&path).map_err(|e| format!("Failed to open the slashing-protection database at {}: {}", path.display(), e))
+}
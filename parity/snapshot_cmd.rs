@@ -16,13 +16,20 @@
 
 //! Snapshot and restoration commands.
 
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{self, BufReader, Read};
+use std::iter;
+use std::str::FromStr;
 use std::time::Duration;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
+use bytes::Bytes;
+use ethereum_types::H256;
 use hash::keccak;
 use snapshot::{SnapshotConfiguration, SnapshotService as SS, SnapshotClient};
-use snapshot::io::{SnapshotReader, PackedReader, PackedWriter};
+use snapshot::io::{SnapshotReader, PackedReader, PackedWriter, TarReader, TarWriter};
 use snapshot::service::Service as SnapshotService;
 use ethcore::client::{Client, DatabaseCompactionProfile};
 use ethcore::miner::Miner;
@@ -30,7 +37,7 @@ use ethcore_service::ClientService;
 use parking_lot::RwLock;
 use types::{
 	ids::BlockId,
-	snapshot::Progress,
+	snapshot::{ManifestData, Progress},
 	client_types::Mode,
 	snapshot::RestorationStatus,
 };
@@ -52,6 +59,33 @@ pub enum Kind {
 	Restore
 }
 
+/// Snapshot file formats.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Format {
+	/// Chunks followed by the manifest, read by seeking in the file.
+	Packed,
+	/// Tar archive starting with the manifest, which can be restored from a stream.
+	Tar,
+}
+
+impl Default for Format {
+	fn default() -> Self {
+		Format::Packed
+	}
+}
+
+impl FromStr for Format {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"packed" => Ok(Format::Packed),
+			"tar" => Ok(Format::Tar),
+			x => Err(format!("Invalid snapshot format: {}", x))
+		}
+	}
+}
+
 /// Command for snapshot creation or restoration.
 #[derive(Debug, PartialEq)]
 pub struct SnapshotCommand {
@@ -65,6 +99,7 @@ pub struct SnapshotCommand {
 	pub fat_db: Switch,
 	pub compaction: DatabaseCompactionProfile,
 	pub file_path: Option<String>,
+	pub format: Format,
 	pub kind: Kind,
 	pub block_at: BlockId,
 	pub max_round_blocks_to_import: usize,
@@ -78,54 +113,149 @@ fn restore_using<R: SnapshotReader>(snapshot: Arc<SnapshotService<Client>>, read
 
 	info!("Restoring to block #{} (0x{:?})", manifest.block_number, manifest.block_hash);
 
-	snapshot.init_restore(manifest.clone(), recover).map_err(|e| {
-		format!("Failed to begin restoration: {}", e)
-	})?;
+	let completed = begin_restore(&snapshot, manifest, recover)?;
 
-	let (num_state, num_blocks) = (manifest.state_hashes.len(), manifest.block_hashes.len());
-
-	let informant_handle = snapshot.clone();
-	::std::thread::spawn(move || {
- 		while let RestorationStatus::Ongoing { state_chunks_done, block_chunks_done, .. } = informant_handle.status() {
- 			info!("Processed {}/{} state chunks and {}/{} block chunks.",
- 				state_chunks_done, num_state, block_chunks_done, num_blocks);
- 			::std::thread::sleep(Duration::from_secs(5));
- 		}
- 	});
-
- 	info!("Restoring state");
- 	for &state_hash in &manifest.state_hashes {
- 		if snapshot.status() == RestorationStatus::Failed {
- 			return Err("Restoration failed".into());
- 		}
-
- 		let chunk = reader.chunk(state_hash)
-			.map_err(|e| format!("Encountered error while reading chunk {:?}: {}", state_hash, e))?;
-
-		let hash = keccak(&chunk);
-		if hash != state_hash {
-			return Err(format!("Mismatched chunk hash. Expected {:?}, got {:?}", state_hash, hash));
-		}
-
- 		snapshot.feed_state_chunk(state_hash, &chunk);
- 	}
+	info!("Restoring state");
+	let mut read_error = None;
+	{
+		let mut state_hashes = manifest.state_hashes.iter().filter(|hash| !completed.contains(hash));
+		let chunks = iter::from_fn(|| {
+			if read_error.is_some() || snapshot.status() == RestorationStatus::Failed {
+				return None;
+			}
+			let &state_hash = state_hashes.next()?;
+			match read_chunk(reader, state_hash) {
+				Ok(chunk) => Some((state_hash, chunk)),
+				Err(e) => {
+					read_error = Some(e);
+					None
+				}
+			}
+		});
+		snapshot.feed_state_chunks(chunks, ::num_cpus::get());
+	}
+	if let Some(e) = read_error {
+		return Err(e);
+	}
 
 	info!("Restoring blocks");
 	for &block_hash in &manifest.block_hashes {
 		if snapshot.status() == RestorationStatus::Failed {
 			return Err("Restoration failed".into());
 		}
+		if completed.contains(&block_hash) {
+			continue;
+		}
+
+		let chunk = read_chunk(reader, block_hash)?;
+		snapshot.feed_block_chunk(block_hash, &chunk);
+	}
+
+	restoration_result(&snapshot)
+}
+
+// read a chunk and check it matches its hash.
+fn read_chunk<R: SnapshotReader>(reader: &R, hash: H256) -> Result<Bytes, String> {
+	let chunk = reader.chunk(hash)
+		.map_err(|e| format!("Encountered error while reading chunk {:?}: {}", hash, e))?;
+
+	let actual = keccak(&chunk);
+	if actual != hash {
+		return Err(format!("Mismatched chunk hash. Expected {:?}, got {:?}", hash, actual));
+	}
+	Ok(chunk)
+}
 
- 		let chunk = reader.chunk(block_hash)
-			.map_err(|e| format!("Encountered error while reading chunk {:?}: {}", block_hash, e))?;
+// helper for restoring from a tar archive, feeding chunks in the order they are read.
+fn restore_from_tar<R: Read>(snapshot: Arc<SnapshotService<Client>>, mut reader: TarReader<R>) -> Result<(), String> {
+	let manifest = reader.manifest().clone();
 
-		let hash = keccak(&chunk);
-		if hash != block_hash {
-			return Err(format!("Mismatched chunk hash. Expected {:?}, got {:?}", block_hash, hash));
+	info!("Restoring to block #{} (0x{:?})", manifest.block_number, manifest.block_hash);
+
+	// always recover, so that an interrupted restoration can be resumed.
+	let completed = begin_restore(&snapshot, &manifest, true)?;
+	let state_hashes: HashSet<_> = manifest.state_hashes.iter().cloned().collect();
+
+	// state chunks come first in the archive and are restored in parallel, until another chunk is read.
+	let mut read_error = None;
+	let mut next_chunk = None;
+	{
+		let chunks = iter::from_fn(|| loop {
+			if read_error.is_some() || snapshot.status() == RestorationStatus::Failed {
+				return None;
+			}
+			match reader.next_chunk() {
+				Ok(Some((hash, _))) if completed.contains(&hash) => continue,
+				Ok(Some((hash, chunk))) if state_hashes.contains(&hash) => return Some((hash, chunk)),
+				Ok(other) => {
+					next_chunk = other;
+					return None;
+				},
+				Err(e) => {
+					read_error = Some(format!("Encountered error while reading snapshot: {}", e));
+					return None;
+				},
+			}
+		});
+		snapshot.feed_state_chunks(chunks, ::num_cpus::get());
+	}
+	if let Some(e) = read_error {
+		return Err(e);
+	}
+
+	// the remaining chunks are fed one at a time.
+	while let Some((hash, chunk)) = match next_chunk.take() {
+		Some(chunk) => Some(chunk),
+		None => reader.next_chunk().map_err(|e| format!("Encountered error while reading snapshot: {}", e))?,
+	} {
+		if snapshot.status() == RestorationStatus::Failed {
+			return Err("Restoration failed".into());
+		}
+		if completed.contains(&hash) {
+			continue;
+		}
+
+		if state_hashes.contains(&hash) {
+			snapshot.feed_state_chunk(hash, &chunk);
+		} else {
+			snapshot.feed_block_chunk(hash, &chunk);
 		}
-		snapshot.feed_block_chunk(block_hash, &chunk);
 	}
 
+	restoration_result(&snapshot)
+}
+
+// start the restoration and its informant. Returns the chunks already restored by
+// a previous, interrupted, restoration of the same snapshot.
+fn begin_restore(snapshot: &Arc<SnapshotService<Client>>, manifest: &ManifestData, recover: bool) -> Result<HashSet<H256>, String> {
+	snapshot.init_restore(manifest.clone(), recover).map_err(|e| {
+		format!("Failed to begin restoration: {}", e)
+	})?;
+
+	let completed: HashSet<H256> = snapshot.completed_chunks().unwrap_or_default().into_iter().collect();
+	if !completed.is_empty() {
+		info!("Resuming restoration, {} chunks were already restored.", completed.len());
+	}
+
+	let (num_state, num_blocks) = (manifest.state_hashes.len(), manifest.block_hashes.len());
+
+	let informant_handle = snapshot.clone();
+	::std::thread::spawn(move || {
+		while let RestorationStatus::Ongoing { state_chunks_done, block_chunks_done, .. } = informant_handle.status() {
+			info!("Processed {}/{} state chunks and {}/{} block chunks.",
+				state_chunks_done, num_state, block_chunks_done, num_blocks);
+			let workers = informant_handle.state_workers_progress();
+			if !workers.is_empty() {
+				info!("State chunks processed per worker: {:?}", workers);
+			}
+			::std::thread::sleep(Duration::from_secs(5));
+		}
+	});
+
+	Ok(completed)
+}
+
+fn restoration_result(snapshot: &SnapshotService<Client>) -> Result<(), String> {
 	match snapshot.status() {
 		RestorationStatus::Ongoing { .. } => Err("Snapshot file is incomplete and missing chunks.".into()),
 		RestorationStatus::Initializing { .. } => Err("Snapshot restoration is still initializing.".into()),
@@ -215,6 +345,7 @@ impl SnapshotCommand {
 	/// restore from a snapshot
 	pub fn restore(self) -> Result<(), String> {
 		let file = self.file_path.clone();
+		let format = self.format;
 		let service = self.start_service()?;
 
 		warn!("Snapshot restoration is experimental and the format may be subject to change.");
@@ -225,12 +356,26 @@ impl SnapshotCommand {
 		if let Some(file) = file {
 			info!("Attempting to restore from snapshot at '{}'", file);
 
-			let reader = PackedReader::new(Path::new(&file))
-				.map_err(|e| format!("Couldn't open snapshot file: {}", e))
-				.and_then(|x| x.ok_or("Snapshot file has invalid format.".into()));
-
-			let reader = reader?;
-			restore_using(snapshot, &reader, true)?;
+			match format {
+				Format::Packed => {
+					let reader = PackedReader::new(Path::new(&file))
+						.map_err(|e| format!("Couldn't open snapshot file: {}", e))
+						.and_then(|x| x.ok_or("Snapshot file has invalid format.".into()));
+
+					let reader = reader?;
+					restore_using(snapshot, &reader, true)?;
+				},
+				Format::Tar => {
+					// `-` streams the archive from the standard input.
+					let input: Box<dyn Read> = match file.as_str() {
+						"-" => Box::new(io::stdin()),
+						_ => Box::new(File::open(&file).map_err(|e| format!("Couldn't open snapshot file: {}", e))?),
+					};
+					let reader = TarReader::new(BufReader::new(input))
+						.map_err(|e| format!("Snapshot file has invalid format: {}", e))?;
+					restore_from_tar(snapshot, reader)?;
+				},
+			}
 		} else {
 			info!("Attempting to restore from local snapshot.");
 
@@ -250,13 +395,11 @@ impl SnapshotCommand {
 		let file_path = self.file_path.clone().ok_or("No file path provided.".to_owned())?;
 		let file_path: PathBuf = file_path.into();
 		let block_at = self.block_at;
+		let format = self.format;
 		let service = self.start_service()?;
 
 		warn!("Snapshots are currently experimental. File formats may be subject to change.");
 
-		let writer = PackedWriter::new(&file_path)
-			.map_err(|e| format!("Failed to open snapshot writer: {}", e))?;
-
 		let progress = Arc::new(RwLock::new(Progress::new()));
 		let p = progress.clone();
 		let informant_handle = ::std::thread::spawn(move || {
@@ -280,7 +423,20 @@ impl SnapshotCommand {
 			}
  		});
 
-		if let Err(e) = service.client().take_snapshot(writer, block_at, &*progress) {
+		let result = match format {
+			Format::Packed => {
+				let writer = PackedWriter::new(&file_path)
+					.map_err(|e| format!("Failed to open snapshot writer: {}", e))?;
+				service.client().take_snapshot(writer, block_at, &*progress)
+			},
+			Format::Tar => {
+				let writer = TarWriter::new(&file_path)
+					.map_err(|e| format!("Failed to open snapshot writer: {}", e))?;
+				service.client().take_snapshot(writer, block_at, &*progress)
+			},
+		};
+
+		if let Err(e) = result {
 			let _ = ::std::fs::remove_file(&file_path);
 			return Err(format!("Encountered fatal error while creating snapshot: {}", e));
 		}
@@ -77,6 +77,7 @@ extern crate ethcore_sync as sync;
 extern crate ethereum_types;
 extern crate ethkey;
 extern crate ethstore;
+extern crate engine;
 extern crate fetch;
 extern crate keccak_hash as hash;
 extern crate parity_runtime;
@@ -106,9 +107,6 @@ extern crate serde_derive;
 #[cfg(test)]
 extern crate rand_xorshift;
 
-#[cfg(test)]
-extern crate engine;
-
 #[cfg(test)]
 extern crate ethjson;
 #[cfg(test)]
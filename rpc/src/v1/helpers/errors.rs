@@ -226,6 +226,14 @@ pub fn cannot_submit_work(err: EthcoreError) -> Error {
 	}
 }
 
+pub fn engine(err: EthcoreError) -> Error {
+	Error {
+		code: ErrorCode::ServerError(codes::UNKNOWN_ERROR),
+		message: "Consensus engine error.".into(),
+		data: Some(Value::String(err.to_string())),
+	}
+}
+
 pub fn unavailable_block(no_ancient_block: bool, by_hash: bool) -> Error {
 	if no_ancient_block {
 		Error {
@@ -460,6 +468,8 @@ pub fn transaction_message(error: &TransactionError) -> String {
 		NotAllowed => "Transaction is not permitted.".into(),
 		TooBig => "Transaction is too big, see chain specification for the limit.".into(),
 		InvalidRlp(ref descr) => format!("Invalid RLP data: {}", descr),
+		TransactionTypeNotEnabled => "Transaction type is not enabled on this chain yet.".into(),
+		TipAboveFeeCap => "Max priority fee per gas is higher than max fee per gas.".into(),
 	}
 }
 
@@ -29,6 +29,7 @@ pub mod fake_sign;
 pub mod ipfs;
 pub mod light_fetch;
 pub mod nonce;
+pub mod remote_signer;
 #[cfg(any(test, feature = "accounts"))]
 pub mod secretstore;
 
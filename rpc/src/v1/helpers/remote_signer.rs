@@ -0,0 +1,246 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Engine signer forwarding hashes to a remote signing service.
+//!
+//! The data to sign is posted to `{url}/api/v1/eth1/sign/{address}` as `{"data": "0x..."}`, the
+//! endpoint of Web3Signer, and the service answers with the hex-encoded 65-byte signature of the
+//! Keccak-256 hash of the data. As the service hashes the data itself, the signer can't sign a
+//! bare hash. Signatures are checked to recover to the signer address before they are used.
+
+use std::io::Read;
+use std::time::Duration;
+
+use crypto::publickey::{public_to_address, recover, Address, Error, Message, Public, Signature};
+use ethereum_types::H520;
+use fetch::{self, Fetch};
+use futures::Future;
+use http::hyper::header::{self, HeaderValue};
+use hash::keccak;
+use parking_lot::Mutex;
+use rustc_hex::{FromHex, ToHex};
+
+/// Maximum time to wait for a signature, as blocks have to be sealed within their step.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// An implementation of EngineSigner using a remote signing service.
+pub struct RemoteSigner {
+	client: fetch::Client,
+	url: fetch::Url,
+	address: Address,
+	/// Step and hash of the last block seal signed.
+	last_seal: Mutex<Option<(u64, Message)>>,
+}
+
+impl RemoteSigner {
+	/// Creates new `RemoteSigner` signing as `address` through the service at `url`.
+	pub fn new(url: &str, address: Address) -> Result<Self, String> {
+		let url = fetch::Url::parse(url)
+			.and_then(|url| url.join(&format!("api/v1/eth1/sign/0x{:x}", address)))
+			.map_err(|e| format!("Invalid signer URL {}: {}", url, e))?;
+		Ok(RemoteSigner {
+			client: fetch::Client::new(1).map_err(|e| format!("Error starting fetch client: {:?}", e))?,
+			url,
+			address,
+			last_seal: Mutex::new(None),
+		})
+	}
+
+	fn request_signature(&self, data: &[u8]) -> Result<Signature, String> {
+		let body = format!(r#"{{"data":"0x{}"}}"#, data.to_hex());
+		let request = fetch::Request::post(self.url.clone())
+			.with_header(header::CONTENT_TYPE, HeaderValue::from_static("application/json"))
+			.with_body(body);
+
+		let abort = fetch::Abort::default().with_max_duration(REQUEST_TIMEOUT);
+		let response = self.client.fetch(request, abort)
+			.wait()
+			.map_err(|e| format!("request failed: {:?}", e))?;
+		if !response.is_success() {
+			return Err(format!("request failed: {}", response.status()));
+		}
+
+		let mut text = String::new();
+		fetch::BodyReader::new(response).read_to_string(&mut text)
+			.map_err(|e| format!("invalid response: {}", e))?;
+		let hex = text.trim().trim_matches('"');
+		let raw: Vec<u8> = hex.trim_start_matches("0x").from_hex()
+			.map_err(|e| format!("invalid signature {}: {}", hex, e))?;
+		if raw.len() != 65 {
+			return Err(format!("invalid signature length: {}", raw.len()));
+		}
+
+		let mut bytes = [0u8; 65];
+		bytes.copy_from_slice(&raw);
+		// the service may return `v` as 27 or 28.
+		if bytes[64] >= 27 {
+			bytes[64] -= 27;
+		}
+		Ok(Signature::from(H520::from(bytes)))
+	}
+}
+
+impl engine::signer::EngineSigner for RemoteSigner {
+	fn sign(&self, message: Message) -> Result<Signature, Error> {
+		warn!("Remote signer can't sign the hash {} without the data it was computed from", message);
+		Err(Error::Custom("Remote signer can only sign data".into()))
+	}
+
+	fn sign_seal(&self, _step: u64, message: Message) -> Result<Signature, Error> {
+		self.sign(message)
+	}
+
+	fn sign_data(&self, data: &[u8]) -> Result<Signature, Error> {
+		let message = keccak(data);
+		let signature = self.request_signature(data).map_err(|e| {
+			warn!("Remote signer failed to sign {}: {}", message, e);
+			Error::InvalidSecretKey
+		})?;
+		if public_to_address(&recover(&signature, &message)?) != self.address {
+			warn!("Remote signer returned a signature of another key than {}", self.address);
+			return Err(Error::InvalidSignature);
+		}
+		Ok(signature)
+	}
+
+	fn sign_seal_data(&self, step: u64, data: &[u8]) -> Result<Signature, Error> {
+		let message = keccak(data);
+		// the lock is held while signing so that concurrent seals for a step can't both succeed.
+		let mut last_seal = self.last_seal.lock();
+		if let Some((last_step, ref last_message)) = *last_seal {
+			if step < last_step || (step == last_step && message != *last_message) {
+				warn!("Refusing to sign seal {} for step {}: a seal was signed for step {} already", message, step, last_step);
+				return Err(Error::Custom(format!("Slashable seal for step {}", step)));
+			}
+		}
+		let signature = self.sign_data(data)?;
+		*last_seal = Some((step, message));
+		Ok(signature)
+	}
+
+	fn decrypt(&self, _auth_data: &[u8], _cipher: &[u8]) -> Result<Vec<u8>, Error> {
+		warn!("Remote signer can't decrypt messages");
+		Err(Error::InvalidSecretKey)
+	}
+
+	fn address(&self) -> Address {
+		self.address
+	}
+
+	fn public(&self) -> Option<Public> {
+		None
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::io::{BufRead, BufReader, Read, Write};
+	use std::net::TcpListener;
+	use std::sync::Arc;
+	use std::sync::atomic::{AtomicUsize, Ordering};
+	use std::thread;
+
+	use crypto::publickey::{sign, Generator, KeyPair, Random};
+	use engine::signer::EngineSigner;
+	use hash::keccak;
+	use rustc_hex::{FromHex, ToHex};
+	use serde_json::{self, Value};
+
+	use super::RemoteSigner;
+
+	/// Serves Web3Signer-style signing requests for `key`, returning the URL and a request counter.
+	fn mock_signer(key: KeyPair) -> (String, Arc<AtomicUsize>) {
+		let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+		let url = format!("http://{}/", listener.local_addr().unwrap());
+		let requests = Arc::new(AtomicUsize::new(0));
+		let counter = requests.clone();
+
+		thread::spawn(move || for stream in listener.incoming() {
+			let mut stream = stream.unwrap();
+			let mut reader = BufReader::new(stream.try_clone().unwrap());
+			let mut line = String::new();
+			reader.read_line(&mut line).unwrap();
+			assert!(line.starts_with("POST /api/v1/eth1/sign/0x"), "unexpected request: {}", line);
+
+			let mut length = 0;
+			loop {
+				line.clear();
+				reader.read_line(&mut line).unwrap();
+				if line.trim().is_empty() {
+					break;
+				}
+				if line.to_lowercase().starts_with("content-length:") {
+					length = line[15..].trim().parse().unwrap();
+				}
+			}
+			let mut body = vec![0; length];
+			reader.read_exact(&mut body).unwrap();
+			let body: Value = serde_json::from_slice(&body).unwrap();
+			let data: Vec<u8> = body["data"].as_str().unwrap()[2..].from_hex().unwrap();
+
+			// Web3Signer signs the hash of the data.
+			let mut signature = sign(key.secret(), &keccak(&data)).unwrap().to_vec();
+			signature[64] += 27;
+			let reply = format!("0x{}", signature.to_hex());
+			counter.fetch_add(1, Ordering::SeqCst);
+			write!(
+				stream,
+				"HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+				reply.len(),
+				reply,
+			).unwrap();
+		});
+		(url, requests)
+	}
+
+	#[test]
+	fn signs_through_remote_service() {
+		let key = Random.generate().unwrap();
+		let (url, _) = mock_signer(key.clone());
+		let signer = RemoteSigner::new(&url, key.address()).unwrap();
+
+		let data = b"block header";
+		let signature = signer.sign_data(data).unwrap();
+		assert_eq!(signature, sign(key.secret(), &keccak(data)).unwrap());
+		assert_eq!(signer.address(), key.address());
+		// the service would sign the hash of the hash.
+		assert!(signer.sign(keccak(data)).is_err());
+	}
+
+	#[test]
+	fn rejects_signatures_of_another_key() {
+		let (url, _) = mock_signer(Random.generate().unwrap());
+		let signer = RemoteSigner::new(&url, Random.generate().unwrap().address()).unwrap();
+		assert!(signer.sign_data(b"block header").is_err());
+	}
+
+	#[test]
+	fn refuses_conflicting_seals() {
+		let key = Random.generate().unwrap();
+		let (url, requests) = mock_signer(key.clone());
+		let signer = RemoteSigner::new(&url, key.address()).unwrap();
+
+		let (first, second): (&[u8], &[u8]) = (b"first", b"second");
+		assert!(signer.sign_seal_data(10, first).is_ok());
+		// the same seal may be signed again.
+		assert!(signer.sign_seal_data(10, first).is_ok());
+		assert!(signer.sign_seal_data(10, second).is_err());
+		assert!(signer.sign_seal_data(9, second).is_err());
+		assert!(signer.sign_seal_data(11, second).is_ok());
+		// refused seals never reach the service.
+		assert_eq!(requests.load(Ordering::SeqCst), 3);
+	}
+}
@@ -18,13 +18,21 @@
 
 use std::sync::Arc;
 
-use client_traits::BlockChainClient;
+use account_state::state::StateInfo;
+use client_traits::{BlockChainClient, BlockInfo, StateClient};
+use ethcore::client::Call;
+use ethereum_types::H256;
 use types::header::Header;
+use types::ids::TransactionId;
 use types::transaction::LocalizedTransaction;
 
 use jsonrpc_core::Result;
+use v1::helpers::{errors, fake_sign};
 use v1::traits::Debug;
-use v1::types::{Block, Bytes, RichBlock, BlockTransactions, Transaction};
+use v1::types::{
+	Block, Bytes, RichBlock, BlockTransactions, Transaction, BlockNumber, CallRequest,
+	StructLoggerOptions, StructLogTrace, StructLogTraceWithTransactionHash, block_number_to_id,
+};
 
 /// Debug rpc implementation.
 pub struct DebugClient<C> {
@@ -40,7 +48,10 @@ impl<C> DebugClient<C> {
 	}
 }
 
-impl<C: BlockChainClient + 'static> Debug for DebugClient<C> {
+impl<C, S> Debug for DebugClient<C> where
+	S: StateInfo + 'static,
+	C: BlockChainClient + StateClient<State=S> + Call<State=S> + 'static,
+{
 	fn bad_blocks(&self) -> Result<Vec<RichBlock>> {
 		fn cast<O, T: Copy + Into<O>>(t: &T) -> O {
 			(*t).into()
@@ -67,6 +78,7 @@ impl<C: BlockChainClient + 'static> Debug for DebugClient<C> {
 					difficulty: cast(block.header.difficulty()),
 					total_difficulty: None,
 					seal_fields: block.header.seal().iter().cloned().map(Into::into).collect(),
+					base_fee_per_gas: block.header.base_fee_per_gas(),
 					uncles: block.uncles.iter().map(Header::hash).collect(),
 					transactions: BlockTransactions::Full(block.transactions
 						.into_iter()
@@ -90,6 +102,38 @@ impl<C: BlockChainClient + 'static> Debug for DebugClient<C> {
 			}
 		}).collect())
 	}
+
+	fn trace_transaction(&self, transaction_hash: H256, options: Option<StructLoggerOptions>) -> Result<StructLogTrace> {
+		self.client.replay_with_struct_logger(TransactionId::Hash(transaction_hash), options.unwrap_or_default().into())
+			.map(Into::into)
+			.map_err(errors::call)
+	}
+
+	fn trace_call(&self, request: CallRequest, block: Option<BlockNumber>, options: Option<StructLoggerOptions>) -> Result<StructLogTrace> {
+		let id = match block.unwrap_or_default() {
+			BlockNumber::Pending => return Err(errors::invalid_params("`BlockNumber::Pending` is not supported", ())),
+			num => block_number_to_id(num),
+		};
+		let signed = fake_sign::sign_call(CallRequest::into(request))?;
+
+		let mut state = self.client.state_at(id).ok_or_else(errors::state_pruned)?;
+		let header = self.client.block_header(id).ok_or_else(errors::state_pruned)?;
+
+		self.client.call_with_struct_logger(&signed, options.unwrap_or_default().into(), &mut state, &header.decode(self.client.eip1559_transition()).map_err(errors::decode)?)
+			.map(Into::into)
+			.map_err(errors::call)
+	}
+
+	fn trace_block_by_number(&self, block: BlockNumber, options: Option<StructLoggerOptions>) -> Result<Vec<StructLogTraceWithTransactionHash>> {
+		let id = match block {
+			BlockNumber::Pending => return Err(errors::invalid_params("`BlockNumber::Pending` is not supported", ())),
+			num => block_number_to_id(num),
+		};
+
+		self.client.replay_block_transactions_with_struct_logger(id, options.unwrap_or_default().into())
+			.map(|results| results.map(Into::into).collect())
+			.map_err(errors::call)
+	}
 }
 
 fn serialize<T: ::serde::Serialize>(t: &T) -> String {
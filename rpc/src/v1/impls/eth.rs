@@ -16,16 +16,15 @@
 
 //! Eth rpc implementation.
 
-use std::thread;
+use std::{cmp, thread};
 use std::time::{Instant, Duration, SystemTime, UNIX_EPOCH};
 use std::sync::Arc;
 
-use rlp::Rlp;
 use ethereum_types::{Address, H64, H160, H256, U64, U256, BigEndianHash};
 use parking_lot::Mutex;
 
 use account_state::state::StateInfo;
-use client_traits::{BlockChainClient, StateClient, ProvingBlockChainClient, StateOrBlock};
+use client_traits::{BlockChainClient, BlockInfo, StateClient, ProvingBlockChainClient, StateOrBlock};
 use ethash::{self, SeedHashCompute};
 use ethcore::client::{Call, EngineInfo};
 use ethcore::miner::{self, MinerService};
@@ -40,7 +39,7 @@ use types::{
 	header::Header,
 	ids::{BlockId, TransactionId, UncleId},
 	filter::Filter as EthcoreFilter,
-	transaction::{SignedTransaction, LocalizedTransaction},
+	transaction::{SignedTransaction, LocalizedTransaction, UnverifiedTransaction},
 	snapshot::RestorationStatus,
 };
 
@@ -52,14 +51,17 @@ use v1::helpers::deprecated::{self, DeprecationNotice};
 use v1::helpers::dispatch::{FullDispatcher, default_gas_price};
 use v1::traits::Eth;
 use v1::types::{
-	RichBlock, Block, BlockTransactions, BlockNumber, Bytes, SyncStatus, SyncInfo,
-	Transaction, CallRequest, Index, Filter, Log, Receipt, Work, EthAccount, StorageProof,
-	block_number_to_id
+	RichBlock, Block, BlockTransactions, BlockNumber, Bytes, FeeHistory, SyncStatus, SyncInfo,
+	Transaction, CallRequest, Index, Filter, Log, Receipt, StateOverride, Work, EthAccount, StorageProof,
+	block_number_to_id, state_override_into
 };
 use v1::metadata::Metadata;
 
 const EXTRA_INFO_PROOF: &str = "Object exists in blockchain (fetched earlier), extra_info is always available if object exists; qed";
 
+/// Maximal number of blocks returned by `eth_feeHistory`.
+const MAX_FEE_HISTORY_BLOCKS: u64 = 1024;
+
 /// Eth RPC options
 #[derive(Copy, Clone)]
 pub struct EthClientOptions {
@@ -293,7 +295,8 @@ impl<C, SN: ?Sized, S: ?Sized, M, EM, T> EthClient<C, SN, S, M, EM> where
 						timestamp: view.timestamp().into(),
 						difficulty: view.difficulty(),
 						total_difficulty: Some(total_difficulty),
-						seal_fields: view.seal().into_iter().map(Into::into).collect(),
+						seal_fields: view.seal(self.client.eip1559_transition()).into_iter().map(Into::into).collect(),
+						base_fee_per_gas: view.base_fee_per_gas(self.client.eip1559_transition()),
 						uncles: block.uncle_hashes(),
 						transactions: match include_txs {
 							true => BlockTransactions::Full(block.view().localized_transactions().into_iter().map(Transaction::from_localized).collect()),
@@ -391,7 +394,7 @@ impl<C, SN: ?Sized, S: ?Sized, M, EM, T> EthClient<C, SN, S, M, EM> where
 				let uncle_id = UncleId { block: block_id, position };
 
 				let uncle = match client.uncle(uncle_id) {
-					Some(hdr) => match hdr.decode() {
+					Some(hdr) => match hdr.decode(self.client.eip1559_transition()) {
 						Ok(h) => h,
 						Err(e) => return Err(errors::decode(e))
 					},
@@ -433,6 +436,7 @@ impl<C, SN: ?Sized, S: ?Sized, M, EM, T> EthClient<C, SN, S, M, EM> where
 				receipts_root: *uncle.receipts_root(),
 				extra_data: uncle.extra_data().clone().into(),
 				seal_fields: uncle.seal().iter().cloned().map(Into::into).collect(),
+				base_fee_per_gas: uncle.base_fee_per_gas(),
 				uncles: vec![],
 				transactions: BlockTransactions::Hashes(vec![]),
 			},
@@ -484,6 +488,83 @@ impl<C, SN: ?Sized, S: ?Sized, M, EM, T> EthClient<C, SN, S, M, EM> where
 			}
 		}
 	}
+
+	/// Base fee of the block following the given one, if EIP-1559 is active for it.
+	fn next_base_fee(&self, parent: &encoded::Header) -> Option<U256> {
+		parent.decode(self.client.eip1559_transition()).ok().and_then(|header| self.client.engine().machine().calc_base_fee(&header))
+	}
+
+	fn collect_fee_history(&self, block_count: U256, newest_block: BlockNumber, reward_percentiles: Option<Vec<f64>>) -> Result<FeeHistory> {
+		if let Some(ref percentiles) = reward_percentiles {
+			let in_range = percentiles.iter().all(|p| *p >= 0.0 && *p <= 100.0);
+			let increasing = percentiles.windows(2).all(|w| w[0] <= w[1]);
+			if !in_range || !increasing {
+				return Err(errors::invalid_params("rewardPercentiles", "expected increasing values between 0 and 100"));
+			}
+		}
+
+		let newest_id = match newest_block {
+			BlockNumber::Pending => BlockId::Latest,
+			number => block_number_to_id(number),
+		};
+		let newest = self.client.block_number(newest_id).ok_or_else(errors::unknown_block)?;
+		let block_count = cmp::min(block_count, MAX_FEE_HISTORY_BLOCKS.into()).as_u64();
+		if block_count == 0 {
+			return Ok(FeeHistory::default());
+		}
+		let oldest = (newest + 1).saturating_sub(block_count);
+
+		let mut history = FeeHistory {
+			oldest_block: oldest.into(),
+			reward: reward_percentiles.as_ref().map(|_| Vec::new()),
+			..Default::default()
+		};
+		for number in oldest..=newest {
+			let header = self.client.block_header(BlockId::Number(number)).ok_or_else(errors::unknown_block)?;
+			let base_fee = header.base_fee_per_gas(self.client.eip1559_transition());
+			history.base_fee_per_gas.push(base_fee.unwrap_or_default());
+			history.gas_used_ratio.push(match header.gas_limit().is_zero() {
+				true => 0.0,
+				false => header.gas_used().low_u64() as f64 / header.gas_limit().low_u64() as f64,
+			});
+			if let (Some(percentiles), Some(rewards)) = (reward_percentiles.as_ref(), history.reward.as_mut()) {
+				let body = self.client.block_body(BlockId::Hash(header.hash())).ok_or_else(errors::unknown_block)?;
+				let receipts = self.client.block_receipts(&header.hash()).ok_or_else(errors::unknown_block)?.receipts;
+				let mut cumulative_gas_used = U256::zero();
+				let tips = body.transactions().iter().zip(receipts.iter()).map(|(tx, receipt)| {
+					let gas_used = receipt.gas_used.saturating_sub(cumulative_gas_used);
+					cumulative_gas_used = receipt.gas_used;
+					(tx.effective_priority_fee(base_fee), gas_used)
+				}).collect();
+				rewards.push(reward_percentiles_of(tips, header.gas_used(), percentiles));
+			}
+			if number == newest {
+				history.base_fee_per_gas.push(self.next_base_fee(&header).unwrap_or_default());
+			}
+		}
+
+		Ok(history)
+	}
+}
+
+/// Priority fees paid at the given percentiles of the gas used in a block, from the
+/// `(priority fee, gas used)` pairs of its transactions.
+fn reward_percentiles_of(mut tips: Vec<(U256, U256)>, block_gas_used: U256, percentiles: &[f64]) -> Vec<U256> {
+	if tips.is_empty() {
+		return vec![U256::zero(); percentiles.len()];
+	}
+
+	tips.sort_by(|a, b| a.0.cmp(&b.0));
+	let mut index = 0;
+	let mut sum_gas_used = tips[0].1;
+	percentiles.iter().map(|percentile| {
+		let threshold = U256::from((block_gas_used.low_u64() as f64 * percentile / 100.0) as u64);
+		while sum_gas_used < threshold && index < tips.len() - 1 {
+			index += 1;
+			sum_gas_used = sum_gas_used.saturating_add(tips[index].1);
+		}
+		tips[index].0
+	}).collect()
 }
 
 pub fn pending_logs<M>(miner: &M, best_block: EthBlockNumber, filter: &EthcoreFilter) -> Vec<Log> where M: MinerService {
@@ -605,6 +686,19 @@ impl<C, SN: ?Sized, S: ?Sized, M, EM, T: StateInfo + 'static> Eth for EthClient<
 		Box::new(future::ok(default_gas_price(&*self.client, &*self.miner, self.options.gas_price_percentile)))
 	}
 
+	fn max_priority_fee_per_gas(&self) -> BoxFuture<U256> {
+		// The gas price corpus holds the fee caps of recent transactions, the tip is what exceeds the base fee.
+		let gas_price = default_gas_price(&*self.client, &*self.miner, self.options.gas_price_percentile);
+		let base_fee = self.client.block_header(BlockId::Latest)
+			.and_then(|header| self.next_base_fee(&header))
+			.unwrap_or_default();
+		Box::new(future::ok(gas_price.saturating_sub(base_fee)))
+	}
+
+	fn fee_history(&self, block_count: U256, newest_block: BlockNumber, reward_percentiles: Option<Vec<f64>>) -> BoxFuture<FeeHistory> {
+		Box::new(future::done(self.collect_fee_history(block_count, newest_block, reward_percentiles)))
+	}
+
 	fn accounts(&self) -> Result<Vec<H160>> {
 		self.deprecation_notice.print("eth_accounts", deprecated::msgs::ACCOUNTS);
 
@@ -941,7 +1035,7 @@ impl<C, SN: ?Sized, S: ?Sized, M, EM, T: StateInfo + 'static> Eth for EthClient<
 	}
 
 	fn send_raw_transaction(&self, raw: Bytes) -> Result<H256> {
-		Rlp::new(&raw.into_vec()).as_val()
+		UnverifiedTransaction::decode_raw(&raw.into_vec())
 			.map_err(errors::rlp)
 			.and_then(|tx| SignedTransaction::new(tx).map_err(errors::transaction))
 			.and_then(|signed_transaction| {
@@ -959,7 +1053,7 @@ impl<C, SN: ?Sized, S: ?Sized, M, EM, T: StateInfo + 'static> Eth for EthClient<
 		self.send_raw_transaction(raw)
 	}
 
-	fn call(&self, request: CallRequest, num: Option<BlockNumber>) -> BoxFuture<Bytes> {
+	fn call(&self, request: CallRequest, num: Option<BlockNumber>, state_override: Option<StateOverride>) -> BoxFuture<Bytes> {
 		let request = CallRequest::into(request);
 		let signed = try_bf!(fake_sign::sign_call(request));
 
@@ -981,12 +1075,17 @@ impl<C, SN: ?Sized, S: ?Sized, M, EM, T: StateInfo + 'static> Eth for EthClient<
 				let state = try_bf!(self.client.state_at(id).ok_or_else(errors::state_pruned));
 				let header = try_bf!(
 					self.client.block_header(id).ok_or_else(errors::state_pruned)
-						.and_then(|h| h.decode().map_err(errors::decode))
+						.and_then(|h| h.decode(self.client.eip1559_transition()).map_err(errors::decode))
 				);
 
 				(state, header)
 			};
 
+		if let Some(state_override) = state_override {
+			let state_override = try_bf!(state_override_into(state_override));
+			try_bf!(self.client.apply_state_override(&mut state, &state_override).map_err(errors::call));
+		}
+
 		let result = self.client.call(&signed, Default::default(), &mut state, &header);
 
 		Box::new(future::done(result
@@ -1001,12 +1100,12 @@ impl<C, SN: ?Sized, S: ?Sized, M, EM, T: StateInfo + 'static> Eth for EthClient<
 		))
 	}
 
-	fn estimate_gas(&self, request: CallRequest, num: Option<BlockNumber>) -> BoxFuture<U256> {
+	fn estimate_gas(&self, request: CallRequest, num: Option<BlockNumber>, state_override: Option<StateOverride>) -> BoxFuture<U256> {
 		let request = CallRequest::into(request);
 		let signed = try_bf!(fake_sign::sign_call(request));
 		let num = num.unwrap_or_default();
 
-		let (state, header) = if num == BlockNumber::Pending {
+		let (mut state, header) = if num == BlockNumber::Pending {
 			self.pending_state_and_header_with_fallback()
 		} else {
 			let id = match num {
@@ -1021,10 +1120,15 @@ impl<C, SN: ?Sized, S: ?Sized, M, EM, T: StateInfo + 'static> Eth for EthClient<
 								.ok_or_else(errors::state_pruned));
 			let header = try_bf!(self.client.block_header(id)
 								 .ok_or_else(errors::state_pruned)
-								 .and_then(|h| h.decode().map_err(errors::decode)));
+								 .and_then(|h| h.decode(self.client.eip1559_transition()).map_err(errors::decode)));
 			(state, header)
 		};
 
+		if let Some(state_override) = state_override {
+			let state_override = try_bf!(state_override_into(state_override));
+			try_bf!(self.client.apply_state_override(&mut state, &state_override).map_err(errors::call));
+		}
+
 		Box::new(future::done(self.client.estimate_gas(&signed, &state, &header)
 			.map_err(errors::call)
 		))
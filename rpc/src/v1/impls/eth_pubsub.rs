@@ -28,24 +28,27 @@ use v1::helpers::{errors, limit_logs, Subscribers, };
 use v1::helpers::light_fetch::LightFetch;
 use v1::metadata::Metadata;
 use v1::traits::EthPubSub;
-use v1::types::{pubsub, RichHeader, Log};
+use v1::types::{pubsub, Header, RichHeader, Log, Transaction};
 
 use sync::{SyncState, Notification};
-use client_traits::{BlockChainClient, ChainNotify};
+use client_traits::{BlockChainClient, BlockInfo, ChainNotify};
 use ethereum_types::H256;
 use light::cache::Cache;
 use light::client::{LightChainClient, LightChainNotify};
 use light::on_demand::OnDemandRequester;
+use miner::pool::TxStatus;
 use parity_runtime::Executor;
 use parking_lot::{RwLock, Mutex};
 
 use sync::{LightSyncProvider, LightNetworkDispatcher, ManageNetwork};
 
 use types::{
+	BlockNumber,
 	chain_notify::{NewBlocks, ChainRouteType},
 	ids::BlockId,
 	encoded,
 	filter::Filter as EthFilter,
+	transaction::SignedTransaction,
 };
 
 type Client = Sink<pubsub::Result>;
@@ -56,6 +59,9 @@ pub struct EthPubSubClient<C> {
 	heads_subscribers: Arc<RwLock<Subscribers<Client>>>,
 	logs_subscribers: Arc<RwLock<Subscribers<(Client, EthFilter)>>>,
 	transactions_subscribers: Arc<RwLock<Subscribers<Client>>>,
+	pending_transactions_subscribers: Arc<RwLock<Subscribers<(Client, pubsub::TransactionFilter)>>>,
+	dropped_transactions_subscribers: Arc<RwLock<Subscribers<Client>>>,
+	finalized_heads_subscribers: Arc<RwLock<Subscribers<Client>>>,
 	sync_subscribers: Arc<RwLock<Subscribers<Client>>>,
 }
 
@@ -82,6 +88,25 @@ impl<C> EthPubSubClient<C>
 			})
 		)
 	}
+
+	/// adds a transaction pool notification channel to the pubsub client,
+	/// `f` looks up the pending transactions by hash
+	pub fn add_transactions_notifier<F>(&mut self, receiver: mpsc::UnboundedReceiver<Arc<Vec<(H256, TxStatus)>>>, f: F)
+		where
+			F: 'static + Fn(&H256) -> Option<SignedTransaction> + Send
+	{
+		let weak_handler = Arc::downgrade(&self.handler);
+
+		self.handler.executor.spawn(
+			receiver.for_each(move |statuses| {
+				if let Some(handler) = weak_handler.upgrade() {
+					handler.notify_transaction_statuses(&statuses, &f);
+					return Ok(())
+				}
+				Err(())
+			})
+		)
+	}
 }
 
 impl<C> EthPubSubClient<C>
@@ -93,6 +118,9 @@ impl<C> EthPubSubClient<C>
 		let heads_subscribers = Arc::new(RwLock::new(Subscribers::default()));
 		let logs_subscribers = Arc::new(RwLock::new(Subscribers::default()));
 		let transactions_subscribers = Arc::new(RwLock::new(Subscribers::default()));
+		let pending_transactions_subscribers = Arc::new(RwLock::new(Subscribers::default()));
+		let dropped_transactions_subscribers = Arc::new(RwLock::new(Subscribers::default()));
+		let finalized_heads_subscribers = Arc::new(RwLock::new(Subscribers::default()));
 		let sync_subscribers = Arc::new(RwLock::new(Subscribers::default()));
 
 		let handler = Arc::new(ChainNotificationHandler {
@@ -101,6 +129,9 @@ impl<C> EthPubSubClient<C>
 			heads_subscribers: heads_subscribers.clone(),
 			logs_subscribers: logs_subscribers.clone(),
 			transactions_subscribers: transactions_subscribers.clone(),
+			pending_transactions_subscribers: pending_transactions_subscribers.clone(),
+			dropped_transactions_subscribers: dropped_transactions_subscribers.clone(),
+			finalized_heads_subscribers: finalized_heads_subscribers.clone(),
 			sync_subscribers: sync_subscribers.clone(),
 		});
 		let handler2 = Arc::downgrade(&handler);
@@ -121,6 +152,9 @@ impl<C> EthPubSubClient<C>
 			heads_subscribers,
 			logs_subscribers,
 			transactions_subscribers,
+			pending_transactions_subscribers,
+			dropped_transactions_subscribers,
+			finalized_heads_subscribers,
 		}
 	}
 
@@ -163,6 +197,9 @@ pub struct ChainNotificationHandler<C> {
 	heads_subscribers: Arc<RwLock<Subscribers<Client>>>,
 	logs_subscribers: Arc<RwLock<Subscribers<(Client, EthFilter)>>>,
 	transactions_subscribers: Arc<RwLock<Subscribers<Client>>>,
+	pending_transactions_subscribers: Arc<RwLock<Subscribers<(Client, pubsub::TransactionFilter)>>>,
+	dropped_transactions_subscribers: Arc<RwLock<Subscribers<Client>>>,
+	finalized_heads_subscribers: Arc<RwLock<Subscribers<Client>>>,
 	sync_subscribers: Arc<RwLock<Subscribers<Client>>>,
 }
 
@@ -175,11 +212,16 @@ impl<C> ChainNotificationHandler<C> {
 		);
 	}
 
-	fn notify_heads(&self, headers: &[(encoded::Header, BTreeMap<String, String>)]) {
-		for subscriber in self.heads_subscribers.read().values() {
+	fn notify_heads(
+		&self,
+		subscribers: &RwLock<Subscribers<Client>>,
+		headers: &[(encoded::Header, BTreeMap<String, String>)],
+		eip1559_transition: BlockNumber,
+	) {
+		for subscriber in subscribers.read().values() {
 			for &(ref header, ref extra_info) in headers {
 				Self::notify(&self.executor, subscriber, pubsub::Result::Header(Box::new(RichHeader {
-					inner: header.into(),
+					inner: Header::new(header, eip1559_transition),
 					extra_info: extra_info.clone(),
 				})));
 			}
@@ -233,6 +275,41 @@ impl<C> ChainNotificationHandler<C> {
 			}
 		}
 	}
+
+	/// Notify subscribers about transactions added to or removed from the pool.
+	fn notify_transaction_statuses<F>(&self, statuses: &[(H256, TxStatus)], transaction: F) where
+		F: Fn(&H256) -> Option<SignedTransaction>,
+	{
+		let pending_subscribers = self.pending_transactions_subscribers.read();
+		let dropped_subscribers = self.dropped_transactions_subscribers.read();
+
+		for &(hash, status) in statuses {
+			match status {
+				TxStatus::Added => {
+					if pending_subscribers.is_empty() { continue }
+					let transaction = match transaction(&hash) {
+						Some(transaction) => Transaction::from_signed(transaction),
+						None => continue,
+					};
+					for &(ref subscriber, ref filter) in pending_subscribers.values() {
+						if filter.matches(&transaction) {
+							Self::notify(&self.executor, subscriber, pubsub::Result::Transaction(Box::new(transaction.clone())));
+						}
+					}
+				},
+				// culled transactions left the pool by being mined.
+				TxStatus::Culled => {},
+				reason => {
+					for subscriber in dropped_subscribers.values() {
+						Self::notify(&self.executor, subscriber, pubsub::Result::DroppedTransaction(pubsub::DroppedTransaction {
+							hash,
+							reason,
+						}));
+					}
+				},
+			}
+		}
+	}
 }
 
 /// A light client wrapper struct.
@@ -242,6 +319,9 @@ pub trait LightClient: Send + Sync {
 
 	/// Fetch logs.
 	fn logs(&self, filter: EthFilter) -> BoxFuture<Vec<Log>>;
+
+	/// Number of the first block whose header carries a base fee.
+	fn eip1559_transition(&self) -> BlockNumber;
 }
 
 impl<S, OD> LightClient for LightFetch<S, OD>
@@ -256,6 +336,10 @@ where
 	fn logs(&self, filter: EthFilter) -> BoxFuture<Vec<Log>> {
 		Box::new(LightFetch::logs(self, filter)) as BoxFuture<_>
 	}
+
+	fn eip1559_transition(&self) -> BlockNumber {
+		self.client.engine().params().eip1559_transition
+	}
 }
 
 impl<C: LightClient> LightChainNotify for ChainNotificationHandler<C> {
@@ -266,7 +350,7 @@ impl<C: LightClient> LightChainNotify for ChainNotificationHandler<C> {
 			.map(|header| (header, Default::default()))
 			.collect::<Vec<_>>();
 
-		self.notify_heads(&headers);
+		self.notify_heads(&self.heads_subscribers, &headers, self.client.eip1559_transition());
 		self.notify_logs(&enacted.iter().map(|h| (*h, ())).collect::<Vec<_>>(), |filter, _| self.client.logs(filter))
 	}
 }
@@ -290,7 +374,7 @@ impl<C: BlockChainClient> ChainNotify for ChainNotificationHandler<C> {
 			.collect::<Vec<_>>();
 
 		// Headers
-		self.notify_heads(&headers);
+		self.notify_heads(&self.heads_subscribers, &headers, self.client.eip1559_transition());
 
 		// We notify logs enacting and retracting as the order in route.
 		self.notify_logs(new_blocks.route.route(), |filter, ex| {
@@ -306,6 +390,20 @@ impl<C: BlockChainClient> ChainNotify for ChainNotificationHandler<C> {
 			}
 		});
 	}
+
+	fn blocks_finalized(&self, finalized: &[H256]) {
+		if self.finalized_heads_subscribers.read().is_empty() { return }
+		let headers = finalized
+			.iter()
+			.filter_map(|hash| {
+				let header = self.client.block_header(BlockId::Hash(*hash))?;
+				let extra_info = self.client.block_extra_info(BlockId::Hash(*hash))?;
+				Some((header, extra_info))
+			})
+			.collect::<Vec<_>>();
+
+		self.notify_heads(&self.finalized_heads_subscribers, &headers, self.client.eip1559_transition());
+	}
 }
 
 impl<C: Send + Sync + 'static> EthPubSub for EthPubSubClient<C> {
@@ -349,6 +447,31 @@ impl<C: Send + Sync + 'static> EthPubSub for EthPubSubClient<C> {
 			(pubsub::Kind::NewPendingTransactions, _) => {
 				errors::invalid_params("newPendingTransactions", "Expected no parameters.")
 			},
+			(pubsub::Kind::PendingTransactions, None) => {
+				self.pending_transactions_subscribers.write().push(subscriber, Default::default());
+				return;
+			},
+			(pubsub::Kind::PendingTransactions, Some(pubsub::Params::Transactions(filter))) => {
+				self.pending_transactions_subscribers.write().push(subscriber, filter);
+				return;
+			},
+			(pubsub::Kind::PendingTransactions, _) => {
+				errors::invalid_params("pendingTransactions", "Expected no parameters or a `from`/`to` filter.")
+			},
+			(pubsub::Kind::DroppedTransactions, None) => {
+				self.dropped_transactions_subscribers.write().push(subscriber);
+				return;
+			},
+			(pubsub::Kind::DroppedTransactions, _) => {
+				errors::invalid_params("droppedTransactions", "Expected no parameters.")
+			},
+			(pubsub::Kind::FinalizedHeads, None) => {
+				self.finalized_heads_subscribers.write().push(subscriber);
+				return;
+			},
+			(pubsub::Kind::FinalizedHeads, _) => {
+				errors::invalid_params("finalizedHeads", "Expected no parameters.")
+			},
 			_ => {
 				errors::unimplemented(None)
 			},
@@ -362,7 +485,10 @@ impl<C: Send + Sync + 'static> EthPubSub for EthPubSubClient<C> {
 		let res2 = self.logs_subscribers.write().remove(&id).is_some();
 		let res3 = self.transactions_subscribers.write().remove(&id).is_some();
 		let res4 = self.sync_subscribers.write().remove(&id).is_some();
+		let res5 = self.pending_transactions_subscribers.write().remove(&id).is_some();
+		let res6 = self.dropped_transactions_subscribers.write().remove(&id).is_some();
+		let res7 = self.finalized_heads_subscribers.write().remove(&id).is_some();
 
-		Ok(res || res2 || res3 || res4)
+		Ok(res || res2 || res3 || res4 || res5 || res6 || res7)
 	}
 }
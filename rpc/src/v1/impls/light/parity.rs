@@ -40,13 +40,14 @@ use v1::helpers::light_fetch::{LightFetch, light_all_transactions};
 use v1::metadata::Metadata;
 use v1::traits::Parity;
 use v1::types::{
-	Bytes, CallRequest,
+	Bytes, CallRequest, StateOverride,
 	Peers, Transaction, RpcSettings, Histogram,
 	TransactionStats, LocalTransactionStatus,
 	LightBlockNumber, ChainStatus, Receipt,
 	BlockNumber, ConsensusCapability, VersionInfo,
 	OperationsInfo, Header, RichHeader, RecoveredAccount,
-	Log, Filter,
+	Log, Filter, ValidatorStats, RandomnessRound,
+	BlockReward, RewardBeneficiary,
 };
 use Host;
 use v1::helpers::errors::light_unimplemented;
@@ -338,7 +339,7 @@ where
 
 		let engine = self.light_dispatch.client.engine().clone();
 		let from_encoded = move |encoded: encoded::Header| {
-			let header = encoded.decode().map_err(errors::decode)?;
+			let header = encoded.decode(engine.params().eip1559_transition).map_err(errors::decode)?;
 			let extra_info = engine.extra_info(&header);
 			Ok(RichHeader {
 				inner: Header {
@@ -358,6 +359,7 @@ where
 					timestamp: header.timestamp().into(),
 					difficulty: *header.difficulty(),
 					seal_fields: header.seal().iter().cloned().map(Into::into).collect(),
+					base_fee_per_gas: header.base_fee_per_gas(),
 					extra_data: Bytes::new(header.extra_data().clone()),
 				},
 				extra_info,
@@ -376,7 +378,7 @@ where
 		ipfs::cid(content)
 	}
 
-	fn call(&self, _requests: Vec<CallRequest>, _block: Option<BlockNumber>) -> Result<Vec<Bytes>> {
+	fn call(&self, _requests: Vec<CallRequest>, _block: Option<BlockNumber>, _state_override: Option<StateOverride>) -> Result<Vec<Bytes>> {
 		Err(errors::light_unimplemented(None))
 	}
 
@@ -418,4 +420,20 @@ where
 	fn submit_raw_block(&self, _block: Bytes) -> Result<H256> {
 		Err(light_unimplemented(None))
 	}
+
+	fn validator_stats(&self, _from: Option<BlockNumber>, _to: Option<BlockNumber>) -> Result<ValidatorStats> {
+		Err(light_unimplemented(None))
+	}
+
+	fn randomness_rounds(&self) -> Result<Vec<RandomnessRound>> {
+		Err(light_unimplemented(None))
+	}
+
+	fn block_rewards(&self, _number: BlockNumber) -> Result<Option<Vec<BlockReward>>> {
+		Err(light_unimplemented(None))
+	}
+
+	fn dry_run_block_reward(&self, _contract: H160, _beneficiaries: Vec<RewardBeneficiary>, _number: Option<BlockNumber>) -> Result<Vec<BlockReward>> {
+		Err(light_unimplemented(None))
+	}
 }
@@ -20,8 +20,8 @@ use std::collections::BTreeMap;
 
 use crypto::DEFAULT_MAC;
 use ethereum_types::{H64, H160, H256, H512, U64, U256};
-use ethcore::client::Call;
-use client_traits::{BlockChainClient, StateClient};
+use ethcore::client::{Call, EngineInfo};
+use client_traits::{BlockChainClient, BlockInfo, StateClient};
 use ethcore::miner::{self, MinerService, FilterOptions};
 use snapshot::SnapshotService;
 use account_state::state::StateInfo;
@@ -45,13 +45,13 @@ use v1::helpers::external_signer::{SigningQueue, SignerService};
 use v1::metadata::Metadata;
 use v1::traits::Parity;
 use v1::types::{
-	Bytes, CallRequest,
+	Bytes, CallRequest, StateOverride, state_override_into,
 	Peers, Transaction, RpcSettings, Histogram,
 	TransactionStats, LocalTransactionStatus,
 	BlockNumber, ConsensusCapability, VersionInfo,
 	OperationsInfo, ChainStatus, Log, Filter,
-	RichHeader, Receipt, RecoveredAccount,
-	block_number_to_id
+	Header, RichHeader, Receipt, RecoveredAccount, ValidatorStats, RandomnessRound,
+	BlockReward, RewardBeneficiary, block_number_to_id
 };
 use Host;
 
@@ -102,7 +102,7 @@ impl<C, M, U> ParityClient<C, M, U> where
 
 impl<C, M, U, S> Parity for ParityClient<C, M, U> where
 	S: StateInfo + 'static,
-	C: miner::BlockChainClient + BlockChainClient + StateClient<State=S> + Call<State=S> + 'static,
+	C: miner::BlockChainClient + BlockChainClient + StateClient<State=S> + Call<State=S> + EngineInfo + 'static,
 	M: MinerService<State=S> + 'static,
 	U: UpdateService + 'static,
 {
@@ -369,7 +369,7 @@ impl<C, M, U, S> Parity for ParityClient<C, M, U> where
 		};
 
 		Box::new(future::ok(RichHeader {
-			inner: header.into(),
+			inner: Header::new(&header, self.client.eip1559_transition()),
 			extra_info: extra.unwrap_or_default(),
 		}))
 	}
@@ -400,7 +400,7 @@ impl<C, M, U, S> Parity for ParityClient<C, M, U> where
 		ipfs::cid(content)
 	}
 
-	fn call(&self, requests: Vec<CallRequest>, num: Option<BlockNumber>) -> Result<Vec<Bytes>> {
+	fn call(&self, requests: Vec<CallRequest>, num: Option<BlockNumber>, state_override: Option<StateOverride>) -> Result<Vec<Bytes>> {
 		let requests = requests
 			.into_iter()
 			.map(|request| Ok((
@@ -427,11 +427,15 @@ impl<C, M, U, S> Parity for ParityClient<C, M, U> where
 			};
 
 			let state = self.client.state_at(id).ok_or_else(errors::state_pruned)?;
-			let header = self.client.block_header(id).ok_or_else(errors::state_pruned)?.decode().map_err(errors::decode)?;
+			let header = self.client.block_header(id).ok_or_else(errors::state_pruned)?.decode(self.client.eip1559_transition()).map_err(errors::decode)?;
 
 			(state, header)
 		};
 
+		if let Some(state_override) = state_override {
+			self.client.apply_state_override(&mut state, &state_override_into(state_override)?).map_err(errors::call)?;
+		}
+
 		self.client.call_many(&requests, &mut state, &header)
 				.map(|res| res.into_iter().map(|res| res.output.into()).collect())
 				.map_err(errors::call)
@@ -480,8 +484,54 @@ impl<C, M, U, S> Parity for ParityClient<C, M, U> where
 
 	fn submit_raw_block(&self, block: Bytes) -> Result<H256> {
 		let result = self.client.import_block(
-			Unverified::from_rlp(block.into_vec()).map_err(errors::rlp)?
+			Unverified::from_rlp(block.into_vec(), self.client.eip1559_transition()).map_err(errors::rlp)?
 		);
 		Ok(result.map_err(errors::cannot_submit_block)?)
 	}
+
+	fn validator_stats(&self, from: Option<BlockNumber>, to: Option<BlockNumber>) -> Result<ValidatorStats> {
+		let block_number = |number| match number {
+			BlockNumber::Pending => Err(errors::invalid_params("blockNumber", "Pending block is not supported")),
+			num => self.client.block_number(block_number_to_id(num)).ok_or_else(errors::unknown_block),
+		};
+		let to = block_number(to.unwrap_or_default())?;
+		let from = match from {
+			Some(from) => Some(block_number(from)?),
+			None => None,
+		};
+
+		self.client.engine()
+			.validator_stats(from, to)
+			.map(Into::into)
+			.map_err(errors::engine)
+	}
+
+	fn randomness_rounds(&self) -> Result<Vec<RandomnessRound>> {
+		self.client.engine()
+			.randomness_rounds()
+			.map(|rounds| rounds.into_iter().map(Into::into).collect())
+			.map_err(errors::engine)
+	}
+
+	fn block_rewards(&self, number: BlockNumber) -> Result<Option<Vec<BlockReward>>> {
+		let id = match number {
+			BlockNumber::Pending => return Err(errors::invalid_params("blockNumber", "Pending block is not supported")),
+			num => block_number_to_id(num),
+		};
+		if self.client.block_hash(id).is_none() {
+			return Err(errors::unknown_block());
+		}
+		Ok(self.client.block_rewards(id).map(|rewards| rewards.into_iter().map(Into::into).collect()))
+	}
+
+	fn dry_run_block_reward(&self, contract: H160, beneficiaries: Vec<RewardBeneficiary>, number: Option<BlockNumber>) -> Result<Vec<BlockReward>> {
+		let id = match number.unwrap_or_default() {
+			BlockNumber::Pending => return Err(errors::invalid_params("blockNumber", "Pending block is not supported")),
+			num => block_number_to_id(num),
+		};
+		let beneficiaries = beneficiaries.into_iter().map(|b| (b.address, b.kind.into())).collect();
+		self.client.dry_run_block_rewards(id, contract, beneficiaries)
+			.map(|rewards| rewards.into_iter().map(Into::into).collect())
+			.map_err(errors::call)
+	}
 }
@@ -21,8 +21,7 @@ use std::sync::Arc;
 use ethereum_types::{U256, H520};
 use parity_runtime::Executor;
 use parking_lot::Mutex;
-use rlp::Rlp;
-use types::transaction::{SignedTransaction, PendingTransaction};
+use types::transaction::{SignedTransaction, PendingTransaction, UnverifiedTransaction};
 
 use jsonrpc_core::{Result, BoxFuture, Error};
 use jsonrpc_core::futures::{future, Future, IntoFuture};
@@ -124,7 +123,7 @@ impl<D: Dispatcher + 'static> SignerClient<D> {
 	fn verify_transaction<F>(bytes: Bytes, request: FilledTransactionRequest, process: F) -> Result<ConfirmationResponse> where
 		F: FnOnce(PendingTransaction) -> Result<ConfirmationResponse>,
 	{
-		let signed_transaction = Rlp::new(&bytes.0).as_val().map_err(errors::rlp)?;
+		let signed_transaction = UnverifiedTransaction::decode_raw(&bytes.0).map_err(errors::rlp)?;
 		let signed_transaction = SignedTransaction::new(signed_transaction).map_err(|e| errors::invalid_params("Invalid signature.", e))?;
 		let sender = signed_transaction.sender();
 
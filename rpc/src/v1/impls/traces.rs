@@ -20,13 +20,12 @@ use std::sync::Arc;
 
 use account_state::state::StateInfo;
 use ethcore::client::Call;
-use client_traits::{BlockChainClient, StateClient};
+use client_traits::{BlockChainClient, BlockInfo, StateClient};
 use ethereum_types::H256;
-use rlp::Rlp;
 use types::{
 	call_analytics::CallAnalytics,
 	ids::{BlockId, TransactionId, TraceId},
-	transaction::SignedTransaction,
+	transaction::{SignedTransaction, UnverifiedTransaction},
 };
 
 use jsonrpc_core::Result;
@@ -34,7 +33,7 @@ use v1::Metadata;
 use v1::traits::Traces;
 use v1::helpers::{errors, fake_sign};
 use v1::types::{TraceFilter, LocalizedTrace, BlockNumber, Index, CallRequest, Bytes, TraceResults,
-	TraceResultsWithTransactionHash, TraceOptions, block_number_to_id};
+	TraceResultsWithTransactionHash, TraceOptions, StateOverride, block_number_to_id, state_override_into};
 
 fn to_call_analytics(flags: TraceOptions) -> CallAnalytics {
 	CallAnalytics {
@@ -94,7 +93,7 @@ impl<C, S> Traces for TracesClient<C> where
 			.map(LocalizedTrace::from))
 	}
 
-	fn call(&self, request: CallRequest, flags: TraceOptions, block: Option<BlockNumber>) -> Result<TraceResults> {
+	fn call(&self, request: CallRequest, flags: TraceOptions, block: Option<BlockNumber>, state_override: Option<StateOverride>) -> Result<TraceResults> {
 		let block = block.unwrap_or_default();
 
 		let request = CallRequest::into(request);
@@ -112,7 +111,11 @@ impl<C, S> Traces for TracesClient<C> where
 		let mut state = self.client.state_at(id).ok_or_else(errors::state_pruned)?;
 		let header = self.client.block_header(id).ok_or_else(errors::state_pruned)?;
 
-		self.client.call(&signed, to_call_analytics(flags), &mut state, &header.decode().map_err(errors::decode)?)
+		if let Some(state_override) = state_override {
+			self.client.apply_state_override(&mut state, &state_override_into(state_override)?).map_err(errors::call)?;
+		}
+
+		self.client.call(&signed, to_call_analytics(flags), &mut state, &header.decode(self.client.eip1559_transition()).map_err(errors::decode)?)
 			.map(TraceResults::from)
 			.map_err(errors::call)
 	}
@@ -140,7 +143,7 @@ impl<C, S> Traces for TracesClient<C> where
 		let mut state = self.client.state_at(id).ok_or_else(errors::state_pruned)?;
 		let header = self.client.block_header(id).ok_or_else(errors::state_pruned)?;
 
-		self.client.call_many(&requests, &mut state, &header.decode().map_err(errors::decode)?)
+		self.client.call_many(&requests, &mut state, &header.decode(self.client.eip1559_transition()).map_err(errors::decode)?)
 			.map(|results| results.into_iter().map(TraceResults::from).collect())
 			.map_err(errors::call)
 	}
@@ -148,7 +151,7 @@ impl<C, S> Traces for TracesClient<C> where
 	fn raw_transaction(&self, raw_transaction: Bytes, flags: TraceOptions, block: Option<BlockNumber>) -> Result<TraceResults> {
 		let block = block.unwrap_or_default();
 
-		let tx = Rlp::new(&raw_transaction.into_vec()).as_val().map_err(|e| errors::invalid_params("Transaction is not valid RLP", e))?;
+		let tx = UnverifiedTransaction::decode_raw(&raw_transaction.into_vec()).map_err(|e| errors::invalid_params("Transaction is not valid RLP", e))?;
 		let signed = SignedTransaction::new(tx).map_err(errors::transaction)?;
 
 		let id = match block {
@@ -163,7 +166,7 @@ impl<C, S> Traces for TracesClient<C> where
 		let mut state = self.client.state_at(id).ok_or_else(errors::state_pruned)?;
 		let header = self.client.block_header(id).ok_or_else(errors::state_pruned)?;
 
-		self.client.call(&signed, to_call_analytics(flags), &mut state, &header.decode().map_err(errors::decode)?)
+		self.client.call(&signed, to_call_analytics(flags), &mut state, &header.decode(self.client.eip1559_transition()).map_err(errors::decode)?)
 			.map(TraceResults::from)
 			.map_err(errors::call)
 	}
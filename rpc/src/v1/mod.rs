@@ -46,6 +46,7 @@ pub use self::impls::*;
 pub use self::helpers::{NetworkSettings, block_import, dispatch};
 pub use self::metadata::Metadata;
 pub use self::types::Origin;
+pub use self::types::{AccountDiff, Block, BlockTransactions, Bytes, Receipt, RichBlock, Transaction};
 pub use self::types::pubsub::PubSubSyncStatus;
 pub use self::extractors::{RpcExtractor, WsExtractor, WsStats, WsDispatcher};
 
@@ -54,5 +55,6 @@ pub mod signer {
 	#[cfg(any(test, feature = "accounts"))]
 	pub use super::helpers::engine_signer::EngineSigner;
 	pub use super::helpers::external_signer::{SignerService, ConfirmationsQueue};
+	pub use super::helpers::remote_signer::RemoteSigner;
 	pub use super::types::{ConfirmationRequest, TransactionModification, TransactionCondition};
 }
@@ -19,7 +19,7 @@
 use std::{env, sync::Arc};
 
 use accounts::AccountProvider;
-use client_traits::{BlockChainClient, ChainInfo, ImportBlock};
+use client_traits::{BlockChainClient, BlockInfo, ChainInfo, ImportBlock};
 use ethcore::client::{Client, ClientConfig};
 use ethcore::miner::Miner;
 use spec::{Genesis, Spec, self};
@@ -95,7 +95,7 @@ impl EthTester {
 		};
 
 		for b in chain.blocks_rlp() {
-			if let Ok(block) = Unverified::from_rlp(b) {
+			if let Ok(block) = Unverified::from_rlp(b, tester.client.eip1559_transition()) {
 				let _ = tester.client.import_block(block);
 				tester.client.flush_queue();
 			}
@@ -474,7 +474,7 @@ fn verify_transaction_counts(name: String, chain: BlockChain) {
 	let tester = EthTester::from_chain(&chain);
 
 	let mut id = 1;
-	for b in chain.blocks_rlp().into_iter().filter_map(|b| Unverified::from_rlp(b).ok()) {
+	for b in chain.blocks_rlp().into_iter().filter_map(|b| Unverified::from_rlp(b, tester.client.eip1559_transition()).ok()) {
 		let count = b.transactions.len();
 
 		let hash = b.header.hash();
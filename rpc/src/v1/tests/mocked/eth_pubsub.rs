@@ -27,10 +27,13 @@ use ethcore::test_helpers::{TestBlockChainClient, EachBlockWith};
 use parity_runtime::Runtime;
 use ethereum_types::{Address, H256};
 use client_traits::{BlockInfo, ChainNotify};
+use miner::pool::TxStatus;
+use serde_json;
 use types::{
 	chain_notify::{NewBlocks, ChainRoute, ChainRouteType},
 	log_entry::{LocalizedLogEntry, LogEntry},
 	ids::BlockId,
+	transaction::{Transaction, Action},
 };
 
 
@@ -109,7 +112,7 @@ fn should_subscribe_to_logs() {
 				data: vec![],
 			},
 			block_hash: h1,
-			block_number: block.header().number(),
+			block_number: block.number(),
 			transaction_hash: tx_hash,
 			transaction_index: 0,
 			log_index: 0,
@@ -208,6 +211,133 @@ fn should_subscribe_to_pending_transactions() {
 	assert_eq!(res, None);
 }
 
+#[test]
+fn should_subscribe_to_pending_transaction_objects() {
+	// given
+	let el = Runtime::with_thread_count(1);
+	let client = TestBlockChainClient::new();
+	let (_, pool_receiver) = mpsc::unbounded();
+	let (status_sender, status_receiver) = mpsc::unbounded();
+
+	let tx = Transaction {
+		nonce: 1.into(),
+		gas_price: 1.into(),
+		gas: 21_000.into(),
+		action: Action::Call(Address::from_low_u64_be(6)),
+		value: 5.into(),
+		data: vec![],
+	}.fake_sign(Address::from_low_u64_be(3));
+	let tx_hash = tx.hash();
+
+	let mut pubsub = EthPubSubClient::new(Arc::new(client), el.executor(), pool_receiver);
+	pubsub.add_transactions_notifier(status_receiver, move |hash| if *hash == tx_hash { Some(tx.clone()) } else { None });
+	let pubsub = pubsub.to_delegate();
+
+	let mut io = MetaIoHandler::default();
+	io.extend_with(pubsub);
+
+	let mut metadata = Metadata::default();
+	let (sender, receiver) = futures::sync::mpsc::channel(8);
+	metadata.session = Some(Arc::new(Session::new(sender)));
+
+	// Subscribe to transactions sent by 0x..03
+	let request = r#"{"jsonrpc": "2.0", "method": "eth_subscribe", "params": ["pendingTransactions", {"from": "0x0000000000000000000000000000000000000003"}], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":"0x43ca64edf03768e1","id":1}"#;
+	assert_eq!(io.handle_request_sync(request, metadata.clone()), Some(response.to_owned()));
+
+	// Unknown and dropped transactions are skipped
+	status_sender.unbounded_send(Arc::new(vec![
+		(H256::from_low_u64_be(5), TxStatus::Added),
+		(tx_hash, TxStatus::Dropped),
+		(tx_hash, TxStatus::Added),
+	])).unwrap();
+
+	let (res, receiver) = receiver.into_future().wait().unwrap();
+	let res: serde_json::Value = serde_json::from_str(&res.unwrap()).unwrap();
+	assert_eq!(res["params"]["result"]["hash"], format!("0x{:x}", tx_hash));
+	assert_eq!(res["params"]["result"]["from"], "0x0000000000000000000000000000000000000003");
+
+	// And unsubscribe
+	let request = r#"{"jsonrpc": "2.0", "method": "eth_unsubscribe", "params": ["0x43ca64edf03768e1"], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":true,"id":1}"#;
+	assert_eq!(io.handle_request_sync(request, metadata), Some(response.to_owned()));
+
+	let (res, _receiver) = receiver.into_future().wait().unwrap();
+	assert_eq!(res, None);
+}
+
+#[test]
+fn should_subscribe_to_dropped_transactions() {
+	// given
+	let el = Runtime::with_thread_count(1);
+	let client = TestBlockChainClient::new();
+	let (_, pool_receiver) = mpsc::unbounded();
+	let (status_sender, status_receiver) = mpsc::unbounded();
+
+	let mut pubsub = EthPubSubClient::new(Arc::new(client), el.executor(), pool_receiver);
+	pubsub.add_transactions_notifier(status_receiver, |_| None);
+	let pubsub = pubsub.to_delegate();
+
+	let mut io = MetaIoHandler::default();
+	io.extend_with(pubsub);
+
+	let mut metadata = Metadata::default();
+	let (sender, receiver) = futures::sync::mpsc::channel(8);
+	metadata.session = Some(Arc::new(Session::new(sender)));
+
+	// Subscribe
+	let request = r#"{"jsonrpc": "2.0", "method": "eth_subscribe", "params": ["droppedTransactions"], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":"0x43ca64edf03768e1","id":1}"#;
+	assert_eq!(io.handle_request_sync(request, metadata.clone()), Some(response.to_owned()));
+
+	// Mined transactions are not dropped
+	status_sender.unbounded_send(Arc::new(vec![
+		(H256::from_low_u64_be(5), TxStatus::Culled),
+		(H256::from_low_u64_be(7), TxStatus::Dropped),
+	])).unwrap();
+
+	let (res, _receiver) = receiver.into_future().wait().unwrap();
+	let response = r#"{"jsonrpc":"2.0","method":"eth_subscription","params":{"result":{"hash":"0x0000000000000000000000000000000000000000000000000000000000000007","reason":"dropped"},"subscription":"0x43ca64edf03768e1"}}"#;
+	assert_eq!(res, Some(response.into()));
+}
+
+#[test]
+fn should_subscribe_to_finalized_heads() {
+	// given
+	let el = Runtime::with_thread_count(1);
+	let mut client = TestBlockChainClient::new();
+	client.add_blocks(2, EachBlockWith::Nothing);
+	let h2 = client.block_hash_delta_minus(1);
+	let h1 = client.block_hash_delta_minus(2);
+
+	let (_, pool_receiver) = mpsc::unbounded();
+
+	let pubsub = EthPubSubClient::new(Arc::new(client), el.executor(), pool_receiver);
+	let handler = pubsub.handler().upgrade().unwrap();
+	let pubsub = pubsub.to_delegate();
+
+	let mut io = MetaIoHandler::default();
+	io.extend_with(pubsub);
+
+	let mut metadata = Metadata::default();
+	let (sender, receiver) = futures::sync::mpsc::channel(8);
+	metadata.session = Some(Arc::new(Session::new(sender)));
+
+	// Subscribe
+	let request = r#"{"jsonrpc": "2.0", "method": "eth_subscribe", "params": ["finalizedHeads"], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":"0x43ca64edf03768e1","id":1}"#;
+	assert_eq!(io.handle_request_sync(request, metadata.clone()), Some(response.to_owned()));
+
+	// New blocks are not finalized yet
+	handler.new_blocks(NewBlocks::new(vec![], vec![], ChainRoute::new(vec![(h2, ChainRouteType::Enacted)]), vec![], vec![], DURATION_ZERO, false));
+	handler.blocks_finalized(&[h1]);
+
+	let (res, _receiver) = receiver.into_future().wait().unwrap();
+	let res: serde_json::Value = serde_json::from_str(&res.unwrap()).unwrap();
+	assert_eq!(res["params"]["result"]["hash"], format!("0x{:x}", h1));
+	assert_eq!(res["params"]["result"]["number"], "0x1");
+}
+
 #[test]
 fn eth_subscribe_syncing() {
 	// given
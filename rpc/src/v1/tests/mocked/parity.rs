@@ -23,6 +23,7 @@ use machine::executed::Executed;
 use miner::pool::local_transactions::Status as LocalTransactionStatus;
 use sync::ManageNetwork;
 use types::{
+	block_reward::{BlockReward, RewardKind},
 	ids::TransactionId,
 	receipt::{LocalizedReceipt, TransactionOutcome},
 };
@@ -620,3 +621,47 @@ fn rpc_parity_verify_signature() {
 
 	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
 }
+
+#[test]
+fn rpc_parity_validator_stats_rejects_pending() {
+	let deps = Dependencies::new();
+	let io = deps.default_client();
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_validatorStats", "params": [null, "pending"], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32602,"message":"Couldn't parse parameters: blockNumber","data":"\"Pending block is not supported\""},"id":1}"#;
+
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_parity_randomness_rounds_unsupported_engine() {
+	let deps = Dependencies::new();
+	let io = deps.default_client();
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_randomnessRounds", "params": [], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32009,"message":"Consensus engine error.","data":"Engine error: Engine error (Randomness rounds are not supported by this engine)"},"id":1}"#;
+
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_parity_block_rewards() {
+	let deps = Dependencies::new();
+	let io = deps.default_client();
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_blockRewards", "params": ["latest"], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":null,"id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+
+	*deps.client.rewards.write() = Some(vec![BlockReward {
+		address: Address::from_low_u64_be(1),
+		kind: RewardKind::EmptyStep,
+		amount: U256::from(10),
+	}]);
+	let response = r#"{"jsonrpc":"2.0","result":[{"address":"0x0000000000000000000000000000000000000001","kind":"emptyStep","amount":"0xa"}],"id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_blockRewards", "params": ["0x10"], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32602,"message":"Unknown block number"},"id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
@@ -19,7 +19,11 @@
 use jsonrpc_core::Result;
 use jsonrpc_derive::rpc;
 
-use v1::types::RichBlock;
+use ethereum_types::H256;
+use v1::types::{
+	BlockNumber, CallRequest, RichBlock, StructLoggerOptions, StructLogTrace,
+	StructLogTraceWithTransactionHash,
+};
 
 /// Debug RPC interface.
 #[rpc(server)]
@@ -27,4 +31,16 @@ pub trait Debug {
 	/// Returns recently seen bad blocks.
 	#[rpc(name = "debug_getBadBlocks")]
 	fn bad_blocks(&self) -> Result<Vec<RichBlock>>;
+
+	/// Replays a transaction, returning Geth-compatible struct logs.
+	#[rpc(name = "debug_traceTransaction")]
+	fn trace_transaction(&self, _: H256, _: Option<StructLoggerOptions>) -> Result<StructLogTrace>;
+
+	/// Executes a call on top of the given block, returning Geth-compatible struct logs.
+	#[rpc(name = "debug_traceCall")]
+	fn trace_call(&self, _: CallRequest, _: Option<BlockNumber>, _: Option<StructLoggerOptions>) -> Result<StructLogTrace>;
+
+	/// Replays all transactions of a block, returning Geth-compatible struct logs.
+	#[rpc(name = "debug_traceBlockByNumber")]
+	fn trace_block_by_number(&self, _: BlockNumber, _: Option<StructLoggerOptions>) -> Result<Vec<StructLogTraceWithTransactionHash>>;
 }
@@ -19,8 +19,8 @@ use jsonrpc_core::{Result, BoxFuture};
 use jsonrpc_derive::rpc;
 use ethereum_types::{H64, H160, H256, U64, U256};
 
-use v1::types::{RichBlock, BlockNumber, Bytes, CallRequest, Filter, FilterChanges, Index, EthAccount};
-use v1::types::{Log, Receipt, SyncStatus, Transaction, Work};
+use v1::types::{RichBlock, BlockNumber, Bytes, CallRequest, FeeHistory, Filter, FilterChanges, Index, EthAccount};
+use v1::types::{Log, Receipt, StateOverride, SyncStatus, Transaction, Work};
 
 /// Eth rpc interface.
 #[rpc(server)]
@@ -58,6 +58,14 @@ pub trait Eth {
 	#[rpc(name = "eth_gasPrice")]
 	fn gas_price(&self) -> BoxFuture<U256>;
 
+	/// Returns suggested priority fee per gas for EIP-1559 transactions.
+	#[rpc(name = "eth_maxPriorityFeePerGas")]
+	fn max_priority_fee_per_gas(&self) -> BoxFuture<U256>;
+
+	/// Returns base fees, gas used ratios and priority fee percentiles of a range of blocks.
+	#[rpc(name = "eth_feeHistory")]
+	fn fee_history(&self, _: U256, _: BlockNumber, _: Option<Vec<f64>>) -> BoxFuture<FeeHistory>;
+
 	/// Returns accounts list.
 	#[rpc(name = "eth_accounts")]
 	fn accounts(&self) -> Result<Vec<H160>>;
@@ -118,13 +126,13 @@ pub trait Eth {
 	#[rpc(name = "eth_submitTransaction")]
 	fn submit_transaction(&self, _: Bytes) -> Result<H256>;
 
-	/// Call contract, returning the output data.
+	/// Call contract, returning the output data. The state may be altered for the call by the state override set.
 	#[rpc(name = "eth_call")]
-	fn call(&self, _: CallRequest, _: Option<BlockNumber>) -> BoxFuture<Bytes>;
+	fn call(&self, _: CallRequest, _: Option<BlockNumber>, _: Option<StateOverride>) -> BoxFuture<Bytes>;
 
 	/// Estimate gas needed for execution of given contract.
 	#[rpc(name = "eth_estimateGas")]
-	fn estimate_gas(&self, _: CallRequest, _: Option<BlockNumber>) -> BoxFuture<U256>;
+	fn estimate_gas(&self, _: CallRequest, _: Option<BlockNumber>, _: Option<StateOverride>) -> BoxFuture<U256>;
 
 	/// Get transaction by its hash.
 	#[rpc(name = "eth_getTransactionByHash")]
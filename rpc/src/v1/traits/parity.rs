@@ -23,12 +23,13 @@ use ethcore::miner::FilterOptions;
 use jsonrpc_core::{BoxFuture, Result};
 use jsonrpc_derive::rpc;
 use v1::types::{
-	Bytes, CallRequest,
+	Bytes, CallRequest, StateOverride,
 	Peers, Transaction, RpcSettings, Histogram, RecoveredAccount,
 	TransactionStats, LocalTransactionStatus,
 	BlockNumber, ConsensusCapability, VersionInfo,
 	OperationsInfo, ChainStatus, Log, Filter,
-	RichHeader, Receipt,
+	RichHeader, Receipt, ValidatorStats, RandomnessRound,
+	BlockReward, RewardBeneficiary,
 };
 
 /// Parity-specific rpc interface.
@@ -213,7 +214,7 @@ pub trait Parity {
 
 	/// Call contract, returning the output data.
 	#[rpc(name = "parity_call")]
-	fn call(&self, _: Vec<CallRequest>, _: Option<BlockNumber>) -> Result<Vec<Bytes>>;
+	fn call(&self, _: Vec<CallRequest>, _: Option<BlockNumber>, _: Option<StateOverride>) -> Result<Vec<Bytes>>;
 
 	/// Used for submitting a proof-of-work solution (similar to `eth_submitWork`,
 	/// but returns block hash on success, and returns an explicit error message on failure).
@@ -247,4 +248,26 @@ pub trait Parity {
 	/// Submit raw block to be published to the network
 	#[rpc(name = "parity_submitRawBlock")]
 	fn submit_raw_block(&self, _: Bytes) -> Result<H256>;
+
+	/// Returns validator liveness statistics (produced blocks, missed steps and signed empty steps)
+	/// for the given block range. If the start of the range is omitted, it defaults to the start
+	/// of the validator set epoch containing the end of the range, which defaults to the latest block.
+	#[rpc(name = "parity_validatorStats")]
+	fn validator_stats(&self, _: Option<BlockNumber>, _: Option<BlockNumber>) -> Result<ValidatorStats>;
+
+	/// Returns the rounds of on-chain randomness generation the local validator committed to,
+	/// oldest first, with whether the committed number was revealed.
+	#[rpc(name = "parity_randomnessRounds")]
+	fn randomness_rounds(&self) -> Result<Vec<RandomnessRound>>;
+
+	/// Returns the rewards paid when closing the given block, with their beneficiaries and kinds,
+	/// or `null` if they weren't recorded when the block was imported.
+	#[rpc(name = "parity_blockRewards")]
+	fn block_rewards(&self, _: BlockNumber) -> Result<Option<Vec<BlockReward>>>;
+
+	/// Calls `reward` of the block reward contract at the given address for the given beneficiaries,
+	/// on top of the state of the given block (default: latest), and returns the rewards it would pay.
+	/// Nothing is applied, so a contract can be checked before switching to it.
+	#[rpc(name = "parity_dryRunBlockReward")]
+	fn dry_run_block_reward(&self, _: H160, _: Vec<RewardBeneficiary>, _: Option<BlockNumber>) -> Result<Vec<BlockReward>>;
 }
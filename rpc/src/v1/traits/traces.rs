@@ -20,7 +20,7 @@ use ethereum_types::H256;
 use jsonrpc_core::Result;
 use jsonrpc_derive::rpc;
 use v1::types::{TraceFilter, LocalizedTrace, BlockNumber, Index, CallRequest, Bytes, TraceResults,
-	TraceResultsWithTransactionHash, TraceOptions};
+	TraceResultsWithTransactionHash, TraceOptions, StateOverride};
 
 /// Traces specific rpc interface.
 #[rpc(server)]
@@ -46,7 +46,7 @@ pub trait Traces {
 
 	/// Executes the given call and returns a number of possible traces for it.
 	#[rpc(name = "trace_call")]
-	fn call(&self, _: CallRequest, _: TraceOptions, _: Option<BlockNumber>) -> Result<TraceResults>;
+	fn call(&self, _: CallRequest, _: TraceOptions, _: Option<BlockNumber>, _: Option<StateOverride>) -> Result<TraceResults>;
 
 	/// Executes all given calls and returns a number of possible traces for each of it.
 	#[rpc(name = "trace_callMany")]
@@ -81,6 +81,9 @@ pub struct Block {
 	pub total_difficulty: Option<U256>,
 	/// Seal fields
 	pub seal_fields: Vec<Bytes>,
+	/// Base fee per gas (EIP-1559)
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub base_fee_per_gas: Option<U256>,
 	/// Uncles' hashes
 	pub uncles: Vec<H256>,
 	/// Transactions
@@ -126,18 +129,16 @@ pub struct Header {
 	pub difficulty: U256,
 	/// Seal fields
 	pub seal_fields: Vec<Bytes>,
+	/// Base fee per gas (EIP-1559)
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub base_fee_per_gas: Option<U256>,
 	/// Size in bytes
 	pub size: Option<U256>,
 }
 
-impl From<EthHeader> for Header {
-	fn from(h: EthHeader) -> Self {
-		(&h).into()
-	}
-}
-
-impl<'a> From<&'a EthHeader> for Header {
-	fn from(h: &'a EthHeader) -> Self {
+impl Header {
+	/// Converts a header of a chain whose blocks carry a base fee from `eip1559_transition` on.
+	pub fn new(h: &EthHeader, eip1559_transition: u64) -> Self {
 		Header {
 			hash: Some(h.hash()),
 			size: Some(h.rlp().as_raw().len().into()),
@@ -155,9 +156,10 @@ impl<'a> From<&'a EthHeader> for Header {
 			timestamp: h.timestamp().into(),
 			difficulty: h.difficulty(),
 			extra_data: h.extra_data().into(),
-			seal_fields: h.view().decode_seal()
+			seal_fields: h.view().decode_seal(eip1559_transition)
 				.expect("Client/Miner returns only valid headers. We only serialize headers from Client/Miner; qed")
 				.into_iter().map(Into::into).collect(),
+			base_fee_per_gas: h.base_fee_per_gas(eip1559_transition),
 		}
 	}
 }
@@ -241,6 +243,7 @@ mod tests {
 			difficulty: U256::default(),
 			total_difficulty: Some(U256::default()),
 			seal_fields: vec![Bytes::default(), Bytes::default()],
+			base_fee_per_gas: None,
 			uncles: vec![],
 			transactions: BlockTransactions::Hashes(vec![].into()),
 			size: Some(69.into()),
@@ -279,6 +282,7 @@ mod tests {
 			difficulty: U256::default(),
 			total_difficulty: Some(U256::default()),
 			seal_fields: vec![Bytes::default(), Bytes::default()],
+			base_fee_per_gas: None,
 			uncles: vec![],
 			transactions: BlockTransactions::Hashes(vec![].into()),
 			size: None,
@@ -316,6 +320,7 @@ mod tests {
 			timestamp: U256::default(),
 			difficulty: U256::default(),
 			seal_fields: vec![Bytes::default(), Bytes::default()],
+			base_fee_per_gas: None,
 			size: Some(69.into()),
 		};
 		let serialized_header = serde_json::to_string(&header).unwrap();
@@ -0,0 +1,119 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Parity Ethereum.
+
+// Parity Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Block rewards.
+
+use ethereum_types::{H160, U256};
+use types::block_reward;
+
+/// The kind of a block reward.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum RewardKind {
+	/// Reward attributed to the block author.
+	Author,
+	/// Reward attributed to the author of an empty step included in the block.
+	EmptyStep,
+	/// Reward attributed by a block reward contract.
+	External,
+	/// Reward attributed to the author of an uncle with the given depth.
+	Uncle(u8),
+}
+
+impl From<block_reward::RewardKind> for RewardKind {
+	fn from(k: block_reward::RewardKind) -> Self {
+		match k {
+			block_reward::RewardKind::Author => RewardKind::Author,
+			block_reward::RewardKind::EmptyStep => RewardKind::EmptyStep,
+			block_reward::RewardKind::External => RewardKind::External,
+			block_reward::RewardKind::Uncle(depth) => RewardKind::Uncle(depth),
+		}
+	}
+}
+
+impl Into<block_reward::RewardKind> for RewardKind {
+	fn into(self) -> block_reward::RewardKind {
+		match self {
+			RewardKind::Author => block_reward::RewardKind::Author,
+			RewardKind::EmptyStep => block_reward::RewardKind::EmptyStep,
+			RewardKind::External => block_reward::RewardKind::External,
+			RewardKind::Uncle(depth) => block_reward::RewardKind::Uncle(depth),
+		}
+	}
+}
+
+/// A reward paid when closing a block.
+#[derive(Debug, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockReward {
+	/// Rewarded address.
+	pub address: H160,
+	/// Kind of the reward.
+	pub kind: RewardKind,
+	/// Rewarded amount.
+	pub amount: U256,
+}
+
+impl From<block_reward::BlockReward> for BlockReward {
+	fn from(r: block_reward::BlockReward) -> Self {
+		BlockReward {
+			address: r.address,
+			kind: r.kind.into(),
+			amount: r.amount,
+		}
+	}
+}
+
+/// A beneficiary passed to a block reward contract.
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+#[serde(rename_all = "camelCase")]
+pub struct RewardBeneficiary {
+	/// Beneficiary address.
+	pub address: H160,
+	/// Kind of the reward.
+	pub kind: RewardKind,
+}
+
+#[cfg(test)]
+mod tests {
+	use serde_json;
+	use ethereum_types::{H160, U256};
+	use types::block_reward;
+	use super::{BlockReward, RewardBeneficiary, RewardKind};
+
+	#[test]
+	fn block_reward_serialization() {
+		let reward = block_reward::BlockReward {
+			address: H160::from_low_u64_be(1),
+			kind: block_reward::RewardKind::Uncle(2),
+			amount: U256::from(5),
+		};
+
+		let serialized = serde_json::to_string(&BlockReward::from(reward)).unwrap();
+		assert_eq!(serialized, r#"{"address":"0x0000000000000000000000000000000000000001","kind":{"uncle":2},"amount":"0x5"}"#);
+	}
+
+	#[test]
+	fn reward_beneficiary_deserialization() {
+		let s = r#"{"address":"0x0000000000000000000000000000000000000001","kind":"emptyStep"}"#;
+		let deserialized: RewardBeneficiary = serde_json::from_str(s).unwrap();
+		assert_eq!(deserialized, RewardBeneficiary {
+			address: H160::from_low_u64_be(1),
+			kind: RewardKind::EmptyStep,
+		});
+	}
+}
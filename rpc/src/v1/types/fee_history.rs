@@ -0,0 +1,52 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Parity Ethereum.
+
+// Parity Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Fee market history (`eth_feeHistory`).
+
+use ethereum_types::U256;
+
+/// Base fees, block fullness and paid priority fees of a range of blocks.
+#[derive(Debug, Default, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FeeHistory {
+	/// Number of the first block of the range.
+	pub oldest_block: U256,
+	/// Base fee of every block of the range, followed by the base fee of the next block.
+	pub base_fee_per_gas: Vec<U256>,
+	/// Ratio of gas used to gas limit of every block of the range.
+	pub gas_used_ratio: Vec<f64>,
+	/// Requested percentiles of the priority fees paid in every block of the range, weighted by gas used.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub reward: Option<Vec<Vec<U256>>>,
+}
+
+#[cfg(test)]
+mod tests {
+	use serde_json;
+	use super::FeeHistory;
+
+	#[test]
+	fn should_serialize_fee_history() {
+		let history = FeeHistory {
+			oldest_block: 5.into(),
+			base_fee_per_gas: vec![7.into(), 8.into()],
+			gas_used_ratio: vec![0.5],
+			reward: Some(vec![vec![1.into(), 2.into()]]),
+		};
+		let expected = r#"{"oldestBlock":"0x5","baseFeePerGas":["0x7","0x8"],"gasUsedRatio":[0.5],"reward":[["0x1","0x2"]]}"#;
+		assert_eq!(serde_json::to_string(&history).unwrap(), expected);
+	}
+}
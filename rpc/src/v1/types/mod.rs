@@ -22,11 +22,13 @@ mod eth_types;
 mod account_info;
 mod block;
 mod block_number;
+mod block_reward;
 mod bytes;
 mod call_request;
 mod confirmations;
 mod consensus_status;
 mod derivation;
+mod fee_history;
 mod filter;
 mod histogram;
 mod index;
@@ -35,15 +37,19 @@ mod node_kind;
 mod private_receipt;
 mod private_log;
 mod provenance;
+mod randomness_round;
 mod receipt;
 mod rpc_settings;
 mod secretstore;
+mod state_override;
+mod struct_log;
 mod sync;
 mod trace;
 mod trace_filter;
 mod transaction;
 mod transaction_request;
 mod transaction_condition;
+mod validator_stats;
 mod work;
 mod eip191;
 
@@ -54,6 +60,7 @@ pub use self::account_info::{AccountInfo, ExtAccountInfo, EthAccount, StoragePro
 pub use self::bytes::Bytes;
 pub use self::block::{RichBlock, Block, BlockTransactions, Header, RichHeader, Rich};
 pub use self::block_number::{BlockNumber, LightBlockNumber, block_number_to_id};
+pub use self::block_reward::{BlockReward, RewardBeneficiary, RewardKind};
 pub use self::call_request::CallRequest;
 pub use self::confirmations::{
 	ConfirmationPayload, ConfirmationRequest, ConfirmationResponse, ConfirmationResponseWithToken,
@@ -61,6 +68,7 @@ pub use self::confirmations::{
 };
 pub use self::consensus_status::*;
 pub use self::derivation::{DeriveHash, DeriveHierarchical, Derive};
+pub use self::fee_history::FeeHistory;
 pub use self::filter::{Filter, FilterChanges};
 pub use self::histogram::Histogram;
 pub use self::index::Index;
@@ -72,15 +80,19 @@ pub use self::provenance::Origin;
 pub use self::receipt::Receipt;
 pub use self::rpc_settings::RpcSettings;
 pub use self::secretstore::EncryptedDocumentKey;
+pub use self::state_override::{StateOverride, AccountOverride, state_override_into};
+pub use self::struct_log::{StructLog, StructLogTrace, StructLogTraceWithTransactionHash, StructLoggerOptions};
 pub use self::sync::{
 	SyncStatus, SyncInfo, Peers, PeerInfo, PeerNetworkInfo, PeerProtocolsInfo,
 	TransactionStats, ChainStatus, EthProtocolInfo, PipProtocolInfo,
 };
-pub use self::trace::{LocalizedTrace, TraceResults, TraceResultsWithTransactionHash};
+pub use self::trace::{AccountDiff, LocalizedTrace, TraceResults, TraceResultsWithTransactionHash};
 pub use self::trace_filter::TraceFilter;
-pub use self::transaction::{Transaction, RichRawTransaction, LocalTransactionStatus};
+pub use self::transaction::{Transaction, RichRawTransaction, LocalTransactionStatus, AccessListItem};
 pub use self::transaction_request::TransactionRequest;
 pub use self::transaction_condition::TransactionCondition;
+pub use self::validator_stats::{ValidatorLiveness, ValidatorStats};
+pub use self::randomness_round::{RandomnessRound, RandomnessRoundStatus};
 pub use self::work::Work;
 
 // TODO [ToDr] Refactor to a proper type Vec of enums?
@@ -16,14 +16,15 @@
 
 //! Pub-Sub types.
 
-use ethereum_types::H256;
+use ethereum_types::{H160, H256};
+use miner::pool::TxStatus;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use serde::de::Error;
 use serde_json::{Value, from_value};
-use v1::types::{RichHeader, Filter, Log};
+use v1::types::{RichHeader, Filter, Log, Transaction};
 
 /// Subscription result.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Result {
 	/// New block header.
 	Header(Box<RichHeader>),
@@ -31,10 +32,24 @@ pub enum Result {
 	Log(Box<Log>),
 	/// Transaction hash
 	TransactionHash(H256),
+	/// Pending transaction
+	Transaction(Box<Transaction>),
+	/// Transaction removed from the pool
+	DroppedTransaction(DroppedTransaction),
 	/// SyncStatus
 	SyncState(PubSubSyncStatus)
 }
 
+/// Transaction removed from the pool without being mined.
+#[derive(Debug, Serialize, Eq, PartialEq, Clone)]
+#[serde(rename_all="camelCase")]
+pub struct DroppedTransaction {
+	/// Transaction hash
+	pub hash: H256,
+	/// Why the transaction was removed
+	pub reason: TxStatus,
+}
+
 /// PubSbub sync status
 #[derive(Debug, Serialize, Eq, PartialEq, Clone)]
 #[serde(rename_all="camelCase")]
@@ -51,6 +66,8 @@ impl Serialize for Result {
 			Result::Header(ref header) => header.serialize(serializer),
 			Result::Log(ref log) => log.serialize(serializer),
 			Result::TransactionHash(ref hash) => hash.serialize(serializer),
+			Result::Transaction(ref transaction) => transaction.serialize(serializer),
+			Result::DroppedTransaction(ref dropped) => dropped.serialize(serializer),
 			Result::SyncState(ref sync) => sync.serialize(serializer),
 		}
 	}
@@ -67,6 +84,12 @@ pub enum Kind {
 	Logs,
 	/// New Pending Transactions subscription.
 	NewPendingTransactions,
+	/// Pending transaction objects subscription.
+	PendingTransactions,
+	/// Transactions dropped from the pool subscription.
+	DroppedTransactions,
+	/// Finalized block headers subscription.
+	FinalizedHeads,
 	/// Node syncing status subscription.
 	Syncing,
 }
@@ -78,6 +101,27 @@ pub enum Params {
 	None,
 	/// Log parameters.
 	Logs(Filter),
+	/// Pending transaction parameters.
+	Transactions(TransactionFilter),
+}
+
+/// Filter of pending transactions by sender and recipient.
+#[derive(Debug, Default, Deserialize, PartialEq, Eq, Hash, Clone)]
+#[serde(deny_unknown_fields)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionFilter {
+	/// Sender of the transaction
+	pub from: Option<H160>,
+	/// Recipient of the transaction
+	pub to: Option<H160>,
+}
+
+impl TransactionFilter {
+	/// Whether the transaction passes the filter.
+	pub fn matches(&self, transaction: &Transaction) -> bool {
+		self.from.map_or(true, |from| from == transaction.from) &&
+			self.to.map_or(true, |to| Some(to) == transaction.to)
+	}
 }
 
 impl Default for Params {
@@ -96,6 +140,7 @@ impl<'a> Deserialize<'a> for Params {
 		}
 
 		from_value(v.clone()).map(Params::Logs)
+			.or_else(|_| from_value(v.clone()).map(Params::Transactions))
 			.map_err(|e| D::Error::custom(format!("Invalid Pub-Sub parameters: {}", e)))
 	}
 }
@@ -103,7 +148,7 @@ impl<'a> Deserialize<'a> for Params {
 #[cfg(test)]
 mod tests {
 	use serde_json;
-	use super::{Result, Kind, Params};
+	use super::{Result, Kind, Params, TransactionFilter};
 	use v1::types::{RichHeader, Header, Filter};
 	use v1::types::filter::VariadicValue;
 
@@ -113,6 +158,20 @@ mod tests {
 		assert_eq!(serde_json::from_str::<Kind>(r#""logs""#).unwrap(), Kind::Logs);
 		assert_eq!(serde_json::from_str::<Kind>(r#""newPendingTransactions""#).unwrap(), Kind::NewPendingTransactions);
 		assert_eq!(serde_json::from_str::<Kind>(r#""syncing""#).unwrap(), Kind::Syncing);
+		assert_eq!(serde_json::from_str::<Kind>(r#""pendingTransactions""#).unwrap(), Kind::PendingTransactions);
+		assert_eq!(serde_json::from_str::<Kind>(r#""droppedTransactions""#).unwrap(), Kind::DroppedTransactions);
+		assert_eq!(serde_json::from_str::<Kind>(r#""finalizedHeads""#).unwrap(), Kind::FinalizedHeads);
+	}
+
+	#[test]
+	fn should_deserialize_transaction_filter() {
+		let filter = serde_json::from_str::<Params>(
+			r#"{"from":"0x0000000000000000000000000000000000000005"}"#
+		).unwrap();
+		assert_eq!(filter, Params::Transactions(TransactionFilter {
+			from: Some("0x0000000000000000000000000000000000000005".parse().unwrap()),
+			to: None,
+		}));
 	}
 
 	#[test]
@@ -174,6 +233,7 @@ mod tests {
 				timestamp: Default::default(),
 				difficulty: Default::default(),
 				seal_fields: vec![Default::default(), Default::default()],
+				base_fee_per_gas: None,
 				size: Some(69.into()),
 			},
 		}));
@@ -0,0 +1,96 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Parity Ethereum.
+
+// Parity Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Randomness rounds of the local validator.
+
+use ethereum_types::{H160, H256, U256, U64};
+use types::engines;
+
+/// Outcome of the validator's participation in a randomness round.
+#[derive(Debug, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum RandomnessRoundStatus {
+	/// The hash of the secret number was committed; it wasn't revealed yet.
+	Committed,
+	/// The secret number was revealed.
+	Revealed,
+	/// The round ended without the secret number being revealed.
+	Missed,
+}
+
+impl From<engines::RandomnessRoundStatus> for RandomnessRoundStatus {
+	fn from(s: engines::RandomnessRoundStatus) -> Self {
+		match s {
+			engines::RandomnessRoundStatus::Committed => RandomnessRoundStatus::Committed,
+			engines::RandomnessRoundStatus::Revealed => RandomnessRoundStatus::Revealed,
+			engines::RandomnessRoundStatus::Missed => RandomnessRoundStatus::Missed,
+		}
+	}
+}
+
+/// The validator's participation in a round of a randomness contract.
+#[derive(Debug, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RandomnessRound {
+	/// Address of the randomness contract.
+	pub contract: H160,
+	/// The contract's collection round.
+	pub round: U256,
+	/// The validator which committed the secret number.
+	pub validator: H160,
+	/// Hash of the committed secret number.
+	pub number_hash: H256,
+	/// Whether the number was revealed.
+	pub status: RandomnessRoundStatus,
+	/// Number of reveal transactions created for the round.
+	pub reveal_attempts: U64,
+}
+
+impl From<engines::RandomnessRound> for RandomnessRound {
+	fn from(r: engines::RandomnessRound) -> Self {
+		RandomnessRound {
+			contract: r.contract,
+			round: r.round,
+			validator: r.validator,
+			number_hash: r.number_hash,
+			status: r.status.into(),
+			reveal_attempts: r.reveal_attempts.into(),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use serde_json;
+	use ethereum_types::{H160, H256, U256};
+	use types::engines;
+	use super::RandomnessRound;
+
+	#[test]
+	fn randomness_round_serialization() {
+		let round = engines::RandomnessRound {
+			contract: H160::from_low_u64_be(1),
+			round: U256::from(7),
+			validator: H160::from_low_u64_be(2),
+			number_hash: H256::from_low_u64_be(3),
+			status: engines::RandomnessRoundStatus::Revealed,
+			reveal_attempts: 2,
+		};
+
+		let serialized = serde_json::to_string(&RandomnessRound::from(round)).unwrap();
+		assert_eq!(serialized, r#"{"contract":"0x0000000000000000000000000000000000000001","round":"0x7","validator":"0x0000000000000000000000000000000000000002","numberHash":"0x0000000000000000000000000000000000000000000000000000000000000003","status":"revealed","revealAttempts":"0x2"}"#);
+	}
+}
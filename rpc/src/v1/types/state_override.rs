@@ -0,0 +1,115 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Parity Ethereum.
+
+// Parity Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Geth-compatible state override set.
+
+use std::collections::BTreeMap;
+
+use ethereum_types::{H160, H256, U256};
+use jsonrpc_core::Error as RpcError;
+use types::state_override as eth;
+use v1::helpers::errors::invalid_params;
+use v1::types::Bytes;
+
+/// Overrides applied to the state before making a call, keyed by account address.
+pub type StateOverride = BTreeMap<H160, AccountOverride>;
+
+/// Overrides of a single account.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountOverride {
+	/// Fake balance to set for the account.
+	pub balance: Option<U256>,
+	/// Fake nonce to set for the account.
+	pub nonce: Option<U256>,
+	/// Fake code to set for the account.
+	pub code: Option<Bytes>,
+	/// Fake storage replacing the whole storage of the account.
+	pub state: Option<BTreeMap<H256, H256>>,
+	/// Fake storage slots patched into the storage of the account.
+	pub state_diff: Option<BTreeMap<H256, H256>>,
+}
+
+impl AccountOverride {
+	/// Convert into the ethcore representation; `state` and `stateDiff` are mutually exclusive.
+	pub fn try_into(self) -> Result<eth::AccountOverride, RpcError> {
+		let storage = match (self.state, self.state_diff) {
+			(Some(_), Some(_)) => return Err(invalid_params("stateOverride", "state is mutually exclusive with stateDiff")),
+			(Some(state), None) => Some(eth::StorageOverride::Replace(state)),
+			(None, Some(diff)) => Some(eth::StorageOverride::Diff(diff)),
+			(None, None) => None,
+		};
+
+		Ok(eth::AccountOverride {
+			balance: self.balance,
+			nonce: self.nonce,
+			code: self.code.map(Bytes::into_vec),
+			storage,
+		})
+	}
+}
+
+/// Convert an RPC state override set into the ethcore representation.
+pub fn state_override_into(overrides: StateOverride) -> Result<eth::StateOverride, RpcError> {
+	overrides.into_iter()
+		.map(|(address, account)| account.try_into().map(|account| (address, account)))
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use ethereum_types::{H160, H256, U256};
+	use serde_json;
+	use types::state_override as eth;
+	use super::{StateOverride, state_override_into};
+
+	#[test]
+	fn state_override_deserialization() {
+		let s = r#"{
+			"0x0000000000000000000000000000000000000001": {
+				"balance": "0x10",
+				"nonce": "0x2",
+				"code": "0x6000",
+				"stateDiff": {
+					"0x0000000000000000000000000000000000000000000000000000000000000001": "0x0000000000000000000000000000000000000000000000000000000000000002"
+				}
+			}
+		}"#;
+		let overrides: StateOverride = serde_json::from_str(s).unwrap();
+		let overrides = state_override_into(overrides).unwrap();
+		let account = &overrides[&H160::from_low_u64_be(1)];
+
+		assert_eq!(account.balance, Some(U256::from(0x10)));
+		assert_eq!(account.nonce, Some(U256::from(2)));
+		assert_eq!(account.code, Some(vec![0x60, 0x00]));
+		assert_eq!(account.storage, Some(eth::StorageOverride::Diff(
+			vec![(H256::from_low_u64_be(1), H256::from_low_u64_be(2))].into_iter().collect()
+		)));
+	}
+
+	#[test]
+	fn state_and_state_diff_are_exclusive() {
+		let s = r#"{
+			"0x0000000000000000000000000000000000000001": {
+				"state": {},
+				"stateDiff": {}
+			}
+		}"#;
+		let overrides: StateOverride = serde_json::from_str(s).unwrap();
+		assert!(state_override_into(overrides).is_err());
+	}
+}
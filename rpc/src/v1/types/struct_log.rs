@@ -0,0 +1,163 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Parity Ethereum.
+
+// Parity Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Geth-compatible struct logger output.
+
+use std::collections::BTreeMap;
+
+use ethereum_types::{H256, U256};
+use machine::executed::StructLogExecuted;
+use rustc_hex::ToHex;
+use trace as et;
+
+/// Options of `debug_trace*` calls.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StructLoggerOptions {
+	/// Don't capture the stack.
+	#[serde(default)]
+	pub disable_stack: bool,
+	/// Don't capture the memory.
+	#[serde(default)]
+	pub disable_memory: bool,
+	/// Don't capture the storage.
+	#[serde(default)]
+	pub disable_storage: bool,
+}
+
+impl From<StructLoggerOptions> for et::StructLoggerConfig {
+	fn from(o: StructLoggerOptions) -> Self {
+		et::StructLoggerConfig {
+			disable_stack: o.disable_stack,
+			disable_memory: o.disable_memory,
+			disable_storage: o.disable_storage,
+		}
+	}
+}
+
+/// A single executed instruction.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StructLog {
+	/// Program counter.
+	pub pc: u64,
+	/// Instruction mnemonic.
+	pub op: String,
+	/// Gas left before the instruction.
+	pub gas: u64,
+	/// Gas cost of the instruction.
+	pub gas_cost: u64,
+	/// Call depth, starting at 1.
+	pub depth: u64,
+	/// Stack before the instruction, as 32-byte words.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub stack: Option<Vec<String>>,
+	/// Memory before the instruction, as 32-byte words.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub memory: Option<Vec<String>>,
+	/// Storage of the executing contract.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub storage: Option<BTreeMap<String, String>>,
+}
+
+fn word(value: &U256) -> String {
+	let mut buf = [0u8; 32];
+	value.to_big_endian(&mut buf);
+	buf.to_hex()
+}
+
+impl From<et::StructLog> for StructLog {
+	fn from(l: et::StructLog) -> Self {
+		StructLog {
+			op: l.op_name().into_owned(),
+			pc: l.pc as u64,
+			gas: l.gas.low_u64(),
+			gas_cost: l.gas_cost.low_u64(),
+			depth: l.depth as u64,
+			stack: l.stack.map(|stack| stack.iter().map(word).collect()),
+			memory: l.memory.map(|memory| memory.chunks(32).map(|chunk| chunk.to_hex()).collect()),
+			storage: l.storage.map(|storage| storage.iter().map(|(k, v)| (word(k), word(v))).collect()),
+		}
+	}
+}
+
+/// Struct logs of a single transaction.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StructLogTrace {
+	/// Gas used by the transaction.
+	pub gas: u64,
+	/// Whether the transaction failed.
+	pub failed: bool,
+	/// Output of the transaction.
+	pub return_value: String,
+	/// Executed instructions.
+	pub struct_logs: Vec<StructLog>,
+}
+
+impl From<StructLogExecuted> for StructLogTrace {
+	fn from(e: StructLogExecuted) -> Self {
+		StructLogTrace {
+			gas: e.gas_used.low_u64(),
+			failed: e.exception.is_some(),
+			return_value: e.output.to_hex(),
+			struct_logs: e.vm_trace.unwrap_or_default().into_iter().map(Into::into).collect(),
+		}
+	}
+}
+
+/// Struct logs of a transaction within a block.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StructLogTraceWithTransactionHash {
+	/// The transaction hash.
+	pub tx_hash: H256,
+	/// The struct logs.
+	pub result: StructLogTrace,
+}
+
+impl From<(H256, StructLogExecuted)> for StructLogTraceWithTransactionHash {
+	fn from((tx_hash, e): (H256, StructLogExecuted)) -> Self {
+		StructLogTraceWithTransactionHash {
+			tx_hash,
+			result: e.into(),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use serde_json;
+	use trace as et;
+	use super::StructLog;
+
+	#[test]
+	fn struct_log_serialization() {
+		let log = et::StructLog {
+			pc: 4,
+			instruction: 0x55,
+			gas: 94.into(),
+			gas_cost: 20.into(),
+			depth: 1,
+			stack: Some(vec![42.into(), 1.into()]),
+			memory: None,
+			storage: Some(vec![(1.into(), 42.into())].into_iter().collect()),
+		};
+
+		let serialized = serde_json::to_string(&StructLog::from(log)).unwrap();
+		assert_eq!(serialized, r#"{"pc":4,"op":"SSTORE","gas":94,"gasCost":20,"depth":1,"stack":["000000000000000000000000000000000000000000000000000000000000002a","0000000000000000000000000000000000000000000000000000000000000001"],"storage":{"0000000000000000000000000000000000000000000000000000000000000001":"000000000000000000000000000000000000000000000000000000000000002a"}}"#);
+	}
+}
@@ -22,7 +22,7 @@ use machine::executive::{contract_address};
 use vm::CreateContractAddress;
 use ethereum_types::{H160, H256, H512, U64, U256};
 use miner;
-use types::transaction::{LocalizedTransaction, Action, PendingTransaction, SignedTransaction};
+use types::transaction::{self as et, LocalizedTransaction, Action, PendingTransaction, SignedTransaction, UnverifiedTransaction};
 use v1::types::{Bytes, TransactionCondition};
 
 /// Transaction
@@ -69,6 +69,46 @@ pub struct Transaction {
 	pub s: U256,
 	/// Transaction activates at specified block.
 	pub condition: Option<TransactionCondition>,
+	/// EIP-2718 transaction type, omitted for legacy transactions.
+	#[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+	pub transaction_type: Option<U64>,
+	/// EIP-2930 access list, omitted for legacy transactions.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub access_list: Option<Vec<AccessListItem>>,
+	/// EIP-1559 fee cap, omitted for non-EIP-1559 transactions.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub max_fee_per_gas: Option<U256>,
+	/// EIP-1559 priority fee cap, omitted for non-EIP-1559 transactions.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub max_priority_fee_per_gas: Option<U256>,
+}
+
+/// Entry of an EIP-2930 access list.
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccessListItem {
+	/// Accessed account.
+	pub address: H160,
+	/// Accessed storage keys.
+	pub storage_keys: Vec<H256>,
+}
+
+impl From<et::AccessListItem> for AccessListItem {
+	fn from(item: et::AccessListItem) -> Self {
+		AccessListItem {
+			address: item.address,
+			storage_keys: item.storage_keys,
+		}
+	}
+}
+
+impl From<AccessListItem> for et::AccessListItem {
+	fn from(item: AccessListItem) -> Self {
+		et::AccessListItem {
+			address: item.address,
+			storage_keys: item.storage_keys,
+		}
+	}
 }
 
 /// Local Transaction Status
@@ -196,14 +236,18 @@ impl Transaction {
 				Action::Create => Some(contract_address(scheme, &t.sender(), &t.nonce, &t.data).0),
 				Action::Call(_) => None,
 			},
-			raw: ::rlp::encode(&t.signed).into(),
+			raw: t.signed.encode().into(),
 			public_key: t.recover_public().ok().map(Into::into),
 			chain_id: t.chain_id().map(U64::from),
 			standard_v: t.standard_v().into(),
-			v: t.original_v().into(),
+			v: rpc_v(&t.signed),
 			r: signature.r().into(),
 			s: signature.s().into(),
 			condition: None,
+			transaction_type: t.tx_type().to_u8().map(U64::from),
+			access_list: rpc_access_list(&t.signed),
+			max_fee_per_gas: rpc_eip1559_fee(&t.signed, UnverifiedTransaction::max_fee_per_gas),
+			max_priority_fee_per_gas: rpc_eip1559_fee(&t.signed, UnverifiedTransaction::max_priority_fee_per_gas),
 		}
 	}
 
@@ -230,14 +274,18 @@ impl Transaction {
 				Action::Create => Some(contract_address(scheme, &t.sender(), &t.nonce, &t.data).0),
 				Action::Call(_) => None,
 			},
-			raw: ::rlp::encode(&t).into(),
+			raw: t.encode().into(),
 			public_key: t.public_key().map(Into::into),
 			chain_id: t.chain_id().map(U64::from),
 			standard_v: t.standard_v().into(),
-			v: t.original_v().into(),
+			v: rpc_v(&t),
 			r: signature.r().into(),
 			s: signature.s().into(),
 			condition: None,
+			transaction_type: t.tx_type().to_u8().map(U64::from),
+			access_list: rpc_access_list(&t),
+			max_fee_per_gas: rpc_eip1559_fee(&t, UnverifiedTransaction::max_fee_per_gas),
+			max_priority_fee_per_gas: rpc_eip1559_fee(&t, UnverifiedTransaction::max_priority_fee_per_gas),
 		}
 	}
 
@@ -249,6 +297,25 @@ impl Transaction {
 	}
 }
 
+/// The `v` value as it appears in the encoded transaction: the y parity for typed transactions.
+fn rpc_v(t: &UnverifiedTransaction) -> U256 {
+	match t.tx_type() {
+		et::TypedTxId::Legacy => t.original_v().into(),
+		_ => t.standard_v().into(),
+	}
+}
+
+fn rpc_eip1559_fee(t: &UnverifiedTransaction, fee: fn(&UnverifiedTransaction) -> U256) -> Option<U256> {
+	match t.tx_type() {
+		et::TypedTxId::EIP1559Transaction => Some(fee(t)),
+		_ => None,
+	}
+}
+
+fn rpc_access_list(t: &UnverifiedTransaction) -> Option<Vec<AccessListItem>> {
+	t.access_list().map(|list| list.iter().cloned().map(Into::into).collect())
+}
+
 impl LocalTransactionStatus {
 	/// Convert `LocalTransactionStatus` into RPC `LocalTransactionStatus`.
 	pub fn from(s: miner::pool::local_transactions::Status) -> Self {
@@ -285,6 +352,40 @@ mod tests {
 		assert_eq!(serialized, r#"{"hash":"0x0000000000000000000000000000000000000000000000000000000000000000","nonce":"0x0","blockHash":null,"blockNumber":null,"transactionIndex":null,"from":"0x0000000000000000000000000000000000000000","to":null,"value":"0x0","gasPrice":"0x0","gas":"0x0","input":"0x","creates":null,"raw":"0x","publicKey":null,"chainId":null,"standardV":"0x0","v":"0x0","r":"0x0","s":"0x0","condition":null}"#);
 	}
 
+	#[test]
+	fn test_access_list_transaction_serialize() {
+		use ethereum_types::{Address, H256};
+		use types::transaction::{AccessListItem, AccessListTx, TypedTransaction};
+
+		let signed = TypedTransaction::AccessList(AccessListTx {
+			transaction: Default::default(),
+			access_list: vec![AccessListItem {
+				address: Address::from_low_u64_be(1),
+				storage_keys: vec![H256::from_low_u64_be(2)],
+			}],
+		}).fake_sign(Address::from_low_u64_be(3));
+
+		let serialized = serde_json::to_string(&Transaction::from_signed(signed)).unwrap();
+		assert!(serialized.ends_with(r#","condition":null,"type":"0x1","accessList":[{"address":"0x0000000000000000000000000000000000000001","storageKeys":["0x0000000000000000000000000000000000000000000000000000000000000002"]}]}"#));
+	}
+
+	#[test]
+	fn test_eip1559_transaction_serialize() {
+		use ethereum_types::{Address, U256};
+		use types::transaction::{AccessListTx, EIP1559TransactionTx, Transaction as EthTransaction, TypedTransaction};
+
+		let signed = TypedTransaction::EIP1559Transaction(EIP1559TransactionTx {
+			transaction: AccessListTx {
+				transaction: EthTransaction { gas_price: U256::from(10), ..Default::default() },
+				access_list: vec![],
+			},
+			max_priority_fee_per_gas: U256::from(2),
+		}).fake_sign(Address::from_low_u64_be(3));
+
+		let serialized = serde_json::to_string(&Transaction::from_signed(signed)).unwrap();
+		assert!(serialized.ends_with(r#","condition":null,"type":"0x2","accessList":[],"maxFeePerGas":"0xa","maxPriorityFeePerGas":"0x2"}"#));
+	}
+
 	#[test]
 	fn test_local_transaction_status_serialize() {
 		use ethereum_types::H256;
@@ -0,0 +1,88 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Parity Ethereum.
+
+// Parity Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Validator liveness statistics.
+
+use std::collections::BTreeMap;
+
+use ethereum_types::{H160, U64};
+use types::engines;
+
+/// Liveness of a single validator.
+#[derive(Debug, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidatorLiveness {
+	/// Number of blocks authored.
+	pub produced_blocks: U64,
+	/// Number of steps in which the validator was the primary but produced neither a block nor
+	/// an empty step.
+	pub missed_steps: U64,
+	/// Number of empty steps signed and included in a block.
+	pub empty_steps_signed: U64,
+}
+
+impl From<engines::ValidatorLiveness> for ValidatorLiveness {
+	fn from(l: engines::ValidatorLiveness) -> Self {
+		ValidatorLiveness {
+			produced_blocks: l.produced_blocks.into(),
+			missed_steps: l.missed_steps.into(),
+			empty_steps_signed: l.empty_steps_signed.into(),
+		}
+	}
+}
+
+/// Per-validator liveness statistics for a range of blocks.
+#[derive(Debug, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidatorStats {
+	/// First block of the range.
+	pub from_block: U64,
+	/// Last block of the range.
+	pub to_block: U64,
+	/// Liveness of every validator seen in the range.
+	pub validators: BTreeMap<H160, ValidatorLiveness>,
+}
+
+impl From<engines::ValidatorStats> for ValidatorStats {
+	fn from(s: engines::ValidatorStats) -> Self {
+		ValidatorStats {
+			from_block: s.from_block.into(),
+			to_block: s.to_block.into(),
+			validators: s.validators.into_iter().map(|(a, l)| (a, l.into())).collect(),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use serde_json;
+	use ethereum_types::H160;
+	use types::engines;
+	use super::ValidatorStats;
+
+	#[test]
+	fn validator_stats_serialization() {
+		let mut stats = engines::ValidatorStats { from_block: 1, to_block: 3, ..Default::default() };
+		stats.validators.insert(H160::from_low_u64_be(1), engines::ValidatorLiveness {
+			produced_blocks: 2,
+			missed_steps: 1,
+			empty_steps_signed: 0,
+		});
+
+		let serialized = serde_json::to_string(&ValidatorStats::from(stats)).unwrap();
+		assert_eq!(serialized, r#"{"fromBlock":"0x1","toBlock":"0x3","validators":{"0x0000000000000000000000000000000000000001":{"producedBlocks":"0x2","missedSteps":"0x1","emptyStepsSigned":"0x0"}}}"#);
+	}
+}
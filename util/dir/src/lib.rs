@@ -224,6 +224,11 @@ impl DatabaseDirectories {
 	pub fn network_path(&self) -> PathBuf {
 		self.spec_root_path().join("network")
 	}
+
+	/// Get the path for the consensus engine's data directory.
+	pub fn engine_path(&self) -> PathBuf {
+		self.spec_root_path().join("engine")
+	}
 }
 
 /// Default data path
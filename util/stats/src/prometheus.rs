@@ -0,0 +1,208 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Parity Ethereum.
+
+// Parity Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Prometheus text exposition format.
+//!
+//! Metrics are not kept in a global registry: every scrape builds a fresh `PrometheusRegistry`
+//! and asks each component to write its current values into it.
+
+use std::fmt::{self, Write};
+
+/// Kind of a metric family, as reported in the `# TYPE` line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetricKind {
+	/// Monotonically increasing value.
+	Counter,
+	/// Value which can go up and down.
+	Gauge,
+}
+
+impl fmt::Display for MetricKind {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match *self {
+			MetricKind::Counter => write!(f, "counter"),
+			MetricKind::Gauge => write!(f, "gauge"),
+		}
+	}
+}
+
+#[derive(Debug)]
+struct Sample {
+	labels: Vec<(String, String)>,
+	value: i64,
+}
+
+#[derive(Debug)]
+struct Family {
+	name: String,
+	help: String,
+	kind: MetricKind,
+	samples: Vec<Sample>,
+}
+
+/// Metrics collected during a single scrape.
+#[derive(Debug, Default)]
+pub struct PrometheusRegistry {
+	prefix: String,
+	families: Vec<Family>,
+}
+
+impl PrometheusRegistry {
+	/// Create an empty registry. `prefix` is prepended to every metric name.
+	pub fn new(prefix: String) -> Self {
+		PrometheusRegistry {
+			prefix,
+			families: Vec::new(),
+		}
+	}
+
+	/// Add a counter.
+	pub fn register_counter(&mut self, name: &str, help: &str, value: i64) {
+		self.register(name, help, MetricKind::Counter, &[], value)
+	}
+
+	/// Add a gauge.
+	pub fn register_gauge(&mut self, name: &str, help: &str, value: i64) {
+		self.register(name, help, MetricKind::Gauge, &[], value)
+	}
+
+	/// Add a labelled sample to a gauge. Samples registered under the same name are grouped into
+	/// one family.
+	pub fn register_gauge_with_labels(&mut self, name: &str, help: &str, labels: &[(&str, &str)], value: i64) {
+		self.register(name, help, MetricKind::Gauge, labels, value)
+	}
+
+	/// Add a labelled sample to a counter. Samples registered under the same name are grouped
+	/// into one family.
+	pub fn register_counter_with_labels(&mut self, name: &str, help: &str, labels: &[(&str, &str)], value: i64) {
+		self.register(name, help, MetricKind::Counter, labels, value)
+	}
+
+	/// Number of metric families registered so far.
+	pub fn len(&self) -> usize {
+		self.families.len()
+	}
+
+	/// Whether no metrics were registered.
+	pub fn is_empty(&self) -> bool {
+		self.families.is_empty()
+	}
+
+	fn register(&mut self, name: &str, help: &str, kind: MetricKind, labels: &[(&str, &str)], value: i64) {
+		let name = format!("{}{}", self.prefix, name);
+		let sample = Sample {
+			labels: labels.iter().map(|&(k, v)| (k.to_owned(), v.to_owned())).collect(),
+			value,
+		};
+
+		if let Some(family) = self.families.iter_mut().find(|f| f.name == name) {
+			if family.kind != kind {
+				warn!(target: "stats", "Metric {} registered both as {} and {}; ignoring the latter.", name, family.kind, kind);
+				return;
+			}
+			family.samples.push(sample);
+			return;
+		}
+
+		self.families.push(Family {
+			name,
+			help: help.to_owned(),
+			kind,
+			samples: vec![sample],
+		});
+	}
+
+	/// Render all registered metrics in the Prometheus text exposition format (version 0.0.4).
+	pub fn render(&self) -> String {
+		let mut buf = String::new();
+		for family in &self.families {
+			self.render_family(&mut buf, family).expect("writing to string won't fail unless OOM; qed");
+		}
+		buf
+	}
+
+	fn render_family(&self, buf: &mut String, family: &Family) -> fmt::Result {
+		writeln!(buf, "# HELP {} {}", family.name, escape(&family.help, false))?;
+		writeln!(buf, "# TYPE {} {}", family.name, family.kind)?;
+		for sample in &family.samples {
+			write!(buf, "{}", family.name)?;
+			if !sample.labels.is_empty() {
+				let labels: Vec<_> = sample.labels.iter()
+					.map(|(k, v)| format!("{}=\"{}\"", k, escape(v, true)))
+					.collect();
+				write!(buf, "{{{}}}", labels.join(","))?;
+			}
+			writeln!(buf, " {}", sample.value)?;
+		}
+		Ok(())
+	}
+}
+
+/// Something which can report its state as Prometheus metrics.
+pub trait PrometheusMetrics {
+	/// Add the current values of all metrics to `registry`.
+	fn prometheus_metrics(&self, registry: &mut PrometheusRegistry);
+}
+
+fn escape(s: &str, quotes: bool) -> String {
+	let mut out = String::with_capacity(s.len());
+	for c in s.chars() {
+		match c {
+			'\\' => out.push_str("\\\\"),
+			'\n' => out.push_str("\\n"),
+			'"' if quotes => out.push_str("\\\""),
+			c => out.push(c),
+		}
+	}
+	out
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn renders_counters_and_gauges() {
+		let mut registry = PrometheusRegistry::new("parity_".into());
+		registry.register_counter("blocks_imported", "Blocks imported", 42);
+		registry.register_gauge("peers", "Connected peers", 5);
+
+		assert_eq!(registry.render(), "\
+# HELP parity_blocks_imported Blocks imported
+# TYPE parity_blocks_imported counter
+parity_blocks_imported 42
+# HELP parity_peers Connected peers
+# TYPE parity_peers gauge
+parity_peers 5
+");
+	}
+
+	#[test]
+	fn groups_labelled_samples() {
+		let mut registry = PrometheusRegistry::new(String::new());
+		registry.register_gauge_with_labels("cache_bytes", "Cache sizes", &[("cache", "db")], 10);
+		registry.register_gauge_with_labels("cache_bytes", "Cache sizes", &[("cache", "q\"ueue")], 20);
+		registry.register_counter("cache_bytes", "Conflicting kind", 1);
+
+		assert_eq!(registry.len(), 1);
+		assert_eq!(registry.render(), "\
+# HELP cache_bytes Cache sizes
+# TYPE cache_bytes gauge
+cache_bytes{cache=\"db\"} 10
+cache_bytes{cache=\"q\\\"ueue\"} 20
+");
+	}
+}